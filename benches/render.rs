@@ -0,0 +1,83 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use iced::advanced::text::highlighter::Highlighter as _;
+use iced::highlighter::{self, Highlighter, Theme};
+
+#[allow(dead_code)]
+#[path = "../src/brackets.rs"]
+mod brackets;
+#[allow(dead_code)]
+#[path = "../src/diff.rs"]
+mod diff;
+
+const BLOCKS: usize = 500;
+const VIEWPORT: usize = 60;
+
+fn source() -> String {
+    (0..BLOCKS)
+        .map(|index| {
+            format!(
+                "/// Item {index}\n\
+                 pub fn item_{index}(values: &[u32]) -> Option<u32> {{\n    \
+                 let total: u32 = values.iter().map(|value| value * {index}).sum();\n    \
+                 if total > {index} {{\n        \
+                 Some(total)\n    \
+                 }} else {{\n        \
+                 None\n    \
+                 }}\n\
+                 }}\n\n"
+            )
+        })
+        .collect()
+}
+
+fn highlight_from(highlighter: &mut Highlighter, lines: &[&str], line: usize) {
+    highlighter.change_line(line);
+    while let Some(line) = lines.get(highlighter.current_line()) {
+        black_box(highlighter.highlight_line(line).count());
+    }
+}
+
+fn highlighting(c: &mut Criterion) {
+    let text = source();
+    let lines: Vec<&str> = text.split('\n').collect();
+    let settings = highlighter::Settings {
+        theme: Theme::SolarizedDark,
+        extension: String::from("rs"),
+    };
+    let mut group = c.benchmark_group("highlight");
+    group.bench_function("full", |b| {
+        b.iter(|| highlight_from(&mut Highlighter::new(&settings), &lines, 0))
+    });
+    let mut highlighter = Highlighter::new(&settings);
+    highlight_from(&mut highlighter, &lines, 0);
+    group.bench_function("edit_in_viewport", |b| {
+        b.iter(|| highlight_from(&mut highlighter, &lines, lines.len() - VIEWPORT))
+    });
+    group.bench_function("edit_at_top", |b| {
+        b.iter(|| highlight_from(&mut highlighter, &lines, 0))
+    });
+    group.finish();
+}
+
+fn layout(c: &mut Criterion) {
+    let text = source();
+    let cursor = text.rfind('{').unwrap_or_default();
+    let scanned = brackets::scan(&text);
+    let old: Vec<&str> = text.split('\n').collect();
+    let mut new = old.clone();
+    new[old.len() / 2] = "    let edited = true;";
+    let mut group = c.benchmark_group("layout");
+    group.bench_function("bracket_scan", |b| {
+        b.iter(|| black_box(brackets::scan(&text)))
+    });
+    group.bench_function("bracket_pair_cached", |b| {
+        b.iter(|| black_box(brackets::pair(&text, &scanned, cursor)))
+    });
+    group.bench_function("line_diff_single_edit", |b| {
+        b.iter(|| black_box(diff::diff(&old, &new)))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, highlighting, layout);
+criterion_main!(benches);
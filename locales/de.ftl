@@ -0,0 +1,67 @@
+tip-new = neue Datei
+tip-open = Datei öffnen
+tip-save = Datei speichern
+tip-save-as = Datei speichern unter
+tip-undo = rückgängig
+tip-redo = wiederholen
+
+recent-files = Zuletzt geöffnet
+drop-files = Dateien hier ablegen, um sie zu öffnen
+loading = { $name } wird geladen
+title-read-only = [schreibgeschützt]
+
+status-read-only = schreibgeschützt
+status-new-file = Neue Datei
+status-wrap = Umbruch
+status-no-wrap = kein Umbruch
+status-position-wrapped = { $line }:{ $column } (Reihe { $row } von { $rows })
+status-no-save-fixes = keine Korrekturen beim Speichern
+status-selection = { $lines ->
+    [0] { $chars } ausgewählt
+    [1] { $chars } ausgewählt
+   *[other] { $chars } ausgewählt ({ $lines } Zeilen)
+}
+status-carets = +{ $count } Cursor
+status-counts = { $lines } Zeilen, { $words } Wörter
+status-page = Seite { $index }/{ $count }
+status-reopen = neu öffnen
+status-problems = { $errors } Fehler, { $warnings } Warnungen
+status-table-view = Tabellenansicht
+status-text-view = Textansicht
+status-following = wird verfolgt
+status-recording = ● Aufnahme ({ $steps } Schritte)
+status-zoom = Zoom { $percent } %
+status-transform = umwandeln
+
+button-save = Speichern
+button-save-as = Speichern unter…
+button-discard = Verwerfen
+button-cancel = Abbrechen
+button-restore = Wiederherstellen
+button-compare = Vergleichen
+button-overwrite = Überschreiben
+button-reload = Neu laden
+button-keep-mine = Meine behalten
+button-start = Starten
+button-run = Ausführen
+button-open = Öffnen
+button-always = Immer
+button-not-now = Nicht jetzt
+
+prompt-recovered = { $count ->
+    [one] Ein ungespeicherter Puffer aus der letzten Sitzung wurde wiederhergestellt.
+   *[other] { $count } ungespeicherte Puffer aus der letzten Sitzung wurden wiederhergestellt.
+}
+prompt-save-changes = Änderungen an { $file } speichern?
+prompt-save-denied = { $file } kann hier nicht geschrieben werden. An einem anderen Ort speichern?
+prompt-save-conflict = { $file } wurde seit dem Öffnen auf dem Datenträger geändert. Überschreiben?
+prompt-reload = { $file } wurde auf dem Datenträger geändert. Neu laden?
+prompt-start-server = Den Sprachserver `{ $command }` für { $language }-Dateien starten?
+prompt-run-formatter = Den Formatierer `{ $command }` auf { $file } anwenden?
+prompt-run-command = `{ $command }` im Terminal ausführen?
+prompt-open-link = `{ $link }` öffnen? Es ist keine Webadresse.
+
+unsaved-changes = Ungespeicherte Änderungen: { $count ->
+    [one] ein Abschnitt
+   *[other] { $count } Abschnitte
+}
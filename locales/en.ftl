@@ -0,0 +1,67 @@
+tip-new = new file
+tip-open = open file
+tip-save = save file
+tip-save-as = save file as
+tip-undo = undo
+tip-redo = redo
+
+recent-files = Recent files
+drop-files = Drop files to open them
+loading = Loading { $name }
+title-read-only = [read-only]
+
+status-read-only = read-only
+status-new-file = New file
+status-wrap = wrap
+status-no-wrap = no wrap
+status-position-wrapped = { $line }:{ $column } (row { $row } of { $rows })
+status-no-save-fixes = no save fixes
+status-selection = { $lines ->
+    [0] { $chars } selected
+    [1] { $chars } selected
+   *[other] { $chars } selected ({ $lines } lines)
+}
+status-carets = +{ $count } carets
+status-counts = { $lines } lines, { $words } words
+status-page = page { $index }/{ $count }
+status-reopen = reopen
+status-problems = { $errors } errors, { $warnings } warnings
+status-table-view = table view
+status-text-view = text view
+status-following = following
+status-recording = ● recording ({ $steps } steps)
+status-zoom = zoom { $percent }%
+status-transform = transform
+
+button-save = Save
+button-save-as = Save as…
+button-discard = Discard
+button-cancel = Cancel
+button-restore = Restore
+button-compare = Compare
+button-overwrite = Overwrite
+button-reload = Reload
+button-keep-mine = Keep mine
+button-start = Start
+button-run = Run
+button-open = Open
+button-always = Always
+button-not-now = Not now
+
+prompt-recovered = { $count ->
+    [one] Recovered one unsaved buffer from a previous session.
+   *[other] Recovered { $count } unsaved buffers from a previous session.
+}
+prompt-save-changes = Save changes to { $file }?
+prompt-save-denied = { $file } cannot be written here. Save it somewhere else?
+prompt-save-conflict = { $file } changed on disk since it was opened. Overwrite it?
+prompt-reload = { $file } was changed on disk. Reload from disk?
+prompt-start-server = Start the language server `{ $command }` for { $language } files?
+prompt-run-formatter = Run the formatter `{ $command }` on { $file }?
+prompt-run-command = Run `{ $command }` in the terminal?
+prompt-open-link = Open `{ $link }`? It is not a web address.
+
+unsaved-changes = Unsaved changes: { $count ->
+    [one] one hunk
+   *[other] { $count } hunks
+}
@@ -0,0 +1,97 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use iced::{Color, Theme, theme::Palette};
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Appearance {
+    #[default]
+    Syntax,
+    System,
+    Light,
+    Dark,
+    Custom(String),
+}
+
+impl Appearance {
+    pub const BUILT_IN: &'static [Appearance] = &[
+        Appearance::Syntax,
+        Appearance::System,
+        Appearance::Light,
+        Appearance::Dark,
+    ];
+
+    pub fn options(palettes: &BTreeMap<String, CustomPalette>) -> Vec<Appearance> {
+        Self::BUILT_IN
+            .iter()
+            .cloned()
+            .chain(palettes.keys().cloned().map(Appearance::Custom))
+            .collect()
+    }
+
+    pub fn theme(
+        &self,
+        palettes: &BTreeMap<String, CustomPalette>,
+        syntax_is_dark: bool,
+        system_is_dark: bool,
+    ) -> Theme {
+        let is_dark = match self {
+            Appearance::Light => false,
+            Appearance::Dark => true,
+            Appearance::System => system_is_dark,
+            Appearance::Syntax => syntax_is_dark,
+            Appearance::Custom(name) => match palettes.get(name).and_then(CustomPalette::palette) {
+                Some(palette) => return Theme::custom(palette),
+                None => syntax_is_dark,
+            },
+        };
+        if is_dark { Theme::Dark } else { Theme::Light }
+    }
+}
+
+impl fmt::Display for Appearance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Appearance::Syntax => f.write_str("Match syntax theme"),
+            Appearance::System => f.write_str("Follow system"),
+            Appearance::Light => f.write_str("Light"),
+            Appearance::Dark => f.write_str("Dark"),
+            Appearance::Custom(name) => f.write_str(name),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct CustomPalette {
+    pub background: String,
+    pub text: String,
+    pub primary: String,
+    pub success: String,
+    pub danger: String,
+}
+
+impl CustomPalette {
+    fn palette(&self) -> Option<Palette> {
+        Some(Palette {
+            background: parse_color(&self.background)?,
+            text: parse_color(&self.text)?,
+            primary: parse_color(&self.primary)?,
+            success: parse_color(&self.success)?,
+            danger: parse_color(&self.danger)?,
+        })
+    }
+}
+
+fn parse_color(hex: &str) -> Option<Color> {
+    let hex = hex.strip_prefix('#').unwrap_or(hex);
+    if hex.len() != 6 || !hex.is_ascii() {
+        return None;
+    }
+    let channel = |index: usize| u8::from_str_radix(&hex[index..index + 2], 16).ok();
+    Some(Color::from_rgb8(channel(0)?, channel(2)?, channel(4)?))
+}
+
+pub async fn system_is_dark() -> bool {
+    dark_light::detect() == dark_light::Mode::Dark
+}
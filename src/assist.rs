@@ -0,0 +1,86 @@
+use std::sync::Arc;
+
+use iced::widget::text_editor::{Action, Content, Edit, Motion};
+
+use crate::config::Config;
+use crate::indent::Indent;
+
+const PAIRS: &[(char, char)] = &[
+    ('(', ')'),
+    ('[', ']'),
+    ('{', '}'),
+    ('"', '"'),
+    ('\'', '\''),
+    ('`', '`'),
+];
+
+pub fn expand(content: &Content, action: Action, config: &Config, indent: Indent) -> Vec<Action> {
+    let Action::Edit(edit) = &action else {
+        return vec![action];
+    };
+    let text = content.text();
+    let (line, column) = content.cursor_position();
+    let current = text.split('\n').nth(line).unwrap_or_default();
+    let split = current
+        .char_indices()
+        .nth(column)
+        .map_or(current.len(), |(index, _)| index);
+    let (before, after) = current.split_at(split);
+    let expanded = match edit {
+        Edit::Insert('\t') if content.selection().is_none() => {
+            Some(vec![paste(indent.tab_at(before.chars().count()))])
+        }
+        Edit::Enter if config.auto_indent => Some(enter(before, after, &indent.unit())),
+        Edit::Insert(c) if config.auto_close => match content.selection() {
+            Some(selection) => surround(*c, &selection),
+            None => close(*c, before, after),
+        },
+        _ => None,
+    };
+    expanded.unwrap_or_else(|| vec![action])
+}
+
+fn enter(before: &str, after: &str, unit: &str) -> Vec<Action> {
+    let indent: String = before.chars().take_while(|c| c.is_whitespace()).collect();
+    let opens = before.trim_end().ends_with(['{', '(', '[', ':']);
+    if !opens {
+        return vec![paste(format!("\n{indent}"))];
+    }
+    let closes = after.trim_start().starts_with(['}', ')', ']']);
+    if !closes {
+        return vec![paste(format!("\n{indent}{unit}"))];
+    }
+    vec![
+        paste(format!("\n{indent}{unit}\n{indent}")),
+        Action::Move(Motion::Up),
+        Action::Move(Motion::End),
+    ]
+}
+
+fn surround(c: char, selection: &str) -> Option<Vec<Action>> {
+    let (open, close) = PAIRS.iter().find(|(open, _)| *open == c)?;
+    Some(vec![paste(format!("{open}{selection}{close}"))])
+}
+
+fn close(c: char, before: &str, after: &str) -> Option<Vec<Action>> {
+    if after.starts_with(c) && PAIRS.iter().any(|(_, close)| *close == c) {
+        return Some(vec![Action::Move(Motion::Right)]);
+    }
+    let (open, close) = PAIRS.iter().find(|(open, _)| *open == c)?;
+    let is_quote = open == close;
+    let glued = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+    if is_quote && (glued(before.chars().next_back()) || glued(after.chars().next())) {
+        return None;
+    }
+    if !is_quote && glued(after.chars().next()) {
+        return None;
+    }
+    Some(vec![
+        paste(format!("{open}{close}")),
+        Action::Move(Motion::Left),
+    ])
+}
+
+fn paste(text: String) -> Action {
+    Action::Edit(Edit::Paste(Arc::new(text)))
+}
@@ -0,0 +1,87 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Bookmarks {
+    pub files: BTreeMap<PathBuf, BTreeSet<usize>>,
+}
+
+impl Bookmarks {
+    pub fn get(&self, path: &Path) -> BTreeSet<usize> {
+        self.files.get(path).cloned().unwrap_or_default()
+    }
+
+    pub fn set(&mut self, path: &Path, lines: &BTreeSet<usize>) {
+        if lines.is_empty() {
+            self.files.remove(path);
+        } else {
+            self.files.insert(path.to_path_buf(), lines.clone());
+        }
+    }
+}
+
+pub fn shift(lines: &mut BTreeSet<usize>, start: usize, delta: isize) {
+    if delta == 0 {
+        return;
+    }
+    *lines = lines
+        .iter()
+        .map(|&line| {
+            if line <= start {
+                line
+            } else {
+                line.saturating_add_signed(delta).max(start)
+            }
+        })
+        .collect();
+}
+
+pub fn next(lines: &BTreeSet<usize>, from: usize, forward: bool) -> Option<usize> {
+    if forward {
+        lines
+            .range(from + 1..)
+            .next()
+            .or_else(|| lines.iter().next())
+            .copied()
+    } else {
+        lines
+            .range(..from)
+            .next_back()
+            .or_else(|| lines.iter().next_back())
+            .copied()
+    }
+}
+
+fn bookmarks_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("bookmarks.toml"))
+}
+
+pub async fn load() -> Bookmarks {
+    let Some(path) = bookmarks_file() else {
+        return Bookmarks::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(bookmarks: Bookmarks) -> Result<(), Error> {
+    let path = bookmarks_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents = toml::to_string(&bookmarks)
+        .map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
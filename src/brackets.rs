@@ -0,0 +1,136 @@
+use std::ops::Range;
+
+const PAIRS: [(char, char); 3] = [('(', ')'), ('[', ']'), ('{', '}')];
+
+pub fn matching(text: &str, offset: usize) -> Option<(usize, usize)> {
+    matching_in(&scan(text), offset)
+}
+
+fn matching_in(brackets: &[(usize, char)], offset: usize) -> Option<(usize, usize)> {
+    let at = brackets
+        .iter()
+        .position(|&(position, _)| position == offset)
+        .or_else(|| {
+            brackets
+                .iter()
+                .position(|&(position, c)| position + c.len_utf8() == offset)
+        })?;
+    let (position, c) = brackets[at];
+    let (open, close) = PAIRS
+        .iter()
+        .copied()
+        .find(|&(open, close)| c == open || c == close)?;
+    let mut depth = 0usize;
+    let mut step = |&(other, bracket): &(usize, char)| {
+        if bracket == c {
+            depth += 1;
+        } else if bracket == open || bracket == close {
+            depth -= 1;
+            if depth == 0 {
+                return Some(other);
+            }
+        }
+        None
+    };
+    let other = if c == open {
+        brackets[at..].iter().find_map(&mut step)
+    } else {
+        brackets[..=at].iter().rev().find_map(&mut step)
+    }?;
+    Some((position, other))
+}
+
+pub fn cursor_offset(text: &str, (line, column): (usize, usize)) -> usize {
+    let line_start: usize = text.split('\n').take(line).map(|line| line.len() + 1).sum();
+    let line_start = line_start.min(text.len());
+    text[line_start..]
+        .char_indices()
+        .nth(column)
+        .map_or(text.len(), |(index, _)| line_start + index)
+}
+
+pub fn scan(text: &str) -> Vec<(usize, char)> {
+    let mut brackets = Vec::new();
+    let mut quote = None;
+    let mut chars = text.char_indices().peekable();
+    while let Some((index, c)) = chars.next() {
+        match (quote, c) {
+            (_, '\n') => quote = None,
+            (Some(_), '\\') => {
+                chars.next();
+            }
+            (Some(open), c) if c == open => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '`') => quote = Some(c),
+            (None, '\'') if is_char_literal(&text[index + 1..]) => quote = Some(c),
+            (None, c) if PAIRS.iter().any(|&(open, close)| c == open || c == close) => {
+                brackets.push((index, c));
+            }
+            (None, _) => {}
+        }
+    }
+    brackets
+}
+
+fn is_char_literal(rest: &str) -> bool {
+    let mut chars = rest.chars();
+    match chars.next() {
+        Some('\\') => rest.chars().take(10).skip(2).any(|c| c == '\''),
+        Some(_) => chars.next() == Some('\''),
+        None => false,
+    }
+}
+
+pub fn pair(
+    text: &str,
+    brackets: &[(usize, char)],
+    cursor: usize,
+) -> Option<[(usize, Range<usize>); 2]> {
+    let (bracket, other) = matching_in(brackets, cursor)?;
+    Some([bracket, other].map(|offset| {
+        let line_start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+        let line = text[..offset].matches('\n').count();
+        (line, offset - line_start..offset - line_start + 1)
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cursor_offset, matching, pair, scan};
+
+    #[test]
+    fn matches_from_either_side() {
+        let text = "fn f(a[0]) {}";
+        assert_eq!(matching(text, 4), Some((4, 9)));
+        assert_eq!(matching(text, 10), Some((9, 4)));
+        assert_eq!(matching(text, 6), Some((6, 8)));
+        assert_eq!(matching(text, 2), None);
+    }
+
+    #[test]
+    fn skips_strings_and_char_literals() {
+        assert_eq!(scan("a(\")\")"), [(1, '('), (5, ')')]);
+        assert_eq!(scan("')' (x)"), [(4, '('), (6, ')')]);
+        assert_eq!(scan("fn f<'a>(x: &'a str)"), [(8, '('), (19, ')')]);
+    }
+
+    #[test]
+    fn leaves_unbalanced_brackets_unmatched() {
+        assert_eq!(matching("(()", 0), None);
+        assert_eq!(matching("(()", 1), Some((1, 2)));
+    }
+
+    #[test]
+    fn converts_cursor_positions_to_offsets() {
+        let text = "ab\ncé\nd";
+        assert_eq!(cursor_offset(text, (0, 0)), 0);
+        assert_eq!(cursor_offset(text, (1, 2)), 6);
+        assert_eq!(cursor_offset(text, (2, 5)), text.len());
+    }
+
+    #[test]
+    fn pairs_report_lines_and_columns() {
+        let text = "x {\n}";
+        assert_eq!(pair(text, &scan(text), 2), Some([(0, 2..3), (1, 0..1)]));
+    }
+}
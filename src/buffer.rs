@@ -0,0 +1,930 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use iced::widget::text_editor::{self, Action, Edit, Motion};
+use zeroize::Zeroize;
+
+use crate::bookmarks;
+use crate::carets;
+use crate::comment;
+use crate::convert::Conversion;
+use crate::crypt::Passphrase;
+use crate::encoding::{self, TextEncoding};
+use crate::fold::{self, Folded};
+use crate::follow::Follow;
+use crate::git;
+use crate::hex;
+use crate::history::{self, History, Snapshot};
+use crate::indent::Indent;
+use crate::language::Language;
+use crate::line_ending::LineEnding;
+use crate::lsp::Diagnostic;
+use crate::markdown;
+use crate::outline::{self, Symbol};
+use crate::remote::Location;
+use crate::rope;
+use crate::saves::Stamp;
+use crate::snippet::{Expansion, Session};
+use crate::table::{self, Table};
+use crate::transform::{self, Report};
+use crate::undo;
+use crate::view_state::ViewState;
+use crate::web;
+
+/// Values derived from the whole text that the view reads every frame. They
+/// are rebuilt at most once per revision, outside of `view`.
+#[derive(Debug, Default)]
+pub struct Frame {
+    key: Option<(u64, Language)>,
+    pub text: String,
+    pub words: usize,
+    pub preview: Option<Vec<markdown::Block>>,
+    pub symbols: Option<Vec<Symbol>>,
+}
+
+pub struct Buffer {
+    pub id: usize,
+    pub path: Option<PathBuf>,
+    pub remote: Option<Location>,
+    pub url: Option<String>,
+    pub content: text_editor::Content,
+    pub is_dirty: bool,
+    pub scratch: bool,
+    pub is_readonly: bool,
+    pub has_conflict: bool,
+    pub is_deleted: bool,
+    pub passphrase: Option<Passphrase>,
+    pub disk: Option<Stamp>,
+    snapshot: rope::Snapshot,
+    pub frame: Frame,
+    pub wrap: bool,
+    pub top_line: usize,
+    pub bookmarks: BTreeSet<usize>,
+    pub folds: BTreeMap<usize, usize>,
+    pub folded: Option<Folded>,
+    pub carets: Vec<Range<usize>>,
+    pub indent: Indent,
+    pub transforms: transform::Options,
+    pub skip_transforms: bool,
+    pub save_report: Option<Report>,
+    pub revision: u64,
+    pub synced: Option<u64>,
+    pub diagnostics: Vec<Diagnostic>,
+    pub snippet: Option<Session>,
+    pub git: Option<git::Info>,
+    pub hex: bool,
+    pub table: Option<Table>,
+    pub follow: Option<Follow>,
+    pub language: Language,
+    pub history: History,
+    pub page: Option<(u64, u64)>,
+    pub encoding: TextEncoding,
+    pub has_bom: bool,
+    pub line_ending: LineEnding,
+}
+
+impl Buffer {
+    pub fn new(id: usize, path: Option<PathBuf>, text: &str) -> Self {
+        Self {
+            id,
+            language: path.as_deref().map(Language::from_path).unwrap_or_default(),
+            path,
+            remote: None,
+            url: None,
+            content: text_editor::Content::with(text),
+            is_dirty: false,
+            scratch: false,
+            is_readonly: false,
+            has_conflict: false,
+            is_deleted: false,
+            passphrase: None,
+            disk: None,
+            snapshot: rope::Snapshot::default(),
+            frame: Frame::default(),
+            wrap: true,
+            top_line: 0,
+            bookmarks: BTreeSet::new(),
+            folds: BTreeMap::new(),
+            folded: None,
+            carets: Vec::new(),
+            indent: Indent::default(),
+            transforms: transform::Options::default(),
+            skip_transforms: false,
+            save_report: None,
+            revision: 0,
+            synced: None,
+            diagnostics: Vec::new(),
+            snippet: None,
+            git: None,
+            hex: false,
+            table: None,
+            follow: None,
+            history: History::new(history::DEFAULT_DEPTH),
+            page: None,
+            encoding: TextEncoding::default(),
+            has_bom: false,
+            line_ending: LineEnding::default(),
+        }
+    }
+
+    pub fn title(&self) -> String {
+        if let (None, Some(remote)) = (&self.path, &self.remote) {
+            return remote.file_name().to_owned();
+        }
+        if let (None, Some(url)) = (&self.path, &self.url) {
+            return web::file_name(url).to_owned();
+        }
+        self.path.as_deref().and_then(Path::file_name).map_or_else(
+            || String::from(if self.scratch { "Scratch" } else { "Untitled" }),
+            |name| name.to_string_lossy().into_owned(),
+        )
+    }
+
+    pub fn view_state(&self) -> Option<ViewState> {
+        if self.hex || self.page.is_some() {
+            return None;
+        }
+        let path = self.path.clone()?;
+        let (line, column) = self.cursor_position();
+        let top_line = match &self.folded {
+            Some(folded) => folded.line(folded.top_line),
+            None => self.top_line,
+        };
+        let folds = if self.is_dirty {
+            Vec::new()
+        } else {
+            self.folds
+                .iter()
+                .map(|(start, end)| (*start, *end))
+                .collect()
+        };
+        let language =
+            (self.language != Language::from_path(&path)).then(|| self.language.token().to_owned());
+        Some(ViewState {
+            hash: self.disk.map_or(0, |stamp| stamp.hash),
+            path,
+            line,
+            column,
+            top_line,
+            folds,
+            language,
+        })
+    }
+
+    pub fn place(&mut self, line: usize, column: usize, top_line: usize) {
+        let text = self.content.text();
+        let line_count = self.content.line_count();
+        let line = line.min(line_count - 1);
+        let length = text
+            .split('\n')
+            .nth(line)
+            .map_or(0, |line| line.chars().count());
+        crate::move_cursor_to(&mut self.content, line, column.min(length));
+        let top_line = top_line.min(line_count - 1);
+        self.content.edit(Action::Scroll {
+            lines: -(line_count as i32),
+        });
+        self.content.edit(Action::Scroll {
+            lines: top_line as i32,
+        });
+        self.top_line = top_line;
+    }
+
+    pub fn restore_view(&mut self, state: ViewState) {
+        if let Some(language) = state.language.as_deref().and_then(Language::from_token) {
+            self.language = language;
+        }
+        self.place(state.line, state.column, state.top_line);
+        let line_count = self.content.line_count();
+        if self.disk.is_some_and(|stamp| stamp.hash == state.hash) {
+            self.folds = state
+                .folds
+                .into_iter()
+                .filter(|(start, end)| start < end && *end < line_count)
+                .collect();
+            self.refold();
+        }
+    }
+
+    pub fn saved_history(&self) -> Option<undo::Saved> {
+        if self.is_dirty || self.hex || self.page.is_some() || self.passphrase.is_some() {
+            return None;
+        }
+        let path = self.path.clone()?;
+        let hash = self.disk?.hash;
+        let (undo, redo) = self.history.export(undo::MAX_BYTES);
+        Some(undo::Saved {
+            path,
+            hash,
+            undo,
+            redo,
+        })
+    }
+
+    /// Zeroizes the plaintext copies a decrypted buffer owns before it is
+    /// dropped: the undo history, the rope and the folded text. The editor
+    /// widget keeps its own line storage, which can only be replaced by
+    /// blanks, so whatever it freed earlier is not overwritten.
+    pub fn wipe(&mut self) {
+        let mut text = self.content.text();
+        let blank: String = text
+            .chars()
+            .flat_map(|c| {
+                let fill = if c == '\n' { '\n' } else { ' ' };
+                std::iter::repeat_n(fill, c.len_utf8())
+            })
+            .collect();
+        text.zeroize();
+        let blank = Arc::new(blank);
+        if let Some(folded) = &mut self.folded {
+            folded.text.zeroize();
+        }
+        let contents = std::iter::once(&mut self.content)
+            .chain(self.folded.as_mut().map(|folded| &mut folded.content));
+        for content in contents {
+            content.edit(Action::Move(Motion::DocumentStart));
+            content.edit(Action::Select(Motion::DocumentEnd));
+            content.edit(Action::Edit(Edit::Paste(blank.clone())));
+        }
+        self.snapshot.wipe(&self.content);
+        self.history.wipe();
+        self.frame.text.zeroize();
+        self.frame = Frame::default();
+    }
+
+    pub fn snapshot(&mut self) -> &rope::Snapshot {
+        self.snapshot.sync(&self.content, self.revision);
+        &self.snapshot
+    }
+
+    pub fn rope(&mut self) -> &ropey::Rope {
+        self.snapshot().rope()
+    }
+
+    pub fn encoded(&mut self) -> (Vec<u8>, bool) {
+        let (line_ending, encoding, bom) = (self.line_ending, self.encoding, self.has_bom);
+        rope::encode(self.rope(), line_ending, encoding, bom)
+    }
+
+    pub fn set_path(&mut self, path: PathBuf) {
+        self.language = Language::from_path(&path);
+        self.path = Some(path);
+        self.scratch = false;
+        self.remote = None;
+        self.url = None;
+    }
+
+    pub fn perform(&mut self, action: Action) {
+        self.unfold();
+        if self.is_readonly && action.is_edit() {
+            return;
+        }
+        if !self.carets.is_empty() {
+            self.snippet = None;
+            match &action {
+                Action::Edit(edit) => return self.edit_carets(&action, edit),
+                Action::Move(motion) => self.move_carets(*motion, false),
+                Action::Select(motion) => self.move_carets(*motion, true),
+                Action::SelectWord | Action::SelectLine => self.carets.clear(),
+                _ => {}
+            }
+        }
+        let pending = action
+            .is_edit()
+            .then(|| self.snapshot.begin(&self.content, self.revision))
+            .flatten();
+        if action.is_edit() {
+            self.history.record(&self.content, &action);
+            self.is_dirty = true;
+            self.save_report = None;
+            self.revision += 1;
+        } else {
+            self.history.break_group();
+        }
+        let before = match &self.snippet {
+            Some(_) if action.is_edit() => Some(self.content.text().len()),
+            _ => None,
+        };
+        let marked = (!self.bookmarks.is_empty() && action.is_edit())
+            .then(|| (self.content.cursor_position().0, self.content.line_count()));
+        let unedited = (!self.folds.is_empty() && action.is_edit()).then(|| self.content.text());
+        self.content.edit(action);
+        if let Some(pending) = pending {
+            self.snapshot.finish(&self.content, pending, self.revision);
+        }
+        if let Some(unedited) = unedited {
+            fold::shift(&mut self.folds, &unedited, &self.content.text());
+        }
+        if let Some((line, count)) = marked {
+            let start = line.min(self.content.cursor_position().0);
+            let delta = self.content.line_count() as isize - count as isize;
+            bookmarks::shift(&mut self.bookmarks, start, delta);
+        }
+        if let Some(before) = before {
+            let text = self.content.text();
+            let delta = text.len() as isize - before as isize;
+            let cursor = crate::brackets::cursor_offset(&text, self.content.cursor_position());
+            if !self
+                .snippet
+                .as_mut()
+                .is_some_and(|session| session.edited(delta, cursor))
+            {
+                self.snippet = None;
+            }
+        }
+    }
+
+    pub fn insert_snippet(&mut self, prefix: &str, expansion: Expansion) {
+        if self.is_readonly {
+            return;
+        }
+        self.snippet = None;
+        for _ in prefix.chars() {
+            self.perform(Action::Edit(Edit::Backspace));
+        }
+        let start =
+            crate::brackets::cursor_offset(&self.content.text(), self.content.cursor_position());
+        let session = Session::new(start, &expansion);
+        self.perform(Action::Edit(Edit::Paste(Arc::new(expansion.text))));
+        self.snippet = Some(session);
+        self.select_tab_stop();
+    }
+
+    pub fn next_tab_stop(&mut self, forward: bool) -> bool {
+        let Some(session) = &mut self.snippet else {
+            return false;
+        };
+        if forward {
+            session.current += 1;
+        } else {
+            session.current = session.current.saturating_sub(1);
+        }
+        self.history.break_group();
+        self.select_tab_stop();
+        true
+    }
+
+    fn select_tab_stop(&mut self) {
+        let Some(session) = &self.snippet else {
+            return;
+        };
+        let Some(stop) = session.stop() else {
+            self.snippet = None;
+            return;
+        };
+        if session.is_last() {
+            self.snippet = None;
+        }
+        let text = self.content.text();
+        if stop.end <= text.len() {
+            crate::select_range(&mut self.content, &text, &stop);
+        }
+    }
+
+    pub fn scroll(&mut self, lines: i32) {
+        let last = self.content.line_count().saturating_sub(1);
+        self.top_line = self
+            .top_line
+            .saturating_add_signed(lines as isize)
+            .min(last);
+    }
+
+    pub fn cursor_position(&self) -> (usize, usize) {
+        match &self.folded {
+            Some(folded) => {
+                let (row, column) = folded.content.cursor_position();
+                (folded.line(row), column)
+            }
+            None => self.content.cursor_position(),
+        }
+    }
+
+    pub fn visible_lines(&self, viewport_lines: usize) -> Vec<usize> {
+        match &self.folded {
+            Some(folded) => folded
+                .lines
+                .iter()
+                .skip(folded.visible_top(viewport_lines))
+                .take(viewport_lines)
+                .copied()
+                .collect(),
+            None => {
+                let top = self.visible_top(viewport_lines);
+                (top..self.content.line_count().min(top + viewport_lines)).collect()
+            }
+        }
+    }
+
+    pub fn toggle_fold(&mut self, line: usize) {
+        if self.folds.remove(&line).is_none() {
+            let regions = fold::regions(&self.content.text(), self.language);
+            let Some((start, end)) = fold::innermost(&regions, line) else {
+                return;
+            };
+            self.folds.insert(start, end);
+        }
+        self.refold();
+    }
+
+    pub fn fold_all(&mut self) {
+        self.folds = fold::regions(&self.content.text(), self.language);
+        self.refold();
+    }
+
+    pub fn unfold_all(&mut self) {
+        self.folds.clear();
+        self.refold();
+    }
+
+    pub fn unfold(&mut self) {
+        let Some(folded) = self.folded.take() else {
+            return;
+        };
+        let (row, column) = folded.content.cursor_position();
+        let line = folded.line(row);
+        let length = self
+            .content
+            .text()
+            .split('\n')
+            .nth(line)
+            .map_or(0, |line| line.chars().count());
+        crate::move_cursor_to(&mut self.content, line, column.min(length));
+        self.top_line = folded.line(folded.top_line);
+    }
+
+    pub fn refresh_frame(&mut self, preview: bool, outline: bool) {
+        let key = (self.revision, self.language);
+        if self.frame.key != Some(key) {
+            self.frame.text.zeroize();
+            let text = self.content.text();
+            self.frame = Frame {
+                key: Some(key),
+                words: text.split_whitespace().count(),
+                text,
+                preview: None,
+                symbols: None,
+            };
+        }
+        if preview && self.frame.preview.is_none() {
+            self.frame.preview = Some(markdown::parse(&self.frame.text));
+        }
+        if outline && self.frame.symbols.is_none() {
+            self.frame.symbols = Some(outline::symbols(&self.frame.text, self.language));
+        }
+    }
+
+    pub fn refresh_folds(&mut self) {
+        if self.folded.is_some() || self.folds.is_empty() {
+            return;
+        }
+        let (line, _) = self.content.cursor_position();
+        self.folds
+            .retain(|start, end| line <= *start || line > *end);
+        self.refold();
+    }
+
+    fn refold(&mut self) {
+        let (line, column) = self.cursor_position();
+        let top_line = match &self.folded {
+            Some(folded) => folded.line(folded.top_line),
+            None => self.top_line,
+        };
+        if self.folds.is_empty() {
+            if self.folded.take().is_some() {
+                crate::move_cursor_to(&mut self.content, line, column);
+                self.top_line = top_line;
+            }
+            return;
+        }
+        let mut folded = Folded::new(&self.content.text(), &self.folds);
+        let row = folded.row(line);
+        let column = if folded.line(row) == line { column } else { 0 };
+        crate::move_cursor_to(&mut folded.content, row, column);
+        folded.top_line = folded.row(top_line);
+        self.folded = Some(folded);
+    }
+
+    pub fn visible_top(&self, viewport_lines: usize) -> usize {
+        let (line, _) = self.content.cursor_position();
+        self.top_line
+            .clamp(line.saturating_sub(viewport_lines.saturating_sub(1)), line)
+    }
+
+    pub fn primary_range(&self, text: &str) -> Range<usize> {
+        let (line, column) = self.content.cursor_position();
+        let cursor = crate::position_to_offset(text, line, column);
+        match self.content.selection() {
+            Some(selection) if text[..cursor].ends_with(&selection) => {
+                cursor - selection.len()..cursor
+            }
+            Some(selection) => cursor..(cursor + selection.len()).min(text.len()),
+            None => cursor..cursor,
+        }
+    }
+
+    pub fn selected_lines(&self, text: &str) -> Range<usize> {
+        let range = self.primary_range(text);
+        let first = text[..range.start].matches('\n').count();
+        let mut last = text[..range.end].matches('\n').count();
+        if last > first && text[..range.end].ends_with('\n') {
+            last -= 1;
+        }
+        first..last + 1
+    }
+
+    pub fn replace_lines(
+        &mut self,
+        lines: Range<usize>,
+        replacement: &[String],
+        selected: Range<usize>,
+    ) {
+        if self.is_readonly {
+            return;
+        }
+        let text = self.content.text();
+        let has_selection = self.content.selection().is_some();
+        let (line, column) = self.content.cursor_position();
+        let origin = self.selected_lines(&text).start;
+        let mut all: Vec<&str> = text.split('\n').collect();
+        let before = all.get(line).map_or(0, |line| line.chars().count());
+        all.splice(lines, replacement.iter().map(String::as_str));
+        if all.is_empty() {
+            all.push("");
+        }
+        let line = (line + selected.start)
+            .saturating_sub(origin)
+            .min(all.len() - 1);
+        let after = all[line].chars().count();
+        let replaced = all.join("\n");
+        self.replace_text(&replaced);
+        if has_selection && !selected.is_empty() {
+            let start = crate::position_to_offset(&replaced, selected.start, 0);
+            let last = selected.end - 1;
+            let end = crate::position_to_offset(&replaced, last, 0) + all[last].len();
+            crate::select_range(&mut self.content, &replaced, &(start..end));
+        } else {
+            let column = (column + after).saturating_sub(before).min(after);
+            crate::move_cursor_to(&mut self.content, line, column);
+        }
+    }
+
+    pub fn toggle_comment(&mut self) {
+        let text = self.content.text();
+        let lines = self.selected_lines(&text);
+        let selected: Vec<&str> = text
+            .split('\n')
+            .skip(lines.start)
+            .take(lines.len())
+            .collect();
+        let toggled = comment::toggle(&selected, self.language.comment());
+        if toggled
+            .iter()
+            .map(String::as_str)
+            .ne(selected.iter().copied())
+        {
+            self.replace_lines(lines.clone(), &toggled, lines);
+        }
+    }
+
+    pub fn move_lines(&mut self, down: bool) {
+        let text = self.content.text();
+        let lines = self.selected_lines(&text);
+        let all: Vec<&str> = text.split('\n').collect();
+        let (range, selected) = if down {
+            if lines.end >= all.len() {
+                return;
+            }
+            (lines.start..lines.end + 1, lines.start + 1..lines.end + 1)
+        } else {
+            if lines.start == 0 {
+                return;
+            }
+            (lines.start - 1..lines.end, lines.start - 1..lines.end - 1)
+        };
+        let mut moved: Vec<String> = all[range.clone()]
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        if down {
+            moved.rotate_right(1);
+        } else {
+            moved.rotate_left(1);
+        }
+        self.replace_lines(range, &moved, selected);
+    }
+
+    pub fn duplicate_lines(&mut self) {
+        let text = self.content.text();
+        let lines = self.selected_lines(&text);
+        let selected: Vec<String> = text
+            .split('\n')
+            .skip(lines.start)
+            .take(lines.len())
+            .map(str::to_owned)
+            .collect();
+        let duplicated = [selected.clone(), selected].concat();
+        let copy = lines.end..lines.end + lines.len();
+        self.replace_lines(lines, &duplicated, copy);
+    }
+
+    pub fn join_lines(&mut self) {
+        let text = self.content.text();
+        let mut lines = self.selected_lines(&text);
+        let all: Vec<&str> = text.split('\n').collect();
+        if lines.len() == 1 {
+            if lines.end >= all.len() {
+                return;
+            }
+            lines.end += 1;
+        }
+        let joined = all[lines.clone()]
+            .iter()
+            .enumerate()
+            .map(|(index, line)| {
+                if index == 0 {
+                    line.trim_end()
+                } else {
+                    line.trim()
+                }
+            })
+            .filter(|line| !line.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        let indent =
+            &all[lines.start][..all[lines.start].len() - all[lines.start].trim_start().len()];
+        let joined = if joined.starts_with(indent) {
+            joined
+        } else {
+            format!("{indent}{joined}")
+        };
+        let start = lines.start;
+        self.replace_lines(lines, &[joined], start..start + 1);
+    }
+
+    pub fn convert(&mut self, conversion: Conversion) {
+        if self.is_readonly {
+            return;
+        }
+        let text = self.content.text();
+        if conversion.is_linewise() {
+            let lines = self.selected_lines(&text);
+            let selected = text
+                .split('\n')
+                .skip(lines.start)
+                .take(lines.len())
+                .collect::<Vec<_>>()
+                .join("\n");
+            if let Some(converted) = conversion.apply(&selected) {
+                let converted: Vec<String> = converted.split('\n').map(str::to_owned).collect();
+                self.replace_lines(lines.clone(), &converted, lines);
+            }
+            return;
+        }
+        let range = self.primary_range(&text);
+        if range.is_empty() {
+            return;
+        }
+        let Some(converted) = conversion.apply(&text[range.clone()]) else {
+            return;
+        };
+        let mut replaced = text;
+        replaced.replace_range(range.clone(), &converted);
+        self.replace_text(&replaced);
+        let converted = range.start..range.start + converted.len();
+        crate::select_range(&mut self.content, &replaced, &converted);
+    }
+
+    pub fn delete_lines(&mut self) {
+        let text = self.content.text();
+        let lines = self.selected_lines(&text);
+        let start = lines.start;
+        self.replace_lines(lines, &[], start..start);
+    }
+
+    pub fn add_caret(&mut self) {
+        let primary = self.primary_range(&self.content.text());
+        if !self.carets.contains(&primary) {
+            self.carets.push(primary);
+        }
+    }
+
+    pub fn select_next_occurrence(&mut self) {
+        let Some(selection) = self.content.selection() else {
+            self.content.edit(Action::SelectWord);
+            return;
+        };
+        let text = self.content.text();
+        let primary = self.primary_range(&text);
+        let Some(next) = carets::next_occurrence(&text, &selection, primary.end) else {
+            return;
+        };
+        if next != primary && !self.carets.contains(&next) {
+            self.carets.push(primary);
+            crate::select_range(&mut self.content, &text, &next);
+        }
+    }
+
+    pub fn select_columns(&mut self, anchor: (usize, usize), head: (usize, usize)) {
+        let text = self.content.text();
+        let mut ranges = carets::columns(&text, anchor, head);
+        if let Some(primary) = ranges.pop() {
+            crate::select_range(&mut self.content, &text, &primary);
+        }
+        self.carets = ranges;
+    }
+
+    fn edit_carets(&mut self, action: &Action, edit: &Edit) {
+        let text = self.content.text();
+        let mut ranges = self.carets.clone();
+        ranges.push(self.primary_range(&text));
+        let (edited, mut offsets) = carets::edit(&text, &ranges, edit);
+        fold::shift(&mut self.folds, &text, &edited);
+        self.history.record(&self.content, action);
+        self.revision += 1;
+        self.content = text_editor::Content::with(&edited);
+        if let Some(primary) = offsets.pop() {
+            let (line, column) = crate::offset_to_position(&edited, primary);
+            crate::move_cursor_to(&mut self.content, line, column);
+        }
+        self.carets = offsets.into_iter().map(|offset| offset..offset).collect();
+        self.is_dirty = true;
+    }
+
+    fn move_carets(&mut self, motion: Motion, extend: bool) {
+        let text = self.content.text();
+        let moved: Option<Vec<_>> = self
+            .carets
+            .iter()
+            .map(|range| {
+                let end = carets::motion(&text, range.end, motion)?;
+                Some(if extend { range.start..end } else { end..end })
+            })
+            .collect();
+        self.carets = moved.unwrap_or_default();
+    }
+
+    pub fn replace_text(&mut self, text: &str) {
+        if self.is_readonly {
+            return;
+        }
+        self.set_text(text);
+        self.is_dirty = true;
+    }
+
+    pub fn apply_transforms(&mut self, tab_width: usize) {
+        self.save_report = None;
+        if self.skip_transforms || self.is_readonly || self.hex || self.page.is_some() {
+            return;
+        }
+        let text = self.content.text();
+        let (transformed, report) =
+            transform::apply(&text, self.transforms, self.indent, tab_width);
+        if transformed != text {
+            self.set_text(&transformed);
+            self.is_dirty = true;
+        }
+        self.save_report = (!report.is_empty()).then_some(report);
+    }
+
+    pub fn toggle_hex(&mut self) -> Result<(), usize> {
+        if self.hex {
+            let bytes = hex::parse(&self.content.text())?;
+            let (decoded, _) = encoding::decode(&bytes, Some(self.encoding));
+            self.set_text(&LineEnding::normalize(&decoded));
+            self.language = self
+                .path
+                .as_deref()
+                .map(Language::from_path)
+                .unwrap_or_default();
+        } else {
+            let (bytes, _) = self.encoded();
+            self.set_text(&hex::format(&bytes));
+            self.language = Language::PlainText;
+        }
+        self.hex = !self.hex;
+        Ok(())
+    }
+
+    pub fn toggle_table(&mut self) -> bool {
+        if self.table.is_some() {
+            self.sync_table();
+            self.table = None;
+            return true;
+        }
+        let Some(delimiter) = self.path.as_deref().and_then(table::delimiter) else {
+            return false;
+        };
+        if self.hex || self.page.is_some() {
+            return false;
+        }
+        self.table = Some(Table::parse(&self.content.text(), delimiter));
+        true
+    }
+
+    pub fn sync_table(&mut self) {
+        if let Some(table) = &mut self.table
+            && table.is_modified
+        {
+            table.is_modified = false;
+            let text = table.to_text();
+            self.replace_text(&text);
+        }
+    }
+
+    fn reparse_table(&mut self) {
+        if let Some(table) = &self.table {
+            self.table = Some(Table::parse(&self.content.text(), table.delimiter));
+        }
+    }
+
+    pub fn append(&mut self, text: &str) {
+        self.unfold();
+        self.content.edit(Action::Move(Motion::DocumentEnd));
+        let pending = self.snapshot.begin(&self.content, self.revision);
+        self.content
+            .edit(Action::Edit(Edit::Paste(Arc::new(text.to_owned()))));
+        self.revision += 1;
+        if let Some(pending) = pending {
+            self.snapshot.finish(&self.content, pending, self.revision);
+        }
+    }
+
+    pub fn refresh_hex(&mut self) {
+        if let Ok(bytes) = hex::parse(&self.content.text()) {
+            let (line, column) = self.content.cursor_position();
+            self.set_text(&hex::format(&bytes));
+            crate::move_cursor_to(&mut self.content, line, column);
+        }
+    }
+
+    pub fn reload(&mut self, text: &str) {
+        self.set_text(text);
+        self.reparse_table();
+        self.is_dirty = false;
+        self.has_conflict = false;
+    }
+
+    fn set_text(&mut self, text: &str) {
+        self.unfold();
+        self.carets.clear();
+        self.snippet = None;
+        self.revision += 1;
+        let snapshot = Snapshot::of(&self.content);
+        let (line, _) = snapshot.cursor;
+        fold::shift(&mut self.folds, &snapshot.text, text);
+        self.history.break_group();
+        self.history.push(snapshot);
+        self.content = text_editor::Content::with(text);
+        crate::move_cursor_to(&mut self.content, line, 0);
+    }
+
+    pub fn undo(&mut self) {
+        if self.is_readonly {
+            return;
+        }
+        self.sync_table();
+        if let Some(snapshot) = self.history.undo(Snapshot::of(&self.content)) {
+            self.restore(snapshot);
+        }
+    }
+
+    pub fn redo(&mut self) {
+        if self.is_readonly {
+            return;
+        }
+        self.sync_table();
+        if let Some(snapshot) = self.history.redo(Snapshot::of(&self.content)) {
+            self.restore(snapshot);
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot) {
+        self.unfold();
+        self.carets.clear();
+        self.snippet = None;
+        let (line, column) = snapshot.cursor;
+        let offset = crate::position_to_offset(&snapshot.text, line, column);
+        let (line, column) = crate::offset_to_position(&snapshot.text, offset);
+        self.revision += 1;
+        if !self.folds.is_empty() {
+            fold::shift(&mut self.folds, &self.content.text(), &snapshot.text);
+        }
+        self.content = text_editor::Content::with(&snapshot.text);
+        crate::move_cursor_to(&mut self.content, line, column);
+        self.reparse_table();
+        self.is_dirty = true;
+    }
+
+    pub fn is_blank(&self) -> bool {
+        self.path.is_none()
+            && !self.is_dirty
+            && !self.scratch
+            && self.content.lines().all(|line| line.trim().is_empty())
+    }
+
+    pub fn needs_save(&self) -> bool {
+        (self.is_dirty || self.is_deleted) && !self.scratch
+    }
+}
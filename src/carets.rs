@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use iced::widget::text_editor::{Edit, Motion};
+
+pub fn edit(text: &str, ranges: &[Range<usize>], edit: &Edit) -> (String, Vec<usize>) {
+    let mut order: Vec<usize> = (0..ranges.len()).collect();
+    order.sort_by_key(|&index| ranges[index].start.min(ranges[index].end));
+    let mut result = String::with_capacity(text.len());
+    let mut carets = vec![0; ranges.len()];
+    let mut copied = 0;
+    for index in order {
+        let range = &ranges[index];
+        let (start, end) = (range.start.min(range.end), range.start.max(range.end));
+        let (start, end) = match edit {
+            Edit::Backspace if start == end => (previous(text, start), end),
+            Edit::Delete if start == end => (start, next(text, end)),
+            _ => (start, end),
+        };
+        let start = start.max(copied);
+        let end = end.max(start);
+        result.push_str(&text[copied..start]);
+        match edit {
+            Edit::Insert(c) => result.push(*c),
+            Edit::Paste(pasted) => result.push_str(pasted),
+            Edit::Enter => result.push('\n'),
+            Edit::Backspace | Edit::Delete => {}
+        }
+        carets[index] = result.len();
+        copied = end;
+    }
+    result.push_str(&text[copied..]);
+    (result, carets)
+}
+
+pub fn motion(text: &str, offset: usize, motion: Motion) -> Option<usize> {
+    let line_start = text[..offset].rfind('\n').map_or(0, |index| index + 1);
+    let line_end = text[offset..]
+        .find('\n')
+        .map_or(text.len(), |index| offset + index);
+    let column = text[line_start..offset].chars().count();
+    let offset = match motion {
+        Motion::Left => previous(text, offset),
+        Motion::Right => next(text, offset),
+        Motion::Home => line_start,
+        Motion::End => line_end,
+        Motion::Up if line_start > 0 => {
+            let start = text[..line_start - 1]
+                .rfind('\n')
+                .map_or(0, |index| index + 1);
+            at_column(text, start, column)
+        }
+        Motion::Down if line_end < text.len() => at_column(text, line_end + 1, column),
+        Motion::Up | Motion::Down => offset,
+        _ => return None,
+    };
+    Some(offset)
+}
+
+pub fn columns(
+    text: &str,
+    (anchor_line, anchor_column): (usize, usize),
+    (head_line, head_column): (usize, usize),
+) -> Vec<Range<usize>> {
+    let (first, last) = (anchor_line.min(head_line), anchor_line.max(head_line));
+    let mut line_start = 0;
+    let mut ranges = Vec::new();
+    for (index, line) in text.split('\n').enumerate() {
+        if (first..=last).contains(&index) {
+            let start = at_column(text, line_start, anchor_column);
+            let end = at_column(text, line_start, head_column);
+            ranges.push(start.min(end)..start.max(end));
+        }
+        line_start += line.len() + 1;
+    }
+    if head_line < anchor_line {
+        ranges.reverse();
+    }
+    ranges
+}
+
+pub fn next_occurrence(text: &str, needle: &str, after: usize) -> Option<Range<usize>> {
+    if needle.is_empty() {
+        return None;
+    }
+    let start = text[after..]
+        .find(needle)
+        .map(|index| after + index)
+        .or_else(|| text[..after].find(needle))?;
+    Some(start..start + needle.len())
+}
+
+fn at_column(text: &str, line_start: usize, column: usize) -> usize {
+    let line_end = text[line_start..]
+        .find('\n')
+        .map_or(text.len(), |index| line_start + index);
+    text[line_start..line_end]
+        .char_indices()
+        .nth(column)
+        .map_or(line_end, |(index, _)| line_start + index)
+}
+
+fn previous(text: &str, offset: usize) -> usize {
+    text[..offset]
+        .char_indices()
+        .next_back()
+        .map_or(0, |(index, _)| index)
+}
+
+fn next(text: &str, offset: usize) -> usize {
+    text[offset..]
+        .chars()
+        .next()
+        .map_or(offset, |c| offset + c.len_utf8())
+}
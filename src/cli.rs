@@ -0,0 +1,187 @@
+use std::path::PathBuf;
+
+pub const USAGE: &str =
+    "usage: iced_editor [--readonly] [--offset N] [+LINE[:COLUMN]] [FILE[:LINE[:COLUMN]]]...";
+
+#[derive(Debug, Clone, Default)]
+pub struct Args {
+    pub files: Vec<FileArg>,
+    pub readonly: bool,
+    pub help: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileArg {
+    pub path: PathBuf,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub offset: Option<usize>,
+    pub readonly: bool,
+}
+
+pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Args, String> {
+    let mut parsed = Args::default();
+    let mut line = None;
+    let mut column = None;
+    let mut offset = None;
+    let mut args = args.into_iter();
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => parsed.help = true,
+            "--readonly" => parsed.readonly = true,
+            "--offset" => {
+                let value = args.next().ok_or("--offset needs a value")?;
+                offset = Some(
+                    value
+                        .parse()
+                        .map_err(|_| format!("invalid offset: {value}"))?,
+                );
+            }
+            _ if arg.starts_with('+') => {
+                let (line_part, column_part) = match arg[1..].split_once(':') {
+                    Some((line, column)) => (line, Some(column)),
+                    None => (&arg[1..], None),
+                };
+                let position = position(line_part, column_part)
+                    .ok_or_else(|| format!("invalid line: {arg}"))?;
+                (line, column) = (Some(position.0), position.1);
+            }
+            _ if arg.starts_with("--") => return Err(format!("unknown option: {arg}")),
+            _ => {
+                let (path, position) = split_position(&arg);
+                let (line, column) = match position {
+                    Some((line, column)) => (Some(line), column),
+                    None => (line.take(), column.take()),
+                };
+                parsed.files.push(FileArg {
+                    path,
+                    line,
+                    column,
+                    offset: offset.take(),
+                    readonly: false,
+                });
+            }
+        }
+    }
+    if line.is_some() || offset.is_some() {
+        return Err(String::from(
+            "+LINE and --offset must come before the file they apply to",
+        ));
+    }
+    let readonly = parsed.readonly;
+    for file in &mut parsed.files {
+        file.readonly = readonly;
+    }
+    Ok(parsed)
+}
+
+fn position(line: &str, column: Option<&str>) -> Option<(usize, Option<usize>)> {
+    let line = line.parse::<usize>().ok()?.max(1);
+    let column = match column {
+        Some(column) => Some(column.parse::<usize>().ok()?.saturating_sub(1)),
+        None => None,
+    };
+    Some((line, column))
+}
+
+/// Splits a trailing `:LINE` or `:LINE:COLUMN` off a file argument, unless
+/// the whole argument is a URL or names an existing file.
+fn split_position(arg: &str) -> (PathBuf, Option<(usize, Option<usize>)>) {
+    let whole = PathBuf::from(arg);
+    if arg.contains("://") || whole.exists() {
+        return (whole, None);
+    }
+    let mut parts = arg.rsplitn(3, ':');
+    let (last, middle, rest) = (parts.next(), parts.next(), parts.next());
+    let split = match (last, middle, rest) {
+        (Some(column), Some(line), Some(path)) if !path.is_empty() => {
+            position(line, Some(column)).map(|position| (path, position))
+        }
+        _ => None,
+    };
+    let split = split.or_else(|| {
+        let (path, line) = arg.rsplit_once(':')?;
+        (!path.is_empty())
+            .then(|| position(line, None))
+            .flatten()
+            .map(|position| (path, position))
+    });
+    match split {
+        Some((path, position)) => (PathBuf::from(path), Some(position)),
+        None => (whole, None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use super::{FileArg, parse};
+
+    fn args(args: &[&str]) -> Result<Vec<FileArg>, String> {
+        parse(args.iter().map(|arg| arg.to_string())).map(|parsed| parsed.files)
+    }
+
+    fn file(path: &str, line: Option<usize>, column: Option<usize>) -> FileArg {
+        FileArg {
+            path: PathBuf::from(path),
+            line,
+            column,
+            offset: None,
+            readonly: false,
+        }
+    }
+
+    #[test]
+    fn applies_line_jumps_to_the_next_file() {
+        assert_eq!(
+            args(&["+12", "a.rs", "b.rs", "+3:5", "c.rs"]).unwrap(),
+            [
+                file("a.rs", Some(12), None),
+                file("b.rs", None, None),
+                file("c.rs", Some(3), Some(4)),
+            ]
+        );
+        assert_eq!(
+            args(&["+0", "a.rs"]).unwrap(),
+            [file("a.rs", Some(1), None)]
+        );
+    }
+
+    #[test]
+    fn reads_positions_after_the_file_name() {
+        assert_eq!(
+            args(&["missing/a.rs:12:3", "missing/b.rs:7"]).unwrap(),
+            [
+                file("missing/a.rs", Some(12), Some(2)),
+                file("missing/b.rs", Some(7), None),
+            ]
+        );
+        assert_eq!(
+            args(&["missing/notes:draft"]).unwrap(),
+            [file("missing/notes:draft", None, None)]
+        );
+        assert_eq!(args(&[":5"]).unwrap(), [file(":5", None, None)]);
+        assert_eq!(
+            args(&["http://localhost:8080"]).unwrap(),
+            [file("http://localhost:8080", None, None)]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_and_dangling_arguments() {
+        assert!(args(&["+x", "a.rs"]).is_err());
+        assert!(args(&["+2:y", "a.rs"]).is_err());
+        assert!(args(&["a.rs", "+4"]).is_err());
+        assert!(args(&["a.rs", "--offset", "10"]).is_err());
+        assert!(args(&["--offset"]).is_err());
+        assert!(args(&["--bogus"]).is_err());
+    }
+
+    #[test]
+    fn marks_every_file_readonly() {
+        let parsed = parse(["a.rs", "--readonly", "b.rs"].map(String::from)).unwrap();
+        assert!(parsed.readonly);
+        assert!(parsed.files.iter().all(|file| file.readonly));
+    }
+}
@@ -0,0 +1,79 @@
+use std::collections::VecDeque;
+
+pub const LIMIT: usize = 20;
+const PREVIEW_CHARS: usize = 80;
+
+#[derive(Debug, Clone, Default)]
+pub struct History {
+    pub entries: VecDeque<String>,
+}
+
+impl History {
+    pub fn push(&mut self, text: String) {
+        if text.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| *entry != text);
+        self.entries.push_front(text);
+        self.entries.truncate(LIMIT);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Picker {
+    pub reindent: bool,
+    pub plain: bool,
+}
+
+pub fn preview(text: &str) -> String {
+    let mut lines = text.lines();
+    let first: String = lines
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .take(PREVIEW_CHARS)
+        .collect();
+    match lines.count() {
+        0 => first,
+        more => format!("{first}  (+{more} lines)"),
+    }
+}
+
+pub fn plain(text: &str) -> String {
+    text.replace("\r\n", "\n")
+        .chars()
+        .filter_map(|c| match c {
+            '\r' => Some('\n'),
+            '\u{a0}' | '\u{2007}' | '\u{202f}' => Some(' '),
+            '\u{2018}' | '\u{2019}' => Some('\''),
+            '\u{201c}' | '\u{201d}' => Some('"'),
+            '\u{200b}' | '\u{feff}' => None,
+            '\n' | '\t' => Some(c),
+            c if c.is_control() => None,
+            c => Some(c),
+        })
+        .collect()
+}
+
+pub fn reindent(text: &str, line: &str) -> String {
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let mut lines = text.split('\n');
+    let first = lines.next().unwrap_or_default();
+    let rest: Vec<&str> = lines.collect();
+    let common = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let mut reindented = first.trim_start().to_owned();
+    for line in rest {
+        reindented.push('\n');
+        if !line.trim().is_empty() {
+            reindented.push_str(&indent);
+            reindented.push_str(line.get(common..).unwrap_or(line.trim_start()));
+        }
+    }
+    reindented
+}
@@ -0,0 +1,206 @@
+use std::ops::Range;
+use std::path::Path;
+
+use iced::Color;
+
+use crate::language::Language;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Format {
+    Hex { alpha: Option<u8> },
+    Function { name: String, alpha: Option<String> },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Literal {
+    pub range: Range<usize>,
+    pub rgb: [u8; 3],
+    format: Format,
+}
+
+impl Literal {
+    pub fn color(&self) -> Color {
+        let [red, green, blue] = self.rgb;
+        Color::from_rgb8(red, green, blue)
+    }
+
+    pub fn rewrite(&self, [red, green, blue]: [u8; 3]) -> String {
+        match &self.format {
+            Format::Hex { alpha: None } => format!("#{red:02x}{green:02x}{blue:02x}"),
+            Format::Hex { alpha: Some(alpha) } => {
+                format!("#{red:02x}{green:02x}{blue:02x}{alpha:02x}")
+            }
+            Format::Function { name, alpha: None } => format!("{name}({red}, {green}, {blue})"),
+            Format::Function {
+                name,
+                alpha: Some(alpha),
+            } => format!("{name}({red}, {green}, {blue}, {alpha})"),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Picker {
+    pub buffer: usize,
+    pub line: usize,
+    pub column: usize,
+    pub original: String,
+    pub literal: Literal,
+    pub rgb: [u8; 3],
+    pub hex: String,
+}
+
+impl Picker {
+    pub fn new(buffer: usize, line: usize, text: &str, literal: Literal) -> Self {
+        Self {
+            buffer,
+            line,
+            column: text[..literal.range.start].chars().count(),
+            original: text[literal.range.clone()].to_string(),
+            rgb: literal.rgb,
+            hex: hex(literal.rgb),
+            literal,
+        }
+    }
+
+    pub fn set_channel(&mut self, channel: usize, value: u8) {
+        if let Some(slot) = self.rgb.get_mut(channel) {
+            *slot = value;
+            self.hex = hex(self.rgb);
+        }
+    }
+
+    pub fn set_hex(&mut self, value: String) {
+        if let Some(rgb) = parse_hex(value.trim().trim_start_matches('#')) {
+            self.rgb = rgb;
+        }
+        self.hex = value;
+    }
+
+    pub fn color(&self) -> Color {
+        let [red, green, blue] = self.rgb;
+        Color::from_rgb8(red, green, blue)
+    }
+}
+
+pub fn applies_to(language: Language, path: Option<&Path>) -> bool {
+    match language {
+        Language::Css
+        | Language::Html
+        | Language::Rust
+        | Language::JavaScript
+        | Language::Json
+        | Language::Yaml
+        | Language::Xml => true,
+        _ => path
+            .and_then(Path::extension)
+            .and_then(|extension| extension.to_str())
+            .is_some_and(|extension| matches!(extension, "toml" | "ini" | "conf" | "cfg")),
+    }
+}
+
+pub fn find(line: &str, language: Language) -> Vec<Literal> {
+    let short = matches!(language, Language::Css | Language::Html);
+    let bytes = line.as_bytes();
+    let mut literals = Vec::new();
+    let mut start = 0;
+    while start < bytes.len() {
+        let rest = &line[start..];
+        let is_boundary = start == 0 || !is_word(bytes[start - 1]);
+        let literal = if !is_boundary {
+            None
+        } else if rest.starts_with('#') {
+            hex_literal(rest, short)
+        } else if rest.starts_with("rgb") {
+            function_literal(rest)
+        } else {
+            None
+        };
+        match literal {
+            Some(mut literal) => {
+                literal.range = start + literal.range.start..start + literal.range.end;
+                start = literal.range.end;
+                literals.push(literal);
+            }
+            None => start += rest.chars().next().map_or(1, char::len_utf8),
+        }
+    }
+    literals
+}
+
+fn is_word(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'&'
+}
+
+fn hex(rgb: [u8; 3]) -> String {
+    let [red, green, blue] = rgb;
+    format!("#{red:02x}{green:02x}{blue:02x}")
+}
+
+fn parse_hex(digits: &str) -> Option<[u8; 3]> {
+    let channel = |range: Range<usize>| u8::from_str_radix(digits.get(range)?, 16).ok();
+    match digits.len() {
+        3 | 4 => {
+            let short = |index: usize| channel(index..index + 1).map(|value| value * 17);
+            Some([short(0)?, short(1)?, short(2)?])
+        }
+        6 | 8 => Some([channel(0..2)?, channel(2..4)?, channel(4..6)?]),
+        _ => None,
+    }
+}
+
+fn hex_literal(rest: &str, short: bool) -> Option<Literal> {
+    let digits = rest[1..]
+        .bytes()
+        .take_while(|byte| byte.is_ascii_hexdigit())
+        .count();
+    let after = rest.as_bytes().get(1 + digits).copied();
+    if after.is_some_and(is_word)
+        || !(matches!(digits, 6 | 8) || (short && matches!(digits, 3 | 4)))
+    {
+        return None;
+    }
+    let text = &rest[1..1 + digits];
+    let alpha = match digits {
+        4 => Some(u8::from_str_radix(&text[3..4], 16).ok()? * 17),
+        8 => Some(u8::from_str_radix(&text[6..8], 16).ok()?),
+        _ => None,
+    };
+    Some(Literal {
+        range: 0..1 + digits,
+        rgb: parse_hex(text)?,
+        format: Format::Hex { alpha },
+    })
+}
+
+fn function_literal(rest: &str) -> Option<Literal> {
+    let name = if rest.starts_with("rgba(") {
+        "rgba"
+    } else if rest.starts_with("rgb(") {
+        "rgb"
+    } else {
+        return None;
+    };
+    let end = rest.find(')')?;
+    let arguments: Vec<&str> = rest[name.len() + 1..end]
+        .split([',', ' ', '/'])
+        .filter(|argument| !argument.is_empty())
+        .collect();
+    let (channels, alpha) = match arguments.as_slice() {
+        [red, green, blue] => ([*red, *green, *blue], None),
+        [red, green, blue, alpha] => ([*red, *green, *blue], Some(alpha.to_string())),
+        _ => return None,
+    };
+    let mut rgb = [0; 3];
+    for (slot, channel) in rgb.iter_mut().zip(channels) {
+        *slot = channel.parse().ok()?;
+    }
+    Some(Literal {
+        range: 0..end + 1,
+        rgb,
+        format: Format::Function {
+            name: name.to_string(),
+            alpha,
+        },
+    })
+}
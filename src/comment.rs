@@ -0,0 +1,158 @@
+use crate::language::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Syntax {
+    pub line: Option<&'static str>,
+    pub block: Option<(&'static str, &'static str)>,
+}
+
+impl Language {
+    pub fn comment(self) -> Syntax {
+        let (line, block) = match self {
+            Language::Rust
+            | Language::JavaScript
+            | Language::C
+            | Language::Cpp
+            | Language::Go
+            | Language::Java => (Some("//"), Some(("/*", "*/"))),
+            Language::Python | Language::Shell | Language::Yaml | Language::Makefile => {
+                (Some("#"), None)
+            }
+            Language::Sql => (Some("--"), Some(("/*", "*/"))),
+            Language::Lua => (Some("--"), Some(("--[[", "]]"))),
+            Language::Css => (None, Some(("/*", "*/"))),
+            Language::Html | Language::Xml | Language::Markdown => (None, Some(("<!--", "-->"))),
+            Language::PlainText | Language::Json => (None, None),
+        };
+        Syntax { line, block }
+    }
+}
+
+pub fn toggle(lines: &[&str], syntax: Syntax) -> Vec<String> {
+    if let Some(block) = syntax.block
+        && (syntax.line.is_none() || is_block_commented(lines, block))
+    {
+        return toggle_block(lines, block);
+    }
+    match syntax.line {
+        Some(prefix) => toggle_line(lines, prefix),
+        None => lines.iter().map(|line| line.to_string()).collect(),
+    }
+}
+
+fn toggle_line(lines: &[&str], prefix: &str) -> Vec<String> {
+    let code = || lines.iter().filter(|line| !line.trim().is_empty());
+    let is_commented = code().all(|line| line.trim_start().starts_with(prefix));
+    let column = code()
+        .map(|line| indentation(line))
+        .min()
+        .unwrap_or_default();
+    lines
+        .iter()
+        .map(|line| {
+            if line.trim().is_empty() {
+                return line.to_string();
+            }
+            if is_commented {
+                let indent = indentation(line);
+                let rest = &line[indent + prefix.len()..];
+                format!(
+                    "{}{}",
+                    &line[..indent],
+                    rest.strip_prefix(' ').unwrap_or(rest)
+                )
+            } else {
+                format!("{}{prefix} {}", &line[..column], &line[column..])
+            }
+        })
+        .collect()
+}
+
+fn is_block_commented(lines: &[&str], (open, close): (&str, &str)) -> bool {
+    let code: Vec<&str> = lines
+        .iter()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty())
+        .collect();
+    match code.as_slice() {
+        [] => false,
+        [only] => {
+            only.len() >= open.len() + close.len()
+                && only.starts_with(open)
+                && only.ends_with(close)
+        }
+        [first, .., last] => first.starts_with(open) && last.ends_with(close),
+    }
+}
+
+fn toggle_block(lines: &[&str], (open, close): (&str, &str)) -> Vec<String> {
+    let is_commented = is_block_commented(lines, (open, close));
+    let mut lines: Vec<String> = lines.iter().map(|line| line.to_string()).collect();
+    let Some(first) = lines.iter().position(|line| !line.trim().is_empty()) else {
+        return lines;
+    };
+    let last = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .unwrap_or(first);
+    let indent = indentation(&lines[first]);
+    if is_commented {
+        let rest = &lines[first][indent + open.len()..];
+        lines[first] = format!(
+            "{}{}",
+            &lines[first][..indent],
+            rest.strip_prefix(' ').unwrap_or(rest)
+        );
+        let end = lines[last].trim_end().len();
+        let kept = lines[last][..end]
+            .strip_suffix(close)
+            .unwrap_or(&lines[last][..end]);
+        lines[last] = kept.strip_suffix(' ').unwrap_or(kept).to_owned();
+    } else {
+        lines[first].insert_str(indent, &format!("{open} "));
+        let end = lines[last].trim_end().len();
+        lines[last].truncate(end);
+        lines[last].push_str(&format!(" {close}"));
+    }
+    lines
+}
+
+fn indentation(line: &str) -> usize {
+    line.len() - line.trim_start_matches([' ', '\t']).len()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::toggle;
+    use crate::language::Language;
+
+    #[test]
+    fn toggles_line_comments_at_the_shallowest_indent() {
+        let syntax = Language::Rust.comment();
+        let commented = toggle(&["    a", "", "      b"], syntax);
+        assert_eq!(commented, ["    // a", "", "    //   b"]);
+        let lines: Vec<&str> = commented.iter().map(String::as_str).collect();
+        assert_eq!(toggle(&lines, syntax), ["    a", "", "      b"]);
+        assert_eq!(toggle(&["x"], Language::Python.comment()), ["# x"]);
+    }
+
+    #[test]
+    fn uses_block_comments_when_there_is_no_line_syntax() {
+        let syntax = Language::Css.comment();
+        let commented = toggle(&["a {", "}"], syntax);
+        assert_eq!(commented, ["/* a {", "} */"]);
+        let lines: Vec<&str> = commented.iter().map(String::as_str).collect();
+        assert_eq!(toggle(&lines, syntax), ["a {", "}"]);
+    }
+
+    #[test]
+    fn removes_an_existing_block_comment() {
+        assert_eq!(toggle(&["/* x */"], Language::Rust.comment()), ["x"]);
+        assert_eq!(toggle(&["<!-- a -->"], Language::Html.comment()), ["a"]);
+    }
+
+    #[test]
+    fn leaves_languages_without_comments_alone() {
+        assert_eq!(toggle(&["{}"], Language::Json.comment()), ["{}"]);
+    }
+}
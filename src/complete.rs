@@ -0,0 +1,85 @@
+use std::collections::BTreeSet;
+
+pub const MAX_ITEMS: usize = 10;
+const MIN_PREFIX: usize = 2;
+const MIN_WORD: usize = 3;
+
+#[derive(Debug, Clone, Default)]
+pub struct Completion {
+    pub buffer: usize,
+    pub prefix: String,
+    pub items: Vec<String>,
+    pub selected: usize,
+    pub request: Option<u64>,
+}
+
+impl Completion {
+    pub fn new(buffer: usize, prefix: String, text: &str) -> Self {
+        let items = words(text, &prefix);
+        Self {
+            buffer,
+            prefix,
+            items,
+            ..Self::default()
+        }
+    }
+
+    pub fn selected(&self) -> Option<&str> {
+        self.items.get(self.selected).map(String::as_str)
+    }
+
+    pub fn select_next(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + 1) % self.items.len();
+        }
+    }
+
+    pub fn select_previous(&mut self) {
+        if !self.items.is_empty() {
+            self.selected = (self.selected + self.items.len() - 1) % self.items.len();
+        }
+    }
+
+    pub fn merge(&mut self, suggested: Vec<String>) {
+        let mut items: Vec<String> = suggested
+            .into_iter()
+            .filter(|item| item.starts_with(&self.prefix) && *item != self.prefix)
+            .collect();
+        for word in self.items.drain(..) {
+            if !items.contains(&word) {
+                items.push(word);
+            }
+        }
+        items.dedup();
+        items.truncate(MAX_ITEMS);
+        self.items = items;
+        self.selected = 0;
+    }
+}
+
+pub fn is_word(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+pub fn prefix(line: &str, column: usize) -> Option<String> {
+    let before: Vec<char> = line.chars().take(column).collect();
+    let start = before
+        .iter()
+        .rposition(|c| !is_word(*c))
+        .map_or(0, |index| index + 1);
+    let prefix: String = before[start..].iter().collect();
+    let starts_with_digit = prefix.starts_with(|c: char| c.is_ascii_digit());
+    (prefix.chars().count() >= MIN_PREFIX && !starts_with_digit).then_some(prefix)
+}
+
+pub fn words(text: &str, prefix: &str) -> Vec<String> {
+    text.split(|c: char| !is_word(c))
+        .filter(|word| {
+            word.chars().count() >= MIN_WORD && word.starts_with(prefix) && *word != prefix
+        })
+        .collect::<BTreeSet<_>>()
+        .into_iter()
+        .take(MAX_ITEMS)
+        .map(str::to_owned)
+        .collect()
+}
@@ -0,0 +1,65 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::path::Path;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Codec {
+    Gzip,
+    Zstd,
+    Xz,
+}
+
+impl Codec {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let extension = path.extension()?.to_str()?;
+        match extension.to_ascii_lowercase().as_str() {
+            "gz" => Some(Codec::Gzip),
+            "zst" => Some(Codec::Zstd),
+            "xz" => Some(Codec::Xz),
+            _ => None,
+        }
+    }
+
+    pub fn decompress(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        let mut decompressed = Vec::new();
+        match self {
+            Codec::Gzip => {
+                flate2::read::MultiGzDecoder::new(bytes).read_to_end(&mut decompressed)?;
+            }
+            Codec::Zstd => {
+                zstd::stream::read::Decoder::new(bytes)?.read_to_end(&mut decompressed)?;
+            }
+            Codec::Xz => {
+                xz2::read::XzDecoder::new_multi_decoder(bytes).read_to_end(&mut decompressed)?;
+            }
+        }
+        Ok(decompressed)
+    }
+
+    pub fn compress(self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Codec::Gzip => {
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Codec::Zstd => zstd::stream::encode_all(bytes, 0),
+            Codec::Xz => {
+                let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+        }
+    }
+}
+
+impl fmt::Display for Codec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Codec::Gzip => "gzip",
+            Codec::Zstd => "zstd",
+            Codec::Xz => "xz",
+        })
+    }
+}
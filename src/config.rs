@@ -0,0 +1,233 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use iced::highlighter;
+use serde::Deserialize;
+
+use crate::Error;
+use crate::appearance::CustomPalette;
+use crate::formatter::FormatterConfig;
+use crate::indent::Indent;
+use crate::lsp::ServerConfig;
+use crate::transform;
+use crate::versions;
+
+pub const DEFAULT_FONT_SIZE: f32 = 16.0;
+pub const MIN_FONT_SIZE: f32 = 8.0;
+pub const MAX_FONT_SIZE: f32 = 48.0;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub theme: Option<String>,
+    pub font: Option<String>,
+    pub font_size: f32,
+    pub history_depth: usize,
+    pub autosave_interval: u64,
+    pub restore_session: bool,
+    pub auto_reload: bool,
+    pub word_wrap: bool,
+    pub line_numbers: LineNumbers,
+    pub auto_indent: bool,
+    pub auto_close: bool,
+    pub smart_home: bool,
+    pub subword_motion: bool,
+    pub tab_width: usize,
+    pub insert_spaces: bool,
+    pub detect_indentation: bool,
+    pub backup: bool,
+    pub trim_trailing_whitespace: bool,
+    pub insert_final_newline: bool,
+    pub normalize_indentation: bool,
+    pub palettes: BTreeMap<String, CustomPalette>,
+    pub language_servers: BTreeMap<String, ServerConfig>,
+    pub formatters: BTreeMap<String, FormatterConfig>,
+    pub confirm_commands: bool,
+    pub dictionary: Option<PathBuf>,
+    pub vim: bool,
+    pub reindent_on_paste: bool,
+    pub highlight_current_line: bool,
+    pub rulers: Vec<usize>,
+    pub show_rulers: bool,
+    pub indent_guides: bool,
+    pub color_swatches: bool,
+    pub author: Option<String>,
+    pub persist_scratch: bool,
+    pub persist_undo: bool,
+    pub persist_view_state: bool,
+    pub local_history: bool,
+    pub local_history_days: u64,
+    pub local_history_megabytes: u64,
+    pub single_instance: bool,
+    pub shell: Option<String>,
+    pub run_commands: BTreeMap<String, String>,
+    pub announce_in_title: bool,
+    pub locale: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            theme: None,
+            font: None,
+            font_size: DEFAULT_FONT_SIZE,
+            history_depth: crate::history::DEFAULT_DEPTH,
+            autosave_interval: 30,
+            restore_session: false,
+            auto_reload: true,
+            word_wrap: true,
+            line_numbers: LineNumbers::default(),
+            auto_indent: true,
+            auto_close: true,
+            smart_home: true,
+            subword_motion: true,
+            tab_width: 4,
+            insert_spaces: true,
+            detect_indentation: true,
+            backup: false,
+            trim_trailing_whitespace: false,
+            insert_final_newline: false,
+            normalize_indentation: false,
+            palettes: BTreeMap::new(),
+            language_servers: BTreeMap::new(),
+            formatters: BTreeMap::new(),
+            confirm_commands: true,
+            dictionary: None,
+            vim: false,
+            reindent_on_paste: false,
+            highlight_current_line: true,
+            rulers: Vec::new(),
+            show_rulers: true,
+            indent_guides: true,
+            color_swatches: true,
+            author: None,
+            persist_scratch: true,
+            persist_undo: true,
+            persist_view_state: true,
+            local_history: true,
+            local_history_days: 30,
+            local_history_megabytes: 10,
+            single_instance: false,
+            shell: None,
+            run_commands: BTreeMap::new(),
+            announce_in_title: false,
+            locale: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LineNumbers {
+    Off,
+    #[default]
+    Absolute,
+    Relative,
+}
+
+impl Config {
+    pub fn indent(&self) -> Indent {
+        if self.insert_spaces {
+            Indent::Spaces(self.tab_width.max(1))
+        } else {
+            Indent::Tabs
+        }
+    }
+
+    pub fn local_history_limits(&self) -> versions::Limits {
+        versions::Limits {
+            max_age: std::time::Duration::from_secs(self.local_history_days * 86400),
+            max_bytes: self.local_history_megabytes * 1024 * 1024,
+        }
+    }
+
+    pub fn transforms(&self) -> transform::Options {
+        transform::Options {
+            trim_trailing_whitespace: self.trim_trailing_whitespace,
+            final_newline: self.insert_final_newline,
+            normalize_indentation: self.normalize_indentation,
+        }
+    }
+
+    pub fn highlighter_theme(&self) -> Option<highlighter::Theme> {
+        let wanted = normalize(self.theme.as_deref()?);
+        highlighter::Theme::ALL
+            .iter()
+            .copied()
+            .find(|theme| normalize(&theme.to_string()) == wanted)
+    }
+}
+
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| c.is_alphanumeric())
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+pub fn config_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("iced_editor").join("config.toml"))
+}
+
+pub async fn save_font_size(size: f32) -> Result<(), Error> {
+    save_setting("font_size", format!("{size:?}")).await
+}
+
+pub async fn save_font(name: String) -> Result<(), Error> {
+    save_setting("font", toml::Value::String(name).to_string()).await
+}
+
+pub async fn save_flag(key: &'static str, value: bool) -> Result<(), Error> {
+    save_setting(key, value.to_string()).await
+}
+
+async fn save_setting(key: &str, value: String) -> Result<(), Error> {
+    let path = config_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents = match tokio::fs::read_to_string(&path).await {
+        Ok(contents) => contents,
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => String::new(),
+        Err(error) => return Err(Error::IOFailed(error.kind())),
+    };
+    let setting = format!("{key} = {value}");
+    let mut lines: Vec<String> = contents.lines().map(str::to_owned).collect();
+    let top_level = lines
+        .iter()
+        .position(|line| line.trim_start().starts_with('['))
+        .unwrap_or(lines.len());
+    match lines[..top_level].iter().position(|line| {
+        line.trim_start()
+            .strip_prefix(key)
+            .is_some_and(|rest| rest.trim_start().starts_with('='))
+    }) {
+        Some(index) => lines[index] = setting,
+        None => lines.insert(0, setting),
+    }
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, lines.join("\n") + "\n")
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
+
+pub fn read() -> Config {
+    config_file()
+        .and_then(|path| std::fs::read_to_string(path).ok())
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn load() -> Result<Config, Error> {
+    let Some(path) = config_file() else {
+        return Ok(Config::default());
+    };
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => {
+            toml::from_str(&contents).map_err(|error| Error::InvalidConfig(error.to_string()))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Config::default()),
+        Err(error) => Err(Error::IOFailed(error.kind())),
+    }
+}
@@ -0,0 +1,287 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Conversion {
+    SortAscending,
+    SortDescending,
+    SortUnique,
+    Reverse,
+    Upper,
+    Lower,
+    Title,
+    UrlEncode,
+    UrlDecode,
+    Base64Encode,
+    Base64Decode,
+    HexEncode,
+    HexDecode,
+}
+
+impl Conversion {
+    pub const ALL: &'static [Conversion] = &[
+        Conversion::SortAscending,
+        Conversion::SortDescending,
+        Conversion::SortUnique,
+        Conversion::Reverse,
+        Conversion::Upper,
+        Conversion::Lower,
+        Conversion::Title,
+        Conversion::UrlEncode,
+        Conversion::UrlDecode,
+        Conversion::Base64Encode,
+        Conversion::Base64Decode,
+        Conversion::HexEncode,
+        Conversion::HexDecode,
+    ];
+
+    pub fn is_linewise(self) -> bool {
+        matches!(
+            self,
+            Conversion::SortAscending
+                | Conversion::SortDescending
+                | Conversion::SortUnique
+                | Conversion::Reverse
+        )
+    }
+
+    pub fn apply(self, text: &str) -> Option<String> {
+        let lines = || text.split('\n').collect::<Vec<_>>();
+        Some(match self {
+            Conversion::SortAscending => {
+                let mut lines = lines();
+                lines.sort_unstable();
+                lines.join("\n")
+            }
+            Conversion::SortDescending => {
+                let mut lines = lines();
+                lines.sort_unstable_by(|a, b| b.cmp(a));
+                lines.join("\n")
+            }
+            Conversion::SortUnique => {
+                let mut lines = lines();
+                lines.sort_unstable();
+                lines.dedup();
+                lines.join("\n")
+            }
+            Conversion::Reverse => {
+                let mut lines = lines();
+                lines.reverse();
+                lines.join("\n")
+            }
+            Conversion::Upper => text.to_uppercase(),
+            Conversion::Lower => text.to_lowercase(),
+            Conversion::Title => title_case(text),
+            Conversion::UrlEncode => url_encode(text),
+            Conversion::UrlDecode => url_decode(text)?,
+            Conversion::Base64Encode => base64_encode(text.as_bytes()),
+            Conversion::Base64Decode => String::from_utf8(base64_decode(text)?).ok()?,
+            Conversion::HexEncode => hex_encode(text.as_bytes()),
+            Conversion::HexDecode => String::from_utf8(hex_decode(text)?).ok()?,
+        })
+    }
+}
+
+impl fmt::Display for Conversion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Conversion::SortAscending => "Sort lines",
+            Conversion::SortDescending => "Sort lines descending",
+            Conversion::SortUnique => "Sort lines, unique",
+            Conversion::Reverse => "Reverse lines",
+            Conversion::Upper => "UPPER CASE",
+            Conversion::Lower => "lower case",
+            Conversion::Title => "Title Case",
+            Conversion::UrlEncode => "URL encode",
+            Conversion::UrlDecode => "URL decode",
+            Conversion::Base64Encode => "Base64 encode",
+            Conversion::Base64Decode => "Base64 decode",
+            Conversion::HexEncode => "Hex encode",
+            Conversion::HexDecode => "Hex decode",
+        })
+    }
+}
+
+fn title_case(text: &str) -> String {
+    let mut title = String::with_capacity(text.len());
+    let mut at_start = true;
+    for c in text.chars() {
+        if at_start {
+            title.extend(c.to_uppercase());
+        } else {
+            title.extend(c.to_lowercase());
+        }
+        at_start = !c.is_alphanumeric() && c != '\'';
+    }
+    title
+}
+
+fn url_encode(text: &str) -> String {
+    let mut encoded = String::with_capacity(text.len());
+    for byte in text.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char);
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn url_decode(text: &str) -> Option<String> {
+    let bytes = text.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut index = 0;
+    while index < bytes.len() {
+        if bytes[index] == b'%' {
+            let hex = text.get(index + 1..index + 3)?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            index += 3;
+        } else {
+            decoded.push(bytes[index]);
+            index += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+fn base64_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let group = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |group, (index, &byte)| {
+                group | (byte as u32) << (16 - index * 8)
+            });
+        for index in 0..4 {
+            if index <= chunk.len() {
+                encoded.push(BASE64[(group >> (18 - index * 6) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+fn base64_decode(text: &str) -> Option<Vec<u8>> {
+    let digits: Vec<u8> = text
+        .bytes()
+        .filter(|byte| !byte.is_ascii_whitespace())
+        .collect();
+    let digits = digits
+        .strip_suffix(b"==")
+        .or_else(|| digits.strip_suffix(b"="))
+        .unwrap_or(&digits);
+    let mut decoded = Vec::with_capacity(digits.len() * 3 / 4);
+    for chunk in digits.chunks(4) {
+        if chunk.len() == 1 {
+            return None;
+        }
+        let mut group = 0u32;
+        for (index, &digit) in chunk.iter().enumerate() {
+            let value = BASE64.iter().position(|&c| c == digit)? as u32;
+            group |= value << (18 - index * 6);
+        }
+        for index in 0..chunk.len() - 1 {
+            decoded.push((group >> (16 - index * 8)) as u8);
+        }
+    }
+    Some(decoded)
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+fn hex_decode(text: &str) -> Option<Vec<u8>> {
+    let digits: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+    if digits.len() % 2 != 0 {
+        return None;
+    }
+    (0..digits.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(digits.get(index..index + 2)?, 16).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Conversion;
+
+    #[test]
+    fn changes_case() {
+        assert_eq!(Conversion::Upper.apply("Straße ok").unwrap(), "STRASSE OK");
+        assert_eq!(Conversion::Lower.apply("MiXeD Case").unwrap(), "mixed case");
+        assert_eq!(
+            Conversion::Title.apply("hello wORLD, it's-fine").unwrap(),
+            "Hello World, It's-Fine"
+        );
+    }
+
+    #[test]
+    fn sorts_and_reverses_lines() {
+        let text = "pear\napple\npear\nfig";
+        assert_eq!(
+            Conversion::SortAscending.apply(text).unwrap(),
+            "apple\nfig\npear\npear"
+        );
+        assert_eq!(
+            Conversion::SortDescending.apply(text).unwrap(),
+            "pear\npear\nfig\napple"
+        );
+        assert_eq!(
+            Conversion::SortUnique.apply(text).unwrap(),
+            "apple\nfig\npear"
+        );
+        assert_eq!(
+            Conversion::Reverse.apply(text).unwrap(),
+            "fig\npear\napple\npear"
+        );
+    }
+
+    #[test]
+    fn url_round_trip() {
+        let encoded = Conversion::UrlEncode.apply("a b/c?d=é&e~").unwrap();
+        assert_eq!(encoded, "a%20b%2Fc%3Fd%3D%C3%A9%26e~");
+        assert_eq!(
+            Conversion::UrlDecode.apply(&encoded).unwrap(),
+            "a b/c?d=é&e~"
+        );
+    }
+
+    #[test]
+    fn base64_round_trip() {
+        for (plain, encoded) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v")] {
+            assert_eq!(Conversion::Base64Encode.apply(plain).unwrap(), encoded);
+            assert_eq!(Conversion::Base64Decode.apply(encoded).unwrap(), plain);
+        }
+        assert_eq!(
+            Conversion::Base64Decode.apply("aGVs\nbG8=").unwrap(),
+            "hello"
+        );
+    }
+
+    #[test]
+    fn hex_round_trip() {
+        assert_eq!(Conversion::HexEncode.apply("Hi!").unwrap(), "486921");
+        assert_eq!(Conversion::HexDecode.apply("48 69 21").unwrap(), "Hi!");
+        assert_eq!(Conversion::HexDecode.apply("4869").unwrap(), "Hi");
+    }
+
+    #[test]
+    fn rejects_invalid_input() {
+        assert_eq!(Conversion::UrlDecode.apply("100%"), None);
+        assert_eq!(Conversion::UrlDecode.apply("%zz"), None);
+        assert_eq!(Conversion::UrlDecode.apply("%FF"), None);
+        assert_eq!(Conversion::Base64Decode.apply("Zm9v!"), None);
+        assert_eq!(Conversion::Base64Decode.apply("Z"), None);
+        assert_eq!(Conversion::Base64Decode.apply("/w=="), None);
+        assert_eq!(Conversion::HexDecode.apply("486"), None);
+        assert_eq!(Conversion::HexDecode.apply("zz"), None);
+        assert_eq!(Conversion::HexDecode.apply("ff"), None);
+    }
+}
@@ -0,0 +1,113 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use age::secrecy::Secret;
+use zeroize::{Zeroize, Zeroizing};
+
+pub fn is_encrypted(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("age"))
+}
+
+pub fn encrypted_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".age");
+    path.with_file_name(name)
+}
+
+#[derive(Clone)]
+pub struct Passphrase(Arc<Zeroizing<String>>);
+
+impl Passphrase {
+    pub fn new(passphrase: String) -> Self {
+        Self(Arc::new(Zeroizing::new(passphrase)))
+    }
+
+    fn secret(&self) -> Secret<String> {
+        Secret::new(self.0.as_str().to_owned())
+    }
+}
+
+impl fmt::Debug for Passphrase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Passphrase(..)")
+    }
+}
+
+pub fn encrypt(bytes: &[u8], passphrase: &Passphrase) -> Result<Vec<u8>, String> {
+    let encryptor = age::Encryptor::with_user_passphrase(passphrase.secret());
+    let mut encrypted = Vec::new();
+    let mut writer = encryptor
+        .wrap_output(&mut encrypted)
+        .map_err(|error| error.to_string())?;
+    writer.write_all(bytes).map_err(|error| error.to_string())?;
+    writer.finish().map_err(|error| error.to_string())?;
+    Ok(encrypted)
+}
+
+pub fn decrypt(bytes: &[u8], passphrase: &Passphrase) -> Result<Zeroizing<Vec<u8>>, String> {
+    let decryptor = match age::Decryptor::new(bytes).map_err(|error| error.to_string())? {
+        age::Decryptor::Passphrase(decryptor) => decryptor,
+        _ => {
+            return Err(String::from(
+                "the file is encrypted to a key, not a passphrase",
+            ));
+        }
+    };
+    let mut reader = decryptor
+        .decrypt(&passphrase.secret(), None)
+        .map_err(|error| error.to_string())?;
+    let mut decrypted = Zeroizing::new(Vec::new());
+    reader
+        .read_to_end(&mut decrypted)
+        .map_err(|error| error.to_string())?;
+    Ok(decrypted)
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Target {
+    Open(PathBuf),
+    Encrypt(usize),
+}
+
+pub struct Prompt {
+    pub target: Target,
+    pub passphrase: String,
+    pub confirmation: String,
+    pub is_working: bool,
+    pub error: Option<String>,
+}
+
+impl Prompt {
+    pub fn new(target: Target) -> Self {
+        Self {
+            target,
+            passphrase: String::new(),
+            confirmation: String::new(),
+            is_working: false,
+            error: None,
+        }
+    }
+
+    pub fn needs_confirmation(&self) -> bool {
+        matches!(self.target, Target::Encrypt(_))
+    }
+}
+
+impl fmt::Debug for Prompt {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Prompt")
+            .field("target", &self.target)
+            .field("is_working", &self.is_working)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Drop for Prompt {
+    fn drop(&mut self) {
+        self.passphrase.zeroize();
+        self.confirmation.zeroize();
+    }
+}
@@ -0,0 +1,342 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Quad, Renderer as _};
+use iced::advanced::widget::{self, Tree, Widget};
+use iced::advanced::{Clipboard, Shell};
+use iced::{Color, Element, Event, Length, Rectangle, Renderer, Theme, Vector, event, mouse};
+
+use crate::guides::Guides;
+use crate::search::Highlight;
+use crate::whitespace::{self, Kind};
+use crate::{CHAR_WIDTH, EDITOR_PADDING, LINE_HEIGHT};
+
+const DOT: f32 = 2.0;
+const TRAILING: Color = Color::from_rgba(0.9, 0.2, 0.2, 0.35);
+const MIXED: Color = Color::from_rgba(0.9, 0.6, 0.1, 0.35);
+const MATCH: Color = Color::from_rgba(0.95, 0.75, 0.2, 0.25);
+const CURRENT_MATCH: Color = Color::from_rgba(0.95, 0.6, 0.1, 0.55);
+
+pub struct Decoration<'a, Message> {
+    base: Element<'a, Message>,
+    lines: Vec<whitespace::Line>,
+    wrap: bool,
+    whitespace: bool,
+    current_line: Option<usize>,
+    rulers: Vec<usize>,
+    guides: Option<Guides>,
+    matches: Vec<Highlight>,
+}
+
+impl<'a, Message> Decoration<'a, Message> {
+    pub fn new(
+        base: impl Into<Element<'a, Message>>,
+        text: &str,
+        top: usize,
+        rows: usize,
+        wrap: bool,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            lines: text
+                .split('\n')
+                .skip(top)
+                .take(rows)
+                .map(whitespace::Line::new)
+                .collect(),
+            wrap,
+            whitespace: false,
+            current_line: None,
+            rulers: Vec::new(),
+            guides: None,
+            matches: Vec::new(),
+        }
+    }
+
+    pub fn whitespace(mut self, whitespace: bool) -> Self {
+        self.whitespace = whitespace;
+        self
+    }
+
+    pub fn current_line(mut self, row: Option<usize>) -> Self {
+        self.current_line = row;
+        self
+    }
+
+    pub fn rulers(mut self, rulers: Vec<usize>) -> Self {
+        self.rulers = rulers;
+        self
+    }
+
+    pub fn guides(mut self, guides: Option<Guides>) -> Self {
+        self.guides = guides;
+        self
+    }
+
+    pub fn matches(mut self, matches: Vec<Highlight>) -> Self {
+        self.matches = matches;
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, Renderer> for Decoration<'a, Message> {
+    fn width(&self) -> Length {
+        self.base.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.base.as_widget().height()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        vec![Tree::new(&self.base)]
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.diff_children(std::slice::from_ref(&self.base));
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.base
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.base.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+        let bounds = layout.bounds();
+        let columns = if self.wrap {
+            (((bounds.width - EDITOR_PADDING * 2.0) / CHAR_WIDTH) as usize).max(1)
+        } else {
+            usize::MAX
+        };
+        let faint = Color {
+            a: 0.3,
+            ..style.text_color
+        };
+        let origin = bounds.position() + Vector::new(EDITOR_PADDING, EDITOR_PADDING);
+        renderer.with_layer(bounds, |renderer| {
+            let mut quad = |x: f32, y: f32, width: f32, height: f32, color: Color| {
+                renderer.fill_quad(
+                    Quad {
+                        bounds: Rectangle {
+                            x: origin.x + x,
+                            y: origin.y + y,
+                            width,
+                            height,
+                        },
+                        border_radius: 0.0.into(),
+                        border_width: 0.0,
+                        border_color: Color::TRANSPARENT,
+                    },
+                    color,
+                );
+            };
+            let guide = Color {
+                a: 0.12,
+                ..style.text_color
+            };
+            let active_guide = Color {
+                a: 0.4,
+                ..style.text_color
+            };
+            let mut row = 0;
+            for (index, line) in self.lines.iter().enumerate() {
+                if row as f32 * LINE_HEIGHT > bounds.height {
+                    break;
+                }
+                let rows = line.width / columns + 1;
+                if self.current_line == Some(index) {
+                    quad(
+                        -EDITOR_PADDING,
+                        row as f32 * LINE_HEIGHT,
+                        bounds.width,
+                        rows as f32 * LINE_HEIGHT,
+                        Color {
+                            a: 0.06,
+                            ..style.text_color
+                        },
+                    );
+                }
+                for highlight in self
+                    .matches
+                    .iter()
+                    .filter(|highlight| highlight.row == index)
+                {
+                    let color = if highlight.is_current {
+                        CURRENT_MATCH
+                    } else {
+                        MATCH
+                    };
+                    let mut column = highlight.columns.start;
+                    while column < highlight.columns.end {
+                        let end = highlight.columns.end.min((column / columns + 1) * columns);
+                        quad(
+                            (column % columns) as f32 * CHAR_WIDTH,
+                            (row + column / columns) as f32 * LINE_HEIGHT,
+                            (end - column) as f32 * CHAR_WIDTH,
+                            LINE_HEIGHT,
+                            color,
+                        );
+                        column = end;
+                    }
+                }
+                if let Some(guides) = &self.guides {
+                    let depth = guides.depths.get(index).copied().unwrap_or_default();
+                    for level in 0..depth {
+                        let is_active = guides.active.as_ref().is_some_and(|(active, rows)| {
+                            *active == level && rows.contains(&index)
+                        });
+                        quad(
+                            (level * guides.width) as f32 * CHAR_WIDTH,
+                            row as f32 * LINE_HEIGHT,
+                            1.0,
+                            rows as f32 * LINE_HEIGHT,
+                            if is_active { active_guide } else { guide },
+                        );
+                    }
+                }
+                if self.whitespace {
+                    let position = |column: usize| {
+                        (
+                            (column % columns) as f32 * CHAR_WIDTH,
+                            (row + column / columns) as f32 * LINE_HEIGHT,
+                        )
+                    };
+                    for mark in &line.marks {
+                        let (x, y) = position(mark.column);
+                        let width = mark.columns() as f32 * CHAR_WIDTH;
+                        if mark.trailing {
+                            quad(x, y, width, LINE_HEIGHT, TRAILING);
+                        } else if mark.mixed {
+                            quad(x, y, width, LINE_HEIGHT, MIXED);
+                        }
+                        let middle = y + (LINE_HEIGHT - DOT) / 2.0;
+                        match mark.kind {
+                            Kind::Space => {
+                                quad(x + (CHAR_WIDTH - DOT) / 2.0, middle, DOT, DOT, faint)
+                            }
+                            Kind::Tab => {
+                                quad(x + DOT, middle, width - DOT * 3.0, 1.0, faint);
+                                quad(
+                                    x + width - DOT * 2.0,
+                                    middle - DOT,
+                                    1.0,
+                                    DOT * 2.0 + 1.0,
+                                    faint,
+                                );
+                            }
+                        }
+                    }
+                    let (x, y) = position(line.width);
+                    quad(
+                        x + DOT,
+                        y + LINE_HEIGHT / 2.0,
+                        CHAR_WIDTH - DOT * 2.0,
+                        1.0,
+                        faint,
+                    );
+                    quad(
+                        x + CHAR_WIDTH - DOT - 1.0,
+                        y + LINE_HEIGHT / 2.0 - DOT,
+                        1.0,
+                        DOT + 1.0,
+                        faint,
+                    );
+                }
+                row += rows;
+            }
+            let ruler = Color {
+                a: 0.15,
+                ..style.text_color
+            };
+            for column in &self.rulers {
+                quad(
+                    *column as f32 * CHAR_WIDTH,
+                    -EDITOR_PADDING,
+                    1.0,
+                    bounds.height,
+                    ruler,
+                );
+            }
+        });
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.base
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        self.base.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.base.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+}
+
+impl<'a, Message: 'a> From<Decoration<'a, Message>> for Element<'a, Message> {
+    fn from(decoration: Decoration<'a, Message>) -> Self {
+        Element::new(decoration)
+    }
+}
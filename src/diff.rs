@@ -0,0 +1,331 @@
+use std::ops::Range;
+
+const MAX_EDITS: isize = 4000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Hunk {
+    pub old: Range<usize>,
+    pub new: Range<usize>,
+}
+
+pub fn diff<T: PartialEq>(old: &[T], new: &[T]) -> Vec<Hunk> {
+    let prefix = old.iter().zip(new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let a = &old[prefix..old.len() - suffix];
+    let b = &new[prefix..new.len() - suffix];
+    if a.is_empty() && b.is_empty() {
+        return Vec::new();
+    }
+    let Some(matches) = myers(a, b) else {
+        return vec![Hunk {
+            old: prefix..prefix + a.len(),
+            new: prefix..prefix + b.len(),
+        }];
+    };
+    let mut hunks = Vec::new();
+    let (mut i, mut j) = (0, 0);
+    for (x, y) in matches.into_iter().chain([(a.len(), b.len())]) {
+        if x > i || y > j {
+            hunks.push(Hunk {
+                old: prefix + i..prefix + x,
+                new: prefix + j..prefix + y,
+            });
+        }
+        (i, j) = (x + 1, y + 1);
+    }
+    hunks
+}
+
+fn myers<T: PartialEq>(a: &[T], b: &[T]) -> Option<Vec<(usize, usize)>> {
+    let (n, m) = (a.len() as isize, b.len() as isize);
+    let max = n + m;
+    let offset = max + 1;
+    let mut v = vec![0isize; 2 * offset as usize + 1];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    for d in 0..=max.min(MAX_EDITS) {
+        for k in (-d..=d).step_by(2) {
+            let index = (k + offset) as usize;
+            let mut x = if k == -d || (k != d && v[index - 1] < v[index + 1]) {
+                v[index + 1]
+            } else {
+                v[index - 1] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && a[x as usize] == b[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[index] = x;
+        }
+        trace.push(v[(offset - d) as usize..=(offset + d) as usize].to_vec());
+        if (-d..=d)
+            .step_by(2)
+            .any(|k| v[(k + offset) as usize] >= n && v[(k + offset) as usize] - k >= m)
+        {
+            return Some(backtrack(&trace, a, b));
+        }
+    }
+    None
+}
+
+fn backtrack<T: PartialEq>(trace: &[Vec<isize>], a: &[T], b: &[T]) -> Vec<(usize, usize)> {
+    let (mut x, mut y) = (a.len() as isize, b.len() as isize);
+    let mut matches = Vec::new();
+    for d in (1..trace.len() as isize).rev() {
+        let previous = &trace[d as usize - 1];
+        let at = |k: isize| previous[(k + d - 1) as usize];
+        let k = x - y;
+        let previous_k = if k == -d || (k != d && at(k - 1) < at(k + 1)) {
+            k + 1
+        } else {
+            k - 1
+        };
+        let previous_x = at(previous_k);
+        let previous_y = previous_x - previous_k;
+        let (start_x, start_y) = if previous_k == k + 1 {
+            (previous_x, previous_y + 1)
+        } else {
+            (previous_x + 1, previous_y)
+        };
+        while x > start_x && y > start_y {
+            x -= 1;
+            y -= 1;
+            matches.push((x as usize, y as usize));
+        }
+        (x, y) = (previous_x, previous_y);
+    }
+    while x > 0 && y > 0 {
+        x -= 1;
+        y -= 1;
+        matches.push((x as usize, y as usize));
+    }
+    matches.reverse();
+    matches
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Equal,
+    Removed,
+    Added,
+    Changed,
+}
+
+#[derive(Debug, Clone)]
+pub struct Line {
+    pub number: usize,
+    pub text: String,
+    pub changes: Vec<Range<usize>>,
+}
+
+impl Line {
+    fn new(lines: &[&str], number: usize) -> Self {
+        Self {
+            number,
+            text: lines[number].to_owned(),
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn spans(&self) -> Vec<(&str, bool)> {
+        let mut spans = Vec::new();
+        let mut at = 0;
+        for change in &self.changes {
+            if change.start > at {
+                spans.push((&self.text[at..change.start], false));
+            }
+            spans.push((&self.text[change.clone()], true));
+            at = change.end;
+        }
+        if at < self.text.len() || spans.is_empty() {
+            spans.push((&self.text[at..], self.changes.is_empty()));
+        }
+        spans
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Row {
+    pub left: Option<Line>,
+    pub right: Option<Line>,
+    pub kind: Kind,
+}
+
+#[derive(Debug, Clone)]
+pub struct View {
+    pub title: String,
+    pub rows: Vec<Row>,
+}
+
+impl View {
+    pub fn new(title: String, old: &str, new: &str) -> Self {
+        let old: Vec<&str> = old.split('\n').collect();
+        let new: Vec<&str> = new.split('\n').collect();
+        let mut rows = Vec::new();
+        let (mut i, mut j) = (0, 0);
+        let hunks = diff(&old, &new);
+        let end = Hunk {
+            old: old.len()..old.len(),
+            new: new.len()..new.len(),
+        };
+        for hunk in hunks.iter().chain([&end]) {
+            while i < hunk.old.start && j < hunk.new.start {
+                rows.push(Row {
+                    left: Some(Line::new(&old, i)),
+                    right: Some(Line::new(&new, j)),
+                    kind: Kind::Equal,
+                });
+                (i, j) = (i + 1, j + 1);
+            }
+            for offset in 0..hunk.old.len().max(hunk.new.len()) {
+                let mut left =
+                    (offset < hunk.old.len()).then(|| Line::new(&old, hunk.old.start + offset));
+                let mut right =
+                    (offset < hunk.new.len()).then(|| Line::new(&new, hunk.new.start + offset));
+                let kind = match (&mut left, &mut right) {
+                    (Some(left), Some(right)) => {
+                        (left.changes, right.changes) = intra_line(&left.text, &right.text);
+                        Kind::Changed
+                    }
+                    (Some(_), None) => Kind::Removed,
+                    _ => Kind::Added,
+                };
+                rows.push(Row { left, right, kind });
+            }
+            (i, j) = (hunk.old.end, hunk.new.end);
+        }
+        Self { title, rows }
+    }
+
+    pub fn changes(&self) -> usize {
+        self.rows
+            .iter()
+            .filter(|row| row.kind != Kind::Equal)
+            .count()
+    }
+}
+
+fn intra_line(old: &str, new: &str) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+    const MAX_CHARS: usize = 1000;
+
+    let old_chars: Vec<(usize, char)> = old.char_indices().collect();
+    let new_chars: Vec<(usize, char)> = new.char_indices().collect();
+    if old_chars.len() > MAX_CHARS || new_chars.len() > MAX_CHARS {
+        return (vec![0..old.len()], vec![0..new.len()]);
+    }
+    let a: Vec<char> = old_chars.iter().map(|(_, c)| *c).collect();
+    let b: Vec<char> = new_chars.iter().map(|(_, c)| *c).collect();
+    let to_bytes = |chars: &[(usize, char)], text: &str, range: Range<usize>| {
+        let byte = |index: usize| chars.get(index).map_or(text.len(), |(byte, _)| *byte);
+        byte(range.start)..byte(range.end)
+    };
+    diff(&a, &b)
+        .into_iter()
+        .map(|hunk| {
+            (
+                to_bytes(&old_chars, old, hunk.old),
+                to_bytes(&new_chars, new, hunk.new),
+            )
+        })
+        .filter(|(old, new)| !old.is_empty() || !new.is_empty())
+        .fold(
+            (Vec::new(), Vec::new()),
+            |(mut olds, mut news), (old, new)| {
+                if !old.is_empty() {
+                    olds.push(old);
+                }
+                if !new.is_empty() {
+                    news.push(new);
+                }
+                (olds, news)
+            },
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Hunk, Kind, View, diff};
+
+    #[test]
+    fn finds_no_hunks_in_equal_input() {
+        assert!(diff(&["a", "b"], &["a", "b"]).is_empty());
+    }
+
+    #[test]
+    fn reports_changes_insertions_and_removals() {
+        assert_eq!(
+            diff(&["a", "b", "c"], &["a", "x", "c"]),
+            [Hunk {
+                old: 1..2,
+                new: 1..2
+            }]
+        );
+        assert_eq!(
+            diff(&["a", "c"], &["a", "b", "c"]),
+            [Hunk {
+                old: 1..1,
+                new: 1..2
+            }]
+        );
+        assert_eq!(
+            diff(&["a", "b", "c"], &["a", "c"]),
+            [Hunk {
+                old: 1..2,
+                new: 1..1
+            }]
+        );
+    }
+
+    #[test]
+    fn keeps_common_lines_between_hunks() {
+        assert_eq!(
+            diff(&["a", "b", "c", "d", "e"], &["x", "b", "c", "y", "e"]),
+            [
+                Hunk {
+                    old: 0..1,
+                    new: 0..1
+                },
+                Hunk {
+                    old: 3..4,
+                    new: 3..4
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn marks_changed_characters_within_lines() {
+        let view = View::new(
+            String::from("test"),
+            "same\nhello world",
+            "same\nhello there",
+        );
+        assert_eq!(view.changes(), 1);
+        let row = &view.rows[1];
+        assert_eq!(row.kind, Kind::Changed);
+        let left = row.left.as_ref().unwrap();
+        let right = row.right.as_ref().unwrap();
+        assert!(left.changes.iter().all(|range| range.start >= 6));
+        assert!(right.changes.iter().all(|range| range.start >= 6));
+        let unchanged: String = right
+            .spans()
+            .into_iter()
+            .filter(|(_, changed)| !changed)
+            .map(|(text, _)| text)
+            .collect();
+        assert!(unchanged.starts_with("hello "));
+    }
+
+    #[test]
+    fn lists_removed_and_added_rows() {
+        let view = View::new(String::from("test"), "a\nb", "a");
+        assert_eq!(view.rows.len(), 2);
+        assert_eq!(view.rows[1].kind, Kind::Removed);
+        assert!(view.rows[1].right.is_none());
+    }
+}
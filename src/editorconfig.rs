@@ -0,0 +1,334 @@
+use std::path::Path;
+
+use encoding_rs::{UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1252};
+use regex::Regex;
+
+use crate::encoding::TextEncoding;
+use crate::indent::Indent;
+use crate::line_ending::LineEnding;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Properties {
+    pub indent_style: Option<IndentStyle>,
+    pub indent_size: Option<IndentSize>,
+    pub tab_width: Option<usize>,
+    pub end_of_line: Option<LineEnding>,
+    pub charset: Option<TextEncoding>,
+    pub trim_trailing_whitespace: Option<bool>,
+    pub insert_final_newline: Option<bool>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentStyle {
+    Tab,
+    Space,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndentSize {
+    Tab,
+    Columns(usize),
+}
+
+impl Properties {
+    pub fn indent(&self, default_width: usize) -> Option<Indent> {
+        let tab_width = self.tab_width.unwrap_or(default_width);
+        let width = match self.indent_size {
+            Some(IndentSize::Columns(width)) => width,
+            Some(IndentSize::Tab) | None => tab_width,
+        };
+        match (self.indent_style, self.indent_size) {
+            (Some(IndentStyle::Tab), _) => Some(Indent::Tabs),
+            (Some(IndentStyle::Space), _) => Some(Indent::Spaces(width.max(1))),
+            (None, Some(IndentSize::Columns(width))) => Some(Indent::Spaces(width.max(1))),
+            (None, _) => None,
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) {
+        let flag = match value {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        };
+        match key {
+            "indent_style" => {
+                self.indent_style = match value {
+                    "tab" => Some(IndentStyle::Tab),
+                    "space" => Some(IndentStyle::Space),
+                    _ => None,
+                }
+            }
+            "indent_size" => {
+                self.indent_size = match value {
+                    "tab" => Some(IndentSize::Tab),
+                    _ => value.parse().ok().map(IndentSize::Columns),
+                }
+            }
+            "tab_width" => self.tab_width = value.parse().ok(),
+            "end_of_line" => {
+                self.end_of_line = match value {
+                    "lf" => Some(LineEnding::Lf),
+                    "crlf" => Some(LineEnding::CrLf),
+                    "cr" => Some(LineEnding::Cr),
+                    _ => None,
+                }
+            }
+            "charset" => {
+                self.charset = match value {
+                    "utf-8" | "utf-8-bom" => Some(TextEncoding(UTF_8)),
+                    "utf-16le" => Some(TextEncoding(UTF_16LE)),
+                    "utf-16be" => Some(TextEncoding(UTF_16BE)),
+                    "latin1" => Some(TextEncoding(WINDOWS_1252)),
+                    _ => None,
+                }
+            }
+            "trim_trailing_whitespace" => self.trim_trailing_whitespace = flag,
+            "insert_final_newline" => self.insert_final_newline = flag,
+            _ => {}
+        }
+    }
+}
+
+pub fn resolve(path: &Path) -> Properties {
+    let mut files = Vec::new();
+    for dir in path.ancestors().skip(1) {
+        let Ok(contents) = std::fs::read_to_string(dir.join(".editorconfig")) else {
+            continue;
+        };
+        let file = parse(&contents);
+        let is_root = file.is_root;
+        files.push((dir, file));
+        if is_root {
+            break;
+        }
+    }
+    let mut properties = Properties::default();
+    for (dir, file) in files.iter().rev() {
+        let Ok(relative) = path.strip_prefix(dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        for section in &file.sections {
+            if section.pattern.is_match(&relative) {
+                for (key, value) in &section.properties {
+                    properties.set(key, value);
+                }
+            }
+        }
+    }
+    properties
+}
+
+struct File {
+    is_root: bool,
+    sections: Vec<Section>,
+}
+
+struct Section {
+    pattern: Regex,
+    properties: Vec<(String, String)>,
+}
+
+fn parse(contents: &str) -> File {
+    let mut file = File {
+        is_root: false,
+        sections: Vec::new(),
+    };
+    let mut current: Option<Section> = None;
+    let mut skipping = false;
+    for line in contents.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with(['#', ';']) {
+            continue;
+        }
+        if let Some(glob) = line
+            .strip_prefix('[')
+            .and_then(|line| line.strip_suffix(']'))
+        {
+            file.sections.extend(current.take());
+            current = to_regex(glob).map(|pattern| Section {
+                pattern,
+                properties: Vec::new(),
+            });
+            skipping = current.is_none();
+            continue;
+        }
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let key = key.trim().to_lowercase();
+        let value = value.trim().to_lowercase();
+        match current.as_mut() {
+            Some(section) => section.properties.push((key, value)),
+            None if !skipping && key == "root" => file.is_root = value == "true",
+            None => {}
+        }
+    }
+    file.sections.extend(current);
+    file
+}
+
+fn to_regex(glob: &str) -> Option<Regex> {
+    let glob = match glob.strip_prefix('/') {
+        Some(anchored) => anchored.to_owned(),
+        None if glob.contains('/') => glob.to_owned(),
+        None => format!("**/{glob}"),
+    };
+    let mut pattern = String::from("^");
+    let mut chars = glob.chars().peekable();
+    let mut braces = 0;
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(?:.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            '[' => {
+                let class: String = chars.by_ref().take_while(|&c| c != ']').collect();
+                let (negated, class) = match class.strip_prefix('!') {
+                    Some(class) => (true, class),
+                    None => (false, class.as_str()),
+                };
+                pattern.push('[');
+                if negated {
+                    pattern.push('^');
+                }
+                pattern.push_str(&class.replace('\\', "\\\\").replace('[', "\\["));
+                pattern.push(']');
+            }
+            '{' => {
+                let rest: String = chars.clone().take_while(|&c| c != '}').collect();
+                if let Some(range) = numeric_range(&rest) {
+                    chars.nth(rest.chars().count());
+                    pattern.push_str(&range);
+                } else {
+                    braces += 1;
+                    pattern.push_str("(?:");
+                }
+            }
+            ',' if braces > 0 => pattern.push('|'),
+            '}' if braces > 0 => {
+                braces -= 1;
+                pattern.push(')');
+            }
+            '\\' => {
+                if let Some(escaped) = chars.next() {
+                    pattern.push_str(&regex::escape(&escaped.to_string()));
+                }
+            }
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    Regex::new(&pattern).ok()
+}
+
+fn numeric_range(range: &str) -> Option<String> {
+    let (start, end) = range.split_once("..")?;
+    let start: i64 = start.parse().ok()?;
+    let end: i64 = end.parse().ok()?;
+    let (start, end) = (start.min(end), start.max(end));
+    if end - start > 1000 {
+        return None;
+    }
+    let numbers: Vec<String> = (start..=end).map(|number| number.to_string()).collect();
+    Some(format!("(?:{})", numbers.join("|")))
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::UTF_8;
+
+    use super::{IndentSize, IndentStyle, Properties, parse, resolve, to_regex};
+    use crate::encoding::TextEncoding;
+    use crate::indent::Indent;
+    use crate::line_ending::LineEnding;
+
+    fn matches(glob: &str, path: &str) -> bool {
+        to_regex(glob).unwrap().is_match(path)
+    }
+
+    #[test]
+    fn translates_globs() {
+        assert!(matches("*.rs", "main.rs"));
+        assert!(matches("*.rs", "src/main.rs"));
+        assert!(!matches("*.rs", "main.rsx"));
+        assert!(matches("/src/*.rs", "src/a.rs"));
+        assert!(!matches("/src/*.rs", "lib/src/a.rs"));
+        assert!(!matches("/src/*.rs", "src/sub/a.rs"));
+        assert!(matches("*.{js,ts}", "web/app.ts"));
+        assert!(matches("file{1..3}.txt", "file2.txt"));
+        assert!(!matches("file{1..3}.txt", "file4.txt"));
+        assert!(matches("[!a]b", "cb"));
+        assert!(!matches("[!a]b", "ab"));
+    }
+
+    #[test]
+    fn parses_sections_and_the_root_flag() {
+        let file = parse(
+            "# comment\nroot = true\n[*]\nindent_style = Space\nindent_size = 2\n\n[*.md]\ntrim_trailing_whitespace = false\n",
+        );
+        assert!(file.is_root);
+        assert_eq!(file.sections.len(), 2);
+        assert_eq!(
+            file.sections[0].properties,
+            [
+                (String::from("indent_style"), String::from("space")),
+                (String::from("indent_size"), String::from("2")),
+            ]
+        );
+    }
+
+    #[test]
+    fn derives_the_indent() {
+        let properties = |style, size, tab_width| Properties {
+            indent_style: style,
+            indent_size: size,
+            tab_width,
+            ..Properties::default()
+        };
+        assert_eq!(
+            properties(Some(IndentStyle::Tab), None, None).indent(4),
+            Some(Indent::Tabs)
+        );
+        assert_eq!(
+            properties(None, Some(IndentSize::Columns(2)), None).indent(4),
+            Some(Indent::Spaces(2))
+        );
+        assert_eq!(
+            properties(Some(IndentStyle::Space), Some(IndentSize::Tab), Some(8)).indent(4),
+            Some(Indent::Spaces(8))
+        );
+        assert_eq!(properties(None, None, None).indent(4), None);
+    }
+
+    #[test]
+    fn lets_nearer_files_override_outer_ones() {
+        let root =
+            std::env::temp_dir().join(format!("iced_editor-editorconfig-{}", std::process::id()));
+        let nested = root.join("sub");
+        std::fs::create_dir_all(&nested).unwrap();
+        std::fs::write(
+            root.join(".editorconfig"),
+            "root = true\n[*.rs]\nindent_style = tab\ncharset = latin1\n",
+        )
+        .unwrap();
+        std::fs::write(
+            nested.join(".editorconfig"),
+            "[*.rs]\nend_of_line = crlf\ncharset = utf-8\n",
+        )
+        .unwrap();
+        let properties = resolve(&nested.join("main.rs"));
+        std::fs::remove_dir_all(&root).unwrap();
+        assert_eq!(properties.indent_style, Some(IndentStyle::Tab));
+        assert_eq!(properties.end_of_line, Some(LineEnding::CrLf));
+        assert_eq!(properties.charset, Some(TextEncoding(UTF_8)));
+    }
+}
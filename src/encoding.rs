@@ -0,0 +1,178 @@
+use std::fmt;
+
+use encoding_rs::{
+    BIG5, CoderResult, EUC_JP, EUC_KR, Encoder, Encoding, GB18030, GBK, ISO_8859_2, ISO_8859_15,
+    KOI8_R, SHIFT_JIS, UTF_8, UTF_16BE, UTF_16LE, WINDOWS_1251, WINDOWS_1252,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TextEncoding(pub &'static Encoding);
+
+impl Default for TextEncoding {
+    fn default() -> Self {
+        TextEncoding(UTF_8)
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.0.name())
+    }
+}
+
+pub static ALL: &[TextEncoding] = &[
+    TextEncoding(UTF_8),
+    TextEncoding(UTF_16LE),
+    TextEncoding(UTF_16BE),
+    TextEncoding(WINDOWS_1252),
+    TextEncoding(ISO_8859_2),
+    TextEncoding(ISO_8859_15),
+    TextEncoding(WINDOWS_1251),
+    TextEncoding(KOI8_R),
+    TextEncoding(SHIFT_JIS),
+    TextEncoding(EUC_JP),
+    TextEncoding(EUC_KR),
+    TextEncoding(GBK),
+    TextEncoding(GB18030),
+    TextEncoding(BIG5),
+];
+
+pub fn detect(bytes: &[u8]) -> TextEncoding {
+    if let Some((encoding, _)) = Encoding::for_bom(bytes) {
+        return TextEncoding(encoding);
+    }
+    if std::str::from_utf8(bytes).is_ok() {
+        return TextEncoding::default();
+    }
+    let mut detector = chardetng::EncodingDetector::new();
+    detector.feed(bytes, true);
+    TextEncoding(detector.guess(None, true))
+}
+
+pub fn decode(bytes: &[u8], forced: Option<TextEncoding>) -> (String, TextEncoding) {
+    let encoding = forced.unwrap_or_else(|| detect(bytes));
+    let (text, _) = encoding.0.decode_with_bom_removal(bytes);
+    (text.into_owned(), encoding)
+}
+
+const UTF_8_BOM: &[u8] = b"\xEF\xBB\xBF";
+
+pub fn has_bom(bytes: &[u8]) -> bool {
+    bytes.starts_with(UTF_8_BOM)
+}
+
+/// Also returns whether characters the encoding cannot represent were
+/// replaced.
+pub fn encode_chunks<S: AsRef<str>>(
+    chunks: impl IntoIterator<Item = S>,
+    encoding: TextEncoding,
+    bom: bool,
+) -> (Vec<u8>, bool) {
+    let mut bytes = Vec::new();
+    if encoding.0 == UTF_8 && bom {
+        bytes.extend_from_slice(UTF_8_BOM);
+    }
+    if encoding.0 == UTF_16LE || encoding.0 == UTF_16BE {
+        let little = encoding.0 == UTF_16LE;
+        bytes.extend(if little { [0xFF, 0xFE] } else { [0xFE, 0xFF] });
+        for chunk in chunks {
+            for unit in chunk.as_ref().encode_utf16() {
+                bytes.extend(if little {
+                    unit.to_le_bytes()
+                } else {
+                    unit.to_be_bytes()
+                });
+            }
+        }
+        return (bytes, false);
+    }
+    let mut encoder = encoding.0.new_encoder();
+    let mut replaced = false;
+    for chunk in chunks {
+        replaced |= encode_into(&mut encoder, chunk.as_ref(), &mut bytes, false);
+    }
+    replaced |= encode_into(&mut encoder, "", &mut bytes, true);
+    (bytes, replaced)
+}
+
+fn encode_into(encoder: &mut Encoder, mut text: &str, bytes: &mut Vec<u8>, last: bool) -> bool {
+    let mut replaced = false;
+    loop {
+        let needed = encoder
+            .max_buffer_length_from_utf8_if_no_unmappables(text.len())
+            .unwrap_or(text.len() * 4 + 16);
+        bytes.reserve(needed);
+        let (result, read, had_replacements) = encoder.encode_from_utf8_to_vec(text, bytes, last);
+        replaced |= had_replacements;
+        text = &text[read..];
+        if result == CoderResult::InputEmpty {
+            return replaced;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use encoding_rs::{SHIFT_JIS, UTF_8, UTF_16LE, WINDOWS_1251, WINDOWS_1252};
+
+    use super::{TextEncoding, decode, detect, encode_chunks, has_bom};
+
+    #[test]
+    fn detects_boms_and_utf8() {
+        assert_eq!(detect(b"\xEF\xBB\xBFhi"), TextEncoding(UTF_8));
+        assert_eq!(detect(b"\xFF\xFEh\0"), TextEncoding(UTF_16LE));
+        assert_eq!(detect("plain é".as_bytes()), TextEncoding(UTF_8));
+    }
+
+    #[test]
+    fn decodes_without_the_bom() {
+        assert_eq!(
+            decode(b"\xEF\xBB\xBFhi", None),
+            (String::from("hi"), TextEncoding(UTF_8))
+        );
+        assert_eq!(
+            decode(b"\xFF\xFEh\0", None),
+            (String::from("h"), TextEncoding(UTF_16LE))
+        );
+    }
+
+    #[test]
+    fn encodes_chunks_as_one_stream() {
+        assert_eq!(
+            encode_chunks(["caf", "é"], TextEncoding(WINDOWS_1252), false),
+            (b"caf\xE9".to_vec(), false)
+        );
+        assert_eq!(
+            encode_chunks(["hé", "y"], TextEncoding(UTF_16LE), false),
+            (vec![0xFF, 0xFE, b'h', 0, 0xE9, 0, b'y', 0], false)
+        );
+    }
+
+    #[test]
+    fn reports_unmappable_characters() {
+        let (bytes, replaced) = encode_chunks(["€ ", "日本"], TextEncoding(WINDOWS_1252), false);
+        assert!(replaced);
+        assert!(bytes.starts_with(b"\x80 "));
+    }
+
+    #[test]
+    fn keeps_a_utf8_bom_when_asked() {
+        let bytes = b"\xEF\xBB\xBFhi";
+        assert!(has_bom(bytes));
+        let (text, encoding) = decode(bytes, None);
+        assert_eq!(encode_chunks([text], encoding, true).0, bytes);
+        assert_eq!(encode_chunks(["hi"], TextEncoding(UTF_8), false).0, b"hi");
+    }
+
+    #[test]
+    fn round_trips_legacy_encodings() {
+        let encoding = TextEncoding(WINDOWS_1251);
+        let (bytes, _) = encode_chunks(["При", "вет"], encoding, false);
+        assert_eq!(decode(&bytes, Some(encoding)).0, "Привет");
+    }
+
+    #[test]
+    fn displays_the_encoding_name() {
+        assert_eq!(TextEncoding(SHIFT_JIS).to_string(), "Shift_JIS");
+    }
+}
@@ -0,0 +1,126 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::Error;
+use crate::language::Language;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Entry {
+    pub path: PathBuf,
+    pub name: String,
+    pub is_dir: bool,
+}
+
+impl Entry {
+    pub fn icon(&self, is_expanded: bool) -> char {
+        if self.is_dir {
+            return if is_expanded { '\u{f07c}' } else { '\u{f07b}' };
+        }
+        match Language::from_path(&self.path) {
+            Language::Rust => '\u{e7a8}',
+            Language::Python => '\u{e73c}',
+            Language::JavaScript => '\u{e74e}',
+            Language::C | Language::Cpp => '\u{e61e}',
+            Language::Go => '\u{e626}',
+            Language::Java => '\u{e738}',
+            Language::Html => '\u{e736}',
+            Language::Css => '\u{e749}',
+            Language::Json => '\u{e60b}',
+            Language::Markdown => '\u{e73e}',
+            Language::Shell => '\u{f489}',
+            _ => '\u{f15b}',
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Explorer {
+    pub root: PathBuf,
+    pub is_visible: bool,
+    children: HashMap<PathBuf, Vec<Entry>>,
+    expanded: HashSet<PathBuf>,
+}
+
+impl Explorer {
+    pub fn set_root(&mut self, root: PathBuf) {
+        self.children.clear();
+        self.expanded.clear();
+        self.expanded.insert(root.clone());
+        self.root = root;
+    }
+
+    pub fn is_loaded(&self, directory: &Path) -> bool {
+        self.children.contains_key(directory)
+    }
+
+    pub fn is_expanded(&self, directory: &Path) -> bool {
+        self.expanded.contains(directory)
+    }
+
+    pub fn insert(&mut self, directory: PathBuf, entries: Vec<Entry>) {
+        self.children.insert(directory, entries);
+    }
+
+    pub fn toggle(&mut self, directory: &Path) -> bool {
+        if !self.expanded.remove(directory) {
+            self.expanded.insert(directory.to_path_buf());
+        }
+        self.is_expanded(directory)
+    }
+
+    pub fn visible(&self) -> Vec<(usize, &Entry)> {
+        let mut visible = Vec::new();
+        self.collect(&self.root, 0, &mut visible);
+        visible
+    }
+
+    fn collect<'a>(
+        &'a self,
+        directory: &Path,
+        depth: usize,
+        visible: &mut Vec<(usize, &'a Entry)>,
+    ) {
+        if !self.is_expanded(directory) {
+            return;
+        }
+        for entry in self.children.get(directory).into_iter().flatten() {
+            visible.push((depth, entry));
+            if entry.is_dir {
+                self.collect(&entry.path, depth + 1, visible);
+            }
+        }
+    }
+}
+
+pub async fn read_dir(directory: PathBuf) -> (PathBuf, Result<Vec<Entry>, Error>) {
+    let entries = read_entries(&directory).await;
+    (directory, entries)
+}
+
+async fn read_entries(directory: &Path) -> Result<Vec<Entry>, Error> {
+    let mut read = tokio::fs::read_dir(directory)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    let mut entries = Vec::new();
+    while let Some(entry) = read
+        .next_entry()
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?
+    {
+        let is_dir = entry
+            .file_type()
+            .await
+            .is_ok_and(|file_type| file_type.is_dir());
+        entries.push(Entry {
+            name: entry.file_name().to_string_lossy().into_owned(),
+            path: entry.path(),
+            is_dir,
+        });
+    }
+    entries.sort_by(|a, b| {
+        b.is_dir
+            .cmp(&a.is_dir)
+            .then_with(|| a.name.to_lowercase().cmp(&b.name.to_lowercase()))
+    });
+    Ok(entries)
+}
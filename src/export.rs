@@ -0,0 +1,295 @@
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use iced::advanced::text::highlighter::Highlighter as _;
+use iced::{Color, highlighter};
+
+use crate::Error;
+
+const PAGE_WIDTH: f32 = 595.0;
+const PAGE_HEIGHT: f32 = 842.0;
+const MARGIN: f32 = 50.0;
+const FONT_SIZE: f32 = 9.0;
+const LEADING: f32 = 11.0;
+const CHAR_WIDTH: f32 = FONT_SIZE * 0.6;
+const NUMBER_COLUMNS: usize = 6;
+const TAB_WIDTH: usize = 4;
+
+type Span = (String, Option<Color>);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Html,
+    Pdf,
+}
+
+impl Format {
+    pub fn extension(self) -> &'static str {
+        match self {
+            Format::Html => "html",
+            Format::Pdf => "pdf",
+        }
+    }
+}
+
+fn highlight(text: &str, theme: highlighter::Theme, extension: &str) -> Vec<Vec<Span>> {
+    let mut highlighter = highlighter::Highlighter::new(&highlighter::Settings {
+        theme,
+        extension: extension.to_owned(),
+    });
+    text.split('\n')
+        .map(|line| {
+            let line = line.replace('\t', &" ".repeat(TAB_WIDTH));
+            let mut spans = Vec::new();
+            let mut at = 0;
+            for (range, highlight) in highlighter.highlight_line(&line) {
+                if range.start < at || range.end > line.len() {
+                    continue;
+                }
+                if range.start > at {
+                    spans.push((line[at..range.start].to_owned(), None));
+                }
+                spans.push((line[range.clone()].to_owned(), highlight.to_format().color));
+                at = range.end;
+            }
+            if at < line.len() {
+                spans.push((line[at..].to_owned(), None));
+            }
+            spans
+        })
+        .collect()
+}
+
+fn hex(color: Color) -> String {
+    let [r, g, b, _] = color.into_rgba8();
+    format!("#{r:02x}{g:02x}{b:02x}")
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn html(
+    text: &str,
+    title: &str,
+    theme: highlighter::Theme,
+    extension: &str,
+    background: Color,
+    foreground: Color,
+) -> String {
+    let lines = highlight(text, theme, extension);
+    let width = lines.len().to_string().len();
+    let mut body = String::new();
+    for (number, spans) in lines.iter().enumerate() {
+        let _ = write!(body, "<span class=\"n\">{:>width$}</span>  ", number + 1);
+        for (span, color) in spans {
+            match color {
+                Some(color) => {
+                    let _ = write!(
+                        body,
+                        "<span style=\"color:{}\">{}</span>",
+                        hex(*color),
+                        escape_html(span)
+                    );
+                }
+                None => body.push_str(&escape_html(span)),
+            }
+        }
+        body.push('\n');
+    }
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n\
+         <style>\nbody {{ background: {background}; color: {foreground}; margin: 0; }}\n\
+         pre {{ font-family: monospace; font-size: 14px; padding: 16px; margin: 0; }}\n\
+         .n {{ opacity: 0.5; user-select: none; }}\n</style>\n</head>\n\
+         <body>\n<pre>{body}</pre>\n</body>\n</html>\n",
+        title = escape_html(title),
+        background = hex(background),
+        foreground = hex(foreground),
+    )
+}
+
+fn escape_pdf(text: &str) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for c in text.chars() {
+        let byte = match c as u32 {
+            code @ (0x20..=0x7e | 0xa0..=0xff) => code as u8,
+            _ => b'?',
+        };
+        if matches!(byte, b'(' | b')' | b'\\') {
+            bytes.push(b'\\');
+        }
+        bytes.push(byte);
+    }
+    bytes
+}
+
+fn wrap(spans: Vec<Span>, columns: usize) -> Vec<Vec<Span>> {
+    let mut rows = vec![Vec::new()];
+    let mut used = 0;
+    for (text, color) in spans {
+        let mut rest: &str = &text;
+        while !rest.is_empty() {
+            if used == columns {
+                rows.push(Vec::new());
+                used = 0;
+            }
+            let take = rest
+                .char_indices()
+                .nth(columns - used)
+                .map_or(rest.len(), |(index, _)| index);
+            let (head, tail) = rest.split_at(take);
+            used += head.chars().count();
+            if let Some(row) = rows.last_mut() {
+                row.push((head.to_owned(), color));
+            }
+            rest = tail;
+        }
+    }
+    rows
+}
+
+pub fn pdf(text: &str, title: &str, theme: highlighter::Theme, extension: &str) -> Vec<u8> {
+    let columns = ((PAGE_WIDTH - 2.0 * MARGIN) / CHAR_WIDTH) as usize - NUMBER_COLUMNS;
+    let rows_per_page = ((PAGE_HEIGHT - 2.0 * MARGIN - 2.0 * LEADING) / LEADING) as usize;
+    let mut rows: Vec<(Option<usize>, Vec<Span>)> = Vec::new();
+    for (number, spans) in highlight(text, theme, extension).into_iter().enumerate() {
+        for (index, row) in wrap(spans, columns).into_iter().enumerate() {
+            rows.push(((index == 0).then_some(number + 1), row));
+        }
+    }
+    let pages: Vec<_> = rows.chunks(rows_per_page.max(1)).collect();
+    let page_count = pages.len().max(1);
+
+    let mut objects: Vec<Vec<u8>> = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        Vec::new(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    let mut kids = Vec::new();
+    for page in 0..page_count {
+        let mut stream = Vec::new();
+        let gray = Color::from_rgb(0.5, 0.5, 0.5);
+        text_line(
+            &mut stream,
+            MARGIN,
+            PAGE_HEIGHT - MARGIN,
+            &[(escape_pdf(title), gray)],
+        );
+        let footer = format!("Page {} of {page_count}", page + 1);
+        text_line(
+            &mut stream,
+            PAGE_WIDTH - MARGIN - footer.len() as f32 * CHAR_WIDTH,
+            MARGIN - LEADING,
+            &[(escape_pdf(&footer), gray)],
+        );
+        for (index, (number, spans)) in pages
+            .get(page)
+            .copied()
+            .unwrap_or_default()
+            .iter()
+            .enumerate()
+        {
+            let y = PAGE_HEIGHT - MARGIN - LEADING * (index as f32 + 2.0);
+            let number = number.map(|number| number.to_string()).unwrap_or_default();
+            let mut parts = vec![(
+                escape_pdf(&format!("{number:>width$} ", width = NUMBER_COLUMNS - 1)),
+                gray,
+            )];
+            parts.extend(
+                spans
+                    .iter()
+                    .map(|(text, color)| (escape_pdf(text), color.unwrap_or(Color::BLACK))),
+            );
+            text_line(&mut stream, MARGIN, y, &parts);
+        }
+        let mut content = format!("<< /Length {} >>\nstream\n", stream.len()).into_bytes();
+        content.extend_from_slice(&stream);
+        content.extend_from_slice(b"endstream");
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 {PAGE_WIDTH} {PAGE_HEIGHT}] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                objects.len() + 2
+            )
+            .into_bytes(),
+        );
+        kids.push(format!("{} 0 R", objects.len()));
+        objects.push(content);
+    }
+    objects[1] = format!(
+        "<< /Type /Pages /Kids [{}] /Count {page_count} >>",
+        kids.join(" ")
+    )
+    .into_bytes();
+
+    let mut output = b"%PDF-1.4\n".to_vec();
+    let mut offsets = Vec::new();
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(output.len());
+        output.extend_from_slice(format!("{} 0 obj\n", index + 1).as_bytes());
+        output.extend_from_slice(object);
+        output.extend_from_slice(b"\nendobj\n");
+    }
+    let xref = output.len();
+    let _ = write!(
+        Bytes(&mut output),
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    );
+    for offset in offsets {
+        let _ = write!(Bytes(&mut output), "{offset:010} 00000 n \n");
+    }
+    let _ = write!(
+        Bytes(&mut output),
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{xref}\n%%EOF\n",
+        objects.len() + 1
+    );
+    output
+}
+
+fn text_line(stream: &mut Vec<u8>, x: f32, y: f32, parts: &[(Vec<u8>, Color)]) {
+    let _ = write!(Bytes(stream), "BT /F1 {FONT_SIZE} Tf {x} {y} Td ");
+    for (part, color) in parts {
+        let _ = write!(Bytes(stream), "{} {} {} rg (", color.r, color.g, color.b);
+        stream.extend_from_slice(part);
+        stream.extend_from_slice(b") Tj ");
+    }
+    stream.extend_from_slice(b"ET\n");
+}
+
+struct Bytes<'a>(&'a mut Vec<u8>);
+
+impl Write for Bytes<'_> {
+    fn write_str(&mut self, text: &str) -> std::fmt::Result {
+        self.0.extend_from_slice(text.as_bytes());
+        Ok(())
+    }
+}
+
+pub async fn save(contents: Vec<u8>, name: String, format: Format) -> Result<PathBuf, Error> {
+    let path = rfd::AsyncFileDialog::new()
+        .set_title("Export as")
+        .set_file_name(format!("{name}.{}", format.extension()))
+        .save_file()
+        .await
+        .ok_or(Error::DialogClosed)?
+        .path()
+        .to_path_buf();
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    Ok(path)
+}
+
+pub async fn print(contents: Vec<u8>, name: String) -> Result<PathBuf, Error> {
+    let path = std::env::temp_dir().join(format!("{name}.print.pdf"));
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    open::that(&path).map_err(|error| Error::IOFailed(error.kind()))?;
+    Ok(path)
+}
@@ -0,0 +1,254 @@
+use std::collections::BTreeMap;
+
+use iced::widget::text_editor::{self, Action};
+
+use crate::language::Language;
+
+pub const PLACEHOLDER: &str = " …";
+
+pub fn regions(text: &str, language: Language) -> BTreeMap<usize, usize> {
+    match language {
+        Language::PlainText
+        | Language::Python
+        | Language::Yaml
+        | Language::Markdown
+        | Language::Makefile => indented(text),
+        _ => bracketed(text),
+    }
+}
+
+fn bracketed(text: &str) -> BTreeMap<usize, usize> {
+    let mut regions = BTreeMap::new();
+    let mut open = Vec::new();
+    for (index, line) in text.split('\n').enumerate() {
+        for c in line.chars() {
+            match c {
+                '{' | '[' | '(' => open.push(index),
+                '}' | ']' | ')' => {
+                    if let Some(start) = open.pop()
+                        && index >= start + 2
+                    {
+                        let end = regions.entry(start).or_insert(index - 1);
+                        *end = (*end).max(index - 1);
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    regions
+}
+
+fn indented(text: &str) -> BTreeMap<usize, usize> {
+    let indents: Vec<Option<usize>> = text
+        .split('\n')
+        .map(|line| {
+            (!line.trim().is_empty()).then(|| {
+                line.chars()
+                    .take_while(|c| c.is_whitespace())
+                    .map(|c| if c == '\t' { 4 } else { 1 })
+                    .sum()
+            })
+        })
+        .collect();
+    let mut regions = BTreeMap::new();
+    for (start, indent) in indents.iter().enumerate() {
+        let Some(indent) = indent else {
+            continue;
+        };
+        let end = indents[start + 1..]
+            .iter()
+            .enumerate()
+            .take_while(|(_, inner)| inner.is_none_or(|inner| inner > *indent))
+            .filter(|(_, inner)| inner.is_some())
+            .last()
+            .map(|(offset, _)| start + 1 + offset);
+        if let Some(end) = end {
+            regions.insert(start, end);
+        }
+    }
+    regions
+}
+
+pub fn innermost(regions: &BTreeMap<usize, usize>, line: usize) -> Option<(usize, usize)> {
+    if let Some(end) = regions.get(&line) {
+        return Some((line, *end));
+    }
+    regions
+        .range(..line)
+        .rev()
+        .find(|(_, end)| **end >= line)
+        .map(|(start, end)| (*start, *end))
+}
+
+pub fn shift(folds: &mut BTreeMap<usize, usize>, before: &str, after: &str) {
+    if folds.is_empty() || before == after {
+        return;
+    }
+    let old: Vec<&str> = before.split('\n').collect();
+    let new: Vec<&str> = after.split('\n').collect();
+    let prefix = old.iter().zip(&new).take_while(|(a, b)| a == b).count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let edited = prefix..old.len() - suffix;
+    let delta = new.len() as isize - old.len() as isize;
+    *folds = folds
+        .iter()
+        .filter_map(|(&start, &end)| {
+            if edited.end <= start {
+                Some((
+                    start.saturating_add_signed(delta),
+                    end.saturating_add_signed(delta),
+                ))
+            } else if edited.start > end || (edited == (start..start + 1) && delta == 0) {
+                Some((start, end))
+            } else {
+                None
+            }
+        })
+        .collect();
+}
+
+pub struct Folded {
+    pub content: text_editor::Content,
+    pub text: String,
+    pub lines: Vec<usize>,
+    pub top_line: usize,
+}
+
+impl Folded {
+    pub fn new(text: &str, folds: &BTreeMap<usize, usize>) -> Self {
+        let mut shown = String::new();
+        let mut lines = Vec::new();
+        let mut hidden_until = None;
+        for (index, line) in text.split('\n').enumerate() {
+            if hidden_until.is_some_and(|end| index <= end) {
+                continue;
+            }
+            if !lines.is_empty() {
+                shown.push('\n');
+            }
+            shown.push_str(line);
+            lines.push(index);
+            if let Some(end) = folds.get(&index) {
+                shown.push_str(PLACEHOLDER);
+                hidden_until = Some(*end);
+            }
+        }
+        Self {
+            content: text_editor::Content::with(&shown),
+            text: shown,
+            lines,
+            top_line: 0,
+        }
+    }
+
+    pub fn line(&self, row: usize) -> usize {
+        self.lines
+            .get(row)
+            .or(self.lines.last())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    pub fn row(&self, line: usize) -> usize {
+        self.lines
+            .partition_point(|shown| *shown <= line)
+            .saturating_sub(1)
+    }
+
+    pub fn perform(&mut self, action: Action, viewport_lines: usize) {
+        match action {
+            Action::Scroll { lines } => {
+                let last = self.lines.len().saturating_sub(1);
+                self.top_line = self
+                    .top_line
+                    .saturating_add_signed(lines as isize)
+                    .min(last);
+                self.content.edit(action);
+            }
+            action => {
+                let is_edit = action.is_edit();
+                self.content.edit(action);
+                if is_edit {
+                    self.text = self.content.text();
+                }
+                self.top_line = self.visible_top(viewport_lines);
+            }
+        }
+    }
+
+    pub fn visible_top(&self, viewport_lines: usize) -> usize {
+        let (row, _) = self.content.cursor_position();
+        self.top_line
+            .clamp(row.saturating_sub(viewport_lines.saturating_sub(1)), row)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeMap;
+
+    use super::{Folded, PLACEHOLDER, innermost, regions, shift};
+    use crate::language::Language;
+
+    fn lines(count: usize) -> Vec<String> {
+        (0..count).map(|index| format!("line {index}")).collect()
+    }
+
+    #[test]
+    fn finds_bracketed_and_indented_regions() {
+        let rust = "fn a() {\n    x\n    y\n}";
+        assert_eq!(regions(rust, Language::Rust), BTreeMap::from([(0, 2)]));
+        let python = "def f():\n    a\n\n    b\nc";
+        assert_eq!(regions(python, Language::Python), BTreeMap::from([(0, 3)]));
+    }
+
+    #[test]
+    fn picks_the_innermost_region() {
+        let regions = BTreeMap::from([(0, 10), (2, 5)]);
+        assert_eq!(innermost(&regions, 2), Some((2, 5)));
+        assert_eq!(innermost(&regions, 3), Some((2, 5)));
+        assert_eq!(innermost(&regions, 7), Some((0, 10)));
+        assert_eq!(innermost(&regions, 11), None);
+    }
+
+    #[test]
+    fn shifts_folds_below_an_edit() {
+        let before = lines(10);
+        let mut after = before.clone();
+        after.insert(1, String::from("new"));
+        let mut folds = BTreeMap::from([(5, 8)]);
+        shift(&mut folds, &before.join("\n"), &after.join("\n"));
+        assert_eq!(folds, BTreeMap::from([(6, 9)]));
+    }
+
+    #[test]
+    fn keeps_folds_outside_an_edit_and_drops_edited_ones() {
+        let before = lines(10);
+        let edit = |line: usize| {
+            let mut after = before.clone();
+            after[line].push('!');
+            let mut folds = BTreeMap::from([(5, 8)]);
+            shift(&mut folds, &before.join("\n"), &after.join("\n"));
+            folds
+        };
+        assert_eq!(edit(9), BTreeMap::from([(5, 8)]));
+        assert_eq!(edit(5), BTreeMap::from([(5, 8)]));
+        assert!(edit(6).is_empty());
+    }
+
+    #[test]
+    fn maps_rows_to_lines_in_folded_text() {
+        let folded = Folded::new("a\nb\nc\nd", &BTreeMap::from([(0, 2)]));
+        assert_eq!(folded.text, format!("a{PLACEHOLDER}\nd"));
+        assert_eq!(folded.lines, [0, 3]);
+        assert_eq!(folded.line(1), 3);
+        assert_eq!(folded.row(2), 0);
+        assert_eq!(folded.row(3), 1);
+    }
+}
@@ -0,0 +1,75 @@
+use std::io::SeekFrom;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::Error;
+use crate::highlight::Mark;
+
+pub const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const MAX_CHUNK: u64 = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Follow {
+    pub offset: u64,
+    pub was_readonly: bool,
+}
+
+#[derive(Debug, Clone)]
+pub enum Appended {
+    Lines(u64, String),
+    Truncated,
+}
+
+pub fn is_log(path: &Path) -> bool {
+    path.extension().is_some_and(|extension| extension == "log")
+}
+
+pub async fn length(path: PathBuf) -> Result<u64, Error> {
+    tokio::fs::metadata(path)
+        .await
+        .map(|metadata| metadata.len())
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
+
+pub async fn read(path: PathBuf, offset: u64) -> Result<Appended, Error> {
+    let io = |error: std::io::Error| Error::IOFailed(error.kind());
+    let mut file = tokio::fs::File::open(&path).await.map_err(io)?;
+    let length = file.metadata().await.map_err(io)?.len();
+    if length < offset {
+        return Ok(Appended::Truncated);
+    }
+    file.seek(SeekFrom::Start(offset)).await.map_err(io)?;
+    let mut bytes = Vec::new();
+    file.take((length - offset).min(MAX_CHUNK))
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(io)?;
+    let complete = bytes
+        .iter()
+        .rposition(|byte| *byte == b'\n')
+        .map_or(0, |end| end + 1);
+    bytes.truncate(complete);
+    let text = String::from_utf8_lossy(&bytes).replace("\r\n", "\n");
+    Ok(Appended::Lines(offset + complete as u64, text))
+}
+
+pub fn marks(text: &str, lines: Range<usize>) -> Vec<(usize, Range<usize>, Mark)> {
+    text.split('\n')
+        .enumerate()
+        .skip(lines.start)
+        .take(lines.len())
+        .filter_map(|(index, line)| {
+            let mark = if line.contains("ERROR") || line.contains("FATAL") {
+                Mark::Error
+            } else if line.contains("WARN") {
+                Mark::Warning
+            } else {
+                return None;
+            };
+            Some((index, 0..line.len(), mark))
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+use std::collections::HashSet;
+use std::sync::{Mutex, OnceLock, PoisonError};
+
+use iced::Font;
+use iced::futures::channel::oneshot;
+
+pub const FALLBACK: &str = "Monospace";
+static INSTALLED: OnceLock<Vec<&'static str>> = OnceLock::new();
+static NAMES: OnceLock<Mutex<HashSet<&'static str>>> = OnceLock::new();
+
+pub const SAMPLE: &str = "fn main() {\n    let quick = \"brown fox\"; // 0O 1lI {}[]()\n}";
+
+#[derive(Debug, Clone)]
+pub struct Picker {
+    pub fonts: Vec<&'static str>,
+    pub filter: String,
+    pub selected: Option<&'static str>,
+    pub previous: Font,
+}
+
+impl Picker {
+    pub fn new(previous: Font) -> Self {
+        Self {
+            fonts: Vec::new(),
+            filter: String::new(),
+            selected: None,
+            previous,
+        }
+    }
+
+    pub fn matching(&self) -> impl Iterator<Item = &'static str> + '_ {
+        let filter = self.filter.to_lowercase();
+        self.fonts
+            .iter()
+            .copied()
+            .filter(move |name| name.to_lowercase().contains(&filter))
+    }
+}
+
+pub fn intern(name: &str) -> &'static str {
+    let mut names = NAMES
+        .get_or_init(Mutex::default)
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner);
+    if let Some(name) = names.get(name) {
+        return name;
+    }
+    let name: &'static str = Box::leak(name.to_owned().into_boxed_str());
+    names.insert(name);
+    name
+}
+
+pub fn font(name: &'static str) -> Font {
+    if name == FALLBACK {
+        Font::MONOSPACE
+    } else {
+        Font::with_name(name)
+    }
+}
+
+pub async fn monospace() -> Vec<&'static str> {
+    if let Some(fonts) = INSTALLED.get() {
+        return fonts.clone();
+    }
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let mut database = fontdb::Database::new();
+        database.load_system_fonts();
+        let mut names: Vec<String> = database
+            .faces()
+            .filter(|face| face.monospaced)
+            .filter_map(|face| face.families.first().map(|(name, _)| name.clone()))
+            .collect();
+        names.sort_by_key(|name| name.to_lowercase());
+        names.dedup();
+        let _ = sender.send(names);
+    });
+    let names = receiver.await.unwrap_or_default();
+    INSTALLED
+        .get_or_init(|| {
+            std::iter::once(FALLBACK)
+                .chain(names.iter().map(|name| intern(name)))
+                .collect()
+        })
+        .clone()
+}
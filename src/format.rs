@@ -0,0 +1,189 @@
+use std::fmt;
+use std::path::Path;
+
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use serde::{Deserialize, Serialize};
+
+use crate::language::Language;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Syntax {
+    Json,
+    Yaml,
+    Toml,
+    Xml,
+}
+
+impl Syntax {
+    pub fn detect(language: Language, path: Option<&Path>) -> Option<Self> {
+        match language {
+            Language::Json => Some(Syntax::Json),
+            Language::Yaml => Some(Syntax::Yaml),
+            Language::Xml => Some(Syntax::Xml),
+            _ => path
+                .and_then(Path::extension)
+                .is_some_and(|extension| extension == "toml")
+                .then_some(Syntax::Toml),
+        }
+    }
+
+    pub fn keeps_comments(self, text: &str) -> bool {
+        match self {
+            Syntax::Json | Syntax::Xml => true,
+            Syntax::Yaml | Syntax::Toml => !text.contains('#'),
+        }
+    }
+}
+
+impl fmt::Display for Syntax {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Syntax::Json => "JSON",
+            Syntax::Yaml => "YAML",
+            Syntax::Toml => "TOML",
+            Syntax::Xml => "XML",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Invalid {
+    pub line: usize,
+    pub column: usize,
+    pub message: String,
+}
+
+impl Invalid {
+    fn at(line: usize, column: usize, message: impl fmt::Display) -> Self {
+        Self {
+            line: line.saturating_sub(1),
+            column: column.saturating_sub(1),
+            message: message.to_string(),
+        }
+    }
+
+    fn at_offset(text: &str, offset: usize, message: impl fmt::Display) -> Self {
+        let (line, column) = crate::offset_to_position(text, offset);
+        Self {
+            line,
+            column,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for Invalid {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let message = self.message.lines().next().unwrap_or_default();
+        write!(f, "{}:{} {message}", self.line + 1, self.column + 1)
+    }
+}
+
+pub fn validate(text: &str, syntax: Syntax) -> Result<(), Invalid> {
+    match syntax {
+        Syntax::Json => json(text, None).map(drop),
+        Syntax::Yaml => yaml(text).map(drop),
+        Syntax::Toml => toml_table(text).map(drop),
+        Syntax::Xml => xml(text, None).map(drop),
+    }
+}
+
+pub fn format(text: &str, syntax: Syntax, indent: &str) -> Result<String, Invalid> {
+    let formatted = match syntax {
+        Syntax::Json => json(text, Some(indent))?,
+        Syntax::Yaml => yaml(text)?,
+        Syntax::Toml => {
+            toml::to_string_pretty(&toml_table(text)?).map_err(|error| Invalid::at(1, 1, error))?
+        }
+        Syntax::Xml => xml(text, Some(indent))?,
+    };
+    Ok(format!("{}\n", formatted.trim_end()))
+}
+
+pub fn minify(text: &str, syntax: Syntax) -> Option<Result<String, Invalid>> {
+    let minified = match syntax {
+        Syntax::Json => json(text, None),
+        Syntax::Xml => xml(text, None),
+        Syntax::Yaml | Syntax::Toml => return None,
+    };
+    Some(minified.map(|minified| {
+        if text.ends_with('\n') {
+            minified + "\n"
+        } else {
+            minified
+        }
+    }))
+}
+
+fn json(text: &str, indent: Option<&str>) -> Result<String, Invalid> {
+    let value: serde_json::Value = serde_json::from_str(text)
+        .map_err(|error| Invalid::at(error.line(), error.column(), &error))?;
+    let written = match indent {
+        Some(indent) => {
+            let mut bytes = Vec::new();
+            let formatter = serde_json::ser::PrettyFormatter::with_indent(indent.as_bytes());
+            let mut serializer = serde_json::Serializer::with_formatter(&mut bytes, formatter);
+            value
+                .serialize(&mut serializer)
+                .map(|_| String::from_utf8_lossy(&bytes).into_owned())
+        }
+        None => serde_json::to_string(&value),
+    };
+    written.map_err(|error| Invalid::at(1, 1, error))
+}
+
+fn yaml(text: &str) -> Result<String, Invalid> {
+    let mut documents = Vec::new();
+    for document in serde_yaml::Deserializer::from_str(text) {
+        let value = serde_yaml::Value::deserialize(document).map_err(yaml_error)?;
+        documents.push(serde_yaml::to_string(&value).map_err(yaml_error)?);
+    }
+    Ok(documents.join("---\n"))
+}
+
+fn yaml_error(error: serde_yaml::Error) -> Invalid {
+    match error.location() {
+        Some(location) => Invalid::at(location.line(), location.column(), error),
+        None => Invalid::at(1, 1, error),
+    }
+}
+
+fn toml_table(text: &str) -> Result<toml::Table, Invalid> {
+    text.parse::<toml::Table>().map_err(|error| {
+        let offset = error.span().map_or(0, |span| span.start);
+        Invalid::at_offset(text, offset, error.message())
+    })
+}
+
+fn xml(text: &str, indent: Option<&str>) -> Result<String, Invalid> {
+    let mut reader = Reader::from_str(text);
+    reader.trim_text(true);
+    let mut writer = match indent.and_then(|indent| Some((indent.bytes().next()?, indent.len()))) {
+        Some((character, size)) => Writer::new_with_indent(Vec::new(), character, size),
+        None => Writer::new(Vec::new()),
+    };
+    let mut open = Vec::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|error| Invalid::at_offset(text, reader.buffer_position(), error))?;
+        match &event {
+            Event::Eof => break,
+            Event::Start(start) => {
+                open.push(reader.buffer_position().saturating_sub(start.len() + 2))
+            }
+            Event::End(_) => {
+                open.pop();
+            }
+            _ => {}
+        }
+        writer
+            .write_event(event)
+            .map_err(|error| Invalid::at_offset(text, reader.buffer_position(), error))?;
+    }
+    if let Some(offset) = open.pop() {
+        return Err(Invalid::at_offset(text, offset, "element is never closed"));
+    }
+    Ok(String::from_utf8_lossy(&writer.into_inner()).into_owned())
+}
@@ -0,0 +1,81 @@
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::time::Duration;
+
+use iced::futures::future;
+use serde::Deserialize;
+use tokio::io::AsyncWriteExt;
+
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct FormatterConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub trusted: bool,
+    pub on_save: bool,
+}
+
+impl FormatterConfig {
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Pending {
+    pub buffer: usize,
+    pub then_save: bool,
+}
+
+pub async fn run(
+    formatter: FormatterConfig,
+    text: String,
+    path: Option<PathBuf>,
+) -> Result<String, String> {
+    let path_arg = path
+        .as_deref()
+        .map(|path| path.display().to_string())
+        .unwrap_or_default();
+    let dir = path
+        .as_deref()
+        .and_then(|path| path.parent())
+        .filter(|dir| dir.is_dir())
+        .map(PathBuf::from)
+        .or_else(|| std::env::current_dir().ok())
+        .unwrap_or_default();
+    let mut child = tokio::process::Command::new(&formatter.command)
+        .args(
+            formatter
+                .args
+                .iter()
+                .map(|arg| arg.replace("{path}", &path_arg)),
+        )
+        .current_dir(dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| format!("{}: {error}", formatter.command))?;
+    let mut stdin = child.stdin.take().ok_or("no stdin")?;
+    let write = async move {
+        let _ = stdin.write_all(text.as_bytes()).await;
+    };
+    let (_, output) = tokio::time::timeout(TIMEOUT, future::join(write, child.wait_with_output()))
+        .await
+        .map_err(|_| format!("{} timed out", formatter.command))?;
+    let output = output.map_err(|error| error.to_string())?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(stderr
+            .lines()
+            .find(|line| !line.trim().is_empty())
+            .map_or_else(|| output.status.to_string(), str::to_owned));
+    }
+    String::from_utf8(output.stdout).map_err(|_| String::from("output is not valid UTF-8"))
+}
@@ -0,0 +1,112 @@
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::Arc;
+
+use crate::diff::{self, Hunk};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Info {
+    pub branch: String,
+    pub is_dirty: bool,
+    pub head: Option<Arc<String>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Marker {
+    Added,
+    Modified,
+    Deleted,
+}
+
+impl Info {
+    pub fn hunks(&self, text: &str) -> Vec<Hunk> {
+        let Some(head) = &self.head else {
+            return Vec::new();
+        };
+        let old: Vec<&str> = head.split('\n').collect();
+        let new: Vec<&str> = text.split('\n').collect();
+        diff::diff(&old, &new)
+    }
+
+    pub fn markers(&self, text: &str) -> Vec<(usize, Marker)> {
+        let last = text.matches('\n').count();
+        self.hunks(text)
+            .into_iter()
+            .flat_map(|hunk| {
+                if hunk.new.is_empty() {
+                    return vec![(hunk.new.start.min(last), Marker::Deleted)];
+                }
+                let marker = if hunk.old.is_empty() {
+                    Marker::Added
+                } else {
+                    Marker::Modified
+                };
+                hunk.new.map(|line| (line, marker)).collect()
+            })
+            .collect()
+    }
+
+    pub fn original(&self, hunk: &Hunk) -> Vec<String> {
+        self.head
+            .iter()
+            .flat_map(|head| head.split('\n'))
+            .skip(hunk.old.start)
+            .take(hunk.old.len())
+            .map(str::to_owned)
+            .collect()
+    }
+
+    pub fn hunk_at(&self, text: &str, line: usize) -> Option<Hunk> {
+        let last = text.matches('\n').count();
+        self.hunks(text).into_iter().find(|hunk| {
+            hunk.new.contains(&line) || (hunk.new.is_empty() && hunk.new.start.min(last) == line)
+        })
+    }
+}
+
+pub async fn inspect(path: PathBuf) -> Option<Info> {
+    if crate::crypt::is_encrypted(&path) {
+        return None;
+    }
+    let dir = path.parent()?;
+    let status = git(dir, &["status", "--porcelain=v1", "--branch"]).await?;
+    let mut lines = status.lines();
+    let header = lines.next()?.strip_prefix("## ")?;
+    let header = header.strip_prefix("No commits yet on ").unwrap_or(header);
+    let branch = header.split("...").next().unwrap_or(header).to_owned();
+    let is_dirty = lines.next().is_some();
+    let name = path.file_name()?.to_str()?;
+    let head = git(dir, &["show", &format!("HEAD:./{name}")])
+        .await
+        .map(|text| Arc::new(text.replace("\r\n", "\n")));
+    Some(Info {
+        branch,
+        is_dirty,
+        head,
+    })
+}
+
+/// Runs read-only git commands on whatever repository the file lives in, so
+/// config settings that would execute programs from it are switched off.
+async fn git(dir: &Path, args: &[&str]) -> Option<String> {
+    let output = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args([
+            "--no-optional-locks",
+            "-c",
+            "core.fsmonitor=false",
+            "-c",
+            "core.hooksPath=/dev/null",
+        ])
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .await
+        .ok()?;
+    output
+        .status
+        .success()
+        .then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
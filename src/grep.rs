@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+
+use iced::futures::{SinkExt, StreamExt, channel::mpsc};
+use iced::{Subscription, subscription};
+use regex::Regex;
+
+use crate::{Message, large, replace, search};
+
+pub const MAX_HITS: usize = 10_000;
+const MAX_LINE_CHARS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Hit {
+    pub path: PathBuf,
+    pub line: usize,
+    pub text: String,
+}
+
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub root: PathBuf,
+    pub query: String,
+    pub options: search::Options,
+    pub hits: Vec<Hit>,
+    pub files: usize,
+    pub running: Option<usize>,
+    pub error: Option<String>,
+    pub pattern: Option<Regex>,
+    pub is_replacing: bool,
+    pub replacement: String,
+    pub preview: Option<Vec<replace::FilePreview>>,
+    pub is_applying: bool,
+}
+
+impl Panel {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            ..Self::default()
+        }
+    }
+
+    pub fn replacement(&self) -> Option<replace::Replacement> {
+        Some(replace::Replacement {
+            pattern: self.pattern.clone()?,
+            text: self.replacement.clone(),
+            expand: self.options.regex,
+        })
+    }
+}
+
+pub fn search(id: usize, root: PathBuf, pattern: Regex) -> Subscription<Message> {
+    subscription::channel(id, 100, move |mut output| async move {
+        let (sender, mut hits) = mpsc::unbounded();
+        std::thread::spawn(move || walk(root, pattern, sender));
+        while let Some(hits) = hits.next().await {
+            let _ = output.send(Message::GrepFound(id, hits)).await;
+        }
+        let _ = output.send(Message::GrepFinished(id)).await;
+        std::future::pending().await
+    })
+}
+
+fn walk(root: PathBuf, pattern: Regex, sender: mpsc::UnboundedSender<Vec<Hit>>) {
+    for entry in ignore::WalkBuilder::new(root).build().flatten() {
+        if sender.is_closed() {
+            return;
+        }
+        let is_small_file = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| metadata.len() <= large::STREAM_THRESHOLD);
+        if !is_small_file {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let hits: Vec<_> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| pattern.is_match(line))
+            .map(|(index, line)| Hit {
+                path: entry.path().to_path_buf(),
+                line: index + 1,
+                text: line.trim().chars().take(MAX_LINE_CHARS).collect(),
+            })
+            .collect();
+        if !hits.is_empty() && sender.unbounded_send(hits).is_err() {
+            return;
+        }
+    }
+}
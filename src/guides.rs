@@ -0,0 +1,81 @@
+use std::ops::Range;
+
+use crate::whitespace::TAB_WIDTH;
+
+const BLANK_SCAN: usize = 200;
+
+#[derive(Debug, Clone, Default)]
+pub struct Guides {
+    pub width: usize,
+    pub depths: Vec<usize>,
+    pub active: Option<(usize, Range<usize>)>,
+}
+
+impl Guides {
+    pub fn new(text: &str, top: usize, rows: usize, cursor: usize, width: usize) -> Self {
+        let width = width.max(1);
+        let lines: Vec<&str> = text.split('\n').collect();
+        let depth = |line: usize| depth(&lines, line, width);
+        let end = lines.len().min(top + rows);
+        let depths = (top..end).map(depth).collect();
+        let active = (cursor < lines.len()).then(|| {
+            let own = depth(cursor);
+            let next = (cursor + 1..lines.len())
+                .find(|&line| indent(lines[line]).is_some())
+                .map_or(0, depth);
+            let (level, start) = if next > own {
+                (next - 1, cursor + 1)
+            } else {
+                (own.checked_sub(1)?, cursor)
+            };
+            let first = (0..start)
+                .rev()
+                .take_while(|&line| depth(line) > level)
+                .last()
+                .unwrap_or(start);
+            let last = (start + 1..lines.len())
+                .take_while(|&line| depth(line) > level)
+                .last()
+                .unwrap_or(start);
+            let rows = first.max(top) - top..(last + 1).min(end).saturating_sub(top);
+            Some((level, rows))
+        });
+        Self {
+            width,
+            depths,
+            active: active.flatten(),
+        }
+    }
+}
+
+fn indent(line: &str) -> Option<usize> {
+    let mut column = 0;
+    for c in line.chars() {
+        match c {
+            ' ' => column += 1,
+            '\t' => column += TAB_WIDTH - column % TAB_WIDTH,
+            c if c.is_whitespace() => {}
+            _ => return Some(column),
+        }
+    }
+    None
+}
+
+fn depth(lines: &[&str], line: usize, width: usize) -> usize {
+    if let Some(column) = indent(lines[line]) {
+        return column.div_ceil(width);
+    }
+    let before = lines[..line]
+        .iter()
+        .rev()
+        .take(BLANK_SCAN)
+        .find_map(|line| indent(line));
+    let after = lines[line + 1..]
+        .iter()
+        .take(BLANK_SCAN)
+        .find_map(|line| indent(line));
+    match (before, after) {
+        (Some(before), Some(after)) => before.min(after).div_ceil(width),
+        _ => 0,
+    }
+}
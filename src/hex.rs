@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+const BYTES_PER_LINE: usize = 16;
+const SAMPLE: usize = 8192;
+
+pub fn is_binary(bytes: &[u8]) -> bool {
+    encoding_rs::Encoding::for_bom(bytes).is_none() && bytes[..bytes.len().min(SAMPLE)].contains(&0)
+}
+
+pub fn format(bytes: &[u8]) -> String {
+    let mut text = String::new();
+    for (index, chunk) in bytes.chunks(BYTES_PER_LINE).enumerate() {
+        if index > 0 {
+            text.push('\n');
+        }
+        let _ = write!(text, "{:08x} ", index * BYTES_PER_LINE);
+        for column in 0..BYTES_PER_LINE {
+            if column % 8 == 0 {
+                text.push(' ');
+            }
+            match chunk.get(column) {
+                Some(byte) => {
+                    let _ = write!(text, "{byte:02x} ");
+                }
+                None => text.push_str("   "),
+            }
+        }
+        text.push_str(" |");
+        text.extend(chunk.iter().map(|byte| {
+            if byte.is_ascii_graphic() || *byte == b' ' {
+                *byte as char
+            } else {
+                '.'
+            }
+        }));
+        text.push('|');
+    }
+    text
+}
+
+pub fn parse(text: &str) -> Result<Vec<u8>, usize> {
+    let mut bytes = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let hex = line.split_once('|').map_or(line, |(hex, _)| hex);
+        for token in hex.split_whitespace().skip(1) {
+            if token.len() != 2 {
+                return Err(index);
+            }
+            bytes.push(u8::from_str_radix(token, 16).map_err(|_| index)?);
+        }
+    }
+    Ok(bytes)
+}
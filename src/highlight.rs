@@ -0,0 +1,159 @@
+use std::ops::Range;
+
+use iced::advanced::text::highlighter::{self as text, Format, Highlighter as _};
+use iced::{Color, Font, Theme, highlighter};
+
+use crate::lsp::{Diagnostic, Severity};
+
+const WARNING_COLOR: Color = Color::from_rgb(0.9, 0.6, 0.1);
+const MISSPELLED_COLOR: Color = Color::from_rgb(0.85, 0.35, 0.75);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    Bracket,
+    Error,
+    Warning,
+    Misspelled,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Settings {
+    pub syntax: highlighter::Settings,
+    pub marks: Vec<(usize, Range<usize>, Mark)>,
+}
+
+#[derive(Debug, Clone)]
+pub enum Highlight {
+    Syntax(highlighter::Highlight),
+    Mark(Mark),
+}
+
+impl Highlight {
+    pub fn to_format(&self, theme: &Theme) -> Format<Font> {
+        let color = match self {
+            Highlight::Syntax(highlight) => return highlight.to_format(),
+            Highlight::Mark(Mark::Bracket) => theme.palette().primary,
+            Highlight::Mark(Mark::Error) => theme.palette().danger,
+            Highlight::Mark(Mark::Warning) => WARNING_COLOR,
+            Highlight::Mark(Mark::Misspelled) => MISSPELLED_COLOR,
+        };
+        Format {
+            color: Some(color),
+            font: None,
+        }
+    }
+}
+
+pub fn diagnostics(
+    text: &str,
+    diagnostics: &[Diagnostic],
+    visible: Range<usize>,
+) -> Vec<(usize, Range<usize>, Mark)> {
+    let lines: Vec<&str> = text.split('\n').take(visible.end).collect();
+    let mut marks = Vec::new();
+    for diagnostic in diagnostics {
+        let mark = match diagnostic.severity {
+            Severity::Error => Mark::Error,
+            Severity::Warning => Mark::Warning,
+            Severity::Information | Severity::Hint => continue,
+        };
+        let (first, last) = (
+            diagnostic.start.0.max(visible.start),
+            diagnostic.end.0.min(diagnostic.start.0 + 20),
+        );
+        for (line, contents) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let start = if line == diagnostic.start.0 {
+                utf16_to_byte(contents, diagnostic.start.1)
+            } else {
+                0
+            };
+            let mut end = if line == diagnostic.end.0 {
+                utf16_to_byte(contents, diagnostic.end.1)
+            } else {
+                contents.len()
+            };
+            if end <= start {
+                end = contents[start..]
+                    .chars()
+                    .next()
+                    .map_or(start, |c| start + c.len_utf8());
+            }
+            if end > start {
+                marks.push((line, start..end, mark));
+            }
+        }
+    }
+    marks
+}
+
+fn first_changed(
+    old: &[(usize, Range<usize>, Mark)],
+    new: &[(usize, Range<usize>, Mark)],
+) -> Option<usize> {
+    let removed = old.iter().filter(|mark| !new.contains(mark));
+    let added = new.iter().filter(|mark| !old.contains(mark));
+    removed.chain(added).map(|(line, _, _)| *line).min()
+}
+
+fn utf16_to_byte(line: &str, column: usize) -> usize {
+    let mut units = 0;
+    for (index, c) in line.char_indices() {
+        if units >= column {
+            return index;
+        }
+        units += c.len_utf16();
+    }
+    line.len()
+}
+
+pub struct Highlighter {
+    syntax: highlighter::Highlighter,
+    settings: Settings,
+}
+
+impl text::Highlighter for Highlighter {
+    type Settings = Settings;
+    type Highlight = Highlight;
+    type Iterator<'a> = std::vec::IntoIter<(Range<usize>, Highlight)>;
+
+    fn new(settings: &Self::Settings) -> Self {
+        Self {
+            syntax: highlighter::Highlighter::new(&settings.syntax),
+            settings: settings.clone(),
+        }
+    }
+
+    fn update(&mut self, new_settings: &Self::Settings) {
+        if new_settings.syntax != self.settings.syntax {
+            self.syntax.update(&new_settings.syntax);
+        } else if let Some(line) = first_changed(&self.settings.marks, &new_settings.marks) {
+            self.syntax.change_line(line);
+        }
+        self.settings = new_settings.clone();
+    }
+
+    fn change_line(&mut self, line: usize) {
+        self.syntax.change_line(line);
+    }
+
+    fn highlight_line(&mut self, line: &str) -> Self::Iterator<'_> {
+        let current = self.syntax.current_line();
+        let mut highlights: Vec<_> = self
+            .syntax
+            .highlight_line(line)
+            .map(|(range, highlight)| (range, Highlight::Syntax(highlight)))
+            .collect();
+        highlights.extend(
+            self.settings
+                .marks
+                .iter()
+                .filter(|(line, _, _)| *line == current)
+                .map(|(_, range, mark)| (range.clone(), Highlight::Mark(*mark))),
+        );
+        highlights.into_iter()
+    }
+
+    fn current_line(&self) -> usize {
+        self.syntax.current_line()
+    }
+}
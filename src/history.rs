@@ -0,0 +1,226 @@
+use std::collections::VecDeque;
+
+use iced::widget::text_editor::{self, Action, Edit};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroize;
+
+pub const DEFAULT_DEPTH: usize = 200;
+const MAX_BYTES: usize = 128 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub text: String,
+    pub cursor: (usize, usize),
+}
+
+impl Snapshot {
+    pub fn of(content: &text_editor::Content) -> Self {
+        Self {
+            text: content.text(),
+            cursor: content.cursor_position(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Group {
+    Typing,
+    Deleting,
+}
+
+#[derive(Debug)]
+pub struct History {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    depth: usize,
+    max_bytes: usize,
+    group: Option<Group>,
+}
+
+impl History {
+    pub fn new(depth: usize) -> Self {
+        Self {
+            undo: VecDeque::new(),
+            redo: Vec::new(),
+            depth: depth.max(1),
+            max_bytes: MAX_BYTES,
+            group: None,
+        }
+    }
+
+    pub fn set_depth(&mut self, depth: usize) {
+        self.depth = depth.max(1);
+        self.trim();
+    }
+
+    fn trim(&mut self) {
+        let mut bytes: usize = self
+            .undo
+            .iter()
+            .chain(&self.redo)
+            .map(|snapshot| snapshot.text.len())
+            .sum();
+        while self.undo.len() > self.depth || (bytes > self.max_bytes && self.undo.len() > 1) {
+            let Some(snapshot) = self.undo.pop_front() else {
+                break;
+            };
+            bytes -= snapshot.text.len();
+        }
+    }
+
+    pub fn record(&mut self, content: &text_editor::Content, action: &Action) {
+        let group = match action {
+            Action::Edit(Edit::Insert(c)) if !c.is_whitespace() => Some(Group::Typing),
+            Action::Edit(Edit::Backspace | Edit::Delete) => Some(Group::Deleting),
+            _ => None,
+        };
+        if group.is_some() && group == self.group {
+            return;
+        }
+        self.group = group;
+        self.push(Snapshot::of(content));
+    }
+
+    pub fn push(&mut self, snapshot: Snapshot) {
+        self.undo.push_back(snapshot);
+        self.redo.clear();
+        self.trim();
+    }
+
+    pub fn break_group(&mut self) {
+        self.group = None;
+    }
+
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.group = None;
+        Some(snapshot)
+    }
+
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let snapshot = self.redo.pop()?;
+        self.undo.push_back(current);
+        self.group = None;
+        self.trim();
+        Some(snapshot)
+    }
+
+    pub fn export(&self, max_bytes: usize) -> (Vec<Snapshot>, Vec<Snapshot>) {
+        let mut budget = max_bytes;
+        let undo = newest(self.undo.iter(), &mut budget);
+        let redo = newest(self.redo.iter(), &mut budget);
+        (undo, redo)
+    }
+
+    pub fn restore(&mut self, undo: Vec<Snapshot>, redo: Vec<Snapshot>) {
+        self.undo = undo.into();
+        self.redo = redo;
+        self.group = None;
+        self.set_depth(self.depth);
+    }
+
+    pub fn wipe(&mut self) {
+        for snapshot in self.undo.iter_mut().chain(self.redo.iter_mut()) {
+            snapshot.text.zeroize();
+        }
+        self.undo.clear();
+        self.redo.clear();
+        self.group = None;
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+}
+
+fn newest<'a>(
+    snapshots: impl DoubleEndedIterator<Item = &'a Snapshot>,
+    budget: &mut usize,
+) -> Vec<Snapshot> {
+    let mut kept: Vec<_> = snapshots
+        .rev()
+        .map_while(|snapshot| {
+            *budget = budget.checked_sub(snapshot.text.len())?;
+            Some(snapshot.clone())
+        })
+        .collect();
+    kept.reverse();
+    kept
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::widget::text_editor::{Action, Content, Edit, Motion};
+
+    use super::{History, Snapshot};
+
+    fn snapshot(text: &str) -> Snapshot {
+        Snapshot {
+            text: text.to_owned(),
+            cursor: (0, 0),
+        }
+    }
+
+    #[test]
+    fn groups_typing_until_whitespace_or_a_move() {
+        let content = Content::with("");
+        let mut history = History::new(10);
+        for action in [
+            Action::Edit(Edit::Insert('a')),
+            Action::Edit(Edit::Insert('b')),
+            Action::Edit(Edit::Insert(' ')),
+            Action::Edit(Edit::Insert('c')),
+            Action::Move(Motion::Left),
+            Action::Edit(Edit::Backspace),
+            Action::Edit(Edit::Backspace),
+        ] {
+            history.record(&content, &action);
+        }
+        assert_eq!(history.undo.len(), 5);
+    }
+
+    #[test]
+    fn undo_and_redo_swap_snapshots() {
+        let mut history = History::new(10);
+        history.push(snapshot("one"));
+        let undone = history.undo(snapshot("two")).unwrap();
+        assert_eq!(undone.text, "one");
+        assert!(history.can_redo());
+        let redone = history.redo(snapshot("one")).unwrap();
+        assert_eq!(redone.text, "two");
+        assert!(!history.can_redo());
+        history.push(snapshot("three"));
+        assert!(!history.can_redo());
+    }
+
+    #[test]
+    fn keeps_at_most_depth_snapshots() {
+        let mut history = History::new(2);
+        for text in ["a", "b", "c"] {
+            history.push(snapshot(text));
+        }
+        assert_eq!(history.undo.len(), 2);
+        assert_eq!(history.undo[0].text, "b");
+        history.set_depth(1);
+        assert_eq!(history.undo.len(), 1);
+        assert_eq!(History::new(0).depth, 1);
+    }
+
+    #[test]
+    fn drops_old_snapshots_past_the_byte_budget() {
+        let mut history = History::new(10);
+        history.max_bytes = 6;
+        for text in ["aaa", "bbb", "ccc"] {
+            history.push(snapshot(text));
+        }
+        assert_eq!(history.undo.len(), 2);
+        assert_eq!(history.undo[0].text, "bbb");
+        history.push(snapshot("a long snapshot"));
+        assert_eq!(history.undo.len(), 1);
+    }
+}
@@ -0,0 +1,82 @@
+use std::sync::RwLock;
+
+use fluent_bundle::concurrent::FluentBundle;
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue};
+use unic_langid::LanguageIdentifier;
+
+const FALLBACK: &str = "en";
+const LOCALES: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+struct Bundles {
+    selected: Option<FluentBundle<FluentResource>>,
+    fallback: FluentBundle<FluentResource>,
+}
+
+static BUNDLES: RwLock<Option<Bundles>> = RwLock::new(None);
+
+pub fn select(locale: Option<&str>) {
+    let requested = locale.map(str::to_owned).or_else(sys_locale::get_locale);
+    let language = requested
+        .as_deref()
+        .and_then(|locale| locale.split(['-', '_', '.']).next())
+        .map(str::to_lowercase)
+        .filter(|language| language != FALLBACK);
+    let selected = language.and_then(|language| {
+        LOCALES
+            .iter()
+            .find(|(name, _)| *name == language)
+            .map(|(name, source)| bundle(name, source))
+    });
+    let fallback = bundle(FALLBACK, LOCALES[0].1);
+    if let Ok(mut bundles) = BUNDLES.write() {
+        *bundles = Some(Bundles { selected, fallback });
+    }
+}
+
+pub fn tr(key: &str) -> String {
+    format(key, None)
+}
+
+pub fn tr_args<'a, V: Into<FluentValue<'a>>>(
+    key: &str,
+    args: impl IntoIterator<Item = (&'a str, V)>,
+) -> String {
+    let args = FluentArgs::from_iter(args.into_iter().map(|(name, value)| (name, value.into())));
+    format(key, Some(&args))
+}
+
+fn bundle(name: &str, source: &str) -> FluentBundle<FluentResource> {
+    let language: LanguageIdentifier = name.parse().unwrap_or_default();
+    let mut bundle = FluentBundle::new_concurrent(vec![language]);
+    bundle.set_use_isolating(false);
+    let resource =
+        FluentResource::try_new(source.to_owned()).unwrap_or_else(|(resource, _)| resource);
+    let _ = bundle.add_resource(resource);
+    bundle
+}
+
+fn format(key: &str, args: Option<&FluentArgs>) -> String {
+    let Ok(bundles) = BUNDLES.read() else {
+        return key.to_owned();
+    };
+    let Some(bundles) = bundles.as_ref() else {
+        return key.to_owned();
+    };
+    bundles
+        .selected
+        .iter()
+        .chain(std::iter::once(&bundles.fallback))
+        .find_map(|bundle| {
+            let pattern = bundle.get_message(key)?.value()?;
+            let mut errors = Vec::new();
+            Some(
+                bundle
+                    .format_pattern(pattern, args, &mut errors)
+                    .into_owned(),
+            )
+        })
+        .unwrap_or_else(|| key.to_owned())
+}
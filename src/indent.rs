@@ -0,0 +1,78 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Tabs,
+    Spaces(usize),
+}
+
+impl Default for Indent {
+    fn default() -> Self {
+        Indent::Spaces(4)
+    }
+}
+
+impl Indent {
+    pub const ALL: &'static [Indent] = &[
+        Indent::Tabs,
+        Indent::Spaces(2),
+        Indent::Spaces(4),
+        Indent::Spaces(8),
+    ];
+
+    pub fn unit(self) -> String {
+        match self {
+            Indent::Tabs => String::from("\t"),
+            Indent::Spaces(width) => " ".repeat(width.max(1)),
+        }
+    }
+
+    pub fn tab_at(self, column: usize) -> String {
+        match self {
+            Indent::Tabs => String::from("\t"),
+            Indent::Spaces(width) => {
+                let width = width.max(1);
+                " ".repeat(width - column % width)
+            }
+        }
+    }
+
+    pub fn detect(text: &str) -> Option<Indent> {
+        let mut tabs = 0;
+        let mut spaces = 0;
+        let mut widths = [0usize; 9];
+        let mut previous = 0;
+        for line in text.lines().filter(|line| !line.trim().is_empty()) {
+            if line.starts_with('\t') {
+                tabs += 1;
+                continue;
+            }
+            let width = line.len() - line.trim_start_matches(' ').len();
+            if width > 0 {
+                spaces += 1;
+            }
+            let change = width.abs_diff(previous);
+            if (2..=8).contains(&change) {
+                widths[change] += 1;
+            }
+            previous = width;
+        }
+        if tabs == 0 && spaces == 0 {
+            return None;
+        }
+        if tabs > spaces {
+            return Some(Indent::Tabs);
+        }
+        let width = (2..=8).max_by_key(|&width| (widths[width], usize::MAX - width))?;
+        Some(Indent::Spaces(if widths[width] == 0 { 4 } else { width }))
+    }
+}
+
+impl fmt::Display for Indent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Indent::Tabs => f.write_str("Tabs"),
+            Indent::Spaces(width) => write!(f, "Spaces: {width}"),
+        }
+    }
+}
@@ -0,0 +1,112 @@
+use std::path::PathBuf;
+
+use iced::Subscription;
+use serde::{Deserialize, Serialize};
+
+use crate::Message;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Request {
+    pub cwd: PathBuf,
+    pub args: Vec<String>,
+}
+
+#[cfg(unix)]
+const READ_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+#[cfg(unix)]
+fn uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail.
+    unsafe { libc::getuid() }
+}
+
+#[cfg(unix)]
+fn socket_path() -> std::io::Result<PathBuf> {
+    use std::io::{Error, ErrorKind};
+    use std::os::unix::fs::{DirBuilderExt, MetadataExt};
+
+    let dir = match dirs::runtime_dir() {
+        Some(dir) => dir.join("iced_editor"),
+        None => std::env::temp_dir().join(format!("iced_editor-{}", uid())),
+    };
+    match std::fs::DirBuilder::new().mode(0o700).create(&dir) {
+        Err(error) if error.kind() != ErrorKind::AlreadyExists => return Err(error),
+        _ => {}
+    }
+    let metadata = std::fs::symlink_metadata(&dir)?;
+    if !metadata.is_dir() || metadata.uid() != uid() || metadata.mode() & 0o077 != 0 {
+        return Err(Error::new(
+            ErrorKind::PermissionDenied,
+            format!("{} is not a private directory", dir.display()),
+        ));
+    }
+    Ok(dir.join("iced_editor.sock"))
+}
+
+#[cfg(unix)]
+pub fn forward(args: &[String]) -> bool {
+    use std::io::Write;
+    use std::os::unix::net::UnixStream;
+
+    let Ok(path) = socket_path() else {
+        return false;
+    };
+    let Ok(mut stream) = UnixStream::connect(path) else {
+        return false;
+    };
+    let request = Request {
+        cwd: std::env::current_dir().unwrap_or_default(),
+        args: args.to_vec(),
+    };
+    let Ok(line) = serde_json::to_string(&request) else {
+        return false;
+    };
+    stream.write_all(format!("{line}\n").as_bytes()).is_ok()
+}
+
+#[cfg(not(unix))]
+pub fn forward(_args: &[String]) -> bool {
+    false
+}
+
+#[cfg(unix)]
+pub fn listen() -> Subscription<Message> {
+    use iced::futures::SinkExt;
+    use iced::subscription;
+    use tokio::io::AsyncBufReadExt;
+    use tokio::net::{UnixListener, UnixStream};
+
+    subscription::channel("instance", 10, |mut output| async move {
+        let Ok(path) = socket_path() else {
+            return std::future::pending().await;
+        };
+        if UnixStream::connect(&path).await.is_err() {
+            let _ = std::fs::remove_file(&path);
+        }
+        let Ok(listener) = UnixListener::bind(&path) else {
+            return std::future::pending().await;
+        };
+        while let Ok((stream, _)) = listener.accept().await {
+            if !stream.peer_cred().is_ok_and(|cred| cred.uid() == uid()) {
+                continue;
+            }
+            let mut output = output.clone();
+            tokio::spawn(async move {
+                let mut lines = tokio::io::BufReader::new(stream).lines();
+                while let Ok(Ok(Some(line))) =
+                    tokio::time::timeout(READ_TIMEOUT, lines.next_line()).await
+                {
+                    if let Ok(request) = serde_json::from_str(&line) {
+                        let _ = output.send(Message::InstanceRequested(request)).await;
+                    }
+                }
+            });
+        }
+        std::future::pending().await
+    })
+}
+
+#[cfg(not(unix))]
+pub fn listen() -> Subscription<Message> {
+    Subscription::none()
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+
+use iced::keyboard::{KeyCode, Modifiers};
+
+use iced::widget::pane_grid::Axis;
+
+use crate::Message;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Shortcut {
+    pub key: KeyCode,
+    pub command: bool,
+    pub shift: bool,
+    pub alt: bool,
+}
+
+impl Shortcut {
+    pub const fn key(key: KeyCode) -> Self {
+        Self {
+            key,
+            command: false,
+            shift: false,
+            alt: false,
+        }
+    }
+
+    pub const fn command(key: KeyCode) -> Self {
+        Self {
+            command: true,
+            ..Self::key(key)
+        }
+    }
+
+    pub const fn shift(self) -> Self {
+        Self {
+            shift: true,
+            ..self
+        }
+    }
+
+    pub const fn alt(self) -> Self {
+        Self { alt: true, ..self }
+    }
+
+    pub fn matches(&self, key: KeyCode, modifiers: Modifiers) -> bool {
+        self.key == key
+            && self.command == modifiers.command()
+            && self.shift == modifiers.shift()
+            && self.alt == modifiers.alt()
+    }
+}
+
+impl fmt::Display for Shortcut {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.command {
+            f.write_str("Ctrl+")?;
+        }
+        if self.alt {
+            f.write_str("Alt+")?;
+        }
+        if self.shift {
+            f.write_str("Shift+")?;
+        }
+        write!(f, "{:?}", self.key)
+    }
+}
+
+pub fn bindings() -> Vec<(Shortcut, Message)> {
+    vec![
+        (Shortcut::command(KeyCode::N), Message::New),
+        (
+            Shortcut::command(KeyCode::N).alt(),
+            Message::NewFromClipboard,
+        ),
+        (Shortcut::command(KeyCode::N).shift(), Message::NewScratch),
+        (
+            Shortcut::command(KeyCode::N).shift().alt(),
+            Message::NewFromTemplate,
+        ),
+        (Shortcut::command(KeyCode::O), Message::Open),
+        (Shortcut::command(KeyCode::S), Message::Save),
+        (Shortcut::command(KeyCode::S).shift(), Message::SaveAs),
+        (Shortcut::command(KeyCode::W), Message::CloseActiveTab),
+        (Shortcut::command(KeyCode::Z), Message::Undo),
+        (Shortcut::command(KeyCode::Y), Message::Redo),
+        (Shortcut::command(KeyCode::Z).shift(), Message::Redo),
+        (Shortcut::command(KeyCode::Tab), Message::NextTab),
+        (
+            Shortcut::command(KeyCode::Tab).shift(),
+            Message::PreviousTab,
+        ),
+        (Shortcut::command(KeyCode::F), Message::ToggleFind),
+        (Shortcut::command(KeyCode::F).shift(), Message::ToggleGrep),
+        (Shortcut::command(KeyCode::H), Message::ToggleReplace),
+        (Shortcut::key(KeyCode::F3), Message::FindNext),
+        (Shortcut::key(KeyCode::F3).shift(), Message::FindPrevious),
+        (Shortcut::key(KeyCode::Escape), Message::Dismiss),
+        (Shortcut::key(KeyCode::Enter), Message::AcceptPrompt),
+        (Shortcut::command(KeyCode::Enter), Message::AcceptPrompt),
+        (Shortcut::key(KeyCode::F6), Message::FocusNext),
+        (Shortcut::key(KeyCode::F6).shift(), Message::FocusPrevious),
+        (
+            Shortcut::command(KeyCode::L).alt(),
+            Message::AnnouncePosition,
+        ),
+        (
+            Shortcut::command(KeyCode::LBracket).shift(),
+            Message::FoldAtCursor,
+        ),
+        (Shortcut::command(KeyCode::LBracket).alt(), Message::FoldAll),
+        (
+            Shortcut::command(KeyCode::RBracket).alt(),
+            Message::UnfoldAll,
+        ),
+        (
+            Shortcut::command(KeyCode::R).alt(),
+            Message::ToggleRecording,
+        ),
+        (Shortcut::command(KeyCode::P).alt(), Message::PlayMacro),
+        (Shortcut::command(KeyCode::P), Message::ToggleFinder),
+        (Shortcut::command(KeyCode::F2), Message::ToggleBookmark),
+        (Shortcut::key(KeyCode::F2), Message::NextBookmark),
+        (
+            Shortcut::key(KeyCode::F2).shift(),
+            Message::PreviousBookmark,
+        ),
+        (Shortcut::command(KeyCode::R), Message::ToggleRecent),
+        (
+            Shortcut::command(KeyCode::T).alt(),
+            Message::ToggleTypewriter,
+        ),
+        (Shortcut::command(KeyCode::Period), Message::RepeatLast),
+        (
+            Shortcut::command(KeyCode::P).shift(),
+            Message::TogglePalette,
+        ),
+        (Shortcut::command(KeyCode::G), Message::ToggleGoToLine),
+        (Shortcut::key(KeyCode::Z).alt(), Message::ToggleWrap),
+        (Shortcut::command(KeyCode::D), Message::SelectNextOccurrence),
+        (Shortcut::command(KeyCode::M), Message::JumpToBracket),
+        (Shortcut::command(KeyCode::Slash), Message::ToggleComment),
+        (Shortcut::key(KeyCode::Up).alt(), Message::MoveLinesUp),
+        (Shortcut::key(KeyCode::Down).alt(), Message::MoveLinesDown),
+        (
+            Shortcut::command(KeyCode::D).shift(),
+            Message::DuplicateLines,
+        ),
+        (Shortcut::command(KeyCode::J), Message::JoinLines),
+        (Shortcut::command(KeyCode::K).shift(), Message::DeleteLines),
+        (Shortcut::key(KeyCode::Tab), Message::InsertTab),
+        (
+            Shortcut::key(KeyCode::Tab).shift(),
+            Message::PreviousTabStop,
+        ),
+        (Shortcut::command(KeyCode::Equals), Message::ZoomIn),
+        (Shortcut::command(KeyCode::Equals).shift(), Message::ZoomIn),
+        (Shortcut::command(KeyCode::Plus), Message::ZoomIn),
+        (Shortcut::command(KeyCode::Minus), Message::ZoomOut),
+        (Shortcut::command(KeyCode::Key0), Message::ResetZoom),
+        (Shortcut::command(KeyCode::B), Message::ToggleExplorer),
+        (
+            Shortcut::command(KeyCode::O).shift(),
+            Message::ToggleOutline,
+        ),
+        (
+            Shortcut::command(KeyCode::M).shift(),
+            Message::ToggleProblems,
+        ),
+        (
+            Shortcut::command(KeyCode::Backslash),
+            Message::SplitPane(Axis::Vertical),
+        ),
+        (
+            Shortcut::command(KeyCode::Backslash).shift(),
+            Message::SplitPane(Axis::Horizontal),
+        ),
+        (Shortcut::command(KeyCode::W).shift(), Message::ClosePane),
+        (Shortcut::command(KeyCode::V).alt(), Message::TogglePreview),
+        (
+            Shortcut::command(KeyCode::V).shift(),
+            Message::ToggleClipboardHistory,
+        ),
+    ]
+}
+
+pub fn shortcut(message: &Message) -> Option<Shortcut> {
+    let wanted = std::mem::discriminant(message);
+    bindings()
+        .into_iter()
+        .find(|(_, message)| std::mem::discriminant(message) == wanted)
+        .map(|(shortcut, _)| shortcut)
+}
+
+pub fn lookup(key: KeyCode, modifiers: Modifiers) -> Option<Message> {
+    bindings()
+        .into_iter()
+        .find(|(shortcut, _)| shortcut.matches(key, modifiers))
+        .map(|(_, message)| message)
+}
@@ -0,0 +1,141 @@
+use std::fmt;
+use std::path::Path;
+
+use crate::compression::Codec;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Language {
+    #[default]
+    PlainText,
+    Rust,
+    Python,
+    JavaScript,
+    C,
+    Cpp,
+    Go,
+    Java,
+    Html,
+    Css,
+    Json,
+    Yaml,
+    Xml,
+    Markdown,
+    Shell,
+    Sql,
+    Lua,
+    Makefile,
+}
+
+impl Language {
+    pub const ALL: &'static [Language] = &[
+        Language::PlainText,
+        Language::Rust,
+        Language::Python,
+        Language::JavaScript,
+        Language::C,
+        Language::Cpp,
+        Language::Go,
+        Language::Java,
+        Language::Html,
+        Language::Css,
+        Language::Json,
+        Language::Yaml,
+        Language::Xml,
+        Language::Markdown,
+        Language::Shell,
+        Language::Sql,
+        Language::Lua,
+        Language::Makefile,
+    ];
+
+    pub fn from_path(path: &Path) -> Self {
+        if Codec::from_path(path).is_some() || crate::crypt::is_encrypted(path) {
+            return Self::from_path(&path.with_extension(""));
+        }
+        if path
+            .file_name()
+            .is_some_and(|name| name == "Makefile" || name == "makefile")
+        {
+            return Language::Makefile;
+        }
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+        match extension.to_ascii_lowercase().as_str() {
+            "rs" => Language::Rust,
+            "py" | "pyw" => Language::Python,
+            "js" | "mjs" | "cjs" => Language::JavaScript,
+            "c" | "h" => Language::C,
+            "cc" | "cpp" | "cxx" | "hpp" | "hh" => Language::Cpp,
+            "go" => Language::Go,
+            "java" => Language::Java,
+            "html" | "htm" => Language::Html,
+            "css" => Language::Css,
+            "json" => Language::Json,
+            "yaml" | "yml" => Language::Yaml,
+            "xml" | "svg" => Language::Xml,
+            "md" | "markdown" => Language::Markdown,
+            "sh" | "bash" | "zsh" => Language::Shell,
+            "sql" => Language::Sql,
+            "lua" => Language::Lua,
+            "mk" => Language::Makefile,
+            _ => Language::PlainText,
+        }
+    }
+
+    pub fn from_token(token: &str) -> Option<Self> {
+        Language::ALL
+            .iter()
+            .copied()
+            .find(|language| language.token() == token)
+    }
+
+    pub fn token(self) -> &'static str {
+        match self {
+            Language::PlainText => "txt",
+            Language::Rust => "rs",
+            Language::Python => "py",
+            Language::JavaScript => "js",
+            Language::C => "c",
+            Language::Cpp => "cpp",
+            Language::Go => "go",
+            Language::Java => "java",
+            Language::Html => "html",
+            Language::Css => "css",
+            Language::Json => "json",
+            Language::Yaml => "yaml",
+            Language::Xml => "xml",
+            Language::Markdown => "md",
+            Language::Shell => "sh",
+            Language::Sql => "sql",
+            Language::Lua => "lua",
+            Language::Makefile => "make",
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Language::PlainText => "Plain Text",
+            Language::Rust => "Rust",
+            Language::Python => "Python",
+            Language::JavaScript => "JavaScript",
+            Language::C => "C",
+            Language::Cpp => "C++",
+            Language::Go => "Go",
+            Language::Java => "Java",
+            Language::Html => "HTML",
+            Language::Css => "CSS",
+            Language::Json => "JSON",
+            Language::Yaml => "YAML",
+            Language::Xml => "XML",
+            Language::Markdown => "Markdown",
+            Language::Shell => "Shell",
+            Language::Sql => "SQL",
+            Language::Lua => "Lua",
+            Language::Makefile => "Makefile",
+        })
+    }
+}
@@ -0,0 +1,117 @@
+use std::{io::SeekFrom, path::PathBuf, sync::Arc};
+
+use iced::{Subscription, subscription};
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+
+use crate::{Error, Message, line_ending::LineEnding};
+
+pub const STREAM_THRESHOLD: u64 = 8 * 1024 * 1024;
+pub const PAGED_THRESHOLD: u64 = 128 * 1024 * 1024;
+pub const PAGE_SIZE: u64 = 1024 * 1024;
+const CHUNK_SIZE: usize = 512 * 1024;
+
+#[derive(Debug, Clone)]
+pub struct Loading {
+    pub id: usize,
+    pub path: PathBuf,
+    pub read: u64,
+    pub total: u64,
+}
+
+#[derive(Debug, Clone)]
+pub struct Page {
+    pub index: u64,
+    pub count: u64,
+    pub text: Arc<String>,
+}
+
+enum State {
+    Start(PathBuf),
+    Reading {
+        path: PathBuf,
+        file: tokio::fs::File,
+        bytes: Vec<u8>,
+        total: u64,
+    },
+    Finished,
+}
+
+pub fn stream(id: usize, path: PathBuf) -> Subscription<Message> {
+    subscription::unfold(id, State::Start(path), move |state| step(id, state))
+}
+
+async fn step(id: usize, state: State) -> (Message, State) {
+    match state {
+        State::Start(path) => match tokio::fs::File::open(&path).await {
+            Ok(file) => {
+                let total = file.metadata().await.map_or(0, |metadata| metadata.len());
+                let bytes = Vec::with_capacity(total as usize);
+                (
+                    Message::LoadProgress(id, 0, total),
+                    State::Reading {
+                        path,
+                        file,
+                        bytes,
+                        total,
+                    },
+                )
+            }
+            Err(error) => (
+                Message::StreamFinished(id, Err(Error::IOFailed(error.kind()))),
+                State::Finished,
+            ),
+        },
+        State::Reading {
+            path,
+            mut file,
+            mut bytes,
+            total,
+        } => {
+            let mut chunk = vec![0; CHUNK_SIZE];
+            match file.read(&mut chunk).await {
+                Ok(0) => {
+                    let loaded = crate::decode_file(path, &bytes, None);
+                    (Message::StreamFinished(id, Ok(loaded)), State::Finished)
+                }
+                Ok(read) => {
+                    bytes.extend_from_slice(&chunk[..read]);
+                    (
+                        Message::LoadProgress(id, bytes.len() as u64, total),
+                        State::Reading {
+                            path,
+                            file,
+                            bytes,
+                            total,
+                        },
+                    )
+                }
+                Err(error) => (
+                    Message::StreamFinished(id, Err(Error::IOFailed(error.kind()))),
+                    State::Finished,
+                ),
+            }
+        }
+        State::Finished => iced::futures::future::pending().await,
+    }
+}
+
+pub async fn read_page(path: PathBuf, index: u64) -> Result<Page, Error> {
+    let io_error = |error: std::io::Error| Error::IOFailed(error.kind());
+    let mut file = tokio::fs::File::open(&path).await.map_err(io_error)?;
+    let size = file.metadata().await.map_err(io_error)?.len();
+    let count = size.div_ceil(PAGE_SIZE).max(1);
+    let index = index.min(count - 1);
+    file.seek(SeekFrom::Start(index * PAGE_SIZE))
+        .await
+        .map_err(io_error)?;
+    let mut bytes = Vec::with_capacity(PAGE_SIZE as usize);
+    file.take(PAGE_SIZE)
+        .read_to_end(&mut bytes)
+        .await
+        .map_err(io_error)?;
+    Ok(Page {
+        index,
+        count,
+        text: Arc::new(LineEnding::normalize(&String::from_utf8_lossy(&bytes))),
+    })
+}
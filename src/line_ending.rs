@@ -0,0 +1,95 @@
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LineEnding {
+    #[default]
+    Lf,
+    CrLf,
+    Cr,
+}
+
+impl LineEnding {
+    pub const ALL: &'static [LineEnding] = &[LineEnding::Lf, LineEnding::CrLf, LineEnding::Cr];
+
+    pub fn detect(text: &str) -> Self {
+        let crlf = text.matches("\r\n").count();
+        let lf = text.matches('\n').count() - crlf;
+        let cr = text.matches('\r').count() - crlf;
+        if crlf > lf && crlf >= cr {
+            LineEnding::CrLf
+        } else if cr > lf && cr > crlf {
+            LineEnding::Cr
+        } else {
+            LineEnding::Lf
+        }
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LineEnding::Lf => "\n",
+            LineEnding::CrLf => "\r\n",
+            LineEnding::Cr => "\r",
+        }
+    }
+
+    /// Converts in a single pass with the final capacity reserved up front,
+    /// so decrypted text never leaves partial copies behind in freed memory.
+    pub fn normalize(text: &str) -> String {
+        let mut normalized = String::with_capacity(text.len());
+        let mut chars = text.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c == '\r' {
+                chars.next_if_eq(&'\n');
+                normalized.push('\n');
+            } else {
+                normalized.push(c);
+            }
+        }
+        normalized
+    }
+
+    pub fn apply(self, text: &str) -> String {
+        match self {
+            LineEnding::Lf => text.to_owned(),
+            _ => text.replace('\n', self.as_str()),
+        }
+    }
+}
+
+impl fmt::Display for LineEnding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            LineEnding::Lf => "LF",
+            LineEnding::CrLf => "CRLF",
+            LineEnding::Cr => "CR",
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LineEnding;
+
+    #[test]
+    fn detects_the_most_common_ending() {
+        assert_eq!(LineEnding::detect("a\r\nb\r\nc\n"), LineEnding::CrLf);
+        assert_eq!(LineEnding::detect("a\rb\rc\n"), LineEnding::Cr);
+        assert_eq!(LineEnding::detect("a\nb\r\n"), LineEnding::Lf);
+        assert_eq!(LineEnding::detect("no endings"), LineEnding::Lf);
+    }
+
+    #[test]
+    fn normalizes_and_applies_endings() {
+        let normalized = LineEnding::normalize("a\r\nb\rc\n");
+        assert_eq!(normalized, "a\nb\nc\n");
+        assert_eq!(LineEnding::CrLf.apply(&normalized), "a\r\nb\r\nc\r\n");
+        assert_eq!(LineEnding::Cr.apply("a\nb"), "a\rb");
+        assert_eq!(LineEnding::Lf.apply("a\nb"), "a\nb");
+    }
+
+    #[test]
+    fn displays_short_names() {
+        let names: Vec<String> = LineEnding::ALL.iter().map(ToString::to_string).collect();
+        assert_eq!(names, ["LF", "CRLF", "CR"]);
+    }
+}
@@ -0,0 +1,465 @@
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use iced::futures::{SinkExt, StreamExt, channel::mpsc, stream};
+use iced::{Subscription, subscription};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::process::{ChildStdin, ChildStdout};
+
+use crate::Message;
+use crate::language::Language;
+
+pub const SYNC_DELAY: Duration = Duration::from_millis(300);
+const MAX_MESSAGE_BYTES: usize = 64 * 1024 * 1024;
+
+static NEXT_REQUEST: AtomicU64 = AtomicU64::new(1);
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(default)]
+pub struct ServerConfig {
+    pub command: String,
+    pub args: Vec<String>,
+    pub trusted: bool,
+}
+
+impl ServerConfig {
+    pub fn command_line(&self) -> String {
+        std::iter::once(self.command.as_str())
+            .chain(self.args.iter().map(String::as_str))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Servers {
+    pub allowed: Vec<(Language, PathBuf)>,
+    pub declined: Vec<Language>,
+    pub clients: Vec<Client>,
+    pub diagnostics: BTreeMap<PathBuf, Vec<Diagnostic>>,
+    pub error: Option<String>,
+}
+
+impl Servers {
+    pub fn client(&self, language: Language) -> Option<&Client> {
+        self.clients
+            .iter()
+            .find(|client| client.language == language)
+    }
+
+    pub fn is_known(&self, language: Language) -> bool {
+        self.declined.contains(&language)
+            || self.allowed.iter().any(|(allowed, _)| *allowed == language)
+    }
+
+    pub fn counts(&self) -> (usize, usize) {
+        self.diagnostics
+            .values()
+            .flatten()
+            .fold((0, 0), |(errors, warnings), diagnostic| {
+                match diagnostic.severity {
+                    Severity::Error => (errors + 1, warnings),
+                    Severity::Warning => (errors, warnings + 1),
+                    _ => (errors, warnings),
+                }
+            })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Client {
+    pub language: Language,
+    sender: mpsc::UnboundedSender<Value>,
+}
+
+impl Client {
+    pub fn did_open(&self, path: &Path, text: &str, version: u64) {
+        self.notify(
+            "textDocument/didOpen",
+            json!({
+                "textDocument": {
+                    "uri": uri(path),
+                    "languageId": language_id(self.language),
+                    "version": version,
+                    "text": text,
+                }
+            }),
+        );
+    }
+
+    pub fn did_change(&self, path: &Path, text: &str, version: u64) {
+        self.notify(
+            "textDocument/didChange",
+            json!({
+                "textDocument": { "uri": uri(path), "version": version },
+                "contentChanges": [{ "text": text }],
+            }),
+        );
+    }
+
+    pub fn did_save(&self, path: &Path) {
+        self.notify(
+            "textDocument/didSave",
+            json!({ "textDocument": { "uri": uri(path) } }),
+        );
+    }
+
+    pub fn did_close(&self, path: &Path) {
+        self.notify(
+            "textDocument/didClose",
+            json!({ "textDocument": { "uri": uri(path) } }),
+        );
+    }
+
+    pub fn completion(&self, path: &Path, line: usize, character: usize) -> u64 {
+        let id = NEXT_REQUEST.fetch_add(1, Ordering::Relaxed);
+        let _ = self.sender.unbounded_send(json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "method": "textDocument/completion",
+            "params": {
+                "textDocument": { "uri": uri(path) },
+                "position": { "line": line, "character": character },
+            },
+        }));
+        id
+    }
+
+    fn notify(&self, method: &str, params: Value) {
+        let _ = self.sender.unbounded_send(json!({
+            "jsonrpc": "2.0",
+            "method": method,
+            "params": params,
+        }));
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Error,
+    Warning,
+    Information,
+    Hint,
+}
+
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub start: (usize, usize),
+    pub end: (usize, usize),
+    pub severity: Severity,
+    pub message: String,
+}
+
+impl Diagnostic {
+    fn parse(value: &Value) -> Option<Self> {
+        let position = |key: &str| {
+            let position = &value["range"][key];
+            Some((
+                position["line"].as_u64()? as usize,
+                position["character"].as_u64()? as usize,
+            ))
+        };
+        let severity = match value["severity"].as_u64() {
+            Some(2) => Severity::Warning,
+            Some(3) => Severity::Information,
+            Some(4) => Severity::Hint,
+            _ => Severity::Error,
+        };
+        let message = value["message"].as_str()?;
+        Some(Self {
+            start: position("start")?,
+            end: position("end")?,
+            severity,
+            message: match value["source"].as_str() {
+                Some(source) => format!("{source}: {message}"),
+                None => message.to_owned(),
+            },
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Event {
+    Ready(Client),
+    Diagnostics(PathBuf, Vec<Diagnostic>),
+    Completions(u64, Vec<String>),
+    Failed(Language, String),
+}
+
+pub fn connect(language: Language, server: ServerConfig, root: PathBuf) -> Subscription<Message> {
+    let id = (language.token(), server.command.clone(), root.clone());
+    subscription::channel(id, 100, move |mut output| async move {
+        if let Err(error) = run(language, &server, &root, &mut output).await {
+            let _ = output
+                .send(Message::Lsp(Event::Failed(
+                    language,
+                    format!("{}: {error}", server.command),
+                )))
+                .await;
+        }
+        std::future::pending().await
+    })
+}
+
+enum Step {
+    Incoming(Result<Value, String>),
+    Outgoing(Value),
+}
+
+async fn run(
+    language: Language,
+    server: &ServerConfig,
+    root: &Path,
+    output: &mut mpsc::Sender<Message>,
+) -> Result<(), String> {
+    let mut child = tokio::process::Command::new(&server.command)
+        .args(&server.args)
+        .current_dir(root)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| error.to_string())?;
+    let mut stdin = child.stdin.take().ok_or("no stdin")?;
+    let mut stdout = BufReader::new(child.stdout.take().ok_or("no stdout")?);
+
+    write(
+        &mut stdin,
+        &json!({
+            "jsonrpc": "2.0",
+            "id": 0,
+            "method": "initialize",
+            "params": {
+                "processId": std::process::id(),
+                "rootUri": uri(root),
+                "capabilities": {
+                    "textDocument": {
+                        "synchronization": { "didSave": true },
+                        "publishDiagnostics": {},
+                        "completion": { "completionItem": { "snippetSupport": false } },
+                    },
+                    "workspace": { "configuration": true },
+                },
+            },
+        }),
+    )
+    .await?;
+    loop {
+        let message = read(&mut stdout).await?;
+        if message["id"] == json!(0) && message.get("method").is_none() {
+            if let Some(error) = message["error"]["message"].as_str() {
+                return Err(error.to_owned());
+            }
+            break;
+        }
+    }
+    write(
+        &mut stdin,
+        &json!({ "jsonrpc": "2.0", "method": "initialized", "params": {} }),
+    )
+    .await?;
+
+    let (sender, outgoing) = mpsc::unbounded();
+    let _ = output
+        .send(Message::Lsp(Event::Ready(Client { language, sender })))
+        .await;
+    let incoming = stream::unfold(stdout, |mut stdout| async move {
+        let message = read(&mut stdout).await;
+        Some((message, stdout))
+    });
+    let mut steps = Box::pin(stream::select(
+        incoming.map(Step::Incoming),
+        outgoing.map(Step::Outgoing),
+    ));
+    while let Some(step) = steps.next().await {
+        match step {
+            Step::Outgoing(message) => write(&mut stdin, &message).await?,
+            Step::Incoming(message) => {
+                let message = message?;
+                if let Some(reply) = reply(&message) {
+                    write(&mut stdin, &reply).await?;
+                } else if let (Some(id), None) = (message["id"].as_u64(), message.get("method")) {
+                    let _ = output
+                        .send(Message::Lsp(Event::Completions(
+                            id,
+                            completions(&message["result"]),
+                        )))
+                        .await;
+                } else if message["method"] == "textDocument/publishDiagnostics" {
+                    let params = &message["params"];
+                    let Some(path) = params["uri"].as_str().and_then(path) else {
+                        continue;
+                    };
+                    let diagnostics = params["diagnostics"]
+                        .as_array()
+                        .map(|diagnostics| {
+                            diagnostics.iter().filter_map(Diagnostic::parse).collect()
+                        })
+                        .unwrap_or_default();
+                    let _ = output
+                        .send(Message::Lsp(Event::Diagnostics(path, diagnostics)))
+                        .await;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+fn completions(result: &Value) -> Vec<String> {
+    let items = match result {
+        Value::Array(items) => items,
+        Value::Object(list) => match list.get("items") {
+            Some(Value::Array(items)) => items,
+            _ => return Vec::new(),
+        },
+        _ => return Vec::new(),
+    };
+    items
+        .iter()
+        .filter_map(|item| {
+            item["insertText"]
+                .as_str()
+                .or_else(|| item["label"].as_str())
+                .map(|text| text.trim().to_owned())
+        })
+        .collect()
+}
+
+fn reply(message: &Value) -> Option<Value> {
+    let id = message.get("id")?;
+    let method = message["method"].as_str()?;
+    let result = match method {
+        "workspace/configuration" => {
+            let items = message["params"]["items"].as_array().map_or(0, Vec::len);
+            Value::Array(vec![Value::Null; items])
+        }
+        _ => Value::Null,
+    };
+    Some(json!({ "jsonrpc": "2.0", "id": id, "result": result }))
+}
+
+async fn write(stdin: &mut ChildStdin, message: &Value) -> Result<(), String> {
+    let body = message.to_string();
+    let header = format!("Content-Length: {}\r\n\r\n", body.len());
+    stdin
+        .write_all(header.as_bytes())
+        .await
+        .map_err(|error| error.to_string())?;
+    stdin
+        .write_all(body.as_bytes())
+        .await
+        .map_err(|error| error.to_string())?;
+    stdin.flush().await.map_err(|error| error.to_string())
+}
+
+async fn read(stdout: &mut BufReader<ChildStdout>) -> Result<Value, String> {
+    let mut length = None;
+    loop {
+        let mut line = String::new();
+        let read = stdout
+            .read_line(&mut line)
+            .await
+            .map_err(|error| error.to_string())?;
+        if read == 0 {
+            return Err(String::from("the server exited"));
+        }
+        let line = line.trim_end();
+        if line.is_empty() && length.is_some() {
+            break;
+        }
+        if let Some(value) = line.strip_prefix("Content-Length:") {
+            length = value.trim().parse::<usize>().ok();
+        }
+    }
+    let length = length.unwrap_or_default();
+    if length > MAX_MESSAGE_BYTES {
+        return Err(format!("the server sent a {length} byte message"));
+    }
+    let mut body = vec![0; length];
+    stdout
+        .read_exact(&mut body)
+        .await
+        .map_err(|error| error.to_string())?;
+    serde_json::from_slice(&body).map_err(|error| error.to_string())
+}
+
+fn language_id(language: Language) -> &'static str {
+    match language {
+        Language::PlainText => "plaintext",
+        Language::Rust => "rust",
+        Language::Python => "python",
+        Language::JavaScript => "javascript",
+        Language::C => "c",
+        Language::Cpp => "cpp",
+        Language::Go => "go",
+        Language::Java => "java",
+        Language::Html => "html",
+        Language::Css => "css",
+        Language::Json => "json",
+        Language::Yaml => "yaml",
+        Language::Xml => "xml",
+        Language::Markdown => "markdown",
+        Language::Shell => "shellscript",
+        Language::Sql => "sql",
+        Language::Lua => "lua",
+        Language::Makefile => "makefile",
+    }
+}
+
+pub fn root(path: &Path) -> PathBuf {
+    let directory = canonical(path.parent().unwrap_or(path));
+    directory
+        .ancestors()
+        .find(|directory| directory.join(".git").exists())
+        .unwrap_or(&directory)
+        .to_path_buf()
+}
+
+pub fn canonical(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn uri(path: &Path) -> String {
+    let path = canonical(path).to_string_lossy().replace('\\', "/");
+    let mut uri = String::from("file://");
+    if !path.starts_with('/') {
+        uri.push('/');
+    }
+    for byte in path.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' | b':' => {
+                uri.push(byte as char);
+            }
+            _ => uri.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    uri
+}
+
+fn path(uri: &str) -> Option<PathBuf> {
+    let encoded = uri.strip_prefix("file://")?;
+    let mut bytes = Vec::with_capacity(encoded.len());
+    let mut index = 0;
+    while index < encoded.len() {
+        if encoded.as_bytes()[index] == b'%' {
+            bytes.push(u8::from_str_radix(encoded.get(index + 1..index + 3)?, 16).ok()?);
+            index += 3;
+        } else {
+            bytes.push(encoded.as_bytes()[index]);
+            index += 1;
+        }
+    }
+    let decoded = String::from_utf8(bytes).ok()?;
+    let decoded = match decoded.as_bytes() {
+        [b'/', _, b':', ..] => &decoded[1..],
+        _ => &decoded,
+    };
+    Some(PathBuf::from(decoded))
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use iced::widget::text_editor::{Action, Edit, Motion};
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+pub const LAST: &str = "(last recorded)";
+pub const MAX_REPEAT: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Step {
+    Insert(char),
+    Paste(String),
+    Enter,
+    Backspace,
+    Delete,
+    Move(Direction),
+    Select(Direction),
+    SelectWord,
+    SelectLine,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Direction {
+    Left,
+    Right,
+    Up,
+    Down,
+    WordLeft,
+    WordRight,
+    Home,
+    End,
+    PageUp,
+    PageDown,
+    DocumentStart,
+    DocumentEnd,
+}
+
+impl Direction {
+    fn of(motion: Motion) -> Self {
+        match motion {
+            Motion::Left => Direction::Left,
+            Motion::Right => Direction::Right,
+            Motion::Up => Direction::Up,
+            Motion::Down => Direction::Down,
+            Motion::WordLeft => Direction::WordLeft,
+            Motion::WordRight => Direction::WordRight,
+            Motion::Home => Direction::Home,
+            Motion::End => Direction::End,
+            Motion::PageUp => Direction::PageUp,
+            Motion::PageDown => Direction::PageDown,
+            Motion::DocumentStart => Direction::DocumentStart,
+            Motion::DocumentEnd => Direction::DocumentEnd,
+        }
+    }
+
+    fn motion(self) -> Motion {
+        match self {
+            Direction::Left => Motion::Left,
+            Direction::Right => Motion::Right,
+            Direction::Up => Motion::Up,
+            Direction::Down => Motion::Down,
+            Direction::WordLeft => Motion::WordLeft,
+            Direction::WordRight => Motion::WordRight,
+            Direction::Home => Motion::Home,
+            Direction::End => Motion::End,
+            Direction::PageUp => Motion::PageUp,
+            Direction::PageDown => Motion::PageDown,
+            Direction::DocumentStart => Motion::DocumentStart,
+            Direction::DocumentEnd => Motion::DocumentEnd,
+        }
+    }
+}
+
+impl Step {
+    pub fn of(action: &Action) -> Option<Self> {
+        Some(match action {
+            Action::Edit(Edit::Insert(c)) => Step::Insert(*c),
+            Action::Edit(Edit::Paste(text)) => Step::Paste(text.as_str().to_owned()),
+            Action::Edit(Edit::Enter) => Step::Enter,
+            Action::Edit(Edit::Backspace) => Step::Backspace,
+            Action::Edit(Edit::Delete) => Step::Delete,
+            Action::Move(motion) => Step::Move(Direction::of(*motion)),
+            Action::Select(motion) => Step::Select(Direction::of(*motion)),
+            Action::SelectWord => Step::SelectWord,
+            Action::SelectLine => Step::SelectLine,
+            _ => return None,
+        })
+    }
+
+    pub fn action(&self) -> Action {
+        match self {
+            Step::Insert(c) => Action::Edit(Edit::Insert(*c)),
+            Step::Paste(text) => Action::Edit(Edit::Paste(Arc::new(text.clone()))),
+            Step::Enter => Action::Edit(Edit::Enter),
+            Step::Backspace => Action::Edit(Edit::Backspace),
+            Step::Delete => Action::Edit(Edit::Delete),
+            Step::Move(direction) => Action::Move(direction.motion()),
+            Step::Select(direction) => Action::Select(direction.motion()),
+            Step::SelectWord => Action::SelectWord,
+            Step::SelectLine => Action::SelectLine,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Macros {
+    pub saved: BTreeMap<String, Vec<Step>>,
+    #[serde(skip)]
+    pub last: Vec<Step>,
+}
+
+impl Macros {
+    pub fn names(&self) -> Vec<String> {
+        std::iter::once(String::from(LAST))
+            .chain(self.saved.keys().cloned())
+            .collect()
+    }
+
+    pub fn get(&self, name: &str) -> Option<&[Step]> {
+        if name == LAST {
+            Some(&self.last)
+        } else {
+            self.saved.get(name).map(Vec::as_slice)
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub selected: String,
+    pub count: String,
+    pub name: String,
+}
+
+impl Default for Prompt {
+    fn default() -> Self {
+        Self {
+            selected: String::from(LAST),
+            count: String::from("1"),
+            name: String::new(),
+        }
+    }
+}
+
+impl Prompt {
+    pub fn count(&self) -> usize {
+        self.count.trim().parse().unwrap_or(1).clamp(1, MAX_REPEAT)
+    }
+}
+
+fn macros_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("macros.toml"))
+}
+
+pub async fn load() -> Macros {
+    let Some(path) = macros_file() else {
+        return Macros::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(macros: Macros) -> Result<(), Error> {
+    let path = macros_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents =
+        toml::to_string(&macros).map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
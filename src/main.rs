@@ -1,196 +1,7473 @@
+mod appearance;
+mod assist;
+mod bookmarks;
+mod brackets;
+mod buffer;
+mod carets;
+mod cli;
+mod clips;
+mod colors;
+mod comment;
+mod complete;
+mod compression;
+mod config;
+mod convert;
+mod crypt;
+mod decoration;
+mod diff;
+mod editorconfig;
+mod encoding;
+mod explorer;
+mod export;
+mod fold;
+mod follow;
+mod fonts;
+mod format;
+mod formatter;
+mod git;
+mod grep;
+mod guides;
+mod hex;
+mod highlight;
+mod history;
+mod i18n;
+mod indent;
+mod instance;
+mod keymap;
+mod language;
+mod large;
+mod line_ending;
+mod lsp;
+mod macros;
+mod markdown;
+mod minimap;
+mod motion;
+mod outline;
+mod palette;
+mod plugins;
+mod popup;
+mod recent;
+mod recovery;
+mod remote;
+mod render;
+mod replace;
+mod rope;
+mod saves;
+mod scratch;
+mod search;
+mod session;
+mod snippet;
+mod spell;
+mod state;
+mod stats;
+mod table;
+mod templates;
+mod terminal;
+mod toast;
+mod todos;
+mod transform;
+mod undo;
+mod unsaved;
+mod versions;
+mod view_state;
+mod vim;
+mod watcher;
+mod web;
+mod whitespace;
+mod workspace;
+
 use std::{
+    collections::{BTreeMap, HashMap},
     io,
-    path::{Path, PathBuf},
+    ops::Range,
+    path::PathBuf,
     sync::Arc,
+    time::{Duration, Instant},
 };
 
 use iced::{
-    Application, Command, Element, Font, Length, Settings, Subscription, Theme, executor,
-    highlighter::{self, Highlighter},
-    keyboard, theme,
+    Application, Color, Command, Element, Event, Font, Length, Settings, Subscription, Theme,
+    Vector, clipboard, executor, highlighter, keyboard, mouse, subscription, theme,
     widget::{
-        button, column, container, horizontal_space, pick_list, row, text, text_editor, tooltip,
+        PaneGrid, button, checkbox, column, container, horizontal_space, pane_grid, pick_list,
+        progress_bar, row, scrollable, slider, text, text_editor, text_input, tooltip,
     },
+    window,
 };
+use zeroize::{Zeroize, Zeroizing};
+
+use buffer::Buffer;
+use config::{Config, LineNumbers};
+use convert::Conversion;
+use encoding::TextEncoding;
+use indent::Indent;
+use language::Language;
+use line_ending::LineEnding;
+use recent::{Recent, RecentFile};
+use search::Search;
+use spell::Spelling;
+
+fn main() -> iced::Result {
+    let raw: Vec<String> = std::env::args().skip(1).collect();
+    let args = match cli::parse(raw.clone()) {
+        Ok(args) if args.help => {
+            println!("{}", cli::USAGE);
+            return Ok(());
+        }
+        Ok(args) => args,
+        Err(error) => {
+            eprintln!("{error}\n{}", cli::USAGE);
+            std::process::exit(2);
+        }
+    };
+    if !args.files.is_empty() && config::read().single_instance && instance::forward(&raw) {
+        return Ok(());
+    }
+    Editor::run(Settings {
+        flags: args,
+        default_font: DEFAULT_FONT,
+        fonts: vec![
+            include_bytes!("../fonts/editor_icons.ttf")
+                .as_slice()
+                .into(),
+        ],
+        window: window::Settings {
+            exit_on_close_request: false,
+            ..window::Settings::default()
+        },
+        ..Settings::default()
+    })
+}
+
+struct Editor {
+    buffers: Vec<Buffer>,
+    active: usize,
+    next_buffer_id: usize,
+    theme: highlighter::Theme,
+    error: Option<Error>,
+    toasts: toast::Toasts,
+    notifications: bool,
+    statistics: Option<stats::Panel>,
+    render: render::Cache,
+    todos: Option<todos::Panel>,
+    last_command: Option<Message>,
+    pending_opens: Vec<cli::FileArg>,
+    check_session: bool,
+    check_scratch: bool,
+    histories: HashMap<PathBuf, undo::Saved>,
+    views: HashMap<PathBuf, view_state::ViewState>,
+    state: state::State,
+    viewport_lines: usize,
+    window_width: u32,
+    confirmation: Option<Confirmation>,
+    after_save: Option<Confirmation>,
+    search: Option<Search>,
+    config: Config,
+    font: Font,
+    recovered: Vec<recovery::Entry>,
+    loading: Vec<large::Loading>,
+    recent: Recent,
+    recent_filter: Option<String>,
+    palette_filter: Option<String>,
+    goto_line: Option<String>,
+    system_is_dark: bool,
+    modifiers: keyboard::Modifiers,
+    column_anchor: Option<(usize, usize)>,
+    is_hovering_files: bool,
+    start_page: bool,
+    syntax_error: Option<(usize, u64, format::Invalid)>,
+    terminal: Option<terminal::Terminal>,
+    context_menu: Option<(pane_grid::Pane, iced::Point)>,
+    color_picker: Option<colors::Picker>,
+    unsaved: Option<unsaved::Panel>,
+    versions: Option<versions::Panel>,
+    next_terminal: usize,
+    explorer: explorer::Explorer,
+    grep: Option<grep::Panel>,
+    next_grep_id: usize,
+    panes: pane_grid::State<PaneState>,
+    focus: pane_grid::Pane,
+    preview: bool,
+    outline: bool,
+    servers: lsp::Servers,
+    pending_server: Option<Language>,
+    pending_formatter: Option<formatter::Pending>,
+    pending_terminal: Option<terminal::Pending>,
+    pending_link: Option<String>,
+    problems: bool,
+    completion: Option<complete::Completion>,
+    snippets: snippet::Snippets,
+    spelling: spell::Spelling,
+    diff: Option<diff::View>,
+    save_denied: Option<usize>,
+    save_conflict: Option<usize>,
+    saves: saves::Queue,
+    zoomed_at: Option<Instant>,
+    sync_at: Option<(Instant, bool)>,
+    font_picker: Option<fonts::Picker>,
+    bookmarks: bookmarks::Bookmarks,
+    bookmark_panel: bool,
+    recording: Option<Vec<macros::Step>>,
+    macros: macros::Macros,
+    macro_prompt: Option<macros::Prompt>,
+    vim: vim::Vim,
+    plugins: plugins::Plugins,
+    clips: clips::History,
+    clip_picker: Option<clips::Picker>,
+    template_picker: Option<Vec<templates::Template>>,
+    workspace: workspace::Workspace,
+    finder: Option<String>,
+    remote_prompt: Option<remote::Prompt>,
+    crypt_prompt: Option<crypt::Prompt>,
+    credentials: remote::Credentials,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct PaneState {
+    buffer: usize,
+    cursor: (usize, usize),
+    top_line: usize,
+}
+
+impl PaneState {
+    fn of(buffer: &Buffer) -> Self {
+        Self {
+            buffer: buffer.id,
+            cursor: buffer.content.cursor_position(),
+            top_line: buffer.top_line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Confirmation {
+    buffer: usize,
+    exit: bool,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Choice {
+    Save,
+    Discard,
+    Cancel,
+}
+#[derive(Debug, Clone)]
+enum Message {
+    New,
+    Open,
+    Save,
+    SaveAs,
+    FileSaved(usize, Result<(PathBuf, saves::Stamp), Error>),
+    OverwriteConflict,
+    SaveConflictAs,
+    CompareConflict,
+    CancelSaveConflict,
+    Edit(text_editor::Action),
+    FileOpened(Result<Loaded, Error>),
+    ThemeSelected(highlighter::Theme),
+    AppearanceSelected(appearance::Appearance),
+    SystemThemeChecked(bool),
+    RepeatLast,
+    GoToOffset(usize),
+    NewFromClipboard,
+    NewFromTemplate,
+    NewScratch,
+    ScratchLoaded(scratch::Scratch),
+    ScratchSaved(Result<(), Error>),
+    TemplatesLoaded(Vec<templates::Template>),
+    TemplateSelected(usize),
+    ClipboardRead(Option<String>),
+    TogglePathDisplay,
+    ToggleTypewriter,
+    WindowResized(u32, u32),
+    StateLoaded(state::State),
+    StateSaved(Result<(), Error>),
+    SelectTab(usize),
+    CloseTab(usize),
+    CloseActiveTab,
+    NextTab,
+    PreviousTab,
+    LanguageSelected(Language),
+    CloseRequested,
+    Confirm(Choice),
+    ToggleFind,
+    ToggleReplace,
+    CloseFind,
+    FindQueryChanged(String),
+    ReplacementChanged(String),
+    SearchOptionsChanged(search::Options),
+    FindNext,
+    FindPrevious,
+    ReplaceOne,
+    ReplaceAll,
+    Undo,
+    Redo,
+    ConfigTick,
+    ConfigLoaded(Result<Config, Error>),
+    ZoomIn,
+    ZoomOut,
+    ResetZoom,
+    WheelScrolled(mouse::ScrollDelta),
+    ZoomSaved(Result<(), Error>),
+    ZoomTick,
+    ToastTick,
+    DismissToast(u64),
+    ToggleNotifications,
+    ToggleStatistics,
+    StatisticsTick,
+    StatisticsComputed(stats::Report),
+    RenderTick,
+    LayoutComputed(usize, Option<render::Layout>),
+    ToggleTodos,
+    TodosTick,
+    TodosComputed(todos::Key, Vec<todos::Annotation>),
+    ToggleWorkspaceTodos(bool),
+    TodosFound(usize, Vec<grep::Hit>),
+    TodosFinished(usize),
+    ClearNotifications,
+    ClipboardChanged,
+    ClipboardCopied(Option<String>),
+    ToggleClipboardHistory,
+    PasteFromHistory(usize),
+    ReindentPasteToggled(bool),
+    PlainPasteToggled(bool),
+    ReloadPlugins,
+    PluginsLoaded(Vec<(PathBuf, String)>),
+    RunPlugin(usize),
+    ToggleRecording,
+    ShowMacros,
+    MacroSelected(String),
+    MacroCountChanged(String),
+    MacroNameChanged(String),
+    PlayMacro,
+    SaveMacro,
+    CloseMacros,
+    MacrosLoaded(macros::Macros),
+    MacrosSaved(Result<(), Error>),
+    ToggleFold(usize),
+    FoldAtCursor,
+    FoldAll,
+    UnfoldAll,
+    ToggleBookmark,
+    NextBookmark,
+    PreviousBookmark,
+    ToggleBookmarkPanel,
+    BookmarksLoaded(bookmarks::Bookmarks),
+    BookmarksSaved(Result<(), Error>),
+    ToggleMinimap,
+    ToggleWhitespace,
+    ToggleCurrentLine,
+    ToggleRulers,
+    ToggleIndentGuides,
+    SettingSaved(Result<(), Error>),
+    MinimapScrolled(pane_grid::Pane, usize),
+    ChooseFont,
+    FontsListed(Vec<&'static str>),
+    FontFilterChanged(String),
+    FontSelected(&'static str),
+    ApplyFont,
+    CancelFont,
+    FontSaved(Result<(), Error>),
+    AutosaveTick,
+    RecoveryUpdated(Result<(), Error>),
+    RecoveryFound(Vec<recovery::Entry>),
+    RestoreRecovery,
+    DiscardRecovery,
+    Exit,
+    LoadProgress(usize, u64, u64),
+    StreamFinished(usize, Result<Loaded, Error>),
+    EncodingSelected(TextEncoding),
+    ReopenWithEncoding,
+    Reopened(usize, Result<Loaded, Error>),
+    LineEndingSelected(LineEnding),
+    RecentLoaded(Recent),
+    RecentSaved(Result<(), Error>),
+    OpenRecent(RecentFile),
+    ToggleRecent,
+    RecentFilterChanged(String),
+    OpenFirstRecent,
+    Dismiss,
+    SessionRestored(session::Session, Vec<Result<Loaded, Error>>),
+    LoadPage(usize, u64),
+    PageLoaded(usize, Result<large::Page, Error>),
+    FileChanged(PathBuf),
+    FileRenamed(PathBuf, PathBuf),
+    DiskChecked(usize, Result<Loaded, Error>),
+    ReloadFromDisk(usize),
+    KeepBuffer(usize),
+    TogglePalette,
+    PaletteFilterChanged(String),
+    RunCommand(palette::Entry),
+    RunFirstCommand,
+    ToggleGoToLine,
+    GoToLineChanged(String),
+    SubmitGoToLine,
+    GoToLine(usize, usize),
+    ToggleWrap,
+    ModifiersChanged(keyboard::Modifiers),
+    SelectNextOccurrence,
+    JumpToBracket,
+    ToggleComment,
+    MoveLinesUp,
+    MoveLinesDown,
+    DuplicateLines,
+    JoinLines,
+    DeleteLines,
+    Convert(Conversion),
+    ToggleOutline,
+    Lsp(lsp::Event),
+    SyncTick,
+    StartServer(Language),
+    TrustServer(Language),
+    DeclineServer(Language),
+    ToggleProblems,
+    AcceptCompletion(usize),
+    IndentSelected(Indent),
+    InsertTab,
+    PreviousTabStop,
+    SnippetsLoaded(Result<snippet::Snippets, Error>),
+    ToggleSpellCheck,
+    DictionaryLoaded(Result<(Arc<spell::Dictionary>, Vec<String>), Error>),
+    SuggestSpelling,
+    ToggleHex,
+    ToggleTable,
+    ToggleTerminal,
+    RestartTerminal,
+    TerminalStarted(usize, terminal::Handle),
+    TerminalOutput(usize, String),
+    TerminalExited(usize, String),
+    TerminalInputChanged(String),
+    TerminalSubmit,
+    TerminalInterrupt,
+    RunCurrentFile,
+    RunTerminal(terminal::Pending),
+    TrustTerminal(terminal::Pending),
+    DeclineTerminal(terminal::Pending),
+    ContextMenu(pane_grid::Pane, iced::Point),
+    PickColor(pane_grid::Pane, usize),
+    PickColorAtCursor,
+    ColorChannelChanged(usize, u8),
+    ColorHexChanged(String),
+    ApplyColor,
+    CancelColor,
+    ContextAction(Box<Message>),
+    Cut,
+    Copy,
+    Paste,
+    Pasted(Option<String>),
+    SelectAll,
+    CopyPath,
+    RevealInFolder,
+    SearchSelectionInFiles,
+    AcceptPrompt,
+    FocusNext,
+    FocusPrevious,
+    AnnouncePosition,
+    ShowUnsavedChanges,
+    UnsavedLoaded(usize, Result<Loaded, Error>),
+    RevertUnsaved(usize),
+    CloseUnsaved,
+    ShowLocalHistory,
+    VersionRecorded(Result<(), Error>),
+    VersionsListed(usize, Vec<versions::Version>),
+    OpenVersion(usize, versions::Action),
+    VersionRead(usize, versions::Action, Result<String, Error>),
+    CloseLocalHistory,
+    FormatDocument,
+    MinifyDocument,
+    ValidateDocument,
+    FormatExternally,
+    RunFormatter(formatter::Pending),
+    TrustFormatter(formatter::Pending),
+    DeclineFormatter(formatter::Pending),
+    Formatted(usize, u64, bool, Result<String, String>),
+    TableCellChanged(usize, usize, String),
+    SortTable(usize),
+    ToggleFollow,
+    FollowStarted(usize, Result<u64, Error>),
+    FollowTick,
+    Appended(usize, Result<follow::Appended, Error>),
+    ReplaceWord(String),
+    AddToDictionary,
+    CloseSpelling,
+    DictionarySaved(Result<(), Error>),
+    GitInspected(usize, Option<git::Info>),
+    RevertHunk,
+    DiffAgainstHead,
+    CompareWithFile,
+    CompareWithSaved,
+    CompareLoaded(Result<Loaded, Error>),
+    CloseDiff,
+    ExportHtml,
+    ExportPdf,
+    Print,
+    Exported(Result<PathBuf, Error>),
+    FilesHovered(bool),
+    FileDropped(PathBuf),
+    InstanceRequested(instance::Request),
+    ToggleExplorer,
+    OpenFolder,
+    OpenRemote,
+    OpenUrl,
+    UrlOpened(String, Result<Vec<u8>, String>),
+    RemoteUrlChanged(String),
+    RemotePasswordChanged(String),
+    PassphraseChanged(String),
+    PassphraseConfirmationChanged(String),
+    SubmitPassphrase,
+    Decrypted(Result<Loaded, Error>),
+    EncryptBuffer,
+    ConnectRemote,
+    RemoteOpened(
+        remote::Location,
+        Option<String>,
+        Result<Vec<u8>, remote::Failure>,
+    ),
+    RemoteSaved(usize, Result<(), remote::Failure>),
+    WorkspaceIndexed(PathBuf, Vec<PathBuf>),
+    ToggleFinder,
+    FinderQueryChanged(String),
+    OpenFirstFinderMatch,
+    OpenWorkspaceFile(PathBuf),
+    FolderPicked(Option<PathBuf>),
+    DirectoryRead(PathBuf, Result<Vec<explorer::Entry>, Error>),
+    ExplorerEntryPressed(explorer::Entry),
+    ToggleGrep,
+    GrepQueryChanged(String),
+    GrepOptionsChanged(search::Options),
+    GrepPickFolder,
+    GrepFolderPicked(Option<PathBuf>),
+    RunGrep,
+    CancelGrep,
+    ToggleGrepReplace,
+    GrepReplacementChanged(String),
+    PreviewReplace,
+    ReplacePreviewed(Vec<replace::FilePreview>),
+    ToggleHunk(usize, usize),
+    ApplyReplace,
+    DiscardReplace,
+    ReplaceApplied(Result<replace::Summary, String>),
+    GrepFound(usize, Vec<grep::Hit>),
+    GrepFinished(usize),
+    OpenHit(grep::Hit),
+    PaneEdit(pane_grid::Pane, text_editor::Action),
+    FocusPane(pane_grid::Pane),
+    SplitPane(pane_grid::Axis),
+    ClosePane,
+    PaneResized(pane_grid::ResizeEvent),
+    TogglePreview,
+    OpenLink(String),
+    FollowLink(String),
+    DeclineLink,
+    SaveDeniedAs,
+    CancelSaveDenied,
+    ToggleReadonly,
+    ToggleSaveTransforms,
+}
+
+impl Message {
+    fn is_repeatable(&self) -> bool {
+        matches!(
+            self,
+            Message::New
+                | Message::NewFromClipboard
+                | Message::Open
+                | Message::Save
+                | Message::SaveAs
+                | Message::ThemeSelected(_)
+                | Message::AppearanceSelected(_)
+                | Message::TogglePathDisplay
+                | Message::ToggleTypewriter
+                | Message::ToggleWrap
+                | Message::Convert(_)
+                | Message::ToggleComment
+                | Message::MoveLinesUp
+                | Message::MoveLinesDown
+                | Message::DuplicateLines
+                | Message::JoinLines
+                | Message::DeleteLines
+                | Message::CloseActiveTab
+                | Message::FindNext
+                | Message::FindPrevious
+                | Message::ReplaceOne
+                | Message::ReplaceAll
+                | Message::Undo
+                | Message::Redo
+        )
+    }
+}
+
+const CONTEXT_MENU_WIDTH: f32 = 240.0;
+const COLOR_PICKER_WIDTH: f32 = 240.0;
+const NEW_TIP: &str = "tip-new";
+const OPEN_TIP: &str = "tip-open";
+const SAVE_TIP: &str = "tip-save";
+const SAVE_AS_TIP: &str = "tip-save-as";
+const UNDO_TIP: &str = "tip-undo";
+const REDO_TIP: &str = "tip-redo";
+
+const DEFAULT_FONT: Font = Font::with_name("JetBrainsMono Nerd Font Propo");
+const CONFIG_POLL_INTERVAL: Duration = Duration::from_secs(2);
+const ZOOM_NOTICE: Duration = Duration::from_millis(1500);
+
+const FIND_INPUT: &str = "find";
+const RECENT_INPUT: &str = "recent";
+const FINDER_INPUT: &str = "finder";
+const REMOTE_INPUT: &str = "remote";
+const PASSWORD_INPUT: &str = "password";
+const PASSPHRASE_INPUT: &str = "passphrase";
+const PALETTE_INPUT: &str = "palette";
+const GOTO_INPUT: &str = "goto";
+const GREP_INPUT: &str = "grep";
+const FONT_INPUT: &str = "font";
+
+const PALETTE_ENTRIES: usize = 12;
+const EXPLORER_WIDTH: f32 = 250.0;
+const GREP_HEIGHT: f32 = 200.0;
+const GREP_SHOWN: usize = 500;
+const MAX_PATH_CHARS: usize = 80;
+const LINE_HEIGHT: f32 = 16.0 * 1.3;
+const CHAR_WIDTH: f32 = 16.0 * 0.6;
+const EDITOR_PADDING: f32 = 5.0;
+const GUTTER_COLOR: Color = Color::from_rgb(0.5, 0.5, 0.5);
+const ADDED_COLOR: Color = Color::from_rgb(0.35, 0.7, 0.35);
+const MODIFIED_COLOR: Color = Color::from_rgb(0.35, 0.55, 0.85);
+const DELETED_COLOR: Color = Color::from_rgb(0.85, 0.35, 0.35);
+const CHROME_HEIGHT: f32 = 130.0;
+const CHROME_WIDTH: f32 = 80.0;
+
+impl Editor {
+    fn export_pdf(&self) -> Vec<u8> {
+        let buffer = self.buffer();
+        let header = buffer
+            .path
+            .as_ref()
+            .map_or_else(|| buffer.title(), |path| path.display().to_string());
+        export::pdf(
+            &buffer.content.text(),
+            &header,
+            highlighter::Theme::InspiredGitHub,
+            buffer.language.token(),
+        )
+    }
+
+    fn buffer(&self) -> &Buffer {
+        &self.buffers[self.active]
+    }
+
+    fn buffer_mut(&mut self) -> &mut Buffer {
+        let buffer = &mut self.buffers[self.active];
+        buffer.unfold();
+        buffer
+    }
+
+    fn open_buffer(&mut self, path: Option<PathBuf>, text: &str) -> &mut Buffer {
+        let mut buffer = Buffer::new(self.next_buffer_id, path, text);
+        buffer.history.set_depth(self.config.history_depth);
+        buffer.wrap = self.config.word_wrap;
+        buffer.indent = self
+            .config
+            .detect_indentation
+            .then(|| Indent::detect(text))
+            .flatten()
+            .unwrap_or_else(|| self.config.indent());
+        buffer.transforms = self.config.transforms();
+        self.next_buffer_id += 1;
+        if self.buffer().is_blank() {
+            self.buffers[self.active] = buffer;
+        } else {
+            self.buffers.push(buffer);
+            self.active = self.buffers.len() - 1;
+        }
+        self.buffer_mut()
+    }
+
+    fn close_buffer(&mut self, index: usize) {
+        if index >= self.buffers.len() {
+            return;
+        }
+        if self.config.persist_undo
+            && let Some(saved) = self.buffers[index].saved_history()
+        {
+            self.histories.insert(saved.path.clone(), saved);
+        }
+        if self.config.persist_view_state
+            && let Some(state) = self.buffers[index].view_state()
+        {
+            self.views.insert(state.path.clone(), state);
+        }
+        if self.buffers[index].passphrase.is_some() {
+            self.buffers[index].wipe();
+            self.render.wipe(self.buffers[index].id);
+        } else {
+            self.render.remove(self.buffers[index].id);
+        }
+        let buffer = &self.buffers[index];
+        if let (Some(path), Some(_), Some(client)) = (
+            buffer.path.as_deref(),
+            buffer.synced,
+            self.servers.client(buffer.language),
+        ) {
+            client.did_close(path);
+        }
+        self.buffers.remove(index);
+        if self.buffers.is_empty() {
+            let blank = Buffer::new(self.next_buffer_id, None, "");
+            self.buffers.push(blank);
+            self.next_buffer_id += 1;
+        }
+        if index < self.active {
+            self.active -= 1;
+        }
+        self.active = self.active.min(self.buffers.len() - 1);
+    }
+
+    fn request_server(&mut self, language: Language, path: &std::path::Path) {
+        if self.servers.is_known(language) || self.pending_server.is_some() {
+            return;
+        }
+        let Some(server) = self.config.language_servers.get(language.token()) else {
+            return;
+        };
+        if server.trusted
+            || !self.config.confirm_commands
+            || self.state.trusted_commands.contains(&server.command_line())
+        {
+            self.servers.allowed.push((language, lsp::root(path)));
+        } else {
+            self.pending_server = Some(language);
+        }
+    }
+
+    fn sync_document(&mut self, index: usize) {
+        let Some(buffer) = self.buffers.get_mut(index) else {
+            return;
+        };
+        let (Some(path), Some(client)) =
+            (buffer.path.as_deref(), self.servers.client(buffer.language))
+        else {
+            return;
+        };
+        if buffer.synced == Some(buffer.revision) {
+            return;
+        }
+        let text = buffer.content.text();
+        match buffer.synced {
+            None => client.did_open(path, &text, buffer.revision),
+            Some(_) => client.did_change(path, &text, buffer.revision),
+        }
+        buffer.synced = Some(buffer.revision);
+    }
+
+    fn schedule_sync(&mut self, completes: bool) {
+        self.sync_at = Some((Instant::now(), completes));
+    }
+
+    fn update_completion(&mut self, refresh: bool) {
+        let buffer = &self.buffers[self.active];
+        if !refresh || buffer.is_readonly || buffer.hex || !buffer.carets.is_empty() {
+            self.completion = None;
+            return;
+        }
+        let text = buffer.content.text();
+        let (line, column) = buffer.content.cursor_position();
+        let line_text = text.lines().nth(line).unwrap_or_default();
+        let Some(prefix) = complete::prefix(line_text, column) else {
+            self.completion = None;
+            return;
+        };
+        let mut completion = complete::Completion::new(buffer.id, prefix, &text);
+        if let (Some(path), true, Some(client)) = (
+            buffer.path.as_deref(),
+            buffer.synced == Some(buffer.revision),
+            self.servers.client(buffer.language),
+        ) {
+            let character = line_text.chars().take(column).map(char::len_utf16).sum();
+            completion.request = Some(client.completion(path, line, character));
+        }
+        self.completion =
+            (!completion.items.is_empty() || completion.request.is_some()).then_some(completion);
+    }
+
+    fn expand_snippet(&mut self) -> bool {
+        let buffer = &self.buffers[self.active];
+        if buffer.is_readonly || !buffer.carets.is_empty() || buffer.content.selection().is_some() {
+            return false;
+        }
+        let text = buffer.content.text();
+        let (line, column) = buffer.content.cursor_position();
+        let line_text = text.lines().nth(line).unwrap_or_default();
+        let before: String = line_text.chars().take(column).collect();
+        let Some((prefix, body)) = self.snippets.find(buffer.language, &before) else {
+            return false;
+        };
+        let indent: String = line_text
+            .chars()
+            .take_while(|c| *c == ' ' || *c == '\t')
+            .collect();
+        let expansion = snippet::expand(body, &indent);
+        let prefix = prefix.to_owned();
+        self.buffers[self.active].insert_snippet(&prefix, expansion);
+        true
+    }
+
+    fn save_buffer(&mut self, index: usize, save_as: bool) -> Command<Message> {
+        let buffer = &self.buffers[index];
+        if !save_as
+            && !buffer.is_readonly
+            && !buffer.hex
+            && buffer.page.is_none()
+            && self
+                .config
+                .formatters
+                .get(buffer.language.token())
+                .is_some_and(|formatter| formatter.on_save)
+        {
+            return self.request_formatter(formatter::Pending {
+                buffer: buffer.id,
+                then_save: true,
+            });
+        }
+        self.write_buffer(index, save_as)
+    }
+
+    fn request_formatter(&mut self, pending: formatter::Pending) -> Command<Message> {
+        let Some(buffer) = self
+            .buffers
+            .iter()
+            .find(|buffer| buffer.id == pending.buffer)
+        else {
+            return Command::none();
+        };
+        let Some(formatter) = self.config.formatters.get(buffer.language.token()) else {
+            self.notify(format!(
+                "No formatter is configured for {}",
+                buffer.language
+            ));
+            return Command::none();
+        };
+        if formatter.trusted
+            || !self.config.confirm_commands
+            || self
+                .state
+                .trusted_commands
+                .contains(&formatter.command_line())
+        {
+            self.run_formatter(pending)
+        } else {
+            self.pending_formatter = Some(pending);
+            Command::none()
+        }
+    }
+
+    fn run_formatter(&mut self, pending: formatter::Pending) -> Command<Message> {
+        let Some(buffer) = self
+            .buffers
+            .iter_mut()
+            .find(|buffer| buffer.id == pending.buffer)
+        else {
+            return Command::none();
+        };
+        let Some(formatter) = self.config.formatters.get(buffer.language.token()) else {
+            return Command::none();
+        };
+        buffer.sync_table();
+        let (id, revision) = (buffer.id, buffer.revision);
+        Command::perform(
+            formatter::run(
+                formatter.clone(),
+                buffer.content.text(),
+                buffer.path.clone(),
+            ),
+            move |result| Message::Formatted(id, revision, pending.then_save, result),
+        )
+    }
+
+    fn write_buffer(&mut self, index: usize, save_as: bool) -> Command<Message> {
+        let buffer = &mut self.buffers[index];
+        buffer.sync_table();
+        buffer.apply_transforms(self.config.tab_width);
+        let id = buffer.id;
+        let path = if save_as { None } else { buffer.path.clone() };
+        let remote = if save_as { None } else { buffer.remote.clone() };
+        let expected = if save_as { None } else { buffer.disk };
+        let passphrase = buffer.passphrase.clone();
+        let bytes = if buffer.hex {
+            match hex::parse(&buffer.content.text()) {
+                Ok(bytes) => bytes,
+                Err(line) => {
+                    self.report(Error::InvalidHex(line + 1));
+                    return Command::none();
+                }
+            }
+        } else {
+            match buffer.encoded() {
+                (_, true) => {
+                    let encoding = buffer.encoding;
+                    self.report(Error::Unmappable(encoding));
+                    return Command::none();
+                }
+                (bytes, false) => bytes,
+            }
+        };
+        if let Some(location) = remote {
+            let password = self.credentials.get(&location);
+            return Command::perform(remote::upload(location, password, bytes), move |result| {
+                Message::RemoteSaved(id, result)
+            });
+        }
+        if !self.saves.start(id) {
+            return Command::none();
+        }
+        Command::perform(
+            save_file(path, bytes, self.config.backup, expected, passphrase),
+            move |result| Message::FileSaved(id, result),
+        )
+    }
+
+    fn index_workspace(&mut self, root: PathBuf) -> Command<Message> {
+        if root == self.workspace.root && self.workspace.is_indexing {
+            return Command::none();
+        }
+        if root != self.workspace.root {
+            self.workspace = workspace::Workspace::new(root.clone());
+        }
+        self.workspace.is_indexing = true;
+        Command::perform(workspace::index(root), |(root, files)| {
+            Message::WorkspaceIndexed(root, files)
+        })
+    }
+
+    fn refresh_statistics(&mut self) -> Command<Message> {
+        let buffer = &mut self.buffers[self.active];
+        let Some(panel) = &mut self.statistics else {
+            return Command::none();
+        };
+        if panel.pending.is_some() {
+            return Command::none();
+        }
+        let key = stats::Key {
+            buffer: buffer.id,
+            revision: buffer.revision,
+            selection: buffer.content.selection(),
+        };
+        if panel.is_current(&key) {
+            return Command::none();
+        }
+        let rope = buffer.rope().clone();
+        if rope.len_bytes() < stats::SYNC_LIMIT {
+            panel.report = Some(stats::Report::new(key, &rope));
+            return Command::none();
+        }
+        panel.pending = Some(key.clone());
+        Command::perform(stats::compute(key, rope), Message::StatisticsComputed)
+    }
+
+    fn shown_buffers(&self) -> Vec<usize> {
+        let active = self.buffer().id;
+        let mut shown: Vec<usize> = self
+            .panes
+            .iter()
+            .map(|(pane, state)| {
+                if *pane == self.focus {
+                    active
+                } else {
+                    state.buffer
+                }
+            })
+            .collect();
+        shown.push(active);
+        shown.sort_unstable();
+        shown.dedup();
+        shown
+    }
+
+    fn refresh_frames(&mut self) {
+        let shown = self.shown_buffers();
+        let active = self.buffer().id;
+        let (preview, outline) = (self.preview, self.outline);
+        let bookmarked = self.bookmark_panel;
+        for buffer in &mut self.buffers {
+            let is_active = buffer.id == active;
+            if shown.contains(&buffer.id) || (bookmarked && !buffer.bookmarks.is_empty()) {
+                buffer.refresh_frame(
+                    is_active && preview && buffer.language == Language::Markdown,
+                    is_active && outline,
+                );
+            }
+        }
+        if let Some(panel) = &mut self.unsaved
+            && let Some(buffer) = self.buffers.iter().find(|buffer| buffer.id == panel.buffer)
+        {
+            panel.refresh(buffer.revision, &buffer.frame.text);
+        }
+    }
+
+    fn refresh_layouts(&mut self) -> Command<Message> {
+        let mut jobs = Vec::new();
+        for id in self.shown_buffers() {
+            let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) else {
+                continue;
+            };
+            let key = render::Key::of(buffer);
+            if !self.render.is_stale(id, &key) {
+                continue;
+            }
+            let rope = buffer.rope().clone();
+            if rope.len_bytes() < render::SYNC_LIMIT {
+                let text = rope.to_string();
+                let layout = render::Layout::new(key, &text, buffer.git.as_ref());
+                self.render.insert(id, layout);
+                continue;
+            }
+            if !self.render.is_settled(id, &key) {
+                continue;
+            }
+            let cancel = self.render.start(id, key.clone());
+            let git = buffer.git.clone();
+            jobs.push(Command::perform(
+                render::run(cancel, move || {
+                    let text = rope.to_string();
+                    render::Layout::new(key, &text, git.as_ref())
+                }),
+                move |layout| Message::LayoutComputed(id, layout),
+            ));
+        }
+        Command::batch(jobs)
+    }
+
+    fn refresh_todos(&mut self) -> Command<Message> {
+        let buffer = &mut self.buffers[self.active];
+        let Some(panel) = &mut self.todos else {
+            return Command::none();
+        };
+        if panel.pending.is_some() {
+            return Command::none();
+        }
+        let key = todos::Key {
+            buffer: buffer.id,
+            revision: buffer.revision,
+        };
+        if panel.is_current(key) {
+            return Command::none();
+        }
+        let rope = buffer.rope().clone();
+        if rope.len_bytes() < todos::SYNC_LIMIT {
+            panel.annotations = todos::scan(rope.lines().map(|line| line.to_string()));
+            panel.key = Some(key);
+            return Command::none();
+        }
+        panel.pending = Some(key);
+        Command::perform(todos::compute(key, rope), |(key, annotations)| {
+            Message::TodosComputed(key, annotations)
+        })
+    }
+
+    fn reformat(
+        &mut self,
+        apply: impl FnOnce(&str, format::Syntax, &str) -> Option<Result<String, format::Invalid>>,
+    ) {
+        let buffer = &self.buffers[self.active];
+        let Some(syntax) = format::Syntax::detect(buffer.language, buffer.path.as_deref()) else {
+            self.notify(String::from(
+                "Formatting needs a JSON, YAML, TOML or XML file",
+            ));
+            return;
+        };
+        let text = buffer.content.text();
+        let (id, revision, indent) = (buffer.id, buffer.revision, buffer.indent.unit());
+        match apply(&text, syntax, &indent) {
+            None => self.notify(format!("{syntax} cannot be minified")),
+            Some(Err(invalid)) => {
+                self.toasts.push(
+                    toast::Level::Warning,
+                    format!("Invalid {syntax} at {invalid}"),
+                );
+                self.syntax_error = Some((id, revision, invalid));
+            }
+            Some(Ok(formatted)) if formatted == text => {
+                self.syntax_error = None;
+                self.notify(format!("{syntax} is valid"));
+            }
+            Some(Ok(_)) if !syntax.keeps_comments(&text) => {
+                self.syntax_error = None;
+                self.notify(format!(
+                    "{syntax} is valid, but formatting would drop its comments"
+                ));
+            }
+            Some(Ok(formatted)) => {
+                self.syntax_error = None;
+                let buffer = self.buffer_mut();
+                let (line, _) = buffer.content.cursor_position();
+                buffer.replace_text(&formatted);
+                move_cursor_to(
+                    &mut buffer.content,
+                    line.min(buffer.content.line_count() - 1),
+                    0,
+                );
+            }
+        }
+    }
+
+    fn open_terminal(&mut self) -> Command<Message> {
+        let cwd = if self.workspace.root.as_os_str().is_empty() {
+            std::env::current_dir().unwrap_or_default()
+        } else {
+            self.workspace.root.clone()
+        };
+        self.terminal = Some(terminal::Terminal::new(self.next_terminal, cwd));
+        self.next_terminal += 1;
+        text_input::focus(text_input::Id::new(terminal::INPUT))
+    }
+
+    fn untrusted_commands(&self, pending: terminal::Pending) -> Vec<String> {
+        let is_running = self
+            .terminal
+            .as_ref()
+            .is_some_and(|terminal| terminal.handle.is_some());
+        let (run, opens) = match pending {
+            terminal::Pending::Shell => (None, true),
+            terminal::Pending::Run(id) => (
+                self.buffers
+                    .iter()
+                    .find(|buffer| buffer.id == id)
+                    .and_then(|buffer| self.config.run_commands.get(buffer.language.token()))
+                    .cloned(),
+                !is_running,
+            ),
+        };
+        let shell = self.config.shell.clone().filter(|_| opens);
+        run.into_iter()
+            .chain(shell)
+            .filter(|command| {
+                self.config.confirm_commands && !self.state.trusted_commands.contains(command)
+            })
+            .collect()
+    }
+
+    fn request_terminal(&mut self, pending: terminal::Pending) -> Command<Message> {
+        if self.untrusted_commands(pending).is_empty() {
+            self.start_terminal(pending)
+        } else {
+            self.pending_terminal = Some(pending);
+            Command::none()
+        }
+    }
+
+    fn start_terminal(&mut self, pending: terminal::Pending) -> Command<Message> {
+        let terminal::Pending::Run(id) = pending else {
+            if let Some(terminal) = self.terminal.take() {
+                terminal.close();
+            }
+            return self.open_terminal();
+        };
+        let Some(buffer) = self.buffers.iter().find(|buffer| buffer.id == id) else {
+            return Command::none();
+        };
+        let (Some(path), Some(template)) = (
+            buffer.path.as_deref(),
+            self.config.run_commands.get(buffer.language.token()),
+        ) else {
+            return Command::none();
+        };
+        let line = template.replace("{path}", &terminal::quote(path));
+        let is_running = self
+            .terminal
+            .as_ref()
+            .is_some_and(|terminal| terminal.handle.is_some());
+        let open = if is_running {
+            Command::none()
+        } else {
+            self.open_terminal()
+        };
+        if let Some(terminal) = &mut self.terminal {
+            match &terminal.handle {
+                Some(handle) => handle.send(&format!("{line}\r")),
+                None => terminal.queued = Some(line),
+            }
+        }
+        open
+    }
+
+    fn report(&mut self, error: Error) {
+        if !matches!(error, Error::DialogClosed) {
+            self.toasts.push(toast::Level::Error, error.to_string());
+        }
+    }
+
+    fn notify(&mut self, message: String) {
+        self.toasts.push(toast::Level::Info, message);
+    }
+
+    fn prompt_actions(&self) -> (Option<Message>, Option<Message>) {
+        let exists = |id: usize| self.buffers.iter().any(|buffer| buffer.id == id);
+        if self
+            .confirmation
+            .is_some_and(|confirmation| exists(confirmation.buffer))
+        {
+            (
+                Some(Message::Confirm(Choice::Save)),
+                Some(Message::Confirm(Choice::Cancel)),
+            )
+        } else if self.save_denied.is_some_and(exists) {
+            (Some(Message::SaveDeniedAs), Some(Message::CancelSaveDenied))
+        } else if self.save_conflict.is_some_and(exists) {
+            (
+                Some(Message::CompareConflict),
+                Some(Message::CancelSaveConflict),
+            )
+        } else if self.buffer().has_conflict {
+            (None, Some(Message::KeepBuffer(self.buffer().id)))
+        } else if let Some(language) = self.pending_server
+            && self.config.language_servers.contains_key(language.token())
+        {
+            (
+                Some(Message::StartServer(language)),
+                Some(Message::DeclineServer(language)),
+            )
+        } else if let Some(pending) = self.pending_formatter
+            && exists(pending.buffer)
+        {
+            (
+                Some(Message::RunFormatter(pending)),
+                Some(Message::DeclineFormatter(pending)),
+            )
+        } else if let Some(pending) = self.pending_terminal {
+            (
+                Some(Message::RunTerminal(pending)),
+                Some(Message::DeclineTerminal(pending)),
+            )
+        } else if let Some(link) = &self.pending_link {
+            (
+                Some(Message::FollowLink(link.clone())),
+                Some(Message::DeclineLink),
+            )
+        } else if !self.recovered.is_empty() {
+            (Some(Message::RestoreRecovery), None)
+        } else {
+            (None, None)
+        }
+    }
+
+    fn request_close(&mut self, index: usize) {
+        match self.buffers.get(index) {
+            Some(buffer) if buffer.needs_save() => {
+                self.confirmation = Some(Confirmation {
+                    buffer: buffer.id,
+                    exit: false,
+                });
+            }
+            Some(_) => self.close_buffer(index),
+            None => {}
+        }
+    }
+
+    fn request_exit(&mut self) -> Command<Message> {
+        match self.buffers.iter().position(Buffer::needs_save) {
+            Some(index) => {
+                self.active = index;
+                self.confirmation = Some(Confirmation {
+                    buffer: self.buffers[index].id,
+                    exit: true,
+                });
+                Command::none()
+            }
+            None => Command::perform(
+                shutdown(
+                    self.session(),
+                    self.scratch(),
+                    self.histories(),
+                    self.view_states(),
+                ),
+                |_| Message::Exit,
+            ),
+        }
+    }
+
+    fn session(&self) -> Option<session::Session> {
+        if !self.config.restore_session {
+            return None;
+        }
+        let files = self
+            .buffers
+            .iter()
+            .filter_map(|buffer| {
+                let path = buffer.path.clone()?;
+                let text = buffer.content.text();
+                let (line, column) = buffer.content.cursor_position();
+                let (line, column) =
+                    offset_to_position(&text, position_to_offset(&text, line, column));
+                Some(session::SessionFile { path, line, column })
+            })
+            .collect();
+        Some(session::Session {
+            files,
+            active: self.buffer().path.clone(),
+        })
+    }
+
+    fn scratch(&self) -> Option<scratch::Scratch> {
+        self.config.persist_scratch.then(|| scratch::Scratch {
+            buffers: self
+                .buffers
+                .iter()
+                .filter(|buffer| buffer.scratch)
+                .map(|buffer| buffer.content.text())
+                .collect(),
+        })
+    }
+
+    fn histories(&self) -> Vec<undo::Saved> {
+        if !self.config.persist_undo {
+            return Vec::new();
+        }
+        let mut histories = self.histories.clone();
+        for saved in self.buffers.iter().filter_map(Buffer::saved_history) {
+            histories.insert(saved.path.clone(), saved);
+        }
+        histories.into_values().collect()
+    }
+
+    fn view_states(&self) -> Vec<view_state::ViewState> {
+        if !self.config.persist_view_state {
+            return Vec::new();
+        }
+        let mut views = self.views.clone();
+        for state in self.buffers.iter().filter_map(Buffer::view_state) {
+            views.insert(state.path.clone(), state);
+        }
+        views.into_values().collect()
+    }
+
+    fn finish_confirmation(&mut self, confirmation: Confirmation) -> Command<Message> {
+        if confirmation.exit {
+            return self.request_exit();
+        }
+        if let Some(index) = self
+            .buffers
+            .iter()
+            .position(|buffer| buffer.id == confirmation.buffer)
+        {
+            self.close_buffer(index);
+        }
+        Command::none()
+    }
+
+    fn toggle_search(&mut self, is_replacing: bool) -> Command<Message> {
+        if self
+            .search
+            .as_ref()
+            .is_some_and(|search| search.is_replacing == is_replacing)
+        {
+            self.search = None;
+            return Command::none();
+        }
+        if let Some(search) = self.search.as_mut() {
+            search.is_replacing = is_replacing;
+        } else {
+            let query = self
+                .buffer()
+                .content
+                .selection()
+                .filter(|selection| !selection.contains('\n'))
+                .unwrap_or_default();
+            self.search = Some(Search::new(query, is_replacing));
+        }
+        text_input::focus(text_input::Id::new(FIND_INPUT))
+    }
+
+    fn find_match(&mut self, forward: bool, anchor: Option<usize>) {
+        let Some(search) = self.search.as_mut() else {
+            return;
+        };
+        let buffer = &mut self.buffers[self.active];
+        buffer.unfold();
+        let (id, (line, column)) = (buffer.id, buffer.content.cursor_position());
+        let snapshot = buffer.snapshot();
+        search.refresh(id, snapshot);
+        let rope = snapshot.rope();
+        let cursor = anchor.unwrap_or_else(|| rope::offset(rope, line, column));
+        let found = if forward {
+            search.next_after(cursor)
+        } else {
+            let before = search
+                .current
+                .as_ref()
+                .map_or(cursor, |current| current.start);
+            search.previous_before(before)
+        };
+        let selection = found.as_ref().map(|range| {
+            let length = rope.byte_to_char(range.end) - rope.byte_to_char(range.start);
+            (rope::position(rope, range.start), length)
+        });
+        if let Some(((line, column), length)) = selection {
+            move_cursor_to(&mut buffer.content, line, column);
+            for _ in 0..length {
+                buffer
+                    .content
+                    .edit(text_editor::Action::Select(text_editor::Motion::Right));
+            }
+        }
+        let is_found = found.is_some();
+        search.current = found;
+        if is_found {
+            self.center_cursor();
+        }
+    }
+
+    fn zoom(&mut self, size: f32) -> Command<Message> {
+        let size = size.clamp(config::MIN_FONT_SIZE, config::MAX_FONT_SIZE);
+        self.zoomed_at = Some(Instant::now());
+        if size == self.config.font_size {
+            return Command::none();
+        }
+        self.config.font_size = size;
+        Command::perform(config::save_font_size(size), Message::ZoomSaved)
+    }
+
+    fn apply_config(&mut self, config: Config) {
+        if let Some(theme) = config.highlighter_theme() {
+            self.theme = theme;
+        }
+        self.font = match config.font.as_deref() {
+            Some(name) if config.font != self.config.font => fonts::font(fonts::intern(name)),
+            Some(_) => self.font,
+            None => DEFAULT_FONT,
+        };
+        for buffer in &mut self.buffers {
+            buffer.history.set_depth(config.history_depth);
+        }
+        if config.locale != self.config.locale {
+            i18n::select(config.locale.as_deref());
+        }
+        self.config = config;
+    }
+
+    fn reindent_paste(&self, text: &str) -> String {
+        let content = &self.buffer().content;
+        let (line, _) = content.cursor_position();
+        let current = content
+            .text()
+            .split('\n')
+            .nth(line)
+            .unwrap_or_default()
+            .to_owned();
+        clips::reindent(text, &current)
+    }
+
+    fn run_vim(&mut self, effects: Vec<vim::Effect>) -> Command<Message> {
+        use text_editor::{Action, Edit, Motion};
+
+        let mut commands = Vec::new();
+        for effect in effects {
+            let buffer = self.buffer_mut();
+            match effect {
+                vim::Effect::Perform(action) => buffer.perform(action),
+                vim::Effect::Selection(operator) => {
+                    let Some(selected) = buffer.content.selection() else {
+                        continue;
+                    };
+                    if operator == vim::Operator::Yank {
+                        let text = buffer.content.text();
+                        let start = buffer.primary_range(&text).start;
+                        let (line, column) = offset_to_position(&text, start);
+                        move_cursor_to(&mut buffer.content, line, column);
+                    } else {
+                        buffer.perform(Action::Edit(Edit::Delete));
+                    }
+                    self.vim.register = Some(vim::Register {
+                        text: selected,
+                        linewise: false,
+                    });
+                }
+                vim::Effect::Lines(operator, count) => {
+                    let text = buffer.content.text();
+                    let (line, _) = buffer.content.cursor_position();
+                    let lines: Vec<&str> = text.split('\n').skip(line).take(count).collect();
+                    let yanked = lines.join("\n") + "\n";
+                    if operator != vim::Operator::Yank {
+                        buffer.perform(Action::Move(Motion::Home));
+                        for _ in 1..lines.len() {
+                            buffer.perform(Action::Select(Motion::Down));
+                        }
+                        if operator == vim::Operator::Delete {
+                            buffer.delete_lines();
+                        } else {
+                            buffer.perform(Action::Select(Motion::End));
+                            if buffer.content.selection().is_some() {
+                                buffer.perform(Action::Edit(Edit::Delete));
+                            }
+                        }
+                    }
+                    self.vim.register = Some(vim::Register {
+                        text: yanked,
+                        linewise: true,
+                    });
+                }
+                vim::Effect::Put { before } => {
+                    let Some(register) = self.vim.register.clone() else {
+                        continue;
+                    };
+                    let buffer = self.buffer_mut();
+                    let text = match (register.linewise, before) {
+                        (true, true) => {
+                            buffer.perform(Action::Move(Motion::Home));
+                            register.text
+                        }
+                        (true, false) => {
+                            buffer.perform(Action::Move(Motion::End));
+                            format!("\n{}", register.text.trim_end_matches('\n'))
+                        }
+                        (false, true) => register.text,
+                        (false, false) => {
+                            buffer.perform(Action::Move(Motion::Right));
+                            register.text
+                        }
+                    };
+                    buffer.perform(Action::Edit(Edit::Paste(Arc::new(text))));
+                }
+                vim::Effect::Undo => buffer.undo(),
+                vim::Effect::Write => commands.push(self.save_buffer(self.active, false)),
+                vim::Effect::Quit => self.request_close(self.active),
+                vim::Effect::WriteQuit => {
+                    self.after_save = Some(Confirmation {
+                        buffer: buffer.id,
+                        exit: false,
+                    });
+                    commands.push(self.save_buffer(self.active, false));
+                }
+                vim::Effect::GoToLine(line) => {
+                    commands.push(self.update(Message::GoToLine(line, 1)))
+                }
+            }
+        }
+        let viewport_lines = self.viewport_lines;
+        let buffer = self.buffer_mut();
+        buffer.top_line = buffer.visible_top(viewport_lines);
+        self.schedule_sync(false);
+        Command::batch(commands)
+    }
+
+    fn jump_to_bookmark(&mut self, forward: bool) -> Command<Message> {
+        let buffer = self.buffer();
+        let (line, _) = buffer.content.cursor_position();
+        match bookmarks::next(&buffer.bookmarks, line, forward) {
+            Some(line) => self.update(Message::GoToLine(line + 1, 1)),
+            None => Command::none(),
+        }
+    }
+
+    fn remember_bookmarks(&mut self, id: usize) -> Command<Message> {
+        let Some(buffer) = self.buffers.iter().find(|buffer| buffer.id == id) else {
+            return Command::none();
+        };
+        let Some(path) = &buffer.path else {
+            return Command::none();
+        };
+        self.bookmarks.set(path, &buffer.bookmarks);
+        Command::perform(
+            bookmarks::save(self.bookmarks.clone()),
+            Message::BookmarksSaved,
+        )
+    }
+
+    fn remember(&mut self, path: &std::path::Path) -> Command<Message> {
+        self.recent.push(path);
+        Command::perform(recent::save(self.recent.clone()), Message::RecentSaved)
+    }
+
+    fn editor_view<'a>(
+        &'a self,
+        buffer: &'a Buffer,
+        pane: pane_grid::Pane,
+    ) -> Element<'a, Message> {
+        let contents = &buffer.frame.text;
+        let fresh;
+        let layout = match self.render.get(buffer.id) {
+            Some(layout) => layout,
+            None => {
+                fresh = render::Layout::new(render::Key::of(buffer), contents, buffer.git.as_ref());
+                &fresh
+            }
+        };
+        let is_current = layout.key.revision == buffer.revision;
+        let visible = {
+            let top = buffer.visible_top(self.viewport_lines);
+            top..top + self.viewport_lines
+        };
+        let mut marks = highlight::diagnostics(contents, &buffer.diagnostics, visible);
+        if is_current {
+            let cursor = brackets::cursor_offset(contents, buffer.content.cursor_position());
+            marks.extend(
+                brackets::pair(contents, &layout.brackets, cursor)
+                    .into_iter()
+                    .flatten()
+                    .map(|(line, range)| (line, range, highlight::Mark::Bracket)),
+            );
+        }
+        if buffer.follow.is_some() || buffer.path.as_deref().is_some_and(follow::is_log) {
+            let top = buffer.visible_top(self.viewport_lines);
+            marks.extend(follow::marks(contents, top..top + self.viewport_lines));
+        }
+        if Spelling::applies_to(buffer.language) && !buffer.hex {
+            let top = buffer.visible_top(self.viewport_lines);
+            marks.extend(
+                self.spelling
+                    .misspelled(contents, buffer.language, top..top + self.viewport_lines)
+                    .into_iter()
+                    .map(|(line, range)| (line, range, highlight::Mark::Misspelled)),
+            );
+        }
+        if buffer.folded.is_some() {
+            marks.clear();
+        }
+        let shown = buffer
+            .folded
+            .as_ref()
+            .map_or(&buffer.content, |folded| &folded.content);
+        let input = text_editor(shown)
+            .on_edit(move |action| Message::PaneEdit(pane, action))
+            .font(self.font)
+            .highlight::<highlight::Highlighter>(
+                highlight::Settings {
+                    syntax: highlighter::Settings {
+                        theme: self.theme,
+                        extension: buffer.language.token().to_string(),
+                    },
+                    marks,
+                },
+                highlight::Highlight::to_format,
+            );
+        let rulers = if self.config.show_rulers {
+            self.config.rulers.clone()
+        } else {
+            Vec::new()
+        };
+        let highlights = match &self.search {
+            Some(search) if buffer.id == self.buffer().id && buffer.folded.is_none() => {
+                let matches = search
+                    .cached(buffer.id, buffer.revision)
+                    .unwrap_or_default();
+                search::visible(
+                    matches,
+                    search.current.as_ref(),
+                    contents,
+                    buffer.visible_top(self.viewport_lines),
+                    self.viewport_lines,
+                )
+            }
+            _ => Vec::new(),
+        };
+        let input: Element<'a, Message> = if self.state.whitespace
+            || !highlights.is_empty()
+            || self.config.highlight_current_line
+            || self.config.indent_guides
+            || !rulers.is_empty()
+        {
+            let top = match &buffer.folded {
+                Some(folded) => folded.visible_top(self.viewport_lines),
+                None => buffer.visible_top(self.viewport_lines),
+            };
+            let current_line = self
+                .config
+                .highlight_current_line
+                .then(|| shown.cursor_position().0.checked_sub(top))
+                .flatten();
+            let shown_text = buffer
+                .folded
+                .as_ref()
+                .map_or(contents, |folded| &folded.text);
+            let guides = self.config.indent_guides.then(|| {
+                let width = match buffer.indent {
+                    Indent::Tabs => whitespace::TAB_WIDTH,
+                    Indent::Spaces(width) => width,
+                };
+                guides::Guides::new(
+                    shown_text,
+                    top,
+                    self.viewport_lines,
+                    shown.cursor_position().0,
+                    width,
+                )
+            });
+            decoration::Decoration::new(input, shown_text, top, self.viewport_lines, buffer.wrap)
+                .guides(guides)
+                .whitespace(self.state.whitespace)
+                .current_line(current_line)
+                .rulers(rulers)
+                .matches(highlights)
+                .into()
+        } else {
+            input.into()
+        };
+        let input: Element<'a, Message> = if buffer.wrap {
+            input.into()
+        } else {
+            let longest = layout.longest;
+            scrollable(container(input).width(longest as f32 * CHAR_WIDTH + CHAR_WIDTH * 4.0))
+                .direction(scrollable::Direction::Horizontal(
+                    scrollable::Properties::default(),
+                ))
+                .height(Length::Fill)
+                .into()
+        };
+        let input: Element<'a, Message> = match self
+            .completion_popup(buffer, pane)
+            .or_else(|| self.context_popup(buffer, pane))
+            .or_else(|| self.color_popup(buffer, pane))
+        {
+            Some((list, offset)) => popup::Popup::new(input, Some(list), offset),
+            None => popup::Popup::new(input, None, Vector::ZERO),
+        }
+        .on_right_click(move |point| Message::ContextMenu(pane, point))
+        .into();
+        let mut editor = row(Vec::new());
+        if self.config.line_numbers != LineNumbers::Off || !buffer.bookmarks.is_empty() {
+            editor = editor.push(gutter(
+                buffer,
+                self.font,
+                self.viewport_lines,
+                self.config.line_numbers,
+            ));
+        }
+        if !layout.folds.is_empty() {
+            editor = editor.push(fold_markers(
+                buffer,
+                &layout.folds,
+                self.font,
+                self.viewport_lines,
+            ));
+        }
+        if self.config.color_swatches
+            && !buffer.hex
+            && colors::applies_to(buffer.language, buffer.path.as_deref())
+        {
+            editor = editor.push(color_swatches(
+                buffer,
+                contents,
+                self.font,
+                self.viewport_lines,
+                pane,
+            ));
+        }
+        if buffer.git.as_ref().is_some_and(|info| info.head.is_some()) {
+            editor = editor.push(change_markers(
+                buffer,
+                &layout.changes,
+                self.font,
+                self.viewport_lines,
+            ));
+        }
+        editor = editor.push(input);
+        if self.state.minimap {
+            let top = buffer
+                .visible_lines(self.viewport_lines)
+                .first()
+                .copied()
+                .unwrap_or_default();
+            editor = editor.push(minimap::Minimap::new(
+                contents,
+                top,
+                self.viewport_lines,
+                move |top| Message::MinimapScrolled(pane, top),
+            ));
+        }
+        editor.into()
+    }
+
+    fn wrap_columns(&self) -> usize {
+        let explorer = if self.explorer.is_visible {
+            EXPLORER_WIDTH
+        } else {
+            0.0
+        };
+        let width = self.window_width as f32 - CHROME_WIDTH - explorer - EDITOR_PADDING * 2.0;
+        ((width / CHAR_WIDTH) as usize).max(1)
+    }
+
+    fn focus_pane(&mut self, pane: pane_grid::Pane) {
+        if pane == self.focus {
+            return;
+        }
+        let left = PaneState::of(self.buffer());
+        if let Some(state) = self.panes.get_mut(&self.focus) {
+            *state = left;
+        }
+        self.focus = pane;
+        self.enter_pane(pane);
+    }
+
+    fn enter_pane(&mut self, pane: pane_grid::Pane) {
+        let Some(state) = self.panes.get(&pane).copied() else {
+            return;
+        };
+        let Some(index) = self
+            .buffers
+            .iter()
+            .position(|buffer| buffer.id == state.buffer)
+        else {
+            return;
+        };
+        self.active = index;
+        let buffer = &mut self.buffers[index];
+        if buffer.folded.is_none() {
+            let (line, column) = state.cursor;
+            buffer.place(line, column, state.top_line);
+        }
+    }
+
+    fn completion_popup<'a>(
+        &'a self,
+        buffer: &Buffer,
+        pane: pane_grid::Pane,
+    ) -> Option<(Element<'a, Message>, Vector)> {
+        let completion = self.completion.as_ref().filter(|completion| {
+            completion.buffer == buffer.id && pane == self.focus && !completion.items.is_empty()
+        })?;
+        let (line, column) = buffer.content.cursor_position();
+        let row = line
+            .checked_sub(buffer.visible_top(self.viewport_lines))
+            .filter(|row| *row < self.viewport_lines)?;
+        let column = column.saturating_sub(completion.prefix.chars().count());
+        let offset = Vector::new(
+            EDITOR_PADDING + column as f32 * CHAR_WIDTH,
+            EDITOR_PADDING + (row + 1) as f32 * LINE_HEIGHT,
+        );
+        let longest = completion
+            .items
+            .iter()
+            .map(|item| item.chars().count())
+            .max()
+            .unwrap_or_default();
+        let items = completion.items.iter().enumerate().map(|(index, item)| {
+            button(text(item).font(self.font))
+                .style(if index == completion.selected {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                })
+                .padding([2, 5])
+                .width(longest as f32 * CHAR_WIDTH + 10.0)
+                .on_press(Message::AcceptCompletion(index))
+                .into()
+        });
+        let list = container(column(items.collect()))
+            .padding(2)
+            .style(theme::Container::Box);
+        Some((list.into(), offset))
+    }
+
+    fn context_popup<'a>(
+        &'a self,
+        buffer: &Buffer,
+        pane: pane_grid::Pane,
+    ) -> Option<(Element<'a, Message>, Vector)> {
+        let (_, point) = self
+            .context_menu
+            .filter(|(menu_pane, _)| *menu_pane == pane)?;
+        let has_selection = buffer.content.selection().is_some();
+        let is_editable = !buffer.is_readonly;
+        let has_path = buffer.path.is_some();
+        let items = [
+            (
+                "Cut",
+                (has_selection && is_editable).then_some(Message::Cut),
+            ),
+            ("Copy", has_selection.then_some(Message::Copy)),
+            ("Paste", is_editable.then_some(Message::Paste)),
+            ("Select all", Some(Message::SelectAll)),
+            ("Copy file path", has_path.then_some(Message::CopyPath)),
+            (
+                "Open containing folder",
+                has_path.then_some(Message::RevealInFolder),
+            ),
+            (
+                "Search for selection in files",
+                has_selection.then_some(Message::SearchSelectionInFiles),
+            ),
+            (
+                "Toggle comment",
+                is_editable.then_some(Message::ToggleComment),
+            ),
+            ("Toggle bookmark", Some(Message::ToggleBookmark)),
+        ];
+        let items = items.into_iter().map(|(label, message)| {
+            button(text(label).size(14))
+                .style(theme::Button::Text)
+                .padding([2, 10])
+                .width(CONTEXT_MENU_WIDTH)
+                .on_press_maybe(message.map(|message| Message::ContextAction(Box::new(message))))
+                .into()
+        });
+        let menu = container(column(items.collect()))
+            .padding(2)
+            .style(theme::Container::Box);
+        Some((menu.into(), Vector::new(point.x, point.y)))
+    }
+
+    fn color_popup<'a>(
+        &'a self,
+        buffer: &Buffer,
+        pane: pane_grid::Pane,
+    ) -> Option<(Element<'a, Message>, Vector)> {
+        let picker = self
+            .color_picker
+            .as_ref()
+            .filter(|picker| picker.buffer == buffer.id && pane == self.focus)?;
+        let row = buffer
+            .visible_lines(self.viewport_lines)
+            .iter()
+            .position(|line| *line == picker.line)?;
+        let offset = Vector::new(
+            EDITOR_PADDING + picker.column as f32 * CHAR_WIDTH,
+            EDITOR_PADDING + (row + 1) as f32 * LINE_HEIGHT,
+        );
+        let channels = ["R", "G", "B"]
+            .into_iter()
+            .enumerate()
+            .map(|(channel, label)| {
+                row!(
+                    text(label).size(14).width(16),
+                    slider(0..=255, picker.rgb[channel], move |value| {
+                        Message::ColorChannelChanged(channel, value)
+                    }),
+                    text(picker.rgb[channel]).size(14).width(30),
+                )
+                .spacing(5)
+                .align_items(iced::Alignment::Center)
+                .into()
+            });
+        let hex = text_input("#rrggbb", &picker.hex)
+            .on_input(Message::ColorHexChanged)
+            .on_submit(Message::ApplyColor)
+            .font(self.font)
+            .size(14)
+            .padding(4);
+        let buttons = row!(
+            horizontal_space(Length::Fill),
+            button(text("Cancel").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::CancelColor),
+            button(text("Apply").size(14)).on_press(Message::ApplyColor),
+        )
+        .spacing(5);
+        let picker = container(
+            column(
+                std::iter::once(text("■■■■").size(28).style(picker.color()).into())
+                    .chain(channels)
+                    .chain([hex.into(), buttons.into()])
+                    .collect(),
+            )
+            .spacing(5)
+            .width(COLOR_PICKER_WIDTH),
+        )
+        .padding(10)
+        .style(theme::Container::Box);
+        Some((picker.into(), offset))
+    }
+
+    fn shows_preview(&self) -> bool {
+        self.preview && self.buffer().language == Language::Markdown
+    }
+
+    fn sync_preview(&self) -> Command<Message> {
+        if !self.shows_preview() {
+            return Command::none();
+        }
+        let buffer = self.buffer();
+        let scrollable_lines = buffer
+            .content
+            .line_count()
+            .saturating_sub(self.viewport_lines)
+            .max(1);
+        let top = buffer.visible_top(self.viewport_lines);
+        scrollable::snap_to(
+            scrollable::Id::new(markdown::PREVIEW),
+            scrollable::RelativeOffset {
+                x: 0.0,
+                y: (top as f32 / scrollable_lines as f32).min(1.0),
+            },
+        )
+    }
+
+    fn center_cursor(&mut self) {
+        let viewport_lines = self.viewport_lines;
+        let buffer = self.buffer_mut();
+        buffer.top_line = center_cursor(&mut buffer.content, viewport_lines);
+    }
+}
+
+impl Editor {
+    fn handle(&mut self, message: Message) -> Command<Message> {
+        if message.is_repeatable() {
+            self.last_command = Some(message.clone());
+        }
+        match message {
+            Message::New => {
+                self.open_buffer(None, "").is_dirty = true;
+                Command::none()
+            }
+            Message::NewFromClipboard => clipboard::read(Message::ClipboardRead),
+            Message::NewScratch => {
+                self.open_buffer(None, "").scratch = true;
+                Command::none()
+            }
+            Message::ScratchLoaded(scratch) => {
+                for text in scratch.buffers {
+                    self.open_buffer(None, &text).scratch = true;
+                }
+                Command::none()
+            }
+            Message::ScratchSaved(Ok(())) => Command::none(),
+            Message::ScratchSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::NewFromTemplate => {
+                if self.template_picker.take().is_some() {
+                    return Command::none();
+                }
+                Command::perform(templates::load(), Message::TemplatesLoaded)
+            }
+            Message::TemplatesLoaded(templates) => {
+                self.template_picker = Some(templates);
+                Command::none()
+            }
+            Message::TemplateSelected(index) => {
+                let Some(template) = self
+                    .template_picker
+                    .take()
+                    .and_then(|templates| templates.into_iter().nth(index))
+                else {
+                    return Command::none();
+                };
+                let text = template.expand(self.config.author.as_deref());
+                let buffer = self.open_buffer(None, &text);
+                buffer.language = Language::from_path(std::path::Path::new(&template.file_name));
+                buffer.is_dirty = true;
+                Command::none()
+            }
+            Message::ClipboardRead(contents) => {
+                self.open_buffer(None, contents.as_deref().unwrap_or_default())
+                    .is_dirty = true;
+                Command::none()
+            }
+            Message::Edit(text_editor::Action::Edit(text_editor::Edit::Enter))
+                if self.modifiers.command() && self.prompt_actions().0.is_some() =>
+            {
+                self.update(Message::AcceptPrompt)
+            }
+            Message::Edit(action) => {
+                self.context_menu = None;
+                self.color_picker = None;
+                if let Some(recording) = &mut self.recording
+                    && let Some(step) = macros::Step::of(&action)
+                {
+                    recording.push(step);
+                }
+                if self.config.vim
+                    && !self.vim.is_inserting()
+                    && let text_editor::Action::Edit(edit) = &action
+                {
+                    let effects = self.vim.edit(edit);
+                    return self.run_vim(effects);
+                }
+                let action = match action {
+                    text_editor::Action::Move(motion)
+                        if self.config.vim && self.vim.mode == vim::Mode::Visual =>
+                    {
+                        text_editor::Action::Select(motion)
+                    }
+                    text_editor::Action::Edit(text_editor::Edit::Paste(text))
+                        if self.config.reindent_on_paste && text.contains('\n') =>
+                    {
+                        text_editor::Action::Edit(text_editor::Edit::Paste(Arc::new(
+                            self.reindent_paste(&text),
+                        )))
+                    }
+                    action => action,
+                };
+                let active = self.buffer().id;
+                if let Some(completion) = self.completion.as_mut().filter(|completion| {
+                    completion.buffer == active && !completion.items.is_empty()
+                }) {
+                    match action {
+                        text_editor::Action::Move(text_editor::Motion::Up) => {
+                            completion.select_previous();
+                            return Command::none();
+                        }
+                        text_editor::Action::Move(text_editor::Motion::Down) => {
+                            completion.select_next();
+                            return Command::none();
+                        }
+                        text_editor::Action::Edit(text_editor::Edit::Enter) => {
+                            let selected = completion.selected;
+                            return self.update(Message::AcceptCompletion(selected));
+                        }
+                        _ => {}
+                    }
+                }
+                let completes = match action {
+                    text_editor::Action::Edit(text_editor::Edit::Insert(c)) => complete::is_word(c),
+                    text_editor::Action::Edit(text_editor::Edit::Backspace) => {
+                        self.completion.is_some()
+                    }
+                    _ => false,
+                };
+                let is_scroll = matches!(action, text_editor::Action::Scroll { .. });
+                let viewport_lines = self.viewport_lines;
+                let modifiers = self.modifiers;
+                let buffer = &mut self.buffers[self.active];
+                if let Some(folded) = &mut buffer.folded {
+                    if !action.is_edit() {
+                        folded.perform(action, viewport_lines);
+                        return Command::none();
+                    }
+                    buffer.unfold();
+                }
+                match action {
+                    text_editor::Action::Scroll { lines } => buffer.scroll(lines),
+                    text_editor::Action::Click(point) => {
+                        if modifiers.command() {
+                            buffer.add_caret();
+                        } else {
+                            buffer.carets.clear();
+                        }
+                        self.column_anchor = modifiers
+                            .alt()
+                            .then(|| position_at(point, buffer.visible_top(viewport_lines)));
+                    }
+                    text_editor::Action::Move(text_editor::Motion::Up) if modifiers.alt() => {
+                        return self.update(Message::MoveLinesUp);
+                    }
+                    text_editor::Action::Move(text_editor::Motion::Down) if modifiers.alt() => {
+                        return self.update(Message::MoveLinesDown);
+                    }
+                    text_editor::Action::Drag(point) if modifiers.alt() => {
+                        if let Some(anchor) = self.column_anchor {
+                            let head = position_at(point, buffer.visible_top(viewport_lines));
+                            buffer.select_columns(anchor, head);
+                            return Command::none();
+                        }
+                    }
+                    _ => {}
+                }
+                let steps = buffer
+                    .carets
+                    .is_empty()
+                    .then(|| motion::expand(&buffer.content, &action, &self.config))
+                    .flatten();
+                if let Some(steps) = steps {
+                    for step in steps {
+                        buffer.perform(step);
+                    }
+                } else if buffer.is_readonly || !buffer.carets.is_empty() {
+                    buffer.perform(action);
+                } else {
+                    for action in
+                        assist::expand(&buffer.content, action, &self.config, buffer.indent)
+                    {
+                        buffer.perform(action);
+                    }
+                }
+                if !is_scroll {
+                    buffer.top_line = buffer.visible_top(viewport_lines);
+                }
+                if self.state.typewriter && !is_scroll {
+                    self.center_cursor();
+                }
+                self.schedule_sync(completes);
+                self.update_completion(completes);
+                self.sync_preview()
+            }
+            Message::Open => Command::perform(pick_file(), Message::FileOpened),
+            Message::FileOpened(Ok(loaded)) => {
+                let launch = self
+                    .pending_opens
+                    .iter()
+                    .position(|file| file.path == loaded.path)
+                    .map(|index| self.pending_opens.remove(index));
+                if let Some(index) = self.buffers.iter().position(|buffer| {
+                    buffer
+                        .path
+                        .as_deref()
+                        .is_some_and(|path| is_same_file(path, &loaded.path))
+                }) {
+                    self.active = index;
+                    if let Some(launch) = launch
+                        && let Some(line) = launch.line
+                    {
+                        move_cursor_to(
+                            &mut self.buffers[index].content,
+                            line - 1,
+                            launch.column.unwrap_or_default(),
+                        );
+                        self.center_cursor();
+                    }
+                    return Command::none();
+                }
+                let view = self
+                    .views
+                    .remove(&loaded.path)
+                    .or(loaded.view)
+                    .filter(|_| self.config.persist_view_state && !loaded.is_binary);
+                let language = view
+                    .as_ref()
+                    .and_then(|view| view.language.as_deref())
+                    .and_then(Language::from_token)
+                    .unwrap_or_else(|| Language::from_path(&loaded.path));
+                self.request_server(language, &loaded.path);
+                let remember = self.remember(&loaded.path);
+                let tab_width = self.config.tab_width;
+                let marked = self.bookmarks.get(&loaded.path);
+                let hash = loaded.stamp.map(|stamp| stamp.hash);
+                let history = self
+                    .histories
+                    .remove(&loaded.path)
+                    .filter(|saved| Some(saved.hash) == hash)
+                    .or(loaded.history)
+                    .filter(|_| self.config.persist_undo);
+                let buffer = self.open_buffer(Some(loaded.path.clone()), &loaded.text);
+                buffer.passphrase = loaded.passphrase;
+                if let Some(saved) = history {
+                    buffer.history.restore(saved.undo, saved.redo);
+                }
+                let line_count = buffer.content.line_count();
+                buffer.bookmarks = marked
+                    .into_iter()
+                    .filter(|line| *line < line_count)
+                    .collect();
+                let id = buffer.id;
+                let remember = Command::batch([
+                    remember,
+                    Command::perform(git::inspect(loaded.path), move |info| {
+                        Message::GitInspected(id, info)
+                    }),
+                ]);
+                buffer.encoding = loaded.encoding;
+                buffer.has_bom = loaded.has_bom;
+                buffer.line_ending = loaded.line_ending;
+                buffer.disk = loaded.stamp;
+                buffer.is_readonly = !loaded.is_writable;
+                if loaded.is_binary {
+                    buffer.hex = true;
+                    buffer.language = Language::PlainText;
+                }
+                let properties = loaded.editorconfig;
+                if let Some(indent) = properties.indent(tab_width) {
+                    buffer.indent = indent;
+                }
+                if let Some(trim) = properties.trim_trailing_whitespace {
+                    buffer.transforms.trim_trailing_whitespace = trim;
+                }
+                if let Some(final_newline) = properties.insert_final_newline {
+                    buffer.transforms.final_newline = final_newline;
+                }
+                if let Some(view) = view
+                    && launch
+                        .as_ref()
+                        .is_none_or(|launch| launch.line.is_none() && launch.offset.is_none())
+                {
+                    buffer.restore_view(view);
+                }
+                let Some(launch) = launch else {
+                    return remember;
+                };
+                buffer.is_readonly |= launch.readonly;
+                if let Some(line) = launch.line {
+                    move_cursor_to(
+                        &mut buffer.content,
+                        line - 1,
+                        launch.column.unwrap_or_default(),
+                    );
+                }
+                match launch.offset {
+                    Some(offset) => {
+                        Command::batch([remember, self.update(Message::GoToOffset(offset))])
+                    }
+                    None => remember,
+                }
+            }
+            Message::FileOpened(Err(Error::LargeFile(path, size))) => {
+                if size > large::PAGED_THRESHOLD {
+                    let buffer = self.open_buffer(Some(path.clone()), "");
+                    buffer.is_readonly = true;
+                    buffer.page = Some((0, 0));
+                    let id = buffer.id;
+                    Command::perform(large::read_page(path, 0), move |result| {
+                        Message::PageLoaded(id, result)
+                    })
+                } else {
+                    self.loading.push(large::Loading {
+                        id: self.next_buffer_id,
+                        path,
+                        read: 0,
+                        total: size,
+                    });
+                    self.next_buffer_id += 1;
+                    Command::none()
+                }
+            }
+            Message::FileOpened(Err(Error::Encrypted(path))) => {
+                if let Some(index) = self.buffers.iter().position(|buffer| {
+                    buffer
+                        .path
+                        .as_deref()
+                        .is_some_and(|open| is_same_file(open, &path))
+                }) {
+                    self.active = index;
+                    return Command::none();
+                }
+                self.crypt_prompt = Some(crypt::Prompt::new(crypt::Target::Open(path)));
+                text_input::focus(text_input::Id::new(PASSPHRASE_INPUT))
+            }
+            Message::FileOpened(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::PassphraseChanged(passphrase) => {
+                if let Some(prompt) = &mut self.crypt_prompt {
+                    prompt.passphrase.zeroize();
+                    prompt.passphrase = passphrase;
+                }
+                Command::none()
+            }
+            Message::PassphraseConfirmationChanged(confirmation) => {
+                if let Some(prompt) = &mut self.crypt_prompt {
+                    prompt.confirmation.zeroize();
+                    prompt.confirmation = confirmation;
+                }
+                Command::none()
+            }
+            Message::SubmitPassphrase => {
+                let Some(prompt) = &mut self.crypt_prompt else {
+                    return Command::none();
+                };
+                if prompt.is_working {
+                    return Command::none();
+                }
+                if prompt.passphrase.is_empty() {
+                    prompt.error = Some(String::from("enter a passphrase"));
+                    return Command::none();
+                }
+                let passphrase = crypt::Passphrase::new(prompt.passphrase.clone());
+                match prompt.target.clone() {
+                    crypt::Target::Open(path) => {
+                        prompt.is_working = true;
+                        prompt.error = None;
+                        Command::perform(load_encrypted(path, passphrase), Message::Decrypted)
+                    }
+                    crypt::Target::Encrypt(id) => {
+                        if prompt.confirmation != prompt.passphrase {
+                            prompt.error = Some(String::from("the passphrases do not match"));
+                            return Command::none();
+                        }
+                        self.crypt_prompt = None;
+                        let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id)
+                        else {
+                            return Command::none();
+                        };
+                        buffer.passphrase = Some(passphrase);
+                        buffer.is_dirty = true;
+                        if let Some(path) = buffer.path.clone()
+                            && !crypt::is_encrypted(&path)
+                        {
+                            buffer.set_path(crypt::encrypted_path(&path));
+                            buffer.disk = None;
+                            let title = buffer.title();
+                            self.notify(format!(
+                                "Save to write {title}. The unencrypted original is left in place"
+                            ));
+                        }
+                        Command::none()
+                    }
+                }
+            }
+            Message::Decrypted(Ok(loaded)) => {
+                self.crypt_prompt = None;
+                self.update(Message::FileOpened(Ok(loaded)))
+            }
+            Message::Decrypted(Err(error)) => {
+                if let Some(prompt) = &mut self.crypt_prompt {
+                    prompt.is_working = false;
+                    prompt.passphrase.zeroize();
+                    prompt.error = Some(error.to_string());
+                }
+                text_input::focus(text_input::Id::new(PASSPHRASE_INPUT))
+            }
+            Message::EncryptBuffer => {
+                let buffer = self.buffer();
+                if buffer.hex || buffer.page.is_some() || buffer.remote.is_some() {
+                    self.notify(String::from("Only local text files can be encrypted"));
+                    return Command::none();
+                }
+                self.crypt_prompt = Some(crypt::Prompt::new(crypt::Target::Encrypt(buffer.id)));
+                text_input::focus(text_input::Id::new(PASSPHRASE_INPUT))
+            }
+            Message::LoadProgress(id, read, total) => {
+                if let Some(loading) = self.loading.iter_mut().find(|loading| loading.id == id) {
+                    loading.read = read;
+                    loading.total = total;
+                }
+                Command::none()
+            }
+            Message::StreamFinished(id, result) => {
+                self.loading.retain(|loading| loading.id != id);
+                self.update(Message::FileOpened(result))
+            }
+            Message::LoadPage(id, index) => {
+                match self.buffers.iter().find(|buffer| buffer.id == id) {
+                    Some(Buffer {
+                        path: Some(path), ..
+                    }) => Command::perform(large::read_page(path.clone(), index), move |result| {
+                        Message::PageLoaded(id, result)
+                    }),
+                    _ => Command::none(),
+                }
+            }
+            Message::PageLoaded(id, Ok(page)) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.content = text_editor::Content::with(&page.text);
+                    buffer.page = Some((page.index, page.count));
+                    buffer.revision += 1;
+                }
+                Command::none()
+            }
+            Message::PageLoaded(_, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::EncodingSelected(encoding) => {
+                let buffer = self.buffer_mut();
+                if buffer.encoding != encoding {
+                    buffer.encoding = encoding;
+                    buffer.is_dirty = true;
+                }
+                Command::none()
+            }
+            Message::ReopenWithEncoding => {
+                let buffer = self.buffer();
+                let id = buffer.id;
+                match buffer.path.clone() {
+                    Some(path) => {
+                        Command::perform(load_file_as(path, Some(buffer.encoding)), move |result| {
+                            Message::Reopened(id, result)
+                        })
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::Reopened(id, Ok(loaded)) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.reload(&loaded.text);
+                    buffer.encoding = loaded.encoding;
+                    buffer.has_bom = loaded.has_bom;
+                    buffer.line_ending = loaded.line_ending;
+                    buffer.hex = loaded.is_binary;
+                    buffer.disk = loaded.stamp;
+                    let title = buffer.title();
+                    self.notify(format!("Reloaded {title} ({})", loaded.encoding));
+                }
+                Command::none()
+            }
+            Message::Reopened(_, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::FileChanged(path) => {
+                let checks = self
+                    .buffers
+                    .iter()
+                    .filter(|buffer| {
+                        buffer.page.is_none() && !buffer.hex && buffer.path.as_ref() == Some(&path)
+                    })
+                    .map(|buffer| {
+                        let id = buffer.id;
+                        Command::perform(
+                            load_file_as(path.clone(), Some(buffer.encoding)),
+                            move |result| Message::DiskChecked(id, result),
+                        )
+                    });
+                Command::batch(checks.collect::<Vec<_>>())
+            }
+            Message::DiskChecked(id, Ok(loaded)) => {
+                let auto_reload = self.config.auto_reload;
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    let text = buffer.content.text();
+                    let title = buffer.title();
+                    let is_restored = std::mem::take(&mut buffer.is_deleted);
+                    if loaded.text.trim_end_matches('\n') == text.trim_end_matches('\n') {
+                        buffer.has_conflict = false;
+                        buffer.disk = loaded.stamp;
+                    } else if auto_reload && !buffer.is_dirty {
+                        buffer.reload(&loaded.text);
+                        buffer.line_ending = loaded.line_ending;
+                        buffer.disk = loaded.stamp;
+                        self.notify(format!("Reloaded {title} after it changed on disk"));
+                    } else if !buffer.has_conflict {
+                        buffer.has_conflict = true;
+                        self.toasts
+                            .push(toast::Level::Warning, format!("{title} changed on disk"));
+                    }
+                    if is_restored {
+                        self.notify(format!("{title} is back on disk"));
+                    }
+                }
+                Command::none()
+            }
+            Message::DiskChecked(id, Err(Error::IOFailed(io::ErrorKind::NotFound))) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id)
+                    && !buffer.is_deleted
+                {
+                    buffer.is_deleted = true;
+                    buffer.has_conflict = false;
+                    let title = buffer.title();
+                    self.toasts.push(
+                        toast::Level::Warning,
+                        format!("{title} was deleted on disk. Save to restore it"),
+                    );
+                }
+                Command::none()
+            }
+            Message::DiskChecked(_, Err(_)) => Command::none(),
+            Message::FileRenamed(from, to) => {
+                let mut renamed = Vec::new();
+                for buffer in &mut self.buffers {
+                    if buffer.path.as_ref() == Some(&from) {
+                        buffer.set_path(to.clone());
+                        renamed.push(buffer.title());
+                    }
+                }
+                for title in renamed {
+                    self.notify(format!(
+                        "{} was renamed to {title} on disk",
+                        from.file_name().unwrap_or_default().to_string_lossy()
+                    ));
+                }
+                Command::none()
+            }
+            Message::ReloadFromDisk(id) => {
+                match self.buffers.iter().find(|buffer| buffer.id == id) {
+                    Some(Buffer {
+                        path: Some(path),
+                        encoding,
+                        ..
+                    }) => Command::perform(
+                        load_file_as(path.clone(), Some(*encoding)),
+                        move |result| Message::Reopened(id, result),
+                    ),
+                    _ => Command::none(),
+                }
+            }
+            Message::KeepBuffer(id) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.has_conflict = false;
+                }
+                Command::none()
+            }
+            Message::LineEndingSelected(line_ending) => {
+                let buffer = self.buffer_mut();
+                if buffer.line_ending != line_ending {
+                    buffer.line_ending = line_ending;
+                    buffer.is_dirty = true;
+                }
+                Command::none()
+            }
+            Message::Save => self.save_buffer(self.active, false),
+            Message::SaveAs => self.save_buffer(self.active, true),
+            Message::FileSaved(id, Ok((path, stamp))) => {
+                let again = self.saves.finish(id);
+                let remember = Command::batch([
+                    self.remember(&path),
+                    Command::perform(git::inspect(path.clone()), move |info| {
+                        Message::GitInspected(id, info)
+                    }),
+                ]);
+                let mut record = Command::none();
+                if let Some(index) = self.buffers.iter().position(|buffer| buffer.id == id) {
+                    let buffer = &mut self.buffers[index];
+                    buffer.is_dirty = false;
+                    buffer.has_conflict = false;
+                    buffer.is_deleted = false;
+                    buffer.disk = Some(stamp);
+                    buffer.set_path(path.clone());
+                    if self.config.local_history
+                        && buffer.passphrase.is_none()
+                        && !buffer.hex
+                        && buffer.page.is_none()
+                    {
+                        let limits = self.config.local_history_limits();
+                        record = Command::perform(
+                            versions::record(path.clone(), buffer.content.text(), limits),
+                            Message::VersionRecorded,
+                        );
+                    }
+                    if let Some(panel) = &mut self.unsaved
+                        && panel.buffer == id
+                    {
+                        panel.set_saved(Arc::new(buffer.content.text()));
+                    }
+                    if let Some(panel) = &mut self.todos
+                        && panel.scanning.is_none()
+                    {
+                        panel.rescan(&path, &buffer.content.text());
+                    }
+                    let title = buffer.title();
+                    self.notify(format!("Saved {title}"));
+                    let buffer = &mut self.buffers[index];
+                    if buffer.hex {
+                        buffer.language = Language::PlainText;
+                        buffer.refresh_hex();
+                    }
+                    let language = buffer.language;
+                    self.request_server(language, &path);
+                    self.sync_document(index);
+                    if let Some(client) = self.servers.client(language) {
+                        client.did_save(&path);
+                    }
+                }
+                let remember = Command::batch([remember, record, self.remember_bookmarks(id)]);
+                if again
+                    && let Some(index) = self
+                        .buffers
+                        .iter()
+                        .position(|buffer| buffer.id == id && buffer.needs_save())
+                {
+                    return Command::batch([remember, self.write_buffer(index, false)]);
+                }
+                let next = match self.after_save.take() {
+                    Some(confirmation) if confirmation.buffer == id => {
+                        self.finish_confirmation(confirmation)
+                    }
+                    after_save => {
+                        self.after_save = after_save;
+                        Command::none()
+                    }
+                };
+                Command::batch([remember, next])
+            }
+            Message::FileSaved(id, Err(Error::IOFailed(io::ErrorKind::PermissionDenied))) => {
+                self.saves.cancel(id);
+                self.save_denied = Some(id);
+                Command::none()
+            }
+            Message::FileSaved(id, Err(Error::SaveConflict)) => {
+                self.saves.cancel(id);
+                self.save_conflict = Some(id);
+                Command::none()
+            }
+            Message::OverwriteConflict => {
+                let index = self
+                    .save_conflict
+                    .take()
+                    .and_then(|id| self.buffers.iter().position(|buffer| buffer.id == id));
+                match index {
+                    Some(index) => {
+                        self.buffers[index].disk = None;
+                        self.write_buffer(index, false)
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::SaveConflictAs => {
+                let index = self
+                    .save_conflict
+                    .take()
+                    .and_then(|id| self.buffers.iter().position(|buffer| buffer.id == id));
+                match index {
+                    Some(index) => self.save_buffer(index, true),
+                    None => Command::none(),
+                }
+            }
+            Message::CompareConflict => {
+                let index = self
+                    .save_conflict
+                    .take()
+                    .and_then(|id| self.buffers.iter().position(|buffer| buffer.id == id));
+                self.after_save = None;
+                match index {
+                    Some(index) => {
+                        self.active = index;
+                        self.update(Message::CompareWithSaved)
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::CancelSaveConflict => {
+                self.save_conflict = None;
+                self.after_save = None;
+                Command::none()
+            }
+            Message::SaveDeniedAs => {
+                let index = self
+                    .save_denied
+                    .take()
+                    .and_then(|id| self.buffers.iter().position(|buffer| buffer.id == id));
+                match index {
+                    Some(index) => self.save_buffer(index, true),
+                    None => Command::none(),
+                }
+            }
+            Message::CancelSaveDenied => {
+                self.save_denied = None;
+                self.after_save = None;
+                Command::none()
+            }
+            Message::ToggleReadonly => {
+                let buffer = self.buffer_mut();
+                if buffer.page.is_none() {
+                    buffer.is_readonly = !buffer.is_readonly;
+                }
+                Command::none()
+            }
+            Message::ToggleSaveTransforms => {
+                let buffer = self.buffer_mut();
+                buffer.skip_transforms = !buffer.skip_transforms;
+                buffer.save_report = None;
+                Command::none()
+            }
+            Message::FileSaved(id, Err(error)) => {
+                self.saves.cancel(id);
+                self.report(error);
+                self.after_save = None;
+                Command::none()
+            }
+            Message::ThemeSelected(theme) => {
+                self.theme = theme;
+
+                Command::none()
+            }
+            Message::AppearanceSelected(appearance) => {
+                let check = appearance == appearance::Appearance::System;
+                self.state.appearance = appearance;
+                let save = Command::perform(state::save(self.state.clone()), Message::StateSaved);
+                if check {
+                    Command::batch([
+                        save,
+                        Command::perform(appearance::system_is_dark(), Message::SystemThemeChecked),
+                    ])
+                } else {
+                    save
+                }
+            }
+            Message::SystemThemeChecked(is_dark) => {
+                self.system_is_dark = is_dark;
+                Command::none()
+            }
+            Message::GoToOffset(offset) => {
+                let content = &mut self.buffer_mut().content;
+                let (line, column) = offset_to_position(&content.text(), offset);
+                move_cursor_to(content, line, column);
+                if self.state.typewriter {
+                    self.center_cursor();
+                }
+                Command::none()
+            }
+            Message::TogglePathDisplay => {
+                self.state.path_display = self.state.path_display.next();
+                Command::perform(state::save(self.state.clone()), Message::StateSaved)
+            }
+            Message::ModifiersChanged(modifiers) => {
+                self.modifiers = modifiers;
+                Command::none()
+            }
+            Message::ZoomIn => self.zoom(self.config.font_size + 1.0),
+            Message::ZoomOut => self.zoom(self.config.font_size - 1.0),
+            Message::ResetZoom => self.zoom(config::DEFAULT_FONT_SIZE),
+            Message::WheelScrolled(delta) if self.modifiers.command() => {
+                let y = match delta {
+                    mouse::ScrollDelta::Lines { y, .. } => y,
+                    mouse::ScrollDelta::Pixels { y, .. } => y / LINE_HEIGHT,
+                };
+                if y > 0.0 {
+                    self.zoom(self.config.font_size + 1.0)
+                } else if y < 0.0 {
+                    self.zoom(self.config.font_size - 1.0)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::WheelScrolled(_) => Command::none(),
+            Message::ZoomSaved(Ok(())) => Command::none(),
+            Message::ZoomSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::ChooseFont => {
+                self.font_picker = Some(fonts::Picker::new(self.font));
+                Command::batch([
+                    Command::perform(fonts::monospace(), Message::FontsListed),
+                    text_input::focus(text_input::Id::new(FONT_INPUT)),
+                ])
+            }
+            Message::FontsListed(list) => {
+                if let Some(picker) = &mut self.font_picker {
+                    picker.fonts = list;
+                }
+                Command::none()
+            }
+            Message::FontFilterChanged(filter) => {
+                if let Some(picker) = &mut self.font_picker {
+                    picker.filter = filter;
+                }
+                Command::none()
+            }
+            Message::FontSelected(name) => {
+                if let Some(picker) = &mut self.font_picker {
+                    picker.selected = Some(name);
+                    self.font = fonts::font(name);
+                }
+                Command::none()
+            }
+            Message::ApplyFont => {
+                let Some(picker) = self.font_picker.take() else {
+                    return Command::none();
+                };
+                let Some(name) = picker.selected else {
+                    return Command::none();
+                };
+                self.config.font = Some(name.to_owned());
+                Command::perform(config::save_font(name.to_owned()), Message::FontSaved)
+            }
+            Message::CancelFont => {
+                if let Some(picker) = self.font_picker.take() {
+                    self.font = picker.previous;
+                }
+                Command::none()
+            }
+            Message::FontSaved(Ok(())) => Command::none(),
+            Message::FontSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::ToastTick => Command::none(),
+            Message::DismissToast(id) => {
+                self.toasts.dismiss(id);
+                Command::none()
+            }
+            Message::ToggleNotifications => {
+                self.notifications = !self.notifications;
+                Command::none()
+            }
+            Message::ClearNotifications => {
+                self.toasts.history.clear();
+                Command::none()
+            }
+            Message::ToggleStatistics => {
+                self.statistics = match self.statistics {
+                    Some(_) => None,
+                    None => Some(stats::Panel::default()),
+                };
+                self.refresh_statistics()
+            }
+            Message::StatisticsTick => self.refresh_statistics(),
+            Message::RenderTick => self.refresh_layouts(),
+            Message::LayoutComputed(id, Some(layout)) => {
+                self.render.finish(id, layout);
+                Command::none()
+            }
+            Message::LayoutComputed(_, None) => Command::none(),
+            Message::ToggleTodos => {
+                if self.todos.take().is_some() {
+                    return Command::none();
+                }
+                let root = if self.explorer.root.as_os_str().is_empty() {
+                    std::env::current_dir().unwrap_or_default()
+                } else {
+                    self.explorer.root.clone()
+                };
+                self.todos = Some(todos::Panel::new(root));
+                self.refresh_todos()
+            }
+            Message::TodosTick => self.refresh_todos(),
+            Message::TodosComputed(key, annotations) => {
+                if let Some(panel) = &mut self.todos
+                    && panel.pending == Some(key)
+                {
+                    panel.pending = None;
+                    panel.key = Some(key);
+                    panel.annotations = annotations;
+                }
+                self.refresh_todos()
+            }
+            Message::ToggleWorkspaceTodos(enabled) => {
+                if let Some(panel) = &mut self.todos {
+                    if enabled {
+                        panel.scan_workspace();
+                    } else {
+                        panel.workspace = None;
+                        panel.scanning = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::TodosFound(id, hits) => {
+                if let Some(panel) = &mut self.todos
+                    && panel.scanning == Some(id)
+                    && let Some(workspace) = &mut panel.workspace
+                {
+                    workspace.extend(hits);
+                    if workspace.len() >= grep::MAX_HITS {
+                        workspace.truncate(grep::MAX_HITS);
+                        panel.scanning = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::TodosFinished(id) => {
+                if let Some(panel) = &mut self.todos
+                    && panel.scanning == Some(id)
+                {
+                    panel.scanning = None;
+                }
+                Command::none()
+            }
+            Message::StatisticsComputed(report) => {
+                if let Some(panel) = &mut self.statistics
+                    && panel.pending.as_ref() == Some(&report.key)
+                {
+                    panel.pending = None;
+                    panel.report = Some(report);
+                }
+                self.refresh_statistics()
+            }
+            Message::SyncTick => {
+                let Some((edited_at, completes)) = self.sync_at else {
+                    return Command::none();
+                };
+                if edited_at.elapsed() < lsp::SYNC_DELAY {
+                    return Command::none();
+                }
+                self.sync_at = None;
+                for index in 0..self.buffers.len() {
+                    self.sync_document(index);
+                }
+                if completes {
+                    self.update_completion(true);
+                }
+                Command::none()
+            }
+            Message::ZoomTick => {
+                if self
+                    .zoomed_at
+                    .is_some_and(|zoomed_at| zoomed_at.elapsed() >= ZOOM_NOTICE)
+                {
+                    self.zoomed_at = None;
+                }
+                Command::none()
+            }
+            Message::SelectNextOccurrence => {
+                self.buffer_mut().select_next_occurrence();
+                Command::none()
+            }
+            Message::ToggleExplorer => {
+                self.explorer.is_visible = !self.explorer.is_visible;
+                if !self.explorer.is_visible || !self.explorer.root.as_os_str().is_empty() {
+                    return Command::none();
+                }
+                let root = self
+                    .buffer()
+                    .path
+                    .as_deref()
+                    .and_then(std::path::Path::parent)
+                    .map(std::path::Path::to_path_buf)
+                    .or_else(|| std::env::current_dir().ok())
+                    .unwrap_or_default();
+                self.update(Message::FolderPicked(Some(root)))
+            }
+            Message::OpenFolder => Command::perform(pick_folder(), Message::FolderPicked),
+            Message::OpenRemote => {
+                self.remote_prompt = Some(remote::Prompt {
+                    url: String::from(remote::SCHEME),
+                    ..remote::Prompt::default()
+                });
+                text_input::focus(text_input::Id::new(REMOTE_INPUT))
+            }
+            Message::OpenUrl => {
+                self.remote_prompt = Some(remote::Prompt {
+                    url: String::from("https://"),
+                    ..remote::Prompt::default()
+                });
+                text_input::focus(text_input::Id::new(REMOTE_INPUT))
+            }
+            Message::UrlOpened(url, Ok(bytes)) => {
+                self.remote_prompt = None;
+                let (text, encoding) = encoding::decode(&bytes, None);
+                let line_ending = LineEnding::detect(&text);
+                let buffer = self.open_buffer(None, &LineEnding::normalize(&text));
+                buffer.has_bom = encoding::has_bom(&bytes);
+                buffer.language = Language::from_path(std::path::Path::new(web::file_name(&url)));
+                buffer.encoding = encoding;
+                buffer.line_ending = line_ending;
+                buffer.is_readonly = true;
+                buffer.url = Some(url.clone());
+                self.notify(format!("Fetched {url}"));
+                Command::none()
+            }
+            Message::UrlOpened(_, Err(error)) => {
+                match &mut self.remote_prompt {
+                    Some(prompt) => {
+                        prompt.is_connecting = false;
+                        prompt.error = Some(error);
+                    }
+                    None => self.report(Error::Fetch(error)),
+                }
+                Command::none()
+            }
+            Message::RemoteUrlChanged(url) => {
+                if let Some(prompt) = &mut self.remote_prompt {
+                    prompt.url = url;
+                    prompt.error = None;
+                }
+                Command::none()
+            }
+            Message::RemotePasswordChanged(password) => {
+                if let Some(prompt) = &mut self.remote_prompt {
+                    prompt.password = password;
+                }
+                Command::none()
+            }
+            Message::ConnectRemote => {
+                let Some(prompt) = &mut self.remote_prompt else {
+                    return Command::none();
+                };
+                if web::is_url(prompt.url.trim()) {
+                    prompt.is_connecting = true;
+                    prompt.error = None;
+                    return fetch_url(prompt.url.trim().to_owned());
+                }
+                let Some(location) = remote::Location::parse(prompt.url.trim()) else {
+                    prompt.error = Some(String::from(
+                        "expected sftp://user@host/path or an http(s) URL",
+                    ));
+                    return Command::none();
+                };
+                let password = if prompt.needs_password {
+                    Some(prompt.password.clone())
+                } else {
+                    self.credentials.get(&location)
+                };
+                prompt.is_connecting = true;
+                prompt.error = None;
+                Command::perform(
+                    remote::download(location.clone(), password.clone()),
+                    move |result| Message::RemoteOpened(location, password, result),
+                )
+            }
+            Message::RemoteOpened(location, password, Ok(bytes)) => {
+                self.remote_prompt = None;
+                if let Some(password) = password {
+                    self.credentials.insert(&location, password);
+                }
+                let (text, encoding) = encoding::decode(&bytes, None);
+                let line_ending = LineEnding::detect(&text);
+                let buffer = self.open_buffer(None, &LineEnding::normalize(&text));
+                buffer.has_bom = encoding::has_bom(&bytes);
+                buffer.language = Language::from_path(std::path::Path::new(location.file_name()));
+                buffer.encoding = encoding;
+                buffer.line_ending = line_ending;
+                self.notify(format!("Downloaded {location}"));
+                self.buffer_mut().remote = Some(location);
+                Command::none()
+            }
+            Message::RemoteOpened(location, password, Err(failure)) => {
+                let prompt = self.remote_prompt.get_or_insert_with(|| remote::Prompt {
+                    url: location.to_string(),
+                    ..remote::Prompt::default()
+                });
+                prompt.is_connecting = false;
+                match failure {
+                    remote::Failure::Denied => {
+                        if password.is_some() {
+                            prompt.error = Some(failure.to_string());
+                        }
+                        prompt.needs_password = true;
+                        prompt.password.clear();
+                        text_input::focus(text_input::Id::new(PASSWORD_INPUT))
+                    }
+                    remote::Failure::Failed(error) => {
+                        prompt.error = Some(error);
+                        Command::none()
+                    }
+                }
+            }
+            Message::RemoteSaved(id, Ok(())) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.is_dirty = false;
+                    let title = buffer.title();
+                    self.notify(format!("Uploaded {title}"));
+                }
+                match self.after_save.take() {
+                    Some(confirmation) if confirmation.buffer == id => {
+                        self.finish_confirmation(confirmation)
+                    }
+                    after_save => {
+                        self.after_save = after_save;
+                        Command::none()
+                    }
+                }
+            }
+            Message::RemoteSaved(_, Err(failure)) => {
+                self.after_save = None;
+                self.report(Error::Remote(failure.to_string()));
+                Command::none()
+            }
+            Message::FolderPicked(Some(root)) => {
+                self.explorer.set_root(root.clone());
+                self.explorer.is_visible = true;
+                Command::batch([
+                    self.index_workspace(root.clone()),
+                    Command::perform(explorer::read_dir(root), |(directory, entries)| {
+                        Message::DirectoryRead(directory, entries)
+                    }),
+                ])
+            }
+            Message::WorkspaceIndexed(root, files) => {
+                if root == self.workspace.root {
+                    self.workspace.files = files;
+                    self.workspace.is_indexing = false;
+                }
+                Command::none()
+            }
+            Message::ToggleFinder => {
+                if self.finder.take().is_some() {
+                    return Command::none();
+                }
+                self.finder = Some(String::new());
+                let root = if self.workspace.root.as_os_str().is_empty() {
+                    std::env::current_dir().unwrap_or_default()
+                } else {
+                    self.workspace.root.clone()
+                };
+                Command::batch([
+                    self.index_workspace(root),
+                    text_input::focus(text_input::Id::new(FINDER_INPUT)),
+                ])
+            }
+            Message::FinderQueryChanged(query) => {
+                self.finder = Some(query);
+                Command::none()
+            }
+            Message::OpenFirstFinderMatch => {
+                if let Some(query) = self.finder.as_deref().map(str::trim)
+                    && web::is_url(query)
+                {
+                    let url = query.to_owned();
+                    self.finder = None;
+                    return fetch_url(url);
+                }
+                let first = self.finder.as_deref().and_then(|query| {
+                    self.workspace
+                        .matching(query)
+                        .first()
+                        .map(|path| path.to_path_buf())
+                });
+                match first {
+                    Some(path) => self.update(Message::OpenWorkspaceFile(path)),
+                    None => Command::none(),
+                }
+            }
+            Message::OpenWorkspaceFile(path) => {
+                self.finder = None;
+                Command::perform(
+                    load_file(self.workspace.root.join(path)),
+                    Message::FileOpened,
+                )
+            }
+            Message::FolderPicked(None) => Command::none(),
+            Message::DirectoryRead(directory, Ok(entries)) => {
+                self.explorer.insert(directory, entries);
+                Command::none()
+            }
+            Message::DirectoryRead(_, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::ExplorerEntryPressed(entry) if entry.is_dir => {
+                if self.explorer.toggle(&entry.path) && !self.explorer.is_loaded(&entry.path) {
+                    Command::perform(explorer::read_dir(entry.path), |(directory, entries)| {
+                        Message::DirectoryRead(directory, entries)
+                    })
+                } else {
+                    Command::none()
+                }
+            }
+            Message::ExplorerEntryPressed(entry) => {
+                match self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.path.as_ref() == Some(&entry.path))
+                {
+                    Some(index) => {
+                        self.active = index;
+                        Command::none()
+                    }
+                    None => Command::perform(load_file(entry.path), Message::FileOpened),
+                }
+            }
+            Message::ToggleGrep => {
+                if self.grep.take().is_some() {
+                    return Command::none();
+                }
+                let root = if self.explorer.root.as_os_str().is_empty() {
+                    std::env::current_dir().unwrap_or_default()
+                } else {
+                    self.explorer.root.clone()
+                };
+                self.grep = Some(grep::Panel::new(root));
+                text_input::focus(text_input::Id::new(GREP_INPUT))
+            }
+            Message::GrepQueryChanged(query) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.query = query;
+                }
+                Command::none()
+            }
+            Message::GrepOptionsChanged(options) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.options = options;
+                }
+                Command::none()
+            }
+            Message::GrepPickFolder => Command::perform(pick_folder(), Message::GrepFolderPicked),
+            Message::GrepFolderPicked(Some(root)) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.root = root;
+                }
+                Command::none()
+            }
+            Message::GrepFolderPicked(None) => Command::none(),
+            Message::RunGrep => {
+                let id = self.next_grep_id;
+                let Some(panel) = self.grep.as_mut() else {
+                    return Command::none();
+                };
+                panel.hits.clear();
+                panel.files = 0;
+                panel.running = None;
+                panel.error = None;
+                panel.preview = None;
+                if panel.query.is_empty() {
+                    return Command::none();
+                }
+                match search::compile(&panel.query, panel.options) {
+                    Ok(pattern) => {
+                        panel.pattern = Some(pattern);
+                        panel.running = Some(id);
+                        self.next_grep_id += 1;
+                    }
+                    Err(error) => panel.error = Some(error.to_string()),
+                }
+                Command::none()
+            }
+            Message::CancelGrep => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.running = None;
+                }
+                Command::none()
+            }
+            Message::ToggleGrepReplace => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.is_replacing = !panel.is_replacing;
+                    panel.preview = None;
+                }
+                Command::none()
+            }
+            Message::GrepReplacementChanged(replacement) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.replacement = replacement;
+                    panel.preview = None;
+                }
+                Command::none()
+            }
+            Message::PreviewReplace => {
+                let Some(panel) = self.grep.as_mut() else {
+                    return Command::none();
+                };
+                let Some(replacement) = panel.replacement() else {
+                    return Command::none();
+                };
+                let mut paths: Vec<PathBuf> = Vec::new();
+                for hit in &panel.hits {
+                    if paths.last() != Some(&hit.path) {
+                        paths.push(hit.path.clone());
+                    }
+                }
+                Command::perform(
+                    replace::preview(paths, replacement),
+                    Message::ReplacePreviewed,
+                )
+            }
+            Message::ReplacePreviewed(preview) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.preview = Some(preview);
+                }
+                Command::none()
+            }
+            Message::ToggleHunk(file, hunk) => {
+                if let Some(hunk) = self
+                    .grep
+                    .as_mut()
+                    .and_then(|panel| panel.preview.as_mut())
+                    .and_then(|preview| preview.get_mut(file))
+                    .and_then(|file| file.hunks.get_mut(hunk))
+                {
+                    hunk.selected = !hunk.selected;
+                }
+                Command::none()
+            }
+            Message::ApplyReplace => {
+                let Some(panel) = self.grep.as_mut() else {
+                    return Command::none();
+                };
+                let (Some(replacement), Some(preview)) =
+                    (panel.replacement(), panel.preview.take())
+                else {
+                    return Command::none();
+                };
+                panel.is_applying = true;
+                Command::perform(
+                    replace::apply(preview, replacement),
+                    Message::ReplaceApplied,
+                )
+            }
+            Message::DiscardReplace => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.preview = None;
+                }
+                Command::none()
+            }
+            Message::ReplaceApplied(result) => {
+                if let Some(panel) = self.grep.as_mut() {
+                    panel.is_applying = false;
+                }
+                match result {
+                    Ok(summary) => {
+                        self.notify(format!(
+                            "Replaced {} occurrences in {} files",
+                            summary.replacements, summary.files
+                        ));
+                        if !summary.skipped.is_empty() {
+                            self.toasts.push(
+                                toast::Level::Warning,
+                                format!(
+                                    "Skipped {} files that changed since the preview",
+                                    summary.skipped.len()
+                                ),
+                            );
+                        }
+                    }
+                    Err(error) => self.report(Error::ReplaceFailed(error)),
+                }
+                Command::none()
+            }
+            Message::GrepFound(id, hits) => {
+                if let Some(panel) = self.grep.as_mut()
+                    && panel.running == Some(id)
+                {
+                    panel.files += 1;
+                    panel.hits.extend(hits);
+                    if panel.hits.len() >= grep::MAX_HITS {
+                        panel.hits.truncate(grep::MAX_HITS);
+                        panel.running = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::GrepFinished(id) => {
+                if let Some(panel) = self.grep.as_mut()
+                    && panel.running == Some(id)
+                {
+                    panel.running = None;
+                }
+                Command::none()
+            }
+            Message::OpenHit(hit) => {
+                match self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.path.as_ref() == Some(&hit.path))
+                {
+                    Some(index) => {
+                        self.active = index;
+                        self.update(Message::GoToLine(hit.line, 1))
+                    }
+                    None => {
+                        self.pending_opens.push(cli::FileArg {
+                            path: hit.path.clone(),
+                            line: Some(hit.line),
+                            column: None,
+                            offset: None,
+                            readonly: false,
+                        });
+                        Command::perform(load_file(hit.path), Message::FileOpened)
+                    }
+                }
+            }
+            Message::PaneEdit(pane, action) => {
+                self.focus_pane(pane);
+                self.update(Message::Edit(action))
+            }
+            Message::FocusPane(pane) => {
+                self.focus_pane(pane);
+                Command::none()
+            }
+            Message::SplitPane(axis) => {
+                let state = PaneState::of(self.buffer());
+                if let Some((pane, _)) = self.panes.split(axis, &self.focus, state) {
+                    self.focus_pane(pane);
+                }
+                Command::none()
+            }
+            Message::ClosePane => {
+                if let Some((_, sibling)) = self.panes.close(&self.focus) {
+                    self.focus = sibling;
+                    self.enter_pane(sibling);
+                }
+                Command::none()
+            }
+            Message::PaneResized(pane_grid::ResizeEvent { split, ratio }) => {
+                self.panes.resize(&split, ratio);
+                Command::none()
+            }
+            Message::Lsp(lsp::Event::Ready(client)) => {
+                let language = client.language;
+                self.servers
+                    .clients
+                    .retain(|client| client.language != language);
+                self.servers.clients.push(client);
+                for index in 0..self.buffers.len() {
+                    if self.buffers[index].language == language {
+                        self.buffers[index].synced = None;
+                        self.sync_document(index);
+                    }
+                }
+                Command::none()
+            }
+            Message::Lsp(lsp::Event::Diagnostics(path, diagnostics)) => {
+                for buffer in &mut self.buffers {
+                    if buffer
+                        .path
+                        .as_deref()
+                        .is_some_and(|buffer| lsp::canonical(buffer) == path)
+                    {
+                        buffer.diagnostics = diagnostics.clone();
+                    }
+                }
+                if diagnostics.is_empty() {
+                    self.servers.diagnostics.remove(&path);
+                } else {
+                    self.servers.diagnostics.insert(path, diagnostics);
+                }
+                Command::none()
+            }
+            Message::Lsp(lsp::Event::Completions(id, items)) => {
+                if let Some(completion) = self
+                    .completion
+                    .as_mut()
+                    .filter(|completion| completion.request == Some(id))
+                {
+                    completion.merge(items);
+                }
+                Command::none()
+            }
+            Message::AcceptCompletion(item) => {
+                let Some(completion) = self.completion.take() else {
+                    return Command::none();
+                };
+                let Some(index) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == completion.buffer)
+                    .filter(|_| item < completion.items.len())
+                else {
+                    return Command::none();
+                };
+                let buffer = &mut self.buffers[index];
+                for _ in completion.prefix.chars() {
+                    buffer.perform(text_editor::Action::Edit(text_editor::Edit::Backspace));
+                }
+                buffer.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                    Arc::new(completion.items[item].clone()),
+                )));
+                self.sync_document(index);
+                Command::none()
+            }
+            Message::Lsp(lsp::Event::Failed(language, error)) => {
+                self.servers
+                    .clients
+                    .retain(|client| client.language != language);
+                self.servers
+                    .allowed
+                    .retain(|(allowed, _)| *allowed != language);
+                self.servers.declined.push(language);
+                self.servers.error = Some(error);
+                Command::none()
+            }
+            Message::StartServer(language) => {
+                self.pending_server = None;
+                if let Some(path) = self.buffers.iter().find_map(|buffer| {
+                    (buffer.language == language).then_some(buffer.path.as_ref()?)
+                }) {
+                    self.servers.allowed.push((language, lsp::root(path)));
+                }
+                Command::none()
+            }
+            Message::TrustServer(language) => {
+                if let Some(server) = self.config.language_servers.get(language.token()) {
+                    self.state.trust(server.command_line());
+                }
+                let save = Command::perform(state::save(self.state.clone()), Message::StateSaved);
+                Command::batch([self.update(Message::StartServer(language)), save])
+            }
+            Message::DeclineServer(language) => {
+                self.pending_server = None;
+                self.servers.declined.push(language);
+                Command::none()
+            }
+            Message::ToggleProblems => {
+                self.problems = !self.problems;
+                Command::none()
+            }
+            Message::ToggleOutline => {
+                self.outline = !self.outline;
+                Command::none()
+            }
+            Message::TogglePreview => {
+                self.preview = !self.preview;
+                self.sync_preview()
+            }
+            Message::OpenLink(destination) => {
+                if web::is_url(&destination) || !self.config.confirm_commands {
+                    self.update(Message::FollowLink(destination))
+                } else {
+                    self.pending_link = Some(destination);
+                    Command::none()
+                }
+            }
+            Message::FollowLink(destination) => {
+                self.pending_link = None;
+                let _ = open::that(destination);
+                Command::none()
+            }
+            Message::DeclineLink => {
+                self.pending_link = None;
+                Command::none()
+            }
+            Message::FilesHovered(is_hovering) => {
+                self.is_hovering_files = is_hovering;
+                Command::none()
+            }
+            Message::InstanceRequested(request) => {
+                let mut args = match cli::parse(request.args) {
+                    Ok(args) => args,
+                    Err(error) => {
+                        self.toasts.push(toast::Level::Warning, error);
+                        return window::gain_focus();
+                    }
+                };
+                for file in &mut args.files {
+                    let argument = file.path.to_str().unwrap_or_default();
+                    if !web::is_url(argument) && remote::Location::parse(argument).is_none() {
+                        file.path = request.cwd.join(&file.path);
+                    }
+                }
+                let opens: Vec<_> = args.files.iter().map(open_argument).collect();
+                self.pending_opens.extend(args.files);
+                Command::batch(opens.into_iter().chain([window::gain_focus()]))
+            }
+            Message::FileDropped(path) => {
+                self.is_hovering_files = false;
+                Command::perform(load_file(path), Message::FileOpened)
+            }
+            Message::InsertTab => {
+                let accepted = self
+                    .completion
+                    .as_ref()
+                    .filter(|completion| !completion.items.is_empty())
+                    .map(|completion| completion.selected);
+                if let Some(selected) = accepted {
+                    self.update(Message::AcceptCompletion(selected))
+                } else if self.buffer_mut().next_tab_stop(true) {
+                    Command::none()
+                } else if self.expand_snippet() {
+                    self.sync_document(self.active);
+                    Command::none()
+                } else {
+                    self.update(Message::Edit(text_editor::Action::Edit(
+                        text_editor::Edit::Insert('\t'),
+                    )))
+                }
+            }
+            Message::PreviousTabStop => {
+                self.buffer_mut().next_tab_stop(false);
+                Command::none()
+            }
+            Message::IndentSelected(indent) => {
+                self.buffer_mut().indent = indent;
+                Command::none()
+            }
+            Message::ToggleComment => {
+                self.buffer_mut().toggle_comment();
+                Command::none()
+            }
+            Message::MoveLinesUp => {
+                self.buffer_mut().move_lines(false);
+                Command::none()
+            }
+            Message::MoveLinesDown => {
+                self.buffer_mut().move_lines(true);
+                Command::none()
+            }
+            Message::DuplicateLines => {
+                self.buffer_mut().duplicate_lines();
+                Command::none()
+            }
+            Message::JoinLines => {
+                self.buffer_mut().join_lines();
+                Command::none()
+            }
+            Message::DeleteLines => {
+                self.buffer_mut().delete_lines();
+                Command::none()
+            }
+            Message::Convert(conversion) => {
+                self.buffer_mut().convert(conversion);
+                Command::none()
+            }
+            Message::JumpToBracket => {
+                let buffer = self.buffer_mut();
+                let text = buffer.content.text();
+                let cursor = brackets::cursor_offset(&text, buffer.content.cursor_position());
+                if let Some((_, other)) = brackets::matching(&text, cursor) {
+                    let (line, column) = offset_to_position(&text, other);
+                    move_cursor_to(&mut buffer.content, line, column);
+                }
+                Command::none()
+            }
+            Message::ToggleWrap => {
+                let buffer = self.buffer_mut();
+                buffer.wrap = !buffer.wrap;
+                Command::none()
+            }
+            Message::ToggleTypewriter => {
+                self.state.typewriter = !self.state.typewriter;
+                if self.state.typewriter {
+                    self.center_cursor();
+                }
+                Command::perform(state::save(self.state.clone()), Message::StateSaved)
+            }
+            Message::ToggleFold(line) => {
+                self.buffers[self.active].toggle_fold(line);
+                Command::none()
+            }
+            Message::FoldAtCursor => {
+                let buffer = &mut self.buffers[self.active];
+                let (line, _) = buffer.cursor_position();
+                buffer.toggle_fold(line);
+                Command::none()
+            }
+            Message::FoldAll => {
+                self.buffers[self.active].fold_all();
+                Command::none()
+            }
+            Message::UnfoldAll => {
+                self.buffers[self.active].unfold_all();
+                Command::none()
+            }
+            Message::ToggleBookmark => {
+                let buffer = &mut self.buffers[self.active];
+                let (line, _) = buffer.cursor_position();
+                if !buffer.bookmarks.remove(&line) {
+                    buffer.bookmarks.insert(line);
+                }
+                let id = buffer.id;
+                self.remember_bookmarks(id)
+            }
+            Message::NextBookmark => self.jump_to_bookmark(true),
+            Message::PreviousBookmark => self.jump_to_bookmark(false),
+            Message::ToggleBookmarkPanel => {
+                self.bookmark_panel = !self.bookmark_panel;
+                Command::none()
+            }
+            Message::BookmarksLoaded(bookmarks) => {
+                for buffer in &mut self.buffers {
+                    if let Some(path) = &buffer.path
+                        && buffer.bookmarks.is_empty()
+                    {
+                        let line_count = buffer.content.line_count();
+                        buffer.bookmarks = bookmarks
+                            .get(path)
+                            .into_iter()
+                            .filter(|line| *line < line_count)
+                            .collect();
+                    }
+                }
+                self.bookmarks = bookmarks;
+                Command::none()
+            }
+            Message::BookmarksSaved(Ok(())) => Command::none(),
+            Message::BookmarksSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::ClipboardChanged => clipboard::read(Message::ClipboardCopied),
+            Message::ClipboardCopied(contents) => {
+                if let Some(contents) = contents {
+                    self.clips.push(contents);
+                }
+                Command::none()
+            }
+            Message::ToggleClipboardHistory => {
+                self.clip_picker = match self.clip_picker {
+                    Some(_) => None,
+                    None => Some(clips::Picker {
+                        reindent: self.config.reindent_on_paste,
+                        plain: false,
+                    }),
+                };
+                Command::none()
+            }
+            Message::ReindentPasteToggled(reindent) => {
+                if let Some(picker) = &mut self.clip_picker {
+                    picker.reindent = reindent;
+                }
+                Command::none()
+            }
+            Message::PlainPasteToggled(plain) => {
+                if let Some(picker) = &mut self.clip_picker {
+                    picker.plain = plain;
+                }
+                Command::none()
+            }
+            Message::PasteFromHistory(index) => {
+                let Some(picker) = self.clip_picker.take() else {
+                    return Command::none();
+                };
+                let Some(text) = self.clips.entries.get(index).cloned() else {
+                    return Command::none();
+                };
+                let text = if picker.plain {
+                    clips::plain(&text)
+                } else {
+                    text
+                };
+                let text = if picker.reindent {
+                    self.reindent_paste(&text)
+                } else {
+                    text
+                };
+                self.buffer_mut()
+                    .perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                        Arc::new(text),
+                    )));
+                self.sync_document(self.active);
+                Command::none()
+            }
+            Message::ReloadPlugins => Command::perform(plugins::read(), Message::PluginsLoaded),
+            Message::PluginsLoaded(sources) => {
+                let errors = self.plugins.load(sources);
+                if let Some(error) = errors.into_iter().next() {
+                    self.report(Error::Plugin(error));
+                }
+                Command::none()
+            }
+            Message::RunPlugin(index) => {
+                let buffer = self.buffer_mut();
+                let context = plugins::Context {
+                    text: buffer.content.text(),
+                    selection: buffer.content.selection(),
+                    cursor: buffer.content.cursor_position(),
+                    ..plugins::Context::default()
+                };
+                let context = match self.plugins.run(index, context) {
+                    Ok(context) => context,
+                    Err(error) => {
+                        self.report(Error::Plugin(error));
+                        return Command::none();
+                    }
+                };
+                let buffer = self.buffer_mut();
+                if let Some(text) = context.new_text {
+                    buffer.replace_text(&text);
+                }
+                if let Some(replacement) = context.replacement {
+                    buffer.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                        Arc::new(replacement),
+                    )));
+                }
+                if let Some((line, column)) = context.moved {
+                    move_cursor_to(&mut buffer.content, line, column);
+                }
+                self.sync_document(self.active);
+                Command::none()
+            }
+            Message::ToggleRecording => {
+                if let Some(steps) = self.recording.take() {
+                    self.macros.last = steps;
+                } else {
+                    self.recording = Some(Vec::new());
+                }
+                Command::none()
+            }
+            Message::ShowMacros => {
+                self.macro_prompt = Some(macros::Prompt::default());
+                Command::none()
+            }
+            Message::MacroSelected(name) => {
+                if let Some(prompt) = &mut self.macro_prompt {
+                    prompt.selected = name;
+                }
+                Command::none()
+            }
+            Message::MacroCountChanged(count) => {
+                if let Some(prompt) = &mut self.macro_prompt {
+                    prompt.count = count;
+                }
+                Command::none()
+            }
+            Message::MacroNameChanged(name) => {
+                if let Some(prompt) = &mut self.macro_prompt {
+                    prompt.name = name;
+                }
+                Command::none()
+            }
+            Message::PlayMacro => {
+                if self.recording.is_some() {
+                    return Command::none();
+                }
+                let (name, count) = match &self.macro_prompt {
+                    Some(prompt) => (prompt.selected.as_str(), prompt.count()),
+                    None => (macros::LAST, 1),
+                };
+                let steps = self.macros.get(name).unwrap_or_default().to_vec();
+                let commands: Vec<_> = std::iter::repeat_n(&steps, count)
+                    .flatten()
+                    .map(|step| self.update(Message::Edit(step.action())))
+                    .collect();
+                Command::batch(commands)
+            }
+            Message::SaveMacro => {
+                let Some(prompt) = &mut self.macro_prompt else {
+                    return Command::none();
+                };
+                let name = prompt.name.trim().to_owned();
+                if name.is_empty() || name == macros::LAST || self.macros.last.is_empty() {
+                    return Command::none();
+                }
+                prompt.selected = name.clone();
+                prompt.name.clear();
+                self.macros.saved.insert(name, self.macros.last.clone());
+                Command::perform(macros::save(self.macros.clone()), Message::MacrosSaved)
+            }
+            Message::CloseMacros => {
+                self.macro_prompt = None;
+                Command::none()
+            }
+            Message::MacrosLoaded(macros) => {
+                self.macros.saved = macros.saved;
+                Command::none()
+            }
+            Message::MacrosSaved(Ok(())) => Command::none(),
+            Message::MacrosSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::ToggleMinimap => {
+                self.state.minimap = !self.state.minimap;
+                Command::perform(state::save(self.state.clone()), Message::StateSaved)
+            }
+            Message::ToggleWhitespace => {
+                self.state.whitespace = !self.state.whitespace;
+                Command::perform(state::save(self.state.clone()), Message::StateSaved)
+            }
+            Message::ToggleCurrentLine => {
+                self.config.highlight_current_line = !self.config.highlight_current_line;
+                Command::perform(
+                    config::save_flag("highlight_current_line", self.config.highlight_current_line),
+                    Message::SettingSaved,
+                )
+            }
+            Message::ToggleRulers => {
+                self.config.show_rulers = !self.config.show_rulers;
+                Command::perform(
+                    config::save_flag("show_rulers", self.config.show_rulers),
+                    Message::SettingSaved,
+                )
+            }
+            Message::ToggleIndentGuides => {
+                self.config.indent_guides = !self.config.indent_guides;
+                Command::perform(
+                    config::save_flag("indent_guides", self.config.indent_guides),
+                    Message::SettingSaved,
+                )
+            }
+            Message::SettingSaved(Ok(())) => Command::none(),
+            Message::SettingSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::MinimapScrolled(pane, top) => {
+                self.focus_pane(pane);
+                let lines = top as i32 - self.buffer().top_line as i32;
+                if lines == 0 {
+                    return Command::none();
+                }
+                self.update(Message::Edit(text_editor::Action::Scroll { lines }))
+            }
+            Message::WindowResized(width, height) => {
+                self.window_width = width;
+                self.viewport_lines = viewport_lines(height);
+                Command::none()
+            }
+            Message::ConfigTick => {
+                let load = Command::batch([
+                    Command::perform(config::load(), Message::ConfigLoaded),
+                    Command::perform(snippet::load(), Message::SnippetsLoaded),
+                ]);
+                if self.state.appearance == appearance::Appearance::System {
+                    Command::batch([
+                        load,
+                        Command::perform(appearance::system_is_dark(), Message::SystemThemeChecked),
+                    ])
+                } else {
+                    load
+                }
+            }
+            Message::ConfigLoaded(Ok(config)) => {
+                if config != self.config {
+                    self.apply_config(config);
+                }
+                if matches!(self.error, Some(Error::InvalidConfig(_))) {
+                    self.error = None;
+                }
+                let scratch =
+                    if std::mem::take(&mut self.check_scratch) && self.config.persist_scratch {
+                        Command::perform(scratch::load(), Message::ScratchLoaded)
+                    } else {
+                        Command::none()
+                    };
+                if std::mem::take(&mut self.check_session) && self.config.restore_session {
+                    return Command::batch([
+                        scratch,
+                        Command::perform(restore_session(), |(session, files)| {
+                            Message::SessionRestored(session, files)
+                        }),
+                    ]);
+                }
+                scratch
+            }
+            Message::SessionRestored(session, files) => {
+                self.pending_opens
+                    .extend(session.files.into_iter().map(|file| cli::FileArg {
+                        path: file.path,
+                        line: Some(file.line + 1),
+                        column: Some(file.column),
+                        offset: None,
+                        readonly: false,
+                    }));
+                let commands: Vec<_> = files
+                    .into_iter()
+                    .map(|result| self.update(Message::FileOpened(result)))
+                    .collect();
+                if let Some(index) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.path.is_some() && buffer.path == session.active)
+                {
+                    self.active = index;
+                }
+                Command::batch(commands)
+            }
+            Message::SnippetsLoaded(Ok(snippets)) => {
+                self.snippets = snippets;
+                if matches!(self.error, Some(Error::InvalidSnippets(_))) {
+                    self.error = None;
+                }
+                Command::none()
+            }
+            Message::SnippetsLoaded(Err(error)) => {
+                if !matches!(self.error, Some(Error::InvalidSnippets(_))) {
+                    self.toasts.push(toast::Level::Error, error.to_string());
+                }
+                self.error = Some(error);
+                Command::none()
+            }
+            Message::ConfigLoaded(Err(error)) => {
+                if !matches!(self.error, Some(Error::InvalidConfig(_))) {
+                    self.toasts.push(toast::Level::Error, error.to_string());
+                }
+                self.error = Some(error);
+                Command::none()
+            }
+            Message::AutosaveTick => {
+                let dirty = self
+                    .buffers
+                    .iter()
+                    .filter(|buffer| buffer.needs_save() && buffer.passphrase.is_none())
+                    .map(|buffer| {
+                        let recovered = recovery::Recovered {
+                            path: buffer.path.clone(),
+                            text: buffer.content.text(),
+                        };
+                        (buffer.id, recovered)
+                    })
+                    .collect();
+                let recovery = Command::perform(recovery::write(dirty), Message::RecoveryUpdated);
+                match self.scratch() {
+                    Some(scratch) => Command::batch([
+                        recovery,
+                        Command::perform(scratch::save(scratch), Message::ScratchSaved),
+                    ]),
+                    None => recovery,
+                }
+            }
+            Message::RecoveryUpdated(Ok(())) => Command::none(),
+            Message::RecoveryUpdated(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::RecoveryFound(entries) => {
+                self.recovered = entries;
+                Command::none()
+            }
+            Message::RestoreRecovery => {
+                let entries = std::mem::take(&mut self.recovered);
+                let files = entries.iter().map(|entry| entry.file.clone()).collect();
+                for entry in entries {
+                    let recovery::Recovered { path, text } = entry.recovered;
+                    self.open_buffer(path, &text).is_dirty = true;
+                }
+                Command::perform(recovery::remove(files), Message::RecoveryUpdated)
+            }
+            Message::DiscardRecovery => {
+                let files = std::mem::take(&mut self.recovered)
+                    .into_iter()
+                    .map(|entry| entry.file)
+                    .collect();
+                Command::perform(recovery::remove(files), Message::RecoveryUpdated)
+            }
+            Message::Exit => window::close(),
+            Message::StateLoaded(state) => {
+                self.state = state;
+                if self.state.spell_check {
+                    Command::perform(
+                        spell::load(self.config.dictionary.clone()),
+                        Message::DictionaryLoaded,
+                    )
+                } else {
+                    Command::none()
+                }
+            }
+            Message::ToggleSpellCheck => {
+                self.state.spell_check = !self.state.spell_check;
+                let save = Command::perform(state::save(self.state.clone()), Message::StateSaved);
+                if self.state.spell_check {
+                    Command::batch([
+                        save,
+                        Command::perform(
+                            spell::load(self.config.dictionary.clone()),
+                            Message::DictionaryLoaded,
+                        ),
+                    ])
+                } else {
+                    self.spelling.dictionary = None;
+                    self.spelling.prompt = None;
+                    save
+                }
+            }
+            Message::DictionaryLoaded(Ok((dictionary, personal))) => {
+                if self.state.spell_check {
+                    self.spelling.dictionary = Some(dictionary);
+                    self.spelling.personal = personal.into_iter().collect();
+                }
+                Command::none()
+            }
+            Message::DictionaryLoaded(Err(error)) => {
+                self.state.spell_check = false;
+                self.report(error);
+                Command::none()
+            }
+            Message::SuggestSpelling => {
+                if !self.state.spell_check {
+                    return self.update(Message::ToggleSpellCheck);
+                }
+                let buffer = self.buffer();
+                let text = buffer.content.text();
+                let (line, column) = buffer.content.cursor_position();
+                let line_start = position_to_offset(&text, line, 0);
+                let line_text = text.lines().nth(line).unwrap_or_default();
+                let column = brackets::cursor_offset(&text, (line, column)) - line_start;
+                let prompt = spell::word_at(line_text, buffer.language, column).map(|range| {
+                    let word = line_text[range.clone()].to_owned();
+                    spell::Prompt {
+                        buffer: buffer.id,
+                        range: line_start + range.start..line_start + range.end,
+                        suggestions: if self.spelling.is_known(&word) {
+                            Vec::new()
+                        } else {
+                            self.spelling.suggestions(&word)
+                        },
+                        word,
+                    }
+                });
+                self.spelling.prompt = prompt;
+                Command::none()
+            }
+            Message::ReplaceWord(replacement) => {
+                let Some(prompt) = self.spelling.prompt.take() else {
+                    return Command::none();
+                };
+                let Some(index) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == prompt.buffer)
+                else {
+                    return Command::none();
+                };
+                let buffer = &mut self.buffers[index];
+                let text = buffer.content.text();
+                if text.get(prompt.range.clone()) == Some(prompt.word.as_str()) {
+                    select_range(&mut buffer.content, &text, &prompt.range);
+                    buffer.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                        Arc::new(replacement),
+                    )));
+                    self.sync_document(index);
+                }
+                Command::none()
+            }
+            Message::AddToDictionary => {
+                let Some(prompt) = self.spelling.prompt.take() else {
+                    return Command::none();
+                };
+                self.spelling.personal.insert(prompt.word);
+                Command::perform(
+                    spell::save_personal(self.spelling.personal.iter().cloned().collect()),
+                    Message::DictionarySaved,
+                )
+            }
+            Message::ToggleHex => {
+                if let Err(line) = self.buffer_mut().toggle_hex() {
+                    self.report(Error::InvalidHex(line + 1));
+                }
+                self.completion = None;
+                Command::none()
+            }
+            Message::FormatDocument => {
+                self.reformat(|text, syntax, indent| Some(format::format(text, syntax, indent)));
+                Command::none()
+            }
+            Message::MinifyDocument => {
+                self.reformat(|text, syntax, _| format::minify(text, syntax));
+                Command::none()
+            }
+            Message::ValidateDocument => {
+                self.reformat(|text, syntax, _| {
+                    Some(format::validate(text, syntax).map(|_| text.to_owned()))
+                });
+                Command::none()
+            }
+            Message::FormatExternally => {
+                let buffer = self.buffer().id;
+                self.request_formatter(formatter::Pending {
+                    buffer,
+                    then_save: false,
+                })
+            }
+            Message::RunFormatter(pending) => {
+                self.pending_formatter = None;
+                self.run_formatter(pending)
+            }
+            Message::TrustFormatter(pending) => {
+                if let Some(buffer) = self
+                    .buffers
+                    .iter()
+                    .find(|buffer| buffer.id == pending.buffer)
+                    && let Some(formatter) = self.config.formatters.get(buffer.language.token())
+                {
+                    self.state.trust(formatter.command_line());
+                }
+                let save = Command::perform(state::save(self.state.clone()), Message::StateSaved);
+                Command::batch([self.update(Message::RunFormatter(pending)), save])
+            }
+            Message::DeclineFormatter(pending) => {
+                self.pending_formatter = None;
+                match self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == pending.buffer)
+                {
+                    Some(index) if pending.then_save => self.write_buffer(index, false),
+                    _ => Command::none(),
+                }
+            }
+            Message::Formatted(id, revision, then_save, result) => {
+                let Some(index) = self.buffers.iter().position(|buffer| buffer.id == id) else {
+                    return Command::none();
+                };
+                match result {
+                    Ok(_) if self.buffers[index].revision != revision => {
+                        self.notify(String::from(
+                            "The buffer changed while formatting, so the result was discarded",
+                        ));
+                    }
+                    Ok(formatted) => {
+                        let buffer = &mut self.buffers[index];
+                        if formatted != buffer.content.text() {
+                            let (line, _) = buffer.content.cursor_position();
+                            buffer.replace_text(&formatted);
+                            let line = line.min(buffer.content.line_count() - 1);
+                            move_cursor_to(&mut buffer.content, line, 0);
+                        }
+                    }
+                    Err(error) => {
+                        self.toasts
+                            .push(toast::Level::Warning, format!("Formatter failed: {error}"));
+                    }
+                }
+                if then_save {
+                    self.write_buffer(index, false)
+                } else {
+                    Command::none()
+                }
+            }
+            Message::ToggleTerminal => {
+                if let Some(terminal) = self.terminal.take() {
+                    terminal.close();
+                    return Command::none();
+                }
+                self.request_terminal(terminal::Pending::Shell)
+            }
+            Message::RestartTerminal => self.request_terminal(terminal::Pending::Shell),
+            Message::TerminalStarted(session, handle) => {
+                match &mut self.terminal {
+                    Some(terminal) if terminal.session == session => {
+                        if let Some(line) = terminal.queued.take() {
+                            handle.send(&format!("{line}\r"));
+                        }
+                        terminal.handle = Some(handle);
+                    }
+                    _ => handle.kill(),
+                }
+                Command::none()
+            }
+            Message::TerminalOutput(session, output) => match &mut self.terminal {
+                Some(terminal) if terminal.session == session => {
+                    terminal.feed(&output);
+                    scrollable::snap_to(
+                        scrollable::Id::new(terminal::OUTPUT),
+                        scrollable::RelativeOffset::END,
+                    )
+                }
+                _ => Command::none(),
+            },
+            Message::TerminalExited(session, status) => {
+                if let Some(terminal) = &mut self.terminal
+                    && terminal.session == session
+                {
+                    terminal.handle = None;
+                    terminal.status = Some(status);
+                }
+                Command::none()
+            }
+            Message::TerminalInputChanged(input) => {
+                if let Some(terminal) = &mut self.terminal {
+                    terminal.input = input;
+                }
+                Command::none()
+            }
+            Message::TerminalSubmit => {
+                if let Some(terminal) = &mut self.terminal {
+                    let input = std::mem::take(&mut terminal.input);
+                    terminal.send(&format!("{input}\r"));
+                }
+                Command::none()
+            }
+            Message::TerminalInterrupt => {
+                if let Some(terminal) = &self.terminal {
+                    terminal.send("\u{3}");
+                }
+                Command::none()
+            }
+            Message::RunCurrentFile => {
+                let buffer = self.buffer();
+                let Some(path) = buffer.path.clone() else {
+                    self.notify(String::from("Save the file before running it"));
+                    return Command::none();
+                };
+                if buffer.needs_save() {
+                    self.notify(format!("Save {} before running it", buffer.title()));
+                    return Command::none();
+                }
+                if !self
+                    .config
+                    .run_commands
+                    .contains_key(buffer.language.token())
+                {
+                    self.notify(format!(
+                        "No run command is configured for {}",
+                        buffer.language
+                    ));
+                    return Command::none();
+                }
+                let id = buffer.id;
+                self.request_terminal(terminal::Pending::Run(id))
+            }
+            Message::RunTerminal(pending) => {
+                self.pending_terminal = None;
+                self.start_terminal(pending)
+            }
+            Message::TrustTerminal(pending) => {
+                for command in self.untrusted_commands(pending) {
+                    self.state.trust(command);
+                }
+                let save = Command::perform(state::save(self.state.clone()), Message::StateSaved);
+                Command::batch([self.update(Message::RunTerminal(pending)), save])
+            }
+            Message::DeclineTerminal(_) => {
+                self.pending_terminal = None;
+                Command::none()
+            }
+            Message::AcceptPrompt => match self.prompt_actions() {
+                (Some(accept), _) => self.update(accept),
+                (None, _) => Command::none(),
+            },
+            Message::FocusNext => iced::widget::focus_next(),
+            Message::FocusPrevious => iced::widget::focus_previous(),
+            Message::AnnouncePosition => {
+                let buffer = self.buffer();
+                let (line, column) = buffer.cursor_position();
+                let message = format!(
+                    "{}, line {} of {}, column {}",
+                    buffer.title(),
+                    line + 1,
+                    buffer.content.line_count(),
+                    column + 1
+                );
+                self.notify(message);
+                Command::none()
+            }
+            Message::ContextMenu(pane, point) => {
+                let command = self.update(Message::FocusPane(pane));
+                self.context_menu = Some((pane, point));
+                command
+            }
+            Message::PickColor(pane, line) => {
+                let command = self.update(Message::FocusPane(pane));
+                let buffer = self.buffer();
+                if buffer.is_readonly {
+                    self.notify(String::from("The buffer is read-only"));
+                    return command;
+                }
+                let id = buffer.id;
+                let text = buffer.content.text();
+                let line_text = text.split('\n').nth(line).unwrap_or_default();
+                let literals = colors::find(line_text, buffer.language);
+                let (cursor_line, cursor_column) = buffer.content.cursor_position();
+                let cursor = line_text
+                    .char_indices()
+                    .nth(cursor_column)
+                    .map_or(line_text.len(), |(offset, _)| offset);
+                let literal = literals
+                    .iter()
+                    .find(|literal| {
+                        cursor_line == line
+                            && (literal.range.start..=literal.range.end).contains(&cursor)
+                    })
+                    .or(literals.first())
+                    .cloned();
+                match literal {
+                    Some(literal) => {
+                        self.color_picker = Some(colors::Picker::new(id, line, line_text, literal));
+                    }
+                    None => self.notify(String::from("No color literal on this line")),
+                }
+                command
+            }
+            Message::PickColorAtCursor => {
+                let (line, _) = self.buffer().content.cursor_position();
+                self.update(Message::PickColor(self.focus, line))
+            }
+            Message::ColorChannelChanged(channel, value) => {
+                if let Some(picker) = &mut self.color_picker {
+                    picker.set_channel(channel, value);
+                }
+                Command::none()
+            }
+            Message::ColorHexChanged(value) => {
+                if let Some(picker) = &mut self.color_picker {
+                    picker.set_hex(value);
+                }
+                Command::none()
+            }
+            Message::ApplyColor => {
+                let Some(picker) = self.color_picker.take() else {
+                    return Command::none();
+                };
+                let Some(index) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == picker.buffer)
+                else {
+                    return Command::none();
+                };
+                let buffer = &mut self.buffers[index];
+                let text = buffer.content.text();
+                let line_text = text.split('\n').nth(picker.line).unwrap_or_default();
+                if line_text.get(picker.literal.range.clone()) != Some(picker.original.as_str()) {
+                    self.notify(String::from(
+                        "The color literal has changed since it was picked",
+                    ));
+                    return Command::none();
+                }
+                let mut replaced = line_text.to_string();
+                replaced.replace_range(
+                    picker.literal.range.clone(),
+                    &picker.literal.rewrite(picker.rgb),
+                );
+                buffer.replace_lines(
+                    picker.line..picker.line + 1,
+                    &[replaced],
+                    picker.line..picker.line,
+                );
+                self.sync_document(index);
+                Command::none()
+            }
+            Message::CancelColor => {
+                self.color_picker = None;
+                Command::none()
+            }
+            Message::ContextAction(message) => {
+                self.context_menu = None;
+                self.update(*message)
+            }
+            Message::Copy | Message::Cut => {
+                let Some(selection) = self.buffer().content.selection() else {
+                    return Command::none();
+                };
+                self.clips.push(selection.clone());
+                let copy = clipboard::write(selection);
+                if matches!(message, Message::Cut) && !self.buffer().is_readonly {
+                    let delete = self.update(Message::Edit(text_editor::Action::Edit(
+                        text_editor::Edit::Delete,
+                    )));
+                    return Command::batch([copy, delete]);
+                }
+                copy
+            }
+            Message::Paste => clipboard::read(Message::Pasted),
+            Message::Pasted(Some(contents)) => self.update(Message::Edit(
+                text_editor::Action::Edit(text_editor::Edit::Paste(Arc::new(contents))),
+            )),
+            Message::Pasted(None) => Command::none(),
+            Message::SelectAll => Command::batch([
+                self.update(Message::Edit(text_editor::Action::Move(
+                    text_editor::Motion::DocumentStart,
+                ))),
+                self.update(Message::Edit(text_editor::Action::Select(
+                    text_editor::Motion::DocumentEnd,
+                ))),
+            ]),
+            Message::CopyPath => match &self.buffer().path {
+                Some(path) => clipboard::write(path.display().to_string()),
+                None => Command::none(),
+            },
+            Message::RevealInFolder => {
+                if let Some(dir) = self
+                    .buffer()
+                    .path
+                    .as_deref()
+                    .and_then(std::path::Path::parent)
+                    && let Err(error) = open::that(dir)
+                {
+                    self.toasts.push(toast::Level::Error, error.to_string());
+                }
+                Command::none()
+            }
+            Message::SearchSelectionInFiles => {
+                let Some(selection) = self.buffer().content.selection() else {
+                    return Command::none();
+                };
+                let open = if self.grep.is_none() {
+                    self.update(Message::ToggleGrep)
+                } else {
+                    Command::none()
+                };
+                let run = Command::batch([
+                    self.update(Message::GrepQueryChanged(selection)),
+                    self.update(Message::RunGrep),
+                ]);
+                Command::batch([open, run])
+            }
+            Message::ShowUnsavedChanges => {
+                let buffer = self.buffer();
+                let id = buffer.id;
+                match buffer.path.clone() {
+                    Some(path) if !buffer.hex && buffer.page.is_none() => {
+                        Command::perform(load_file(path), move |result| {
+                            Message::UnsavedLoaded(id, result)
+                        })
+                    }
+                    _ => {
+                        self.notify(String::from("Only files on disk can be compared"));
+                        Command::none()
+                    }
+                }
+            }
+            Message::UnsavedLoaded(id, Ok(loaded)) => {
+                let mut panel = unsaved::Panel::new(id, Arc::new(loaded.text.to_string()));
+                if let Some(buffer) = self.buffers.iter().find(|buffer| buffer.id == id)
+                    && panel
+                        .refresh(buffer.revision, &buffer.content.text())
+                        .is_empty()
+                {
+                    self.notify(format!("{} has no unsaved changes", buffer.title()));
+                }
+                self.unsaved = Some(panel);
+                Command::none()
+            }
+            Message::UnsavedLoaded(_, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::RevertUnsaved(index) => {
+                let Some(panel) = &mut self.unsaved else {
+                    return Command::none();
+                };
+                let Some(position) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == panel.buffer)
+                else {
+                    return Command::none();
+                };
+                let buffer = &mut self.buffers[position];
+                let Some(change) = panel
+                    .refresh(buffer.revision, &buffer.content.text())
+                    .get(index)
+                    .cloned()
+                else {
+                    return Command::none();
+                };
+                let start = change.hunk.new.start;
+                buffer.replace_lines(change.hunk.new, &change.removed, start..start);
+                if buffer.content.text() == panel.saved() {
+                    buffer.is_dirty = false;
+                }
+                self.sync_document(position);
+                Command::none()
+            }
+            Message::CloseUnsaved => {
+                self.unsaved = None;
+                Command::none()
+            }
+            Message::ShowLocalHistory => {
+                let buffer = self.buffer();
+                let id = buffer.id;
+                match buffer.path.clone() {
+                    Some(path) => Command::perform(versions::list(path), move |versions| {
+                        Message::VersionsListed(id, versions)
+                    }),
+                    None => {
+                        self.notify(String::from("Only files on disk have a local history"));
+                        Command::none()
+                    }
+                }
+            }
+            Message::VersionRecorded(Ok(())) => Command::none(),
+            Message::VersionRecorded(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::VersionsListed(id, versions) => {
+                if versions.is_empty() {
+                    self.notify(String::from("No saved versions yet"));
+                    return Command::none();
+                }
+                self.versions = Some(versions::Panel {
+                    buffer: id,
+                    versions,
+                });
+                Command::none()
+            }
+            Message::OpenVersion(index, action) => {
+                let Some(panel) = &self.versions else {
+                    return Command::none();
+                };
+                let Some(version) = panel.versions.get(index).cloned() else {
+                    return Command::none();
+                };
+                let id = panel.buffer;
+                Command::perform(versions::read(version), move |result| {
+                    Message::VersionRead(id, action, result)
+                })
+            }
+            Message::VersionRead(id, action, Ok(text)) => {
+                let Some(index) = self.buffers.iter().position(|buffer| buffer.id == id) else {
+                    return Command::none();
+                };
+                match action {
+                    versions::Action::View => {
+                        let language = self.buffers[index].language;
+                        let buffer = self.open_buffer(None, &text);
+                        buffer.language = language;
+                        buffer.is_readonly = true;
+                        self.sync_document(self.active);
+                    }
+                    versions::Action::Compare => {
+                        let buffer = &self.buffers[index];
+                        let title = format!("{}: saved version ↔ buffer", buffer.title());
+                        self.diff = Some(diff::View::new(title, &text, &buffer.content.text()));
+                    }
+                    versions::Action::Restore => {
+                        let buffer = &mut self.buffers[index];
+                        if buffer.is_readonly {
+                            self.notify(String::from("The buffer is read-only"));
+                            return Command::none();
+                        }
+                        if buffer.content.text() != text {
+                            buffer.replace_text(&text);
+                            self.sync_document(index);
+                        }
+                        let title = self.buffers[index].title();
+                        self.notify(format!("Restored an older version of {title}"));
+                    }
+                }
+                Command::none()
+            }
+            Message::VersionRead(_, _, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::CloseLocalHistory => {
+                self.versions = None;
+                Command::none()
+            }
+            Message::ToggleTable => {
+                if !self.buffer_mut().toggle_table() {
+                    self.notify(String::from("Table view needs a .csv or .tsv file"));
+                }
+                self.completion = None;
+                Command::none()
+            }
+            Message::TableCellChanged(row, column, value) => {
+                let buffer = self.buffer_mut();
+                if !buffer.is_readonly
+                    && let Some(table) = &mut buffer.table
+                {
+                    table.set(row, column, value);
+                    buffer.is_dirty = true;
+                }
+                Command::none()
+            }
+            Message::SortTable(column) => {
+                let buffer = self.buffer_mut();
+                if let Some(table) = &mut buffer.table {
+                    table.sort_by(column);
+                    buffer.is_dirty |= !buffer.is_readonly;
+                }
+                Command::none()
+            }
+            Message::ToggleFollow => {
+                let buffer = self.buffer_mut();
+                let id = buffer.id;
+                match (buffer.follow.take(), buffer.path.clone()) {
+                    (Some(follow), _) => {
+                        buffer.is_readonly = follow.was_readonly;
+                        Command::none()
+                    }
+                    (None, Some(path)) => Command::perform(follow::length(path), move |result| {
+                        Message::FollowStarted(id, result)
+                    }),
+                    (None, None) => Command::none(),
+                }
+            }
+            Message::FollowStarted(id, Ok(offset)) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.follow = Some(follow::Follow {
+                        offset,
+                        was_readonly: buffer.is_readonly,
+                    });
+                    buffer.is_readonly = true;
+                    buffer.perform(text_editor::Action::Move(text_editor::Motion::DocumentEnd));
+                    buffer.top_line = buffer.content.line_count();
+                }
+                Command::none()
+            }
+            Message::FollowTick => Command::batch(self.buffers.iter().filter_map(|buffer| {
+                let offset = buffer.follow?.offset;
+                let path = buffer.path.clone()?;
+                let id = buffer.id;
+                Some(Command::perform(
+                    follow::read(path, offset),
+                    move |result| Message::Appended(id, result),
+                ))
+            })),
+            Message::Appended(id, Ok(appended)) => {
+                let Some(index) = self.buffers.iter().position(|buffer| buffer.id == id) else {
+                    return Command::none();
+                };
+                let buffer = &mut self.buffers[index];
+                let Some(follow) = buffer.follow.as_mut() else {
+                    return Command::none();
+                };
+                match appended {
+                    follow::Appended::Lines(offset, text) => {
+                        follow.offset = offset;
+                        if !text.is_empty() {
+                            buffer.append(&text);
+                            buffer.top_line = buffer.content.line_count();
+                            self.sync_document(index);
+                        }
+                    }
+                    follow::Appended::Truncated => {
+                        follow.offset = 0;
+                        buffer.reload("");
+                    }
+                }
+                Command::none()
+            }
+            Message::FollowStarted(_, Err(error)) | Message::Appended(_, Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::CloseSpelling => {
+                self.spelling.prompt = None;
+                Command::none()
+            }
+            Message::GitInspected(id, info) => {
+                if let Some(buffer) = self.buffers.iter_mut().find(|buffer| buffer.id == id) {
+                    buffer.git = info;
+                }
+                Command::none()
+            }
+            Message::RevertHunk => {
+                let buffer = self.buffer();
+                let Some(info) = buffer.git.as_ref() else {
+                    return Command::none();
+                };
+                let (line, _) = buffer.content.cursor_position();
+                let Some(hunk) = info.hunk_at(&buffer.content.text(), line) else {
+                    return Command::none();
+                };
+                let original = info.original(&hunk);
+                let start = hunk.new.start;
+                self.buffer_mut()
+                    .replace_lines(hunk.new, &original, start..start);
+                self.sync_document(self.active);
+                Command::none()
+            }
+            Message::DiffAgainstHead => {
+                let buffer = self.buffer();
+                let view = buffer
+                    .git
+                    .as_ref()
+                    .and_then(|info| info.head.as_ref())
+                    .map(|head| {
+                        diff::View::new(
+                            format!("{}: HEAD ↔ working copy", buffer.title()),
+                            head,
+                            &buffer.content.text(),
+                        )
+                    });
+                if view.is_some() {
+                    self.diff = view;
+                }
+                Command::none()
+            }
+            Message::CompareWithFile => Command::perform(pick_file(), Message::CompareLoaded),
+            Message::CompareWithSaved => match self.buffer().path.clone() {
+                Some(path) => Command::perform(load_file(path), Message::CompareLoaded),
+                None => Command::none(),
+            },
+            Message::CompareLoaded(Ok(loaded)) => {
+                let buffer = self.buffer();
+                let title = if buffer.path.as_ref() == Some(&loaded.path) {
+                    format!("{}: saved ↔ buffer", buffer.title())
+                } else {
+                    let other = loaded
+                        .path
+                        .file_name()
+                        .map(|name| name.to_string_lossy().into_owned())
+                        .unwrap_or_default();
+                    format!("{other} ↔ {}", buffer.title())
+                };
+                let view = diff::View::new(title, &loaded.text, &buffer.content.text());
+                self.diff = Some(view);
+                Command::none()
+            }
+            Message::CompareLoaded(Err(Error::DialogClosed)) => Command::none(),
+            Message::CompareLoaded(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::CloseDiff => {
+                self.diff = None;
+                Command::none()
+            }
+            Message::ExportHtml => {
+                let buffer = self.buffer();
+                let palette = self.theme().palette();
+                let html = export::html(
+                    &buffer.content.text(),
+                    &buffer.title(),
+                    self.theme,
+                    buffer.language.token(),
+                    palette.background,
+                    palette.text,
+                );
+                Command::perform(
+                    export::save(html.into_bytes(), buffer.title(), export::Format::Html),
+                    Message::Exported,
+                )
+            }
+            Message::ExportPdf => Command::perform(
+                export::save(
+                    self.export_pdf(),
+                    self.buffer().title(),
+                    export::Format::Pdf,
+                ),
+                Message::Exported,
+            ),
+            Message::Print => Command::perform(
+                export::print(self.export_pdf(), self.buffer().title()),
+                Message::Exported,
+            ),
+            Message::Exported(Ok(path)) => {
+                self.notify(format!("Exported to {}", path.display()));
+                Command::none()
+            }
+            Message::Exported(Err(Error::DialogClosed)) => Command::none(),
+            Message::Exported(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::DictionarySaved(Ok(())) => Command::none(),
+            Message::DictionarySaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::StateSaved(Ok(())) => Command::none(),
+            Message::StateSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::SelectTab(index) => {
+                if index < self.buffers.len() {
+                    self.active = index;
+                }
+                Command::none()
+            }
+            Message::CloseTab(index) => {
+                self.request_close(index);
+                Command::none()
+            }
+            Message::CloseActiveTab => {
+                self.request_close(self.active);
+                Command::none()
+            }
+            Message::CloseRequested => self.request_exit(),
+            Message::Confirm(choice) => {
+                let Some(confirmation) = self.confirmation.take() else {
+                    return Command::none();
+                };
+                let Some(index) = self
+                    .buffers
+                    .iter()
+                    .position(|buffer| buffer.id == confirmation.buffer)
+                else {
+                    return Command::none();
+                };
+                match choice {
+                    Choice::Save => {
+                        self.after_save = Some(confirmation);
+                        self.save_buffer(index, false)
+                    }
+                    Choice::Discard => {
+                        self.buffers[index].is_dirty = false;
+                        self.finish_confirmation(confirmation)
+                    }
+                    Choice::Cancel => Command::none(),
+                }
+            }
+            Message::NextTab => {
+                self.active = (self.active + 1) % self.buffers.len();
+                Command::none()
+            }
+            Message::PreviousTab => {
+                self.active = (self.active + self.buffers.len() - 1) % self.buffers.len();
+                Command::none()
+            }
+            Message::LanguageSelected(language) => {
+                let buffer = &mut self.buffers[self.active];
+                if let (Some(path), Some(_), Some(client)) = (
+                    buffer.path.as_deref(),
+                    buffer.synced.take(),
+                    self.servers.client(buffer.language),
+                ) {
+                    client.did_close(path);
+                }
+                buffer.language = language;
+                buffer.diagnostics.clear();
+                if let Some(path) = buffer.path.clone() {
+                    self.request_server(language, &path);
+                    self.sync_document(self.active);
+                }
+                Command::none()
+            }
+            Message::ToggleFind => self.toggle_search(false),
+            Message::ToggleReplace => self.toggle_search(true),
+            Message::CloseFind => {
+                self.search = None;
+                Command::none()
+            }
+            Message::Dismiss => {
+                if self.config.vim && self.vim.mode != vim::Mode::Normal {
+                    let effects = self.vim.escape();
+                    return self.run_vim(effects);
+                }
+                if self.context_menu.is_some() {
+                    self.context_menu = None;
+                } else if self.color_picker.is_some() {
+                    self.color_picker = None;
+                } else if self.completion.is_some() {
+                    self.completion = None;
+                } else if let (_, Some(cancel)) = self.prompt_actions() {
+                    return self.update(cancel);
+                } else if self.diff.is_some() {
+                    self.diff = None;
+                } else if self.spelling.prompt.is_some() {
+                    self.spelling.prompt = None;
+                } else if self.clip_picker.is_some() {
+                    self.clip_picker = None;
+                } else if self.template_picker.is_some() {
+                    self.template_picker = None;
+                } else if self.remote_prompt.is_some() {
+                    self.remote_prompt = None;
+                } else if self.crypt_prompt.is_some() {
+                    self.crypt_prompt = None;
+                } else if self.macro_prompt.is_some() {
+                    self.macro_prompt = None;
+                } else if self.font_picker.is_some() {
+                    return self.update(Message::CancelFont);
+                } else if self.palette_filter.is_some() {
+                    self.palette_filter = None;
+                } else if self.goto_line.is_some() {
+                    self.goto_line = None;
+                } else if self.recent_filter.is_some() {
+                    self.recent_filter = None;
+                } else if self.finder.is_some() {
+                    self.finder = None;
+                } else if self.unsaved.is_some() {
+                    self.unsaved = None;
+                } else if self.versions.is_some() {
+                    self.versions = None;
+                } else if self.todos.is_some() {
+                    self.todos = None;
+                } else if self.grep.is_some() {
+                    self.grep = None;
+                } else if self.bookmark_panel {
+                    self.bookmark_panel = false;
+                } else if self.problems {
+                    self.problems = false;
+                } else if !self.buffer().carets.is_empty() {
+                    self.buffer_mut().carets.clear();
+                } else {
+                    self.search = None;
+                }
+                Command::none()
+            }
+            Message::RecentLoaded(recent) => {
+                self.recent = recent;
+                Command::none()
+            }
+            Message::RecentSaved(Ok(())) => Command::none(),
+            Message::RecentSaved(Err(error)) => {
+                self.report(error);
+                Command::none()
+            }
+            Message::OpenRecent(RecentFile(path)) => {
+                self.recent_filter = None;
+                Command::perform(load_file(path), Message::FileOpened)
+            }
+            Message::ToggleRecent => {
+                if self.recent_filter.take().is_some() {
+                    return Command::none();
+                }
+                self.recent_filter = Some(String::new());
+                text_input::focus(text_input::Id::new(RECENT_INPUT))
+            }
+            Message::RecentFilterChanged(filter) => {
+                self.recent_filter = Some(filter);
+                Command::none()
+            }
+            Message::OpenFirstRecent => {
+                let first = self
+                    .recent_filter
+                    .as_deref()
+                    .and_then(|filter| self.recent.matching(filter).into_iter().next());
+                match first {
+                    Some(file) => self.update(Message::OpenRecent(file)),
+                    None => Command::none(),
+                }
+            }
+            Message::TogglePalette => {
+                if self.palette_filter.take().is_some() {
+                    return Command::none();
+                }
+                self.palette_filter = Some(String::new());
+                text_input::focus(text_input::Id::new(PALETTE_INPUT))
+            }
+            Message::PaletteFilterChanged(filter) => {
+                self.palette_filter = Some(filter);
+                Command::none()
+            }
+            Message::RunCommand(entry) => {
+                self.palette_filter = None;
+                self.update(entry.message)
+            }
+            Message::RunFirstCommand => {
+                let first = self.palette_filter.as_deref().and_then(|filter| {
+                    palette::matching(filter, self.plugins.entries())
+                        .into_iter()
+                        .next()
+                });
+                match first {
+                    Some(entry) => self.update(Message::RunCommand(entry)),
+                    None => Command::none(),
+                }
+            }
+            Message::ToggleGoToLine => {
+                if self.goto_line.take().is_some() {
+                    return Command::none();
+                }
+                self.goto_line = Some(String::new());
+                text_input::focus(text_input::Id::new(GOTO_INPUT))
+            }
+            Message::GoToLineChanged(input) => {
+                self.goto_line = Some(input);
+                Command::none()
+            }
+            Message::SubmitGoToLine => {
+                match self.goto_line.as_deref().and_then(parse_line_column) {
+                    Some((line, column)) => {
+                        self.goto_line = None;
+                        self.update(Message::GoToLine(line, column))
+                    }
+                    None => Command::none(),
+                }
+            }
+            Message::GoToLine(line, column) => {
+                let content = &mut self.buffer_mut().content;
+                let text = content.text();
+                let line = line
+                    .saturating_sub(1)
+                    .min(content.line_count().saturating_sub(1));
+                let length = text
+                    .split('\n')
+                    .nth(line)
+                    .map_or(0, |line| line.chars().count());
+                move_cursor_to(content, line, column.saturating_sub(1).min(length));
+                self.center_cursor();
+                Command::none()
+            }
+            Message::FindQueryChanged(query) => {
+                if let Some(search) = self.search.as_mut() {
+                    let anchor = search.current.as_ref().map(|current| current.start);
+                    search.set_query(query);
+                    self.find_match(true, anchor);
+                }
+                Command::none()
+            }
+            Message::ReplacementChanged(replacement) => {
+                if let Some(search) = self.search.as_mut() {
+                    search.replacement = replacement;
+                }
+                Command::none()
+            }
+            Message::SearchOptionsChanged(options) => {
+                if let Some(search) = self.search.as_mut() {
+                    let anchor = search.current.as_ref().map(|current| current.start);
+                    search.set_options(options);
+                    self.find_match(true, anchor);
+                }
+                Command::none()
+            }
+            Message::FindNext => {
+                self.find_match(true, None);
+                Command::none()
+            }
+            Message::FindPrevious => {
+                self.find_match(false, None);
+                Command::none()
+            }
+            Message::ReplaceOne => {
+                if let Some(search) = self.search.as_ref() {
+                    let buffer = &mut self.buffers[self.active];
+                    let selection = buffer.content.selection();
+                    let rope = buffer.rope();
+                    if let Some(range) = search.current.clone()
+                        && let Some(selection) = selection
+                        && rope
+                            .get_byte_slice(range.clone())
+                            .is_some_and(|found| found == selection.as_str())
+                    {
+                        let replacement = search.expand(rope, &range);
+                        buffer.perform(text_editor::Action::Edit(text_editor::Edit::Paste(
+                            Arc::new(replacement),
+                        )));
+                    }
+                }
+                self.find_match(true, None);
+                Command::none()
+            }
+            Message::ReplaceAll => {
+                if let Some(search) = self.search.as_mut() {
+                    let buffer = &mut self.buffers[self.active];
+                    let text = buffer.content.text();
+                    if let Some(replaced) = search.replace_all(&text)
+                        && replaced != text
+                    {
+                        buffer.replace_text(&replaced);
+                        search.current = None;
+                    }
+                }
+                Command::none()
+            }
+            Message::Undo => {
+                self.buffer_mut().undo();
+                Command::none()
+            }
+            Message::Redo => {
+                self.buffer_mut().redo();
+                Command::none()
+            }
+            Message::RepeatLast => match self.last_command.clone() {
+                Some(command) => self.update(command),
+                None => Command::none(),
+            },
+        }
+    }
+}
+
+impl Application for Editor {
+    type Message = Message;
+    type Executor = executor::Default;
+    type Theme = Theme;
+    type Flags = cli::Args;
+
+    fn new(args: Self::Flags) -> (Self, Command<Message>) {
+        i18n::select(None);
+        let (panes, focus) = pane_grid::State::new(PaneState {
+            buffer: 0,
+            cursor: (0, 0),
+            top_line: 0,
+        });
+        let opens = args.files.iter().map(open_argument);
+        (
+            Self {
+                buffers: vec![Buffer::new(0, None, "")],
+                active: 0,
+                next_buffer_id: 1,
+                error: None,
+                toasts: toast::Toasts::default(),
+                notifications: false,
+                statistics: None,
+                render: render::Cache::default(),
+                todos: None,
+                theme: highlighter::Theme::SolarizedDark,
+                last_command: None,
+                pending_opens: args.files.clone(),
+                check_session: args.files.is_empty(),
+                check_scratch: true,
+                histories: HashMap::new(),
+                views: HashMap::new(),
+                state: state::State::default(),
+                viewport_lines: viewport_lines(Settings::<()>::default().window.size.1),
+                window_width: Settings::<()>::default().window.size.0,
+                confirmation: None,
+                after_save: None,
+                search: None,
+                config: Config::default(),
+                font: DEFAULT_FONT,
+                recovered: Vec::new(),
+                loading: Vec::new(),
+                recent: Recent::default(),
+                recent_filter: None,
+                palette_filter: None,
+                goto_line: None,
+                system_is_dark: true,
+                modifiers: keyboard::Modifiers::default(),
+                column_anchor: None,
+                is_hovering_files: false,
+                start_page: args.files.is_empty(),
+                syntax_error: None,
+                terminal: None,
+                context_menu: None,
+                color_picker: None,
+                unsaved: None,
+                versions: None,
+                next_terminal: 0,
+                explorer: explorer::Explorer::default(),
+                grep: None,
+                next_grep_id: 0,
+                panes,
+                focus,
+                preview: false,
+                outline: false,
+                servers: lsp::Servers::default(),
+                pending_server: None,
+                pending_formatter: None,
+                pending_terminal: None,
+                pending_link: None,
+                problems: false,
+                completion: None,
+                snippets: snippet::Snippets::default(),
+                spelling: spell::Spelling::default(),
+                diff: None,
+                save_denied: None,
+                save_conflict: None,
+                saves: saves::Queue::default(),
+                zoomed_at: None,
+                sync_at: None,
+                font_picker: None,
+                bookmarks: bookmarks::Bookmarks::default(),
+                bookmark_panel: false,
+                recording: None,
+                macros: macros::Macros::default(),
+                macro_prompt: None,
+                vim: vim::Vim::default(),
+                plugins: plugins::Plugins::new(),
+                clips: clips::History::default(),
+                clip_picker: None,
+                template_picker: None,
+                workspace: workspace::Workspace::default(),
+                finder: None,
+                remote_prompt: None,
+                crypt_prompt: None,
+                credentials: remote::Credentials::default(),
+            },
+            Command::batch(opens.chain([
+                Command::perform(state::load(), Message::StateLoaded),
+                Command::perform(config::load(), Message::ConfigLoaded),
+                Command::perform(snippet::load(), Message::SnippetsLoaded),
+                Command::perform(appearance::system_is_dark(), Message::SystemThemeChecked),
+                Command::perform(recovery::find(), Message::RecoveryFound),
+                Command::perform(recent::load(), Message::RecentLoaded),
+                Command::perform(bookmarks::load(), Message::BookmarksLoaded),
+                Command::perform(macros::load(), Message::MacrosLoaded),
+                Command::perform(plugins::read(), Message::PluginsLoaded),
+            ])),
+        )
+    }
+
+    fn title(&self) -> String {
+        let buffer = self.buffer();
+        let mut title = buffer.title();
+        if buffer.needs_save() {
+            title.push_str(" *");
+        }
+        if buffer.is_deleted {
+            title.push_str(" [deleted on disk]");
+        }
+        if buffer.is_readonly {
+            title.push(' ');
+            title.push_str(&i18n::tr("title-read-only"));
+        }
+        if let (None, Some(remote)) = (&buffer.path, &buffer.remote) {
+            title.push_str(&format!(" \u{2014} {}", remote.server()));
+        }
+        if let (None, Some(url)) = (&buffer.path, &buffer.url) {
+            title.push_str(&format!(" \u{2014} {url}"));
+        }
+        if let Some(dir) = buffer.path.as_deref().and_then(std::path::Path::parent) {
+            let dir = match dirs::home_dir().and_then(|home| dir.strip_prefix(home).ok()) {
+                Some(relative) => std::path::Path::new("~").join(relative),
+                None => dir.to_path_buf(),
+            };
+            title.push_str(&format!(" \u{2014} {}", dir.display()));
+        }
+        if self.config.announce_in_title {
+            let (line, column) = buffer.cursor_position();
+            title.push_str(&format!(" \u{2014} Ln {}, Col {}", line + 1, column + 1));
+            if let Some(toast) = self.toasts.active().next() {
+                title.push_str(&format!(" \u{2014} {}: {}", toast.level, toast.message));
+            }
+        }
+        title
+    }
+
+    fn update(&mut self, message: Message) -> Command<Message> {
+        let command = self.handle(message);
+        for buffer in &mut self.buffers {
+            buffer.refresh_folds();
+        }
+        self.refresh_frames();
+        if let Some(search) = &mut self.search {
+            let buffer = &mut self.buffers[self.active];
+            search.refresh(buffer.id, buffer.snapshot());
+        }
+        command
+    }
+
+    fn subscription(&self) -> Subscription<Message> {
+        let window = subscription::events_with(|event, _status| match event {
+            Event::Window(window::Event::Resized { width, height }) => {
+                Some(Message::WindowResized(width, height))
+            }
+            Event::Window(window::Event::CloseRequested) => Some(Message::CloseRequested),
+            Event::Window(window::Event::FileHovered(_)) => Some(Message::FilesHovered(true)),
+            Event::Window(window::Event::FilesHoveredLeft) => Some(Message::FilesHovered(false)),
+            Event::Window(window::Event::FileDropped(path)) => Some(Message::FileDropped(path)),
+            Event::Keyboard(keyboard::Event::ModifiersChanged(modifiers)) => {
+                Some(Message::ModifiersChanged(modifiers))
+            }
+            Event::Keyboard(keyboard::Event::KeyPressed {
+                key_code: keyboard::KeyCode::C | keyboard::KeyCode::X,
+                modifiers,
+            }) if modifiers.command() => Some(Message::ClipboardChanged),
+            Event::Mouse(mouse::Event::WheelScrolled { delta }) => {
+                Some(Message::WheelScrolled(delta))
+            }
+            _ => None,
+        });
+        let keys = keyboard::on_key_press(keymap::lookup);
+        let config = iced::time::every(CONFIG_POLL_INTERVAL).map(|_| Message::ConfigTick);
+        let autosave = if self.config.autosave_interval > 0 {
+            iced::time::every(Duration::from_secs(self.config.autosave_interval))
+                .map(|_| Message::AutosaveTick)
+        } else {
+            Subscription::none()
+        };
+        let loading = Subscription::batch(
+            self.loading
+                .iter()
+                .map(|loading| large::stream(loading.id, loading.path.clone())),
+        );
+        let watched = watcher::watch(
+            self.buffers
+                .iter()
+                .filter(|buffer| buffer.page.is_none() && buffer.follow.is_none())
+                .filter_map(|buffer| buffer.path.clone())
+                .collect(),
+        );
+        let grep = match self.grep.as_ref() {
+            Some(grep::Panel {
+                running: Some(id),
+                root,
+                pattern: Some(pattern),
+                ..
+            }) => grep::search(*id, root.clone(), pattern.clone()),
+            _ => Subscription::none(),
+        };
+        let servers =
+            Subscription::batch(self.servers.allowed.iter().filter_map(|(language, root)| {
+                let server = self.config.language_servers.get(language.token())?;
+                Some(lsp::connect(*language, server.clone(), root.clone()))
+            }));
+        let sync = if self.sync_at.is_some() {
+            iced::time::every(Duration::from_millis(100)).map(|_| Message::SyncTick)
+        } else {
+            Subscription::none()
+        };
+        let follow = if self.buffers.iter().any(|buffer| buffer.follow.is_some()) {
+            iced::time::every(follow::POLL_INTERVAL).map(|_| Message::FollowTick)
+        } else {
+            Subscription::none()
+        };
+        let toasts = if self.toasts.is_showing() {
+            iced::time::every(Duration::from_millis(500)).map(|_| Message::ToastTick)
+        } else {
+            Subscription::none()
+        };
+        let statistics = if self.statistics.is_some() {
+            iced::time::every(Duration::from_millis(300)).map(|_| Message::StatisticsTick)
+        } else {
+            Subscription::none()
+        };
+        let todos = match &self.todos {
+            Some(panel) => Subscription::batch([
+                iced::time::every(Duration::from_millis(300)).map(|_| Message::TodosTick),
+                match panel.scanning {
+                    Some(id) => todos::search(id, panel.root.clone()),
+                    None => Subscription::none(),
+                },
+            ]),
+            None => Subscription::none(),
+        };
+        let render = if self
+            .shown_buffers()
+            .into_iter()
+            .filter_map(|id| self.buffers.iter().find(|buffer| buffer.id == id))
+            .any(|buffer| self.render.is_stale(buffer.id, &render::Key::of(buffer)))
+        {
+            iced::time::every(render::DEBOUNCE).map(|_| Message::RenderTick)
+        } else {
+            Subscription::none()
+        };
+        let instance = if self.config.single_instance {
+            instance::listen()
+        } else {
+            Subscription::none()
+        };
+        let zoom = if self.zoomed_at.is_some() {
+            iced::time::every(Duration::from_millis(250)).map(|_| Message::ZoomTick)
+        } else {
+            Subscription::none()
+        };
+        let terminal = match &self.terminal {
+            Some(terminal) => terminal::run(
+                terminal.session,
+                self.config.shell.clone(),
+                terminal.cwd.clone(),
+            ),
+            None => Subscription::none(),
+        };
+        Subscription::batch([
+            window, keys, config, autosave, loading, watched, grep, servers, sync, follow, zoom,
+            toasts, statistics, todos, render, instance, terminal,
+        ])
+    }
+    fn view(&self) -> Element<'_, Message> {
+        let buffer = self.buffer();
+        let controls = row!(
+            action(new_icon(), NEW_TIP, Some(Message::New)),
+            action(open_icon(), OPEN_TIP, Some(Message::Open)),
+            action(
+                save_icon(),
+                SAVE_TIP,
+                (buffer.is_dirty || buffer.is_deleted).then_some(Message::Save)
+            ),
+            action(text("…").into(), SAVE_AS_TIP, Some(Message::SaveAs)),
+            action(
+                text("↶").into(),
+                UNDO_TIP,
+                buffer.history.can_undo().then_some(Message::Undo)
+            ),
+            action(
+                text("↷").into(),
+                REDO_TIP,
+                buffer.history.can_redo().then_some(Message::Redo)
+            ),
+            horizontal_space(Length::Fill),
+            pick_list(self.recent.matching(""), None, Message::OpenRecent)
+                .placeholder(i18n::tr("recent-files")),
+            pick_list(
+                appearance::Appearance::options(&self.config.palettes),
+                Some(self.state.appearance.clone()),
+                Message::AppearanceSelected
+            ),
+            pick_list(
+                highlighter::Theme::ALL,
+                Some(self.theme),
+                Message::ThemeSelected
+            ),
+        )
+        .spacing(10);
+        let tabs = row(self
+            .buffers
+            .iter()
+            .enumerate()
+            .map(|(index, buffer)| tab(buffer, index, index == self.active))
+            .collect())
+        .spacing(5);
+        let input = PaneGrid::new(&self.panes, |pane, state, _is_maximized| {
+            let buffer = if pane == self.focus {
+                buffer
+            } else {
+                self.buffers
+                    .iter()
+                    .find(|buffer| buffer.id == state.buffer)
+                    .unwrap_or(buffer)
+            };
+            let style = if pane == self.focus && self.panes.len() > 1 {
+                theme::Container::Box
+            } else {
+                theme::Container::Transparent
+            };
+            pane_grid::Content::new(
+                container(self.editor_view(buffer, pane))
+                    .padding(2)
+                    .style(style),
+            )
+        })
+        .spacing(5)
+        .on_click(Message::FocusPane)
+        .on_resize(10, Message::PaneResized);
+        let input: Element<'_, Message> =
+            if self.start_page && self.buffers.len() == 1 && buffer.is_blank() {
+                start_page(&self.recent)
+            } else if let Some(table) = &buffer.table {
+                table::view(table, buffer.is_readonly, self.font)
+            } else if self.shows_preview() {
+                row!(
+                    input,
+                    markdown::view(
+                        buffer.frame.preview.as_deref().unwrap_or_default(),
+                        self.font
+                    )
+                )
+                .spacing(10)
+                .into()
+            } else {
+                input.into()
+            };
+
+        let status_bar = {
+            let marker = if buffer.is_dirty { " *" } else { "" };
+            let lock = button(
+                text(if buffer.is_readonly {
+                    format!("\u{f023} {}", i18n::tr("status-read-only"))
+                } else {
+                    String::from("\u{f09c}")
+                })
+                .font(self.font)
+                .size(14),
+            )
+            .padding(0)
+            .style(theme::Button::Text)
+            .on_press_maybe(buffer.page.is_none().then_some(Message::ToggleReadonly));
+            let status = match buffer.path.as_deref() {
+                Some(path) => {
+                    let root = [&self.explorer.root, &self.workspace.root]
+                        .into_iter()
+                        .find(|root| !root.as_os_str().is_empty());
+                    let path = self
+                        .state
+                        .path_display
+                        .format(path, root.map(PathBuf::as_path));
+                    text(format!("{}{marker}", elide_middle(&path, MAX_PATH_CHARS))).size(14)
+                }
+                None => text(format!("{}{marker}", i18n::tr("status-new-file"))),
+            };
+            let status = button(status)
+                .padding(0)
+                .style(theme::Button::Text)
+                .on_press(Message::TogglePathDisplay);
+            let position = {
+                let (line, column) = buffer.cursor_position();
+                let length = buffer
+                    .content
+                    .lines()
+                    .nth(line)
+                    .map_or(0, |line| line.chars().count());
+                let columns = self.wrap_columns();
+                let label = if buffer.wrap && length > columns {
+                    i18n::tr_args(
+                        "status-position-wrapped",
+                        [
+                            ("line", (line + 1).to_string()),
+                            ("column", (column + 1).to_string()),
+                            ("row", (column / columns + 1).to_string()),
+                            ("rows", length.div_ceil(columns).to_string()),
+                        ],
+                    )
+                } else {
+                    format!("{}:{}", line + 1, column + 1)
+                };
+                segment(label, Message::ToggleGoToLine)
+            };
+            let wrap = segment(
+                i18n::tr(if buffer.wrap {
+                    "status-wrap"
+                } else {
+                    "status-no-wrap"
+                }),
+                Message::ToggleWrap,
+            );
+            let transforms = segment(
+                match (buffer.skip_transforms, buffer.save_report) {
+                    (true, _) => i18n::tr("status-no-save-fixes"),
+                    (false, Some(report)) => report.to_string(),
+                    (false, None) => String::new(),
+                },
+                Message::ToggleSaveTransforms,
+            );
+            let stats = {
+                let words = buffer.frame.words;
+                let lines = buffer.content.line_count();
+                let mut parts = Vec::new();
+                if !buffer.carets.is_empty() {
+                    parts.push(i18n::tr_args(
+                        "status-carets",
+                        [("count", buffer.carets.len())],
+                    ));
+                }
+                if let Some(selection) = buffer.content.selection() {
+                    parts.push(i18n::tr_args(
+                        "status-selection",
+                        [
+                            ("chars", selection.chars().count()),
+                            ("lines", selection.lines().count()),
+                        ],
+                    ));
+                }
+                parts.push(i18n::tr_args(
+                    "status-counts",
+                    [("lines", lines), ("words", words)],
+                ));
+                text(parts.join(", ")).size(14)
+            };
+
+            let language = pick_list(
+                Language::ALL,
+                Some(buffer.language),
+                Message::LanguageSelected,
+            )
+            .text_size(14)
+            .padding([0, 5]);
+
+            let mut status_bar = row!(status, horizontal_space(Length::Fill)).spacing(10);
+            if let Some((index, count)) = buffer.page {
+                status_bar = status_bar.push(
+                    row!(
+                        button("◀").style(theme::Button::Text).on_press_maybe(
+                            (index > 0).then(|| Message::LoadPage(buffer.id, index - 1))
+                        ),
+                        text(i18n::tr_args(
+                            "status-page",
+                            [("index", index + 1), ("count", count)]
+                        ))
+                        .size(14),
+                        button("▶").style(theme::Button::Text).on_press_maybe(
+                            (index + 1 < count).then(|| Message::LoadPage(buffer.id, index + 1))
+                        ),
+                    )
+                    .align_items(iced::Alignment::Center),
+                );
+            }
+            let encoding = pick_list(
+                encoding::ALL,
+                Some(buffer.encoding),
+                Message::EncodingSelected,
+            )
+            .text_size(14)
+            .padding([0, 5]);
+            let compression = text(
+                buffer
+                    .path
+                    .as_deref()
+                    .and_then(compression::Codec::from_path)
+                    .map(|codec| codec.to_string())
+                    .unwrap_or_default(),
+            )
+            .size(14);
+            let reopen = button(text(i18n::tr("status-reopen")).size(14))
+                .padding(0)
+                .style(theme::Button::Text)
+                .on_press_maybe(buffer.path.is_some().then_some(Message::ReopenWithEncoding));
+            let problems = segment(
+                if self.servers.client(buffer.language).is_some()
+                    || !self.servers.diagnostics.is_empty()
+                    || self.servers.error.is_some()
+                {
+                    let (errors, warnings) = self.servers.counts();
+                    i18n::tr_args(
+                        "status-problems",
+                        [("errors", errors), ("warnings", warnings)],
+                    )
+                } else {
+                    String::new()
+                },
+                Message::ToggleProblems,
+            );
+            let branch = segment(
+                buffer
+                    .git
+                    .as_ref()
+                    .map(|info| {
+                        let dirty = if info.is_dirty { "*" } else { "" };
+                        format!("\u{e0a0} {}{dirty}", info.branch)
+                    })
+                    .unwrap_or_default(),
+                Message::DiffAgainstHead,
+            );
+            let syntax = match &self.syntax_error {
+                Some((id, revision, invalid))
+                    if *id == buffer.id && *revision == buffer.revision =>
+                {
+                    segment(
+                        format!("✗ {}", elide_middle(&invalid.to_string(), 60)),
+                        Message::GoToLine(invalid.line + 1, invalid.column + 1),
+                    )
+                }
+                _ => segment(String::new(), Message::Dismiss),
+            };
+            let table = segment(
+                match (
+                    &buffer.table,
+                    buffer.path.as_deref().and_then(table::delimiter),
+                ) {
+                    (Some(_), _) => i18n::tr("status-table-view"),
+                    (None, Some(_)) => i18n::tr("status-text-view"),
+                    (None, None) => String::new(),
+                },
+                Message::ToggleTable,
+            );
+            let following = segment(
+                if buffer.follow.is_some() {
+                    i18n::tr("status-following")
+                } else {
+                    String::new()
+                },
+                Message::ToggleFollow,
+            );
+            let vim = segment(
+                if self.config.vim {
+                    self.vim.mode.to_string()
+                } else {
+                    String::new()
+                },
+                Message::Dismiss,
+            );
+            let recording = segment(
+                self.recording
+                    .as_ref()
+                    .map(|steps| i18n::tr_args("status-recording", [("steps", steps.len())]))
+                    .unwrap_or_default(),
+                Message::ToggleRecording,
+            );
+            let zoom = segment(
+                if self.zoomed_at.is_some() {
+                    let percent = self.config.font_size / config::DEFAULT_FONT_SIZE * 100.0;
+                    i18n::tr_args("status-zoom", [("percent", format!("{percent:.0}"))])
+                } else {
+                    String::new()
+                },
+                Message::ResetZoom,
+            );
+            let convert = pick_list(Conversion::ALL, None, Message::Convert)
+                .placeholder(i18n::tr("status-transform"))
+                .text_size(14)
+                .padding([0, 5]);
+            let indent = pick_list(Indent::ALL, Some(buffer.indent), Message::IndentSelected)
+                .text_size(14)
+                .padding([0, 5]);
+            let line_ending = pick_list(
+                LineEnding::ALL,
+                Some(buffer.line_ending),
+                Message::LineEndingSelected,
+            )
+            .text_size(14)
+            .padding([0, 5]);
+            status_bar
+                .push(lock)
+                .push(indent)
+                .push(line_ending)
+                .push(encoding)
+                .push(reopen)
+                .push(compression)
+                .push(table)
+                .push(language)
+                .push(stats)
+                .push(syntax)
+                .push(problems)
+                .push(branch)
+                .push(following)
+                .push(vim)
+                .push(recording)
+                .push(zoom)
+                .push(convert)
+                .push(transforms)
+                .push(wrap)
+                .push(position)
+        };
+        let prompt = self.confirmation.and_then(|confirmation| {
+            self.buffers
+                .iter()
+                .find(|buffer| buffer.id == confirmation.buffer)
+        });
+        let mut content = column!(controls, tabs);
+        if self.is_hovering_files {
+            content = content.push(
+                container(text(i18n::tr("drop-files")))
+                    .width(Length::Fill)
+                    .center_x()
+                    .padding(10)
+                    .style(theme::Container::Box),
+            );
+        }
+        for loading in &self.loading {
+            let name = loading
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            content = content.push(
+                row!(
+                    text(i18n::tr_args("loading", [("name", name)])).size(14),
+                    progress_bar(0.0..=loading.total as f32, loading.read as f32).height(10),
+                )
+                .spacing(10)
+                .align_items(iced::Alignment::Center),
+            );
+        }
+        if !self.recovered.is_empty() {
+            content = content.push(recovery_prompt(self.recovered.len()));
+        }
+        if let Some(buffer) = prompt {
+            content = content.push(confirm_prompt(buffer));
+        } else if let Some(denied) = self
+            .save_denied
+            .and_then(|id| self.buffers.iter().find(|buffer| buffer.id == id))
+        {
+            content = content.push(save_denied_prompt(denied));
+        } else if let Some(conflicted) = self
+            .save_conflict
+            .and_then(|id| self.buffers.iter().find(|buffer| buffer.id == id))
+        {
+            content = content.push(save_conflict_prompt(conflicted));
+        } else if buffer.has_conflict {
+            content = content.push(conflict_prompt(buffer));
+        } else if let Some(language) = self.pending_server
+            && let Some(server) = self.config.language_servers.get(language.token())
+        {
+            content = content.push(server_prompt(language, server));
+        } else if let Some(pending) = self.pending_formatter
+            && let Some(pending_buffer) = self
+                .buffers
+                .iter()
+                .find(|buffer| buffer.id == pending.buffer)
+            && let Some(formatter) = self.config.formatters.get(pending_buffer.language.token())
+        {
+            content = content.push(formatter_prompt(pending, pending_buffer, formatter));
+        } else if let Some(pending) = self.pending_terminal {
+            content = content.push(terminal_prompt(pending, &self.untrusted_commands(pending)));
+        } else if let Some(link) = &self.pending_link {
+            content = content.push(link_prompt(link));
+        } else if let Some(prompt) = &self.spelling.prompt {
+            content = content.push(spelling_prompt(prompt));
+        } else if let Some(prompt) = &self.macro_prompt {
+            content = content.push(macro_prompt(prompt, &self.macros));
+        } else if let Some(prompt) = &self.remote_prompt {
+            content = content.push(remote_prompt(prompt));
+        } else if let Some(prompt) = &self.crypt_prompt {
+            content = content.push(passphrase_prompt(prompt));
+        }
+        if let Some(search) = self.search.as_ref() {
+            let matches = search
+                .cached(buffer.id, buffer.revision)
+                .unwrap_or_default();
+            content = content.push(find_bar(search, matches.len(), search.position(matches)));
+        }
+        if let Some(panel) = self.grep.as_ref() {
+            content = content.push(grep_panel(panel));
+        }
+        if self.problems {
+            content = content.push(problems_panel(&self.servers));
+        }
+        if self.bookmark_panel {
+            content = content.push(bookmark_panel(&self.buffers));
+        }
+        if let Some(panel) = &self.unsaved
+            && panel.buffer == buffer.id
+        {
+            content = content.push(unsaved_panel(panel.changes(), self.font));
+        }
+        if let Some(panel) = &self.versions
+            && panel.buffer == buffer.id
+        {
+            content = content.push(versions_panel(&panel.versions));
+        }
+        if self.notifications {
+            content = content.push(notification_panel(&self.toasts));
+        }
+        if let Some(panel) = &self.statistics {
+            content = content.push(statistics_panel(panel));
+        }
+        if let Some(panel) = &self.todos {
+            content = content.push(todos_panel(panel));
+        }
+        if let Some(input) = self.goto_line.as_deref() {
+            content = content.push(goto_line(input, buffer.content.line_count()));
+        }
+        if let Some(filter) = self.palette_filter.as_deref() {
+            content = content.push(command_palette(
+                filter,
+                palette::matching(filter, self.plugins.entries()),
+            ));
+        }
+        if let Some(filter) = self.recent_filter.as_deref() {
+            content = content.push(recent_picker(filter, self.recent.matching(filter)));
+        }
+        if let Some(query) = self.finder.as_deref() {
+            content = content.push(file_finder(query, &self.workspace));
+        }
+        if let Some(picker) = &self.font_picker {
+            content = content.push(font_picker(picker));
+        }
+        if let Some(templates) = &self.template_picker {
+            content = content.push(template_picker(templates));
+        }
+        if let Some(picker) = self.clip_picker {
+            content = content.push(clip_picker(picker, &self.clips));
+        }
+        let content = match &self.diff {
+            Some(view) => content.push(diff_view(view, self.font)),
+            None => content.push(input),
+        };
+        let content = match &self.terminal {
+            Some(terminal) => content.push(terminal::view(terminal, self.font)),
+            None => content,
+        };
+        let content = match toasts(&self.toasts) {
+            Some(toasts) => content.push(toasts),
+            None => content,
+        }
+        .push(status_bar);
+        let content: Element<'_, Message> = if self.outline {
+            row!(content, symbol_outline(buffer)).spacing(10).into()
+        } else {
+            content.into()
+        };
+        let body: Element<'_, Message> = if self.explorer.is_visible {
+            row!(file_tree(&self.explorer, self.font), content)
+                .spacing(10)
+                .into()
+        } else {
+            content
+        };
+        container(body)
+            .padding(10)
+            .style(if self.is_hovering_files {
+                theme::Container::Box
+            } else {
+                theme::Container::Transparent
+            })
+            .into()
+    }
+
+    fn scale_factor(&self) -> f64 {
+        f64::from(self.config.font_size.max(1.0) / config::DEFAULT_FONT_SIZE)
+    }
+
+    fn theme(&self) -> Theme {
+        self.state.appearance.theme(
+            &self.config.palettes,
+            self.theme.is_dark(),
+            self.system_is_dark,
+        )
+    }
+}
+
+fn find_bar<'a>(search: &Search, matches: usize, position: Option<usize>) -> Element<'a, Message> {
+    let options = search.options;
+    let status = match (&search.error, position) {
+        (Some(error), _) => text(error.lines().last().unwrap_or_default()).size(14),
+        (None, _) if search.query.is_empty() => text(""),
+        (None, _) if matches == 0 => text("No matches").size(14),
+        (None, Some(position)) => text(format!("{position} of {matches}")).size(14),
+        (None, None) => text(format!("{matches} matches")).size(14),
+    };
+    let find = row!(
+        text_input("Find", &search.query)
+            .id(text_input::Id::new(FIND_INPUT))
+            .on_input(Message::FindQueryChanged)
+            .on_submit(Message::FindNext)
+            .width(250),
+        checkbox("Aa", options.case_sensitive, move |case_sensitive| {
+            Message::SearchOptionsChanged(search::Options {
+                case_sensitive,
+                ..options
+            })
+        }),
+        checkbox("Word", options.whole_word, move |whole_word| {
+            Message::SearchOptionsChanged(search::Options {
+                whole_word,
+                ..options
+            })
+        }),
+        checkbox("Regex", options.regex, move |regex| {
+            Message::SearchOptionsChanged(search::Options { regex, ..options })
+        }),
+        button("Previous").on_press(Message::FindPrevious),
+        button("Next").on_press(Message::FindNext),
+        status,
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::CloseFind),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+
+    let mut bar = column!(find).spacing(5);
+    if search.is_replacing {
+        bar = bar.push(
+            row!(
+                text_input("Replace", &search.replacement)
+                    .on_input(Message::ReplacementChanged)
+                    .on_submit(Message::ReplaceOne)
+                    .width(250),
+                button("Replace").on_press(Message::ReplaceOne),
+                button("Replace all").on_press(Message::ReplaceAll),
+            )
+            .spacing(10)
+            .align_items(iced::Alignment::Center),
+        );
+    }
+    container(bar)
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn template_picker<'a>(templates: &[templates::Template]) -> Element<'a, Message> {
+    let templates = templates.iter().enumerate().map(|(index, template)| {
+        button(text(&template.file_name).size(14))
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::TemplateSelected(index))
+            .into()
+    });
+    container(
+        column!(
+            text("New file from template").size(14),
+            column(templates.collect()).spacing(2)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn clip_picker<'a>(picker: clips::Picker, history: &clips::History) -> Element<'a, Message> {
+    let options = row!(
+        text("Paste from history").size(14),
+        horizontal_space(Length::Fill),
+        checkbox("Reindent", picker.reindent, Message::ReindentPasteToggled)
+            .size(14)
+            .text_size(14),
+        checkbox("Plain text", picker.plain, Message::PlainPasteToggled)
+            .size(14)
+            .text_size(14),
+    )
+    .spacing(10);
+    let entries: Vec<Element<'a, Message>> = if history.entries.is_empty() {
+        vec![
+            text("Nothing copied yet")
+                .size(14)
+                .style(GUTTER_COLOR)
+                .into(),
+        ]
+    } else {
+        history
+            .entries
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                button(text(clips::preview(entry)).size(14))
+                    .width(Length::Fill)
+                    .style(theme::Button::Text)
+                    .on_press(Message::PasteFromHistory(index))
+                    .into()
+            })
+            .collect()
+    };
+    container(column!(options, column(entries).spacing(2)).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn start_page<'a>(recent: &Recent) -> Element<'a, Message> {
+    let actions = row!(
+        button("New file").on_press(Message::New),
+        button("Open file...")
+            .style(theme::Button::Secondary)
+            .on_press(Message::Open),
+        button("Open folder...")
+            .style(theme::Button::Secondary)
+            .on_press(Message::OpenFolder),
+    )
+    .spacing(10);
+    let files: Vec<Element<'a, Message>> = recent
+        .matching("")
+        .into_iter()
+        .map(|file| {
+            let name = file
+                .0
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_else(|| file.to_string());
+            let dir = file
+                .0
+                .parent()
+                .map(|dir| dir.display().to_string())
+                .unwrap_or_default();
+            button(
+                row!(
+                    text(name).size(14),
+                    text(elide_middle(&dir, 60)).size(14).style(GUTTER_COLOR),
+                )
+                .spacing(10),
+            )
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::OpenRecent(file))
+            .into()
+        })
+        .collect();
+    let recent: Element<'a, Message> = if files.is_empty() {
+        text("No recent files").size(14).style(GUTTER_COLOR).into()
+    } else {
+        column(files).spacing(2).into()
+    };
+    container(
+        column!(
+            text("iced_editor").size(28),
+            actions,
+            text("Recent").size(14).style(GUTTER_COLOR),
+            recent,
+            text("Drop files onto the window to open them")
+                .size(14)
+                .style(GUTTER_COLOR),
+        )
+        .spacing(15)
+        .max_width(600),
+    )
+    .width(Length::Fill)
+    .height(Length::Fill)
+    .center_x()
+    .center_y()
+    .into()
+}
+
+fn recent_picker<'a>(filter: &str, files: Vec<RecentFile>) -> Element<'a, Message> {
+    let input = text_input("Open recent file...", filter)
+        .id(text_input::Id::new(RECENT_INPUT))
+        .on_input(Message::RecentFilterChanged)
+        .on_submit(Message::OpenFirstRecent);
+    let files = files.into_iter().map(|file| {
+        button(text(file.to_string()).size(14))
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::OpenRecent(file))
+            .into()
+    });
+    container(column!(input, column(files.collect()).spacing(2)).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn file_finder<'a>(query: &str, workspace: &workspace::Workspace) -> Element<'a, Message> {
+    let input = text_input("Go to file...", query)
+        .id(text_input::Id::new(FINDER_INPUT))
+        .on_input(Message::FinderQueryChanged)
+        .on_submit(Message::OpenFirstFinderMatch);
+    let status = match (workspace.is_indexing, workspace.files.len()) {
+        (true, 0) => String::from("Indexing..."),
+        (true, count) => format!("Indexing... {count} files"),
+        (false, count) => format!("{count} files in {}", workspace.root.display()),
+    };
+    let files = workspace.matching(query).into_iter().map(|path| {
+        button(text(path.display()).size(14))
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::OpenWorkspaceFile(path.to_path_buf()))
+            .into()
+    });
+    container(
+        column!(
+            input,
+            text(status).size(14).style(GUTTER_COLOR),
+            column(files.collect()).spacing(2)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn font_picker<'a>(picker: &fonts::Picker) -> Element<'a, Message> {
+    let input = text_input("Font family...", &picker.filter)
+        .id(text_input::Id::new(FONT_INPUT))
+        .on_input(Message::FontFilterChanged)
+        .on_submit(Message::ApplyFont);
+    let list: Element<'a, Message> = if picker.fonts.is_empty() {
+        text("Loading fonts...").size(14).into()
+    } else {
+        let fonts = picker.matching().map(|name| {
+            button(text(name).size(14).font(fonts::font(name)))
+                .width(Length::Fill)
+                .style(if picker.selected == Some(name) {
+                    theme::Button::Primary
+                } else {
+                    theme::Button::Text
+                })
+                .on_press(Message::FontSelected(name))
+                .into()
+        });
+        scrollable(column(fonts.collect()).spacing(2))
+            .height(200)
+            .into()
+    };
+    let preview = text(fonts::SAMPLE).font(picker.selected.map_or(picker.previous, fonts::font));
+    let actions = row!(
+        horizontal_space(Length::Fill),
+        button("Cancel")
+            .style(theme::Button::Secondary)
+            .on_press(Message::CancelFont),
+        button("Apply").on_press_maybe(picker.selected.map(|_| Message::ApplyFont)),
+    )
+    .spacing(10);
+    container(column!(input, list, preview, actions).spacing(10))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn grep_panel<'a>(panel: &grep::Panel) -> Element<'a, Message> {
+    let options = panel.options;
+    let status = match (&panel.error, panel.running) {
+        (Some(error), _) => error.lines().last().unwrap_or_default().to_owned(),
+        (None, Some(_)) => format!(
+            "{} results in {} files, searching...",
+            panel.hits.len(),
+            panel.files
+        ),
+        (None, None) if panel.query.is_empty() => String::new(),
+        (None, None) => format!("{} results in {} files", panel.hits.len(), panel.files),
+    };
+    let query = row!(
+        text_input("Find in files", &panel.query)
+            .id(text_input::Id::new(GREP_INPUT))
+            .on_input(Message::GrepQueryChanged)
+            .on_submit(Message::RunGrep)
+            .width(250),
+        checkbox("Aa", options.case_sensitive, move |case_sensitive| {
+            Message::GrepOptionsChanged(search::Options {
+                case_sensitive,
+                ..options
+            })
+        }),
+        checkbox("Word", options.whole_word, move |whole_word| {
+            Message::GrepOptionsChanged(search::Options {
+                whole_word,
+                ..options
+            })
+        }),
+        checkbox("Regex", options.regex, move |regex| {
+            Message::GrepOptionsChanged(search::Options { regex, ..options })
+        }),
+        button(text(elide_middle(&panel.root.display().to_string(), 40)).size(14))
+            .style(theme::Button::Secondary)
+            .on_press(Message::GrepPickFolder),
+        if panel.running.is_some() {
+            button("Cancel").on_press(Message::CancelGrep)
+        } else {
+            button("Search").on_press(Message::RunGrep)
+        },
+        text(status).size(14),
+        horizontal_space(Length::Fill),
+        checkbox("Replace", panel.is_replacing, |_| {
+            Message::ToggleGrepReplace
+        }),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleGrep),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let mut bar = column!(query).spacing(5);
+    if panel.is_replacing {
+        let ready = panel.pattern.is_some() && panel.running.is_none() && !panel.is_applying;
+        let mut replace = row!(
+            text_input("Replace with", &panel.replacement)
+                .on_input(Message::GrepReplacementChanged)
+                .on_submit(Message::PreviewReplace)
+                .width(250),
+            button("Preview").on_press_maybe(ready.then_some(Message::PreviewReplace)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center);
+        if let Some(preview) = &panel.preview {
+            let selected: usize = preview.iter().map(replace::FilePreview::selected).sum();
+            let files = preview.iter().filter(|file| file.selected() > 0).count();
+            replace = replace
+                .push(
+                    button(text(format!("Apply {selected} changes in {files} files")))
+                        .on_press_maybe((selected > 0 && ready).then_some(Message::ApplyReplace)),
+                )
+                .push(
+                    button("Discard")
+                        .style(theme::Button::Secondary)
+                        .on_press(Message::DiscardReplace),
+                );
+        } else if panel.is_applying {
+            replace = replace.push(text("Applying...").size(14));
+        }
+        bar = bar.push(replace);
+    }
+    if let Some(preview) = &panel.preview {
+        let mut files = column!().spacing(2);
+        for (index, file) in preview.iter().enumerate() {
+            let path = file.path.strip_prefix(&panel.root).unwrap_or(&file.path);
+            files = files.push(
+                text(format!(
+                    "{} ({} of {})",
+                    path.display(),
+                    file.selected(),
+                    file.hunks.len()
+                ))
+                .size(14)
+                .style(GUTTER_COLOR),
+            );
+            for (hunk_index, hunk) in file.hunks.iter().enumerate() {
+                files = files.push(checkbox(
+                    format!(
+                        "{}: {}  \u{2192}  {}",
+                        hunk.line,
+                        replace::shorten(&hunk.before),
+                        replace::shorten(&hunk.after)
+                    ),
+                    hunk.selected,
+                    move |_| Message::ToggleHunk(index, hunk_index),
+                ));
+            }
+        }
+        return container(column!(bar, scrollable(files).height(GREP_HEIGHT)).spacing(5))
+            .padding(10)
+            .style(theme::Container::Box)
+            .into();
+    }
+    let hits = panel.hits.iter().take(GREP_SHOWN).map(|hit| {
+        let path = hit
+            .path
+            .strip_prefix(&panel.root)
+            .unwrap_or(&hit.path)
+            .display();
+        button(text(format!("{path}:{}: {}", hit.line, hit.text)).size(14))
+            .padding([2, 4])
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::OpenHit(hit.clone()))
+            .into()
+    });
+    container(column!(bar, scrollable(column(hits.collect())).height(GREP_HEIGHT)).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn goto_line<'a>(input: &str, line_count: usize) -> Element<'a, Message> {
+    let hint = match parse_line_column(input) {
+        _ if input.is_empty() => format!("1-{line_count}"),
+        Some(_) => String::new(),
+        None => String::from("expected line[:column]"),
+    };
+    container(
+        row!(
+            text_input("Go to line[:column]", input)
+                .id(text_input::Id::new(GOTO_INPUT))
+                .on_input(Message::GoToLineChanged)
+                .on_submit(Message::SubmitGoToLine)
+                .width(250),
+            text(hint).size(14),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn parse_line_column(input: &str) -> Option<(usize, usize)> {
+    let (line, column) = match input.trim().split_once(':') {
+        Some((line, column)) => (line, column.trim().parse().ok()?),
+        None => (input.trim(), 1),
+    };
+    Some((line.trim().parse().ok()?, column))
+}
+
+fn command_palette<'a>(filter: &str, entries: Vec<palette::Entry>) -> Element<'a, Message> {
+    let input = text_input("Run a command...", filter)
+        .id(text_input::Id::new(PALETTE_INPUT))
+        .on_input(Message::PaletteFilterChanged)
+        .on_submit(Message::RunFirstCommand);
+    let entries = entries.into_iter().take(PALETTE_ENTRIES).map(|entry| {
+        let shortcut = entry
+            .shortcut()
+            .map(|shortcut| shortcut.to_string())
+            .unwrap_or_default();
+        button(
+            row!(
+                text(entry.name.clone()).size(14),
+                horizontal_space(Length::Fill),
+                text(shortcut).size(14),
+            )
+            .spacing(10),
+        )
+        .width(Length::Fill)
+        .style(theme::Button::Text)
+        .on_press(Message::RunCommand(entry))
+        .into()
+    });
+    container(column!(input, column(entries.collect()).spacing(2)).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn recovery_prompt<'a>(count: usize) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args("prompt-recovered", [("count", count)])),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-restore"))).on_press(Message::RestoreRecovery),
+            button(text(i18n::tr("button-discard")))
+                .style(theme::Button::Destructive)
+                .on_press(Message::DiscardRecovery),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn confirm_prompt<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-save-changes",
+                [("file", buffer.title())]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-save"))).on_press(Message::Confirm(Choice::Save)),
+            button(text(i18n::tr("button-discard")))
+                .style(theme::Button::Destructive)
+                .on_press(Message::Confirm(Choice::Discard)),
+            button(text(i18n::tr("button-cancel")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::Confirm(Choice::Cancel)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn gutter<'a>(
+    buffer: &Buffer,
+    font: Font,
+    viewport_lines: usize,
+    mode: LineNumbers,
+) -> Element<'a, Message> {
+    let (cursor, _) = buffer.cursor_position();
+    let width = buffer.content.line_count().to_string().len();
+    let numbers = buffer
+        .visible_lines(viewport_lines)
+        .into_iter()
+        .map(|line| {
+            let number = match mode {
+                LineNumbers::Off => String::new(),
+                LineNumbers::Relative if line != cursor => line.abs_diff(cursor).to_string(),
+                _ => (line + 1).to_string(),
+            };
+            let mark = if buffer.bookmarks.contains(&line) {
+                "•"
+            } else {
+                " "
+            };
+            let number = text(format!("{mark}{number:>width$}")).font(font);
+            if line == cursor {
+                number.into()
+            } else {
+                number.style(GUTTER_COLOR).into()
+            }
+        });
+    container(column(numbers.collect()))
+        .padding([5, 10, 5, 0])
+        .into()
+}
+
+fn fold_markers<'a>(
+    buffer: &Buffer,
+    regions: &BTreeMap<usize, usize>,
+    font: Font,
+    viewport_lines: usize,
+) -> Element<'a, Message> {
+    let lines = buffer
+        .visible_lines(viewport_lines)
+        .into_iter()
+        .map(|line| {
+            let symbol = if buffer.folds.contains_key(&line) {
+                "▸"
+            } else if regions.contains_key(&line) {
+                "▾"
+            } else {
+                return text(" ").font(font).into();
+            };
+            button(text(symbol).font(font).style(GUTTER_COLOR))
+                .padding(0)
+                .style(theme::Button::Text)
+                .on_press(Message::ToggleFold(line))
+                .into()
+        });
+    container(column(lines.collect())).padding([5, 2]).into()
+}
+
+fn color_swatches<'a>(
+    buffer: &Buffer,
+    contents: &str,
+    font: Font,
+    viewport_lines: usize,
+    pane: pane_grid::Pane,
+) -> Element<'a, Message> {
+    let lines: Vec<&str> = contents.split('\n').collect();
+    let swatches = buffer
+        .visible_lines(viewport_lines)
+        .into_iter()
+        .map(|line| {
+            let literals = colors::find(
+                lines.get(line).copied().unwrap_or_default(),
+                buffer.language,
+            );
+            let Some(literal) = literals.first() else {
+                return text(" ").font(font).into();
+            };
+            button(text("■").font(font).style(literal.color()))
+                .padding(0)
+                .style(theme::Button::Text)
+                .on_press(Message::PickColor(pane, line))
+                .into()
+        });
+    container(column(swatches.collect())).padding([5, 2]).into()
+}
+
+fn change_markers<'a>(
+    buffer: &Buffer,
+    markers: &[(usize, git::Marker)],
+    font: Font,
+    viewport_lines: usize,
+) -> Element<'a, Message> {
+    let lines = buffer
+        .visible_lines(viewport_lines)
+        .into_iter()
+        .map(|line| {
+            let marker = markers
+                .iter()
+                .find(|(at, _)| *at == line)
+                .map(|(_, marker)| *marker);
+            let (symbol, color) = match marker {
+                Some(git::Marker::Added) => ("▎", ADDED_COLOR),
+                Some(git::Marker::Modified) => ("▎", MODIFIED_COLOR),
+                Some(git::Marker::Deleted) => ("▔", DELETED_COLOR),
+                None => (" ", GUTTER_COLOR),
+            };
+            text(symbol).font(font).style(color).into()
+        });
+    container(column(lines.collect())).padding([5, 2]).into()
+}
+
+fn diff_view<'a>(view: &diff::View, font: Font) -> Element<'a, Message> {
+    let header = row!(
+        text(&view.title).size(14),
+        text(format!("{} changed lines", view.changes()))
+            .size(14)
+            .style(GUTTER_COLOR),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::CloseDiff),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let cell = |side: &Option<diff::Line>, color: Option<Color>| {
+        let Some(line) = side else {
+            return container(text("")).width(Length::FillPortion(1));
+        };
+        let number = text(format!("{:>5} ", line.number + 1))
+            .font(font)
+            .size(14)
+            .style(GUTTER_COLOR);
+        let spans = line.spans().into_iter().map(|(span, changed)| {
+            let span = text(span).font(font).size(14);
+            match color {
+                Some(color) if changed => span.style(color).into(),
+                _ => span.into(),
+            }
+        });
+        container(row(std::iter::once(number.into()).chain(spans).collect()))
+            .width(Length::FillPortion(1))
+    };
+    let rows = view.rows.iter().map(|row| {
+        let (left, right) = match row.kind {
+            diff::Kind::Equal => (None, None),
+            diff::Kind::Removed => (Some(DELETED_COLOR), None),
+            diff::Kind::Added => (None, Some(ADDED_COLOR)),
+            diff::Kind::Changed => (Some(DELETED_COLOR), Some(ADDED_COLOR)),
+        };
+        row!(cell(&row.left, left), cell(&row.right, right))
+            .spacing(10)
+            .into()
+    });
+    column!(
+        header,
+        scrollable(column(rows.collect())).height(Length::Fill)
+    )
+    .spacing(5)
+    .height(Length::Fill)
+    .into()
+}
+
+fn file_tree<'a>(explorer: &explorer::Explorer, font: Font) -> Element<'a, Message> {
+    let root = explorer
+        .root
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| explorer.root.display().to_string());
+    let header = row!(
+        text(root).size(14),
+        horizontal_space(Length::Fill),
+        button(text("…").size(14))
+            .padding([0, 4])
+            .style(theme::Button::Text)
+            .on_press(Message::OpenFolder),
+    )
+    .align_items(iced::Alignment::Center);
+    let entries = explorer.visible().into_iter().map(|(depth, entry)| {
+        let icon = entry.icon(explorer.is_expanded(&entry.path));
+        button(row!(text(icon).font(font).size(14), text(&entry.name).size(14)).spacing(5))
+            .padding([2, 4, 2, 4 + 12 * depth as u16])
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::ExplorerEntryPressed(entry.clone()))
+            .into()
+    });
+    container(column!(header, scrollable(column(entries.collect()))).spacing(5))
+        .width(EXPLORER_WIDTH)
+        .height(Length::Fill)
+        .padding(5)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn symbol_outline<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    let symbols = buffer.frame.symbols.as_deref().unwrap_or_default();
+    let header = text(format!("Outline: {}", buffer.title())).size(14);
+    let body: Element<'a, Message> = if symbols.is_empty() {
+        text("No symbols").size(14).into()
+    } else {
+        let entries = symbols.iter().cloned().map(|symbol| {
+            button(
+                row!(
+                    text(symbol.kind).size(12).style(GUTTER_COLOR),
+                    text(symbol.name).size(14)
+                )
+                .spacing(5)
+                .align_items(iced::Alignment::Center),
+            )
+            .padding([2, 4, 2, 4 + 12 * symbol.depth as u16])
+            .width(Length::Fill)
+            .style(theme::Button::Text)
+            .on_press(Message::GoToLine(symbol.line + 1, 1))
+            .into()
+        });
+        scrollable(column(entries.collect())).into()
+    };
+    container(column!(header, body).spacing(5))
+        .width(outline::WIDTH)
+        .height(Length::Fill)
+        .padding(5)
+        .style(theme::Container::Box)
+        .into()
+}
+
+fn segment<'a>(label: String, on_press: Message) -> Element<'a, Message> {
+    button(text(label).size(14))
+        .padding(0)
+        .style(theme::Button::Text)
+        .on_press(on_press)
+        .into()
+}
+
+fn save_denied_prompt<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-save-denied",
+                [("file", buffer.title())]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-save-as"))).on_press(Message::SaveDeniedAs),
+            button(text(i18n::tr("button-cancel")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::CancelSaveDenied),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn save_conflict_prompt<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-save-conflict",
+                [("file", buffer.title())]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-compare"))).on_press(Message::CompareConflict),
+            button(text(i18n::tr("button-overwrite")))
+                .style(theme::Button::Destructive)
+                .on_press(Message::OverwriteConflict),
+            button(text(i18n::tr("button-save-as"))).on_press(Message::SaveConflictAs),
+            button(text(i18n::tr("button-cancel")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::CancelSaveConflict),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn server_prompt<'a>(language: Language, server: &lsp::ServerConfig) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-start-server",
+                [
+                    ("command", server.command_line()),
+                    ("language", language.to_string())
+                ]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-start"))).on_press(Message::StartServer(language)),
+            button(text(i18n::tr("button-always")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::TrustServer(language)),
+            button(text(i18n::tr("button-not-now")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::DeclineServer(language)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn formatter_prompt<'a>(
+    pending: formatter::Pending,
+    buffer: &Buffer,
+    formatter: &formatter::FormatterConfig,
+) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-run-formatter",
+                [
+                    ("command", formatter.command_line()),
+                    ("file", buffer.title())
+                ]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-run"))).on_press(Message::RunFormatter(pending)),
+            button(text(i18n::tr("button-always")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::TrustFormatter(pending)),
+            button(text(i18n::tr("button-not-now")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::DeclineFormatter(pending)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn terminal_prompt<'a>(pending: terminal::Pending, commands: &[String]) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args(
+                "prompt-run-command",
+                [("command", commands.join("`, `"))]
+            )),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-run"))).on_press(Message::RunTerminal(pending)),
+            button(text(i18n::tr("button-always")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::TrustTerminal(pending)),
+            button(text(i18n::tr("button-not-now")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::DeclineTerminal(pending)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn link_prompt<'a>(link: &str) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args("prompt-open-link", [("link", link)])),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-open"))).on_press(Message::FollowLink(link.to_owned())),
+            button(text(i18n::tr("button-not-now")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::DeclineLink),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-fn main() -> iced::Result {
-    Editor::run(Settings {
-        default_font:Font::with_name("JetBrainsMono Nerd Font Propo"),
-        fonts: vec![
-            include_bytes!("../fonts/editor_icons.ttf")
-                .as_slice()
-                .into(),
-        ],
-        ..Settings::default()
-    })
+fn remote_prompt<'a>(prompt: &remote::Prompt) -> Element<'a, Message> {
+    let mut fields = row!(
+        text("Open"),
+        text_input("sftp://user@host/path or https://...", &prompt.url)
+            .id(text_input::Id::new(REMOTE_INPUT))
+            .on_input(Message::RemoteUrlChanged)
+            .on_submit(Message::ConnectRemote)
+            .width(350),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    if prompt.needs_password {
+        fields = fields.push(
+            text_input("Password", &prompt.password)
+                .id(text_input::Id::new(PASSWORD_INPUT))
+                .password()
+                .on_input(Message::RemotePasswordChanged)
+                .on_submit(Message::ConnectRemote)
+                .width(200),
+        );
+    }
+    let status = match (&prompt.error, prompt.is_connecting) {
+        (Some(error), _) => text(error).size(14).style(Color::from_rgb(0.9, 0.3, 0.3)),
+        (None, true) => text("Connecting...").size(14).style(GUTTER_COLOR),
+        (None, false) => text(""),
+    };
+    container(
+        fields
+            .push(status)
+            .push(horizontal_space(Length::Fill))
+            .push(
+                button("Connect")
+                    .on_press_maybe((!prompt.is_connecting).then_some(Message::ConnectRemote)),
+            )
+            .push(
+                button("Cancel")
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::Dismiss),
+            ),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
 }
 
-struct Editor {
-    path: Option<PathBuf>,
-    content: text_editor::Content,
-    theme: highlighter::Theme,
-    error: Option<Error>,
-    is_dirty: bool,
+fn passphrase_prompt<'a>(prompt: &crypt::Prompt) -> Element<'a, Message> {
+    let label = match &prompt.target {
+        crypt::Target::Open(path) => format!(
+            "Passphrase for {}",
+            path.file_name().unwrap_or_default().to_string_lossy()
+        ),
+        crypt::Target::Encrypt(_) => String::from("New passphrase"),
+    };
+    let mut fields = row!(
+        text(label),
+        text_input("Passphrase", &prompt.passphrase)
+            .id(text_input::Id::new(PASSPHRASE_INPUT))
+            .password()
+            .on_input(Message::PassphraseChanged)
+            .on_submit(Message::SubmitPassphrase)
+            .width(200),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    if prompt.needs_confirmation() {
+        fields = fields.push(
+            text_input("Repeat passphrase", &prompt.confirmation)
+                .password()
+                .on_input(Message::PassphraseConfirmationChanged)
+                .on_submit(Message::SubmitPassphrase)
+                .width(200),
+        );
+    }
+    let status = match (&prompt.error, prompt.is_working) {
+        (Some(error), _) => text(error).size(14).style(Color::from_rgb(0.9, 0.3, 0.3)),
+        (None, true) => text("Decrypting...").size(14).style(GUTTER_COLOR),
+        (None, false) => text(""),
+    };
+    container(
+        fields
+            .push(status)
+            .push(horizontal_space(Length::Fill))
+            .push(
+                button("OK")
+                    .on_press_maybe((!prompt.is_working).then_some(Message::SubmitPassphrase)),
+            )
+            .push(
+                button("Cancel")
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::Dismiss),
+            ),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
 }
-#[derive(Debug, Clone)]
-enum Message {
-    New,
-    Open,
-    Save,
-    FileSaved(Result<PathBuf, Error>),
-    Edit(text_editor::Action),
-    FileOpened(Result<(PathBuf, Arc<String>), Error>),
-    ThemeSelected(highlighter::Theme),
+
+fn macro_prompt<'a>(prompt: &macros::Prompt, macros: &macros::Macros) -> Element<'a, Message> {
+    let steps = macros.get(&prompt.selected).map_or(0, <[_]>::len);
+    container(
+        row!(
+            text("Macro"),
+            pick_list(
+                macros.names(),
+                Some(prompt.selected.clone()),
+                Message::MacroSelected
+            ),
+            text(format!("{steps} steps")).style(GUTTER_COLOR),
+            text_input("times", &prompt.count)
+                .on_input(Message::MacroCountChanged)
+                .on_submit(Message::PlayMacro)
+                .width(80),
+            button("Play").on_press_maybe((steps > 0).then_some(Message::PlayMacro)),
+            horizontal_space(Length::Fill),
+            text_input("Save last recording as...", &prompt.name)
+                .on_input(Message::MacroNameChanged)
+                .on_submit(Message::SaveMacro)
+                .width(220),
+            button("Save")
+                .style(theme::Button::Secondary)
+                .on_press_maybe(
+                    (!macros.last.is_empty() && !prompt.name.trim().is_empty())
+                        .then_some(Message::SaveMacro)
+                ),
+            button("Close")
+                .style(theme::Button::Secondary)
+                .on_press(Message::CloseMacros),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
 }
 
-const NEW_TIP: &str = "new file";
-const OPEN_TIP: &str = "open file";
-const SAVE_TIP: &str = "save file";
+fn spelling_prompt<'a>(prompt: &spell::Prompt) -> Element<'a, Message> {
+    let label = if prompt.suggestions.is_empty() {
+        format!("No suggestions for `{}`", prompt.word)
+    } else {
+        format!("Replace `{}` with", prompt.word)
+    };
+    let suggestions = prompt.suggestions.iter().map(|suggestion| {
+        button(text(suggestion))
+            .style(theme::Button::Secondary)
+            .on_press(Message::ReplaceWord(suggestion.clone()))
+            .into()
+    });
+    container(
+        row(std::iter::once(text(label).into())
+            .chain(suggestions)
+            .chain([
+                horizontal_space(Length::Fill).into(),
+                button("Add to dictionary")
+                    .on_press(Message::AddToDictionary)
+                    .into(),
+                button("Close")
+                    .style(theme::Button::Secondary)
+                    .on_press(Message::CloseSpelling)
+                    .into(),
+            ])
+            .collect())
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-impl Application for Editor {
-    type Message = Message;
-    type Executor = executor::Default;
-    type Theme = Theme;
-    type Flags = ();
+fn unsaved_panel<'a>(changes: &[unsaved::Change], font: Font) -> Element<'a, Message> {
+    const SHOWN_LINES: usize = 12;
 
-    fn new(_flags: Self::Flags) -> (Self, Command<Message>) {
-        let default_file = PathBuf::from(format!("{}/src/main.rs", env!("CARGO_MANIFEST_DIR")));
-        (
-            Self {
-                content: text_editor::Content::new(),
-                error: None,
-                path: None,
-                theme: highlighter::Theme::SolarizedDark,
-                is_dirty: false,
-            },
-            Command::perform(load_file(default_file), Message::FileOpened),
+    let header = row!(
+        text(i18n::tr_args("unsaved-changes", [("count", changes.len())])).size(14),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::CloseUnsaved),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let entries = changes.iter().enumerate().map(|(index, change)| {
+        let line = change.hunk.new.start + 1;
+        let title = row!(
+            text(format!("Line {line}")).size(14),
+            horizontal_space(Length::Fill),
+            button(text("Go to").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::GoToLine(line, 1)),
+            button(text("Revert").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::RevertUnsaved(index)),
         )
-    }
+        .spacing(10)
+        .align_items(iced::Alignment::Center);
+        let removed = change.removed.iter().take(SHOWN_LINES).map(|line| {
+            text(format!("- {line}"))
+                .font(font)
+                .size(14)
+                .style(DELETED_COLOR)
+                .into()
+        });
+        let added = change.added.iter().take(SHOWN_LINES).map(|line| {
+            text(format!("+ {line}"))
+                .font(font)
+                .size(14)
+                .style(ADDED_COLOR)
+                .into()
+        });
+        column(
+            std::iter::once(title.into())
+                .chain(removed)
+                .chain(added)
+                .collect(),
+        )
+        .spacing(2)
+        .into()
+    });
+    container(
+        column!(
+            header,
+            scrollable(column(entries.collect()).spacing(10)).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-    fn title(&self) -> String {
-        String::from("A cool editor!")
-    }
+fn versions_panel<'a>(versions: &[versions::Version]) -> Element<'a, Message> {
+    let header = row!(
+        text(format!("Local history: {} saved versions", versions.len())).size(14),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::CloseLocalHistory),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let entries = versions.iter().enumerate().map(|(index, version)| {
+        row!(
+            text(format!("{} ({})", version.age(), version.size())).size(14),
+            horizontal_space(Length::Fill),
+            button(text("View").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::OpenVersion(index, versions::Action::View)),
+            button(text("Compare").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::OpenVersion(index, versions::Action::Compare)),
+            button(text("Restore").size(14))
+                .style(theme::Button::Secondary)
+                .on_press(Message::OpenVersion(index, versions::Action::Restore)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center)
+        .into()
+    });
+    container(
+        column!(
+            header,
+            scrollable(column(entries.collect()).spacing(5)).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-    fn update(&mut self, message: Message) -> Command<Message> {
-        match message {
-            Message::New => {
-                self.is_dirty = true;
-                self.path = None;
-                self.content = text_editor::Content::new();
-                Command::none()
-            }
-            Message::Edit(action) => {
-                self.is_dirty = self.is_dirty || action.is_edit();
-                self.content.edit(action);
-                self.error = None;
-                Command::none()
-            }
-            Message::Open => Command::perform(pick_file(), Message::FileOpened),
-            Message::FileOpened(Ok((path, content))) => {
-                self.is_dirty = false;
-                self.path = Some(path);
-                self.content = text_editor::Content::with(&content);
-                Command::none()
-            }
-            Message::FileOpened(Err(error)) => {
-                self.error = Some(error);
-                Command::none()
-            }
-            Message::Save => {
-                let text = self.content.text();
-                Command::perform(save_file(self.path.clone(), text), Message::FileSaved)
-            }
-            Message::FileSaved(Ok(path)) => {
-                self.is_dirty = false;
-                self.path = Some(path);
-                Command::none()
-            }
-            Message::FileSaved(Err(error)) => {
-                self.error = Some(error);
-                Command::none()
-            }
-            Message::ThemeSelected(theme) => {
-                self.theme = theme;
+fn bookmark_panel<'a>(buffers: &[Buffer]) -> Element<'a, Message> {
+    let header = row!(
+        text("Bookmarks").size(14),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleBookmarkPanel),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let entries = buffers
+        .iter()
+        .filter_map(|buffer| Some((buffer, buffer.path.as_ref()?)))
+        .flat_map(|(buffer, path)| {
+            let lines: Vec<&str> = buffer.frame.text.split('\n').collect();
+            buffer
+                .bookmarks
+                .iter()
+                .map(|&line| grep::Hit {
+                    path: path.clone(),
+                    line: line + 1,
+                    text: lines.get(line).map_or("", |line| line.trim()).to_owned(),
+                })
+                .collect::<Vec<_>>()
+        })
+        .take(GREP_SHOWN)
+        .map(|hit| {
+            let name = hit
+                .path
+                .file_name()
+                .map(|name| name.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            button(text(format!("{name}:{}: {}", hit.line, hit.text)).size(14))
+                .padding([2, 4])
+                .width(Length::Fill)
+                .style(theme::Button::Text)
+                .on_press(Message::OpenHit(hit))
+                .into()
+        });
+    container(
+        column!(
+            header,
+            scrollable(column(entries.collect())).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-                Command::none()
-            }
-        }
+fn toast_color(level: toast::Level) -> Color {
+    match level {
+        toast::Level::Info => GUTTER_COLOR,
+        toast::Level::Warning => Color::from_rgb(0.9, 0.6, 0.1),
+        toast::Level::Error => Color::from_rgb(0.9, 0.3, 0.3),
     }
-    fn subscription(&self) -> Subscription<Message> {
-        keyboard::on_key_press(|key_code, modifiers| match key_code {
-            keyboard::KeyCode::S if modifiers.command() => Some(Message::Save),
-            _ => None,
+}
+
+fn toasts<'a>(toasts: &toast::Toasts) -> Option<Element<'a, Message>> {
+    let toasts: Vec<Element<'a, Message>> = toasts
+        .active()
+        .map(|toast| {
+            button(
+                row!(
+                    text(toast.level.to_string())
+                        .size(14)
+                        .style(toast_color(toast.level)),
+                    text(&toast.message).size(14),
+                )
+                .spacing(10),
+            )
+            .padding([4, 8])
+            .style(theme::Button::Secondary)
+            .on_press(Message::DismissToast(toast.id))
+            .into()
         })
+        .collect();
+    if toasts.is_empty() {
+        return None;
     }
-    fn view(&self) -> Element<'_, Message> {
-        let controls = row!(
-            action(new_icon(), NEW_TIP, Some(Message::New)),
-            action(open_icon(), OPEN_TIP, Some(Message::Open)),
-            action(
-                save_icon(),
-                SAVE_TIP,
-                self.is_dirty.then_some(Message::Save)
-            ),
+    Some(
+        row!(
             horizontal_space(Length::Fill),
-            pick_list(
-                highlighter::Theme::ALL,
-                Some(self.theme),
-                Message::ThemeSelected
-            ),
+            column(toasts).spacing(5).align_items(iced::Alignment::End)
         )
-        .spacing(10);
-        let file_ext = self
-            .path
-            .as_ref()
-            .and_then(|path| path.extension()?.to_str())
-            .unwrap_or("rs")
-            .to_string();
-        let input = text_editor(&self.content)
-            .on_edit(Message::Edit)
-            .highlight::<Highlighter>(
-                highlighter::Settings {
-                    theme: self.theme,
-                    extension: file_ext,
-                },
-                |highlighter, _theme| highlighter.to_format(),
-            );
+        .into(),
+    )
+}
 
-        let status_bar = {
-            let status = if let Some(Error::IOFailed(error)) = self.error.as_ref() {
-                text(error.to_string())
-            } else {
-                match self.path.as_deref().and_then(Path::to_str) {
-                    Some(path) => text(path).size(14),
-                    None => text("New file"),
-                }
-            };
-            let position = {
-                let (line, column) = self.content.cursor_position();
-                text(format!("{}:{}", line + 1, column + 1))
-            };
+fn notification_panel<'a>(toasts: &toast::Toasts) -> Element<'a, Message> {
+    let header = row!(
+        text(format!("Notifications: {}", toasts.history.len())).size(14),
+        horizontal_space(Length::Fill),
+        button(text("Clear").size(14))
+            .style(theme::Button::Text)
+            .on_press(Message::ClearNotifications),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleNotifications),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let entries = toasts.history.iter().map(|toast| {
+        row!(
+            text(toast.age()).size(14).style(GUTTER_COLOR).width(80),
+            text(toast.level.to_string())
+                .size(14)
+                .style(toast_color(toast.level))
+                .width(70),
+            text(&toast.message).size(14),
+        )
+        .spacing(10)
+        .into()
+    });
+    container(
+        column!(
+            header,
+            scrollable(column(entries.collect()).spacing(2)).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
 
-            row!(status, horizontal_space(Length::Fill), position)
-        };
-        container(column!(controls, input, status_bar))
+fn todos_panel<'a>(panel: &todos::Panel) -> Element<'a, Message> {
+    let status = match (&panel.workspace, panel.scanning) {
+        (Some(hits), Some(_)) => format!("{} in workspace, scanning...", hits.len()),
+        (Some(hits), None) => format!("{} in workspace", hits.len()),
+        (None, _) => String::new(),
+    };
+    let header = row!(
+        text(format!("TODOs: {} in buffer", panel.annotations.len())).size(14),
+        checkbox(
+            "Workspace",
+            panel.workspace.is_some(),
+            Message::ToggleWorkspaceTodos
+        ),
+        text(status).size(14).style(GUTTER_COLOR),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleTodos),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let entry = |label: String, content: String, message: Message| -> Element<'a, Message> {
+        button(
+            row!(
+                text(label)
+                    .size(14)
+                    .style(GUTTER_COLOR)
+                    .width(Length::Shrink),
+                text(content).size(14),
+            )
+            .spacing(10),
+        )
+        .style(theme::Button::Text)
+        .padding([2, 5])
+        .width(Length::Fill)
+        .on_press(message)
+        .into()
+    };
+    let annotations = panel.annotations.iter().map(|annotation| {
+        entry(
+            format!("{:>5} {}", annotation.line, annotation.tag),
+            annotation.text.clone(),
+            Message::GoToLine(annotation.line, 1),
+        )
+    });
+    let hits = panel.workspace.iter().flatten().map(|hit| {
+        let path = hit.path.strip_prefix(&panel.root).unwrap_or(&hit.path);
+        entry(
+            format!("{}:{}", path.display(), hit.line),
+            hit.text.clone(),
+            Message::OpenHit(hit.clone()),
+        )
+    });
+    container(
+        column!(
+            header,
+            scrollable(column(annotations.chain(hits).collect()).spacing(2)).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn statistics_panel<'a>(panel: &stats::Panel) -> Element<'a, Message> {
+    let header = row!(
+        text("Document statistics").size(14),
+        text(if panel.pending.is_some() {
+            "updating..."
+        } else {
+            ""
+        })
+        .size(14)
+        .style(GUTTER_COLOR),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleStatistics),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let Some(report) = &panel.report else {
+        return container(header)
             .padding(10)
-            .into()
-    }
+            .style(theme::Container::Box)
+            .into();
+    };
+    let rows: [(&str, fn(&stats::Counts) -> String); 6] = [
+        ("Characters", |counts| counts.characters.to_string()),
+        ("Words", |counts| counts.words.to_string()),
+        ("Unique words", |counts| counts.unique_words.to_string()),
+        ("Lines", |counts| counts.lines.to_string()),
+        ("Bytes", |counts| counts.bytes.to_string()),
+        ("Longest line", |counts| {
+            let (length, line) = counts.longest_line;
+            format!("{length} (line {line})")
+        }),
+    ];
+    let heading = row!(
+        text("").width(120),
+        text("Document").size(14).style(GUTTER_COLOR).width(150),
+        text(if report.selection.is_some() {
+            "Selection"
+        } else {
+            ""
+        })
+        .size(14)
+        .style(GUTTER_COLOR),
+    )
+    .spacing(10);
+    let entries = rows.into_iter().map(|(label, value)| {
+        row!(
+            text(label).size(14).width(120),
+            text(value(&report.document)).size(14).width(150),
+            text(report.selection.as_ref().map(value).unwrap_or_default()).size(14),
+        )
+        .spacing(10)
+        .into()
+    });
+    container(column!(header, heading, column(entries.collect()).spacing(2)).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
 
-    fn theme(&self) -> Theme {
-        if self.theme.is_dark() {
-            self::Theme::Dark
+fn problems_panel<'a>(servers: &lsp::Servers) -> Element<'a, Message> {
+    let (errors, warnings) = servers.counts();
+    let header = row!(
+        text(format!("Problems: {errors} errors, {warnings} warnings")).size(14),
+        text(servers.error.clone().unwrap_or_default())
+            .size(14)
+            .style(GUTTER_COLOR),
+        horizontal_space(Length::Fill),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleProblems),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let root = std::env::current_dir()
+        .ok()
+        .map(|root| lsp::canonical(&root));
+    let entries = servers
+        .diagnostics
+        .iter()
+        .flat_map(|(path, diagnostics)| {
+            diagnostics.iter().map(move |diagnostic| (path, diagnostic))
+        })
+        .take(GREP_SHOWN)
+        .map(|(path, diagnostic)| {
+            let shown = root
+                .as_deref()
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path)
+                .display();
+            let line = diagnostic.start.0 + 1;
+            let message = diagnostic.message.lines().next().unwrap_or_default();
+            button(text(format!("{shown}:{line}: {message}")).size(14))
+                .padding([2, 4])
+                .width(Length::Fill)
+                .style(theme::Button::Text)
+                .on_press(Message::OpenHit(grep::Hit {
+                    path: path.clone(),
+                    line,
+                    text: message.to_owned(),
+                }))
+                .into()
+        });
+    container(
+        column!(
+            header,
+            scrollable(column(entries.collect())).height(GREP_HEIGHT)
+        )
+        .spacing(5),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn conflict_prompt<'a>(buffer: &Buffer) -> Element<'a, Message> {
+    container(
+        row!(
+            text(i18n::tr_args("prompt-reload", [("file", buffer.title())])),
+            horizontal_space(Length::Fill),
+            button(text(i18n::tr("button-reload")))
+                .style(theme::Button::Destructive)
+                .on_press(Message::ReloadFromDisk(buffer.id)),
+            button(text(i18n::tr("button-keep-mine")))
+                .style(theme::Button::Secondary)
+                .on_press(Message::KeepBuffer(buffer.id)),
+        )
+        .spacing(10)
+        .align_items(iced::Alignment::Center),
+    )
+    .padding(10)
+    .style(theme::Container::Box)
+    .into()
+}
+
+fn tab<'a>(buffer: &Buffer, index: usize, is_active: bool) -> Element<'a, Message> {
+    let title = match (buffer.needs_save(), buffer.has_conflict) {
+        _ if buffer.is_deleted => format!("{} (deleted)", buffer.title()),
+        (_, true) => format!("{} !", buffer.title()),
+        (true, false) => format!("{} *", buffer.title()),
+        (false, false) => buffer.title(),
+    };
+    let select = button(text(title).size(14))
+        .padding([4, 8])
+        .style(if is_active {
+            theme::Button::Primary
         } else {
-            self::Theme::Light
-        }
-    }
+            theme::Button::Secondary
+        })
+        .on_press(Message::SelectTab(index));
+    let close = button(text("×").size(14))
+        .padding([4, 6])
+        .style(theme::Button::Text)
+        .on_press(Message::CloseTab(index));
+    row!(select, close).into()
 }
 
 fn action<'a>(
     content: Element<'a, Message>,
-    label: &str,
+    tip: &str,
     on_press: Option<Message>,
 ) -> Element<'a, Message> {
     let is_disabled = on_press.is_none();
+    let label = i18n::tr(tip);
+    let label = match on_press.as_ref().and_then(keymap::shortcut) {
+        Some(shortcut) => format!("{label} ({shortcut})"),
+        None => label,
+    };
     let btn = button(container(content).width(30).center_x())
         .on_press_maybe(on_press)
         .padding([5, 10])
@@ -199,7 +7476,7 @@ fn action<'a>(
         } else {
             theme::Button::Primary
         });
-    tooltip(btn, label, tooltip::Position::FollowCursor)
+    tooltip(btn, text(label), tooltip::Position::FollowCursor)
         .style(theme::Container::Box)
         .into()
 }
@@ -222,7 +7499,163 @@ fn icon<'a, Message>(code_point: char) -> Element<'a, Message> {
     text(code_point).font(ICON_FONT).into()
 }
 
-async fn pick_file() -> Result<(PathBuf, Arc<String>), Error> {
+fn elide_middle(text: &str, max_chars: usize) -> String {
+    let count = text.chars().count();
+    if count <= max_chars {
+        return text.to_string();
+    }
+    let keep = max_chars.saturating_sub(1);
+    let head: String = text.chars().take(keep / 2).collect();
+    let tail: String = text.chars().skip(count - (keep - keep / 2)).collect();
+    format!("{head}…{tail}")
+}
+
+fn viewport_lines(window_height: u32) -> usize {
+    ((window_height as f32 - CHROME_HEIGHT) / LINE_HEIGHT).max(1.0) as usize
+}
+
+fn center_cursor(content: &mut text_editor::Content, viewport_lines: usize) -> usize {
+    use text_editor::Action;
+
+    let (line, _) = content.cursor_position();
+    let top = line.saturating_sub(viewport_lines / 2);
+    content.edit(Action::Scroll {
+        lines: -(content.line_count() as i32),
+    });
+    content.edit(Action::Scroll { lines: top as i32 });
+    top
+}
+
+fn offset_to_position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line_start = before.rfind('\n').map_or(0, |index| index + 1);
+    (
+        before.matches('\n').count(),
+        before[line_start..].chars().count(),
+    )
+}
+
+fn position_to_offset(text: &str, line: usize, column: usize) -> usize {
+    brackets::cursor_offset(text, (line, column))
+}
+
+fn select_range(content: &mut text_editor::Content, text: &str, range: &Range<usize>) {
+    use text_editor::{Action, Motion};
+
+    let (line, column) = offset_to_position(text, range.start);
+    move_cursor_to(content, line, column);
+    for _ in text[range.clone()].chars() {
+        content.edit(Action::Select(Motion::Right));
+    }
+}
+
+fn position_at(point: iced::Point, top_line: usize) -> (usize, usize) {
+    let line = ((point.y - EDITOR_PADDING) / LINE_HEIGHT).max(0.0) as usize;
+    let column = ((point.x - EDITOR_PADDING) / CHAR_WIDTH).max(0.0).round() as usize;
+    (top_line + line, column)
+}
+
+fn move_cursor_to(content: &mut text_editor::Content, line: usize, column: usize) {
+    use text_editor::{Action, Motion};
+
+    content.edit(Action::Move(Motion::DocumentStart));
+    let mut position = content.cursor_position();
+    while position.0 < line {
+        content.edit(Action::Move(Motion::Down));
+        let moved = content.cursor_position();
+        if moved == position {
+            break;
+        }
+        position = moved;
+    }
+    content.edit(Action::Move(Motion::Home));
+    for _ in 0..column {
+        content.edit(Action::Move(Motion::Right));
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Loaded {
+    path: PathBuf,
+    text: Arc<Zeroizing<String>>,
+    encoding: TextEncoding,
+    has_bom: bool,
+    line_ending: LineEnding,
+    is_writable: bool,
+    is_binary: bool,
+    editorconfig: editorconfig::Properties,
+    stamp: Option<saves::Stamp>,
+    history: Option<undo::Saved>,
+    view: Option<view_state::ViewState>,
+    passphrase: Option<crypt::Passphrase>,
+}
+
+fn decode_file(path: PathBuf, bytes: &[u8], encoding: Option<TextEncoding>) -> Loaded {
+    let editorconfig = editorconfig::resolve(&path);
+    let is_binary = encoding.is_none() && hex::is_binary(bytes);
+    let (text, encoding) = if is_binary {
+        (hex::format(bytes), TextEncoding::default())
+    } else {
+        encoding::decode(bytes, encoding.or(editorconfig.charset))
+    };
+    let text = Zeroizing::new(text);
+    Loaded {
+        has_bom: !is_binary && encoding::has_bom(bytes),
+        is_binary,
+        is_writable: std::fs::OpenOptions::new().write(true).open(&path).is_ok(),
+        path,
+        line_ending: editorconfig
+            .end_of_line
+            .unwrap_or_else(|| LineEnding::detect(&text)),
+        editorconfig,
+        text: Arc::new(Zeroizing::new(LineEnding::normalize(&text))),
+        encoding,
+        stamp: None,
+        history: None,
+        view: None,
+        passphrase: None,
+    }
+}
+
+async fn restore_session() -> (session::Session, Vec<Result<Loaded, Error>>) {
+    let session = session::load().await;
+    let mut files = Vec::new();
+    for file in &session.files {
+        files.push(load_file(file.path.clone()).await);
+    }
+    (session, files)
+}
+
+async fn shutdown(
+    session: Option<session::Session>,
+    scratch: Option<scratch::Scratch>,
+    histories: Vec<undo::Saved>,
+    views: Vec<view_state::ViewState>,
+) {
+    if let Some(session) = session {
+        let _ = session::save(session).await;
+    }
+    if let Some(scratch) = scratch {
+        let _ = scratch::save(scratch).await;
+    }
+    undo::save(histories).await;
+    view_state::save(views).await;
+    let _ = recovery::clear().await;
+}
+
+async fn pick_folder() -> Option<PathBuf> {
+    rfd::AsyncFileDialog::new()
+        .set_title("Choose a folder...")
+        .pick_folder()
+        .await
+        .map(|handle| handle.path().to_path_buf())
+}
+
+async fn pick_file() -> Result<Loaded, Error> {
     let handle = rfd::AsyncFileDialog::new()
         .set_title("Choose a text file...")
         .pick_file()
@@ -231,18 +7664,102 @@ async fn pick_file() -> Result<(PathBuf, Arc<String>), Error> {
     load_file(handle.path().to_path_buf()).await
 }
 
-async fn load_file(path: PathBuf) -> Result<(PathBuf, Arc<String>), Error> {
-    let content = tokio::fs::read_to_string(&path)
+async fn load_encrypted(path: PathBuf, passphrase: crypt::Passphrase) -> Result<Loaded, Error> {
+    let bytes = tokio::fs::read(&path)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    let modified = tokio::fs::metadata(&path)
+        .await
+        .ok()
+        .and_then(|metadata| metadata.modified().ok());
+    let stamp = saves::Stamp::new(&bytes, modified);
+    let secret = passphrase.clone();
+    let decrypted = tokio::task::spawn_blocking(move || crypt::decrypt(&bytes, &secret))
+        .await
+        .map_err(|error| Error::Crypt(error.to_string()))?
+        .map_err(Error::Crypt)?;
+    let mut loaded = decode_file(path, &decrypted, None);
+    loaded.stamp = Some(stamp);
+    loaded.passphrase = Some(passphrase);
+    Ok(loaded)
+}
+
+async fn load_file(path: PathBuf) -> Result<Loaded, Error> {
+    load_file_as(path, None).await
+}
+
+async fn load_file_as(path: PathBuf, encoding: Option<TextEncoding>) -> Result<Loaded, Error> {
+    let metadata = tokio::fs::metadata(&path)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    if crypt::is_encrypted(&path) {
+        return Err(Error::Encrypted(path));
+    }
+    let size = metadata.len();
+    let codec = compression::Codec::from_path(&path);
+    if size > large::STREAM_THRESHOLD && codec.is_none() {
+        return Err(Error::LargeFile(path, size));
+    }
+    let mut bytes = tokio::fs::read(&path)
         .await
-        .map(Arc::new)
         .map_err(|error| Error::IOFailed(error.kind()))?;
-    Ok((path, content))
+    let stamp = saves::Stamp::new(&bytes, metadata.modified().ok());
+    if let Some(codec) = codec {
+        bytes = codec
+            .decompress(&bytes)
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    let mut loaded = decode_file(path, &bytes, encoding);
+    loaded.history = undo::load(&loaded.path, stamp.hash).await;
+    loaded.view = view_state::load(&loaded.path).await;
+    loaded.stamp = Some(stamp);
+    Ok(loaded)
+}
+
+fn open_argument(file: &cli::FileArg) -> Command<Message> {
+    let argument = file.path.to_str().unwrap_or_default();
+    if web::is_url(argument) {
+        return fetch_url(argument.to_owned());
+    }
+    match remote::Location::parse(argument) {
+        Some(location) => {
+            Command::perform(remote::download(location.clone(), None), move |result| {
+                Message::RemoteOpened(location, None, result)
+            })
+        }
+        None => Command::perform(load_file(file.path.clone()), Message::FileOpened),
+    }
+}
+
+fn is_same_file(a: &std::path::Path, b: &std::path::Path) -> bool {
+    a == b
+        || matches!(
+            (std::fs::canonicalize(a), std::fs::canonicalize(b)),
+            (Ok(a), Ok(b)) if a == b
+        )
 }
 
-async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error> {
+fn fetch_url(url: String) -> Command<Message> {
+    Command::perform(web::fetch(url.clone()), move |result| {
+        Message::UrlOpened(url, result)
+    })
+}
+
+async fn save_file(
+    path: Option<PathBuf>,
+    bytes: Vec<u8>,
+    backup: bool,
+    expected: Option<saves::Stamp>,
+    passphrase: Option<crypt::Passphrase>,
+) -> Result<(PathBuf, saves::Stamp), Error> {
     let path = if let Some(path) = path
         && path.is_file()
     {
+        if let Some(expected) = expected
+            && !saves::is_unchanged(&path, expected).await
+        {
+            return Err(Error::SaveConflict);
+        }
         path
     } else {
         rfd::AsyncFileDialog::new()
@@ -252,14 +7769,117 @@ async fn save_file(path: Option<PathBuf>, text: String) -> Result<PathBuf, Error
             .ok_or(Error::DialogClosed)
             .map(|handle| handle.path().to_path_buf())?
     };
-    tokio::fs::write(&path, &text)
+    let bytes = match (passphrase, compression::Codec::from_path(&path)) {
+        (Some(passphrase), _) => tokio::task::spawn_blocking(move || {
+            let mut bytes = bytes;
+            let encrypted = crypt::encrypt(&bytes, &passphrase);
+            bytes.zeroize();
+            encrypted
+        })
+        .await
+        .map_err(|error| Error::Crypt(error.to_string()))?
+        .map_err(Error::Crypt)?,
+        (None, _) if crypt::is_encrypted(&path) => return Err(Error::NeedsPassphrase),
+        (None, Some(codec)) => codec
+            .compress(&bytes)
+            .map_err(|error| Error::IOFailed(error.kind()))?,
+        (None, None) => bytes,
+    };
+    write_atomically(&path, &bytes, backup)
         .await
         .map_err(|error| Error::IOFailed(error.kind()))?;
-    Ok(path)
+    let modified = tokio::fs::metadata(&path)
+        .await
+        .ok()
+        .and_then(|metadata| metadata.modified().ok());
+    Ok((path, saves::Stamp::new(&bytes, modified)))
+}
+
+async fn write_atomically(path: &std::path::Path, bytes: &[u8], backup: bool) -> io::Result<()> {
+    use tokio::io::AsyncWriteExt;
+
+    let is_link = tokio::fs::symlink_metadata(path)
+        .await
+        .is_ok_and(|metadata| metadata.is_symlink());
+    let target = if is_link {
+        tokio::fs::canonicalize(path).await?
+    } else {
+        path.to_path_buf()
+    };
+    let path = target.as_path();
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    let temporary = path.with_file_name(format!(".{name}.{}.tmp", std::process::id()));
+    let existing = tokio::fs::metadata(path).await.ok();
+    if backup && existing.is_some() {
+        tokio::fs::copy(path, path.with_file_name(format!("{name}~"))).await?;
+    }
+    let written = async {
+        let mut file = tokio::fs::File::create(&temporary).await?;
+        file.write_all(bytes).await?;
+        file.sync_all().await?;
+        if let Some(metadata) = &existing {
+            tokio::fs::set_permissions(&temporary, metadata.permissions()).await?;
+        }
+        tokio::fs::rename(&temporary, path).await
+    }
+    .await;
+    if written.is_err() {
+        let _ = tokio::fs::remove_file(&temporary).await;
+        return written;
+    }
+    #[cfg(unix)]
+    if let Some(parent) = path.parent()
+        && let Ok(dir) = tokio::fs::File::open(parent).await
+    {
+        let _ = dir.sync_all().await;
+    }
+    Ok(())
 }
 
 #[derive(Debug, Clone)]
 enum Error {
     DialogClosed,
     IOFailed(io::ErrorKind),
+    InvalidConfig(String),
+    InvalidSnippets(String),
+    Plugin(String),
+    Remote(String),
+    Fetch(String),
+    SaveConflict,
+    ReplaceFailed(String),
+    InvalidHex(usize),
+    LargeFile(PathBuf, u64),
+    Encrypted(PathBuf),
+    NeedsPassphrase,
+    Crypt(String),
+    Unmappable(TextEncoding),
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::DialogClosed => f.write_str("dialog closed"),
+            Error::IOFailed(error) => write!(f, "{error}"),
+            Error::InvalidConfig(error) => write!(f, "config.toml: {error}"),
+            Error::InvalidSnippets(error) => write!(f, "snippets.toml: {error}"),
+            Error::Plugin(error) => write!(f, "plugin: {error}"),
+            Error::Remote(error) => write!(f, "remote: {error}"),
+            Error::Fetch(error) => write!(f, "fetch: {error}"),
+            Error::SaveConflict => write!(f, "the file changed on disk since it was opened"),
+            Error::ReplaceFailed(error) => write!(f, "replace in files: {error}"),
+            Error::InvalidHex(line) => write!(f, "invalid hex bytes on line {line}"),
+            Error::LargeFile(path, size) => {
+                write!(f, "{} is too large ({size} bytes)", path.display())
+            }
+            Error::Encrypted(path) => write!(f, "{} is encrypted", path.display()),
+            Error::NeedsPassphrase => {
+                write!(f, "use \"Encrypt buffer\" before saving to an .age file")
+            }
+            Error::Crypt(error) => write!(f, "encryption: {error}"),
+            Error::Unmappable(encoding) => write!(
+                f,
+                "{encoding} cannot represent every character; choose another encoding"
+            ),
+        }
+    }
 }
@@ -0,0 +1,190 @@
+use iced::widget::{button, column, container, horizontal_rule, row, scrollable, text};
+use iced::{Element, Font, Length, theme};
+use pulldown_cmark::{Event, HeadingLevel, Parser, Tag};
+
+use crate::Message;
+
+pub const PREVIEW: &str = "preview";
+
+#[derive(Debug, Clone)]
+pub enum Block {
+    Heading(HeadingLevel, String),
+    Paragraph(String),
+    Item(usize, String),
+    Code(String),
+    Quote(String),
+    Rule,
+    Links(Vec<(String, String)>),
+}
+
+#[derive(Default)]
+struct Builder {
+    blocks: Vec<Block>,
+    text: String,
+    links: Vec<(String, String)>,
+    link: Option<(String, String)>,
+    lists: Vec<Option<u64>>,
+    quote: usize,
+}
+
+impl Builder {
+    fn flush(&mut self, block: impl FnOnce(String) -> Block) {
+        let text = std::mem::take(&mut self.text);
+        if !text.trim().is_empty() {
+            self.blocks.push(block(text.trim().to_owned()));
+        }
+        if !self.links.is_empty() {
+            self.blocks
+                .push(Block::Links(std::mem::take(&mut self.links)));
+        }
+    }
+
+    fn flush_item(&mut self) {
+        let text = std::mem::take(&mut self.text);
+        if let Some(Block::Item(_, item)) = self
+            .blocks
+            .iter_mut()
+            .rev()
+            .find(|block| matches!(block, Block::Item(..)))
+        {
+            item.push_str(text.trim_end());
+            item.push(' ');
+        }
+        if !self.links.is_empty() {
+            self.blocks
+                .push(Block::Links(std::mem::take(&mut self.links)));
+        }
+    }
+
+    fn flush_pending(&mut self) {
+        if self.lists.is_empty() {
+            self.flush(Block::Paragraph);
+        } else {
+            self.flush_item();
+        }
+    }
+
+    fn push_text(&mut self, text: &str) {
+        self.text.push_str(text);
+        if let Some((_, label)) = self.link.as_mut() {
+            label.push_str(text);
+        }
+    }
+}
+
+pub fn parse(source: &str) -> Vec<Block> {
+    let mut builder = Builder::default();
+    for event in Parser::new_ext(source, pulldown_cmark::Options::all()) {
+        match event {
+            Event::Start(Tag::List(start)) => {
+                builder.flush_pending();
+                builder.lists.push(start);
+            }
+            Event::End(Tag::List(_)) => {
+                builder.flush_pending();
+                builder.lists.pop();
+            }
+            Event::Start(Tag::Item) => {
+                builder.flush_pending();
+                let depth = builder.lists.len().saturating_sub(1);
+                let marker = match builder.lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!("{}. ", *number - 1)
+                    }
+                    _ => String::from("• "),
+                };
+                builder.text = marker;
+                builder.blocks.push(Block::Item(depth, String::new()));
+            }
+            Event::End(Tag::Item) | Event::End(Tag::Paragraph) if !builder.lists.is_empty() => {
+                builder.flush_item();
+            }
+            Event::Start(Tag::BlockQuote) => {
+                builder.flush_pending();
+                builder.quote += 1;
+            }
+            Event::End(Tag::BlockQuote) => {
+                builder.quote = builder.quote.saturating_sub(1);
+            }
+            Event::End(Tag::Heading(level, ..)) => {
+                builder.flush(|text| Block::Heading(level, text))
+            }
+            Event::End(Tag::Paragraph) if builder.quote > 0 => builder.flush(Block::Quote),
+            Event::End(Tag::Paragraph) => builder.flush(Block::Paragraph),
+            Event::End(Tag::CodeBlock(_)) => {
+                let code = std::mem::take(&mut builder.text);
+                builder.blocks.push(Block::Code(code.trim_end().to_owned()));
+            }
+            Event::Start(Tag::Link(_, destination, _)) => {
+                builder.link = Some((destination.into_string(), String::new()));
+            }
+            Event::End(Tag::Link(..)) => {
+                if let Some(link) = builder.link.take() {
+                    builder.links.push(link);
+                }
+            }
+            Event::Text(text) => builder.push_text(&text),
+            Event::Code(code) => builder.push_text(&format!("`{code}`")),
+            Event::SoftBreak => builder.push_text(" "),
+            Event::HardBreak => builder.push_text("\n"),
+            Event::Rule => {
+                builder.flush_pending();
+                builder.blocks.push(Block::Rule);
+            }
+            Event::TaskListMarker(done) => builder.push_text(if done { "[x] " } else { "[ ] " }),
+            _ => {}
+        }
+    }
+    builder.flush(Block::Paragraph);
+    builder.blocks
+}
+
+pub fn view<'a>(blocks: &[Block], font: Font) -> Element<'a, Message> {
+    let blocks = blocks.iter().cloned().map(|block| match block {
+        Block::Heading(level, heading) => {
+            let size = match level {
+                HeadingLevel::H1 => 30,
+                HeadingLevel::H2 => 26,
+                HeadingLevel::H3 => 22,
+                _ => 18,
+            };
+            text(heading).size(size).into()
+        }
+        Block::Paragraph(paragraph) => text(paragraph).into(),
+        Block::Item(depth, item) => container(text(item.trim_end().to_owned()))
+            .padding([0, 0, 0, 20 * depth as u16])
+            .into(),
+        Block::Code(code) => container(text(code).font(font).size(14))
+            .width(Length::Fill)
+            .padding(10)
+            .style(theme::Container::Box)
+            .into(),
+        Block::Quote(quote) => container(text(quote))
+            .padding([0, 0, 0, 15])
+            .style(theme::Container::Box)
+            .into(),
+        Block::Rule => horizontal_rule(1).into(),
+        Block::Links(links) => row(links
+            .into_iter()
+            .map(|(destination, label)| {
+                button(text(label).size(14))
+                    .padding([0, 4])
+                    .style(theme::Button::Text)
+                    .on_press(Message::OpenLink(destination))
+                    .into()
+            })
+            .collect())
+        .spacing(5)
+        .into(),
+    });
+    scrollable(
+        column(blocks.collect())
+            .spacing(10)
+            .padding(10)
+            .width(Length::Fill),
+    )
+    .id(scrollable::Id::new(PREVIEW))
+    .height(Length::Fill)
+    .into()
+}
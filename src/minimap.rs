@@ -0,0 +1,292 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer::{self, Quad, Renderer as _};
+use iced::advanced::widget::{Tree, Widget, tree};
+use iced::advanced::{Clipboard, Shell};
+use iced::{Color, Element, Event, Length, Rectangle, Renderer, Size, Theme, event, mouse};
+
+pub const WIDTH: f32 = 80.0;
+const MAX_LINE_HEIGHT: f32 = 2.0;
+const MAX_COLUMNS: usize = 80;
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Default)]
+struct Line {
+    hash: u64,
+    runs: Vec<(u8, u8)>,
+}
+
+impl Line {
+    fn new(text: &str, hash: u64) -> Self {
+        let mut runs = Vec::new();
+        let mut column = 0;
+        let mut start = None;
+        for c in text.chars() {
+            if column >= MAX_COLUMNS {
+                break;
+            }
+            if c.is_whitespace() {
+                if let Some(start) = start.take() {
+                    runs.push((start as u8, (column - start) as u8));
+                }
+                column += if c == '\t' { TAB_WIDTH } else { 1 };
+            } else {
+                start.get_or_insert(column);
+                column += 1;
+            }
+        }
+        if let Some(start) = start {
+            let end = column.min(MAX_COLUMNS);
+            runs.push((start as u8, (end - start) as u8));
+        }
+        Self { hash, runs }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Overview {
+    lines: Vec<Line>,
+}
+
+impl Overview {
+    fn update(&mut self, text: &str) {
+        let hashes: Vec<u64> = text
+            .split('\n')
+            .map(|line| {
+                let mut hasher = DefaultHasher::new();
+                line.hash(&mut hasher);
+                hasher.finish()
+            })
+            .collect();
+        let prefix = self
+            .lines
+            .iter()
+            .zip(&hashes)
+            .take_while(|(line, hash)| line.hash == **hash)
+            .count();
+        let suffix = self.lines[prefix..]
+            .iter()
+            .rev()
+            .zip(hashes[prefix..].iter().rev())
+            .take_while(|(line, hash)| line.hash == **hash)
+            .count();
+        let changed = text
+            .split('\n')
+            .zip(&hashes)
+            .skip(prefix)
+            .take(hashes.len() - prefix - suffix)
+            .map(|(line, hash)| Line::new(line, *hash));
+        let end = self.lines.len() - suffix;
+        self.lines.splice(prefix..end, changed);
+    }
+
+    fn line_height(&self, height: f32) -> f32 {
+        (height / self.lines.len().max(1) as f32).min(MAX_LINE_HEIGHT)
+    }
+}
+
+#[derive(Debug, Default)]
+struct State {
+    overview: Overview,
+    is_dragging: bool,
+}
+
+pub struct Minimap<'a, Message> {
+    text: String,
+    top: usize,
+    visible: usize,
+    on_scroll: Box<dyn Fn(usize) -> Message + 'a>,
+}
+
+impl<'a, Message> Minimap<'a, Message> {
+    pub fn new(
+        text: String,
+        top: usize,
+        visible: usize,
+        on_scroll: impl Fn(usize) -> Message + 'a,
+    ) -> Self {
+        Self {
+            text,
+            top,
+            visible,
+            on_scroll: Box::new(on_scroll),
+        }
+    }
+
+    fn scroll_to(&self, state: &State, bounds: Rectangle, y: f32, shell: &mut Shell<'_, Message>) {
+        let line_height = state.overview.line_height(bounds.height);
+        let line = ((y - bounds.y).max(0.0) / line_height) as usize;
+        let last = state.overview.lines.len().saturating_sub(1);
+        let top = line.min(last).saturating_sub(self.visible / 2);
+        shell.publish((self.on_scroll)(top));
+    }
+}
+
+impl<'a, Message> Widget<Message, Renderer> for Minimap<'a, Message> {
+    fn tag(&self) -> tree::Tag {
+        tree::Tag::of::<State>()
+    }
+
+    fn state(&self) -> tree::State {
+        let mut state = State::default();
+        state.overview.update(&self.text);
+        tree::State::new(state)
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        tree.state
+            .downcast_mut::<State>()
+            .overview
+            .update(&self.text);
+    }
+
+    fn width(&self) -> Length {
+        Length::Fixed(WIDTH)
+    }
+
+    fn height(&self) -> Length {
+        Length::Fill
+    }
+
+    fn layout(
+        &self,
+        _tree: &mut Tree,
+        _renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        let limits = limits.width(Length::Fixed(WIDTH)).height(Length::Fill);
+        layout::Node::new(limits.resolve(Size::ZERO))
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        _cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+    ) {
+        let state = tree.state.downcast_ref::<State>();
+        let bounds = layout.bounds();
+        let lines = &state.overview.lines;
+        let line_height = state.overview.line_height(bounds.height);
+        let column_width = WIDTH / MAX_COLUMNS as f32;
+        let color = Color {
+            a: 0.5,
+            ..style.text_color
+        };
+        let mut quad = |rectangle: Rectangle, color: Color| {
+            renderer.fill_quad(
+                Quad {
+                    bounds: rectangle,
+                    border_radius: 0.0.into(),
+                    border_width: 0.0,
+                    border_color: Color::TRANSPARENT,
+                },
+                color,
+            );
+        };
+        let rows = if line_height >= 1.0 {
+            lines.len()
+        } else {
+            bounds.height as usize
+        };
+        let row_height = line_height.max(1.0);
+        for row in 0..rows {
+            let index = if line_height >= 1.0 {
+                row
+            } else {
+                (row as f32 / line_height) as usize
+            };
+            let Some(line) = lines.get(index) else {
+                break;
+            };
+            let y = bounds.y + row as f32 * row_height;
+            for (start, length) in &line.runs {
+                quad(
+                    Rectangle {
+                        x: bounds.x + f32::from(*start) * column_width,
+                        y,
+                        width: f32::from(*length) * column_width,
+                        height: (row_height * 0.75).max(1.0),
+                    },
+                    color,
+                );
+            }
+        }
+        let viewport = Color {
+            a: 0.2,
+            ..theme.palette().primary
+        };
+        quad(
+            Rectangle {
+                x: bounds.x,
+                y: bounds.y + self.top as f32 * line_height,
+                width: bounds.width,
+                height: (self.visible as f32 * line_height).max(2.0),
+            },
+            viewport,
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _renderer: &Renderer,
+        _clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        _viewport: &Rectangle,
+    ) -> event::Status {
+        let state = tree.state.downcast_mut::<State>();
+        let bounds = layout.bounds();
+        match event {
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Left)) => {
+                let Some(position) = cursor.position_over(bounds) else {
+                    return event::Status::Ignored;
+                };
+                state.is_dragging = true;
+                self.scroll_to(state, bounds, position.y, shell);
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::CursorMoved { position }) if state.is_dragging => {
+                self.scroll_to(state, bounds, position.y, shell);
+                event::Status::Captured
+            }
+            Event::Mouse(mouse::Event::ButtonReleased(mouse::Button::Left))
+                if state.is_dragging =>
+            {
+                state.is_dragging = false;
+                event::Status::Captured
+            }
+            _ => event::Status::Ignored,
+        }
+    }
+
+    fn mouse_interaction(
+        &self,
+        _tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        _viewport: &Rectangle,
+        _renderer: &Renderer,
+    ) -> mouse::Interaction {
+        if cursor.is_over(layout.bounds()) {
+            mouse::Interaction::Pointer
+        } else {
+            mouse::Interaction::default()
+        }
+    }
+}
+
+impl<'a, Message: 'a> From<Minimap<'a, Message>> for Element<'a, Message> {
+    fn from(minimap: Minimap<'a, Message>) -> Self {
+        Element::new(minimap)
+    }
+}
@@ -0,0 +1,149 @@
+use iced::widget::text_editor::{Action, Content, Motion};
+
+use crate::config::Config;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Class {
+    Space,
+    Upper,
+    Lower,
+    Digit,
+    Punctuation,
+}
+
+fn class(c: char) -> Class {
+    if c.is_whitespace() || c == '_' {
+        Class::Space
+    } else if c.is_uppercase() {
+        Class::Upper
+    } else if c.is_alphabetic() {
+        Class::Lower
+    } else if c.is_numeric() {
+        Class::Digit
+    } else {
+        Class::Punctuation
+    }
+}
+
+pub fn expand(content: &Content, action: &Action, config: &Config) -> Option<Vec<Action>> {
+    let (motion, select) = match action {
+        Action::Move(motion) => (*motion, false),
+        Action::Select(motion) => (*motion, true),
+        _ => return None,
+    };
+    let handled = match motion {
+        Motion::Home => config.smart_home,
+        Motion::WordLeft | Motion::WordRight => config.subword_motion,
+        _ => false,
+    };
+    if !handled || (!select && content.selection().is_some()) {
+        return None;
+    }
+    let (line, column) = content.cursor_position();
+    let text = content.text();
+    let chars: Vec<char> = text.split('\n').nth(line)?.chars().collect();
+    let at = column.min(chars.len());
+    let target = match motion {
+        Motion::Home => {
+            let indent = chars.iter().take_while(|c| c.is_whitespace()).count();
+            if at == indent { 0 } else { indent }
+        }
+        Motion::WordLeft if at > 0 => subword_left(&chars, at),
+        Motion::WordRight if at < chars.len() => subword_right(&chars, at),
+        _ => return None,
+    };
+    let step = match (target < at, select) {
+        (true, false) => Action::Move(Motion::Left),
+        (true, true) => Action::Select(Motion::Left),
+        (false, false) => Action::Move(Motion::Right),
+        (false, true) => Action::Select(Motion::Right),
+    };
+    Some(vec![step; target.abs_diff(at)])
+}
+
+fn subword_left(chars: &[char], mut at: usize) -> usize {
+    while at > 0 && class(chars[at - 1]) == Class::Space {
+        at -= 1;
+    }
+    let Some(&previous) = at.checked_sub(1).and_then(|index| chars.get(index)) else {
+        return at;
+    };
+    match class(previous) {
+        Class::Lower => {
+            while at > 0 && class(chars[at - 1]) == Class::Lower {
+                at -= 1;
+            }
+            if at > 0 && class(chars[at - 1]) == Class::Upper {
+                at -= 1;
+            }
+        }
+        kind => {
+            while at > 0 && class(chars[at - 1]) == kind {
+                at -= 1;
+            }
+        }
+    }
+    at
+}
+
+fn subword_right(chars: &[char], mut at: usize) -> usize {
+    while at < chars.len() && class(chars[at]) == Class::Space {
+        at += 1;
+    }
+    let Some(&first) = chars.get(at) else {
+        return at;
+    };
+    match class(first) {
+        Class::Upper => {
+            let start = at;
+            while at < chars.len() && class(chars[at]) == Class::Upper {
+                at += 1;
+            }
+            let next_is_lower = chars.get(at).is_some_and(|c| class(*c) == Class::Lower);
+            if at - start > 1 && next_is_lower {
+                at -= 1;
+            } else {
+                while at < chars.len() && class(chars[at]) == Class::Lower {
+                    at += 1;
+                }
+            }
+        }
+        kind => {
+            while at < chars.len() && class(chars[at]) == kind {
+                at += 1;
+            }
+        }
+    }
+    at
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{subword_left, subword_right};
+
+    fn chars(text: &str) -> Vec<char> {
+        text.chars().collect()
+    }
+
+    #[test]
+    fn moves_right_over_sub_words() {
+        let camel = chars("fooBarBaz");
+        assert_eq!(subword_right(&camel, 0), 3);
+        assert_eq!(subword_right(&camel, 3), 6);
+        assert_eq!(subword_right(&chars("HTTPServer"), 0), 4);
+        let snake = chars("snake_case");
+        assert_eq!(subword_right(&snake, 0), 5);
+        assert_eq!(subword_right(&snake, 5), 10);
+        assert_eq!(subword_right(&chars("a + b"), 1), 3);
+    }
+
+    #[test]
+    fn moves_left_over_sub_words() {
+        let camel = chars("fooBarBaz");
+        assert_eq!(subword_left(&camel, 9), 6);
+        assert_eq!(subword_left(&camel, 6), 3);
+        assert_eq!(subword_left(&chars("HTTPServer"), 4), 0);
+        assert_eq!(subword_left(&chars("x  42"), 5), 3);
+        assert_eq!(subword_left(&chars("  "), 2), 0);
+    }
+}
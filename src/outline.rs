@@ -0,0 +1,108 @@
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::language::Language;
+
+pub const WIDTH: f32 = 250.0;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Symbol {
+    pub line: usize,
+    pub depth: usize,
+    pub kind: String,
+    pub name: String,
+}
+
+fn sources(language: Language) -> &'static [&'static str] {
+    match language {
+        Language::Rust => &[
+            r#"^(?P<indent>\s*)(?:pub(?:\([^)]*\))?\s+)?(?:(?:async|const|unsafe|extern\s+"[^"]*")\s+)*(?P<kind>fn|struct|enum|trait|union|mod|type|macro_rules!)\s*(?P<name>[A-Za-z_]\w*)"#,
+            r"^(?P<indent>\s*)(?:unsafe\s+)?(?P<kind>impl)(?:<[^{]*?>)?\s+(?P<name>[^{]+?)\s*(?:where\b.*)?\{?$",
+        ],
+        Language::Python => &[r"^(?P<indent>\s*)(?:async\s+)?(?P<kind>def|class)\s+(?P<name>\w+)"],
+        Language::JavaScript => &[
+            r"^(?P<indent>\s*)(?:export\s+)?(?:default\s+)?(?:async\s+)?(?P<kind>function\*?|class)\s+(?P<name>[\w$]+)",
+            r"^(?P<indent>\s*)(?:export\s+)?(?P<kind>const|let|var)\s+(?P<name>[\w$]+)\s*=\s*(?:async\s*)?(?:\([^)]*\)|[\w$]+)\s*=>",
+        ],
+        Language::C | Language::Cpp => &[
+            r"^(?P<indent>\s*)(?:typedef\s+)?(?:template\s*<[^>]*>\s*)?(?P<kind>struct|class|enum|union|namespace)\s+(?P<name>\w+)[^;]*$",
+            r"^(?P<indent>)(?:[A-Za-z_][\w:<>,\*&\s]*?[\s\*&])?(?P<name>[A-Za-z_~][\w:~]*)\s*\([^;]*$",
+        ],
+        Language::Go => &[
+            r"^(?P<indent>)(?P<kind>func)\s+(?:\([^)]*\)\s*)?(?P<name>\w+)",
+            r"^(?P<indent>)(?P<kind>type)\s+(?P<name>\w+)",
+        ],
+        Language::Java => &[
+            r"^(?P<indent>\s*)(?:(?:public|private|protected|static|final|abstract|sealed)\s+)*(?P<kind>class|interface|enum|record)\s+(?P<name>\w+)",
+            r"^(?P<indent>\s+)(?:(?:public|private|protected|static|final|abstract|synchronized)\s+)+[\w<>\[\],.?\s]+\s+(?P<name>\w+)\s*\(",
+        ],
+        Language::Markdown => &[r"^(?P<indent>#{1,6})\s+(?P<name>.+?)\s*#*$"],
+        Language::Html => &[r"^(?P<indent>\s*)<(?P<kind>h[1-6])[^>]*>(?P<name>.*?)</h[1-6]>"],
+        Language::Css => &[r"^(?P<indent>\s*)(?P<name>[^\s{}/][^{}]*?)\s*\{"],
+        Language::Lua => &[r"^(?P<indent>\s*)(?:local\s+)?(?P<kind>function)\s+(?P<name>[\w.:]+)"],
+        Language::Shell => &[
+            r"^(?P<indent>\s*)(?:function\s+)?(?P<name>[\w-]+)\s*\(\)",
+            r"^(?P<indent>\s*)(?P<kind>function)\s+(?P<name>[\w-]+)",
+        ],
+        Language::Makefile => &[r"^(?P<indent>)(?P<name>[\w./%-]+)\s*::?(?:[^=]|$)"],
+        Language::Sql => &[
+            r"(?i)^(?P<indent>\s*)create\s+(?:or\s+replace\s+)?(?P<kind>table|view|function|procedure|index|trigger)\s+(?:if\s+not\s+exists\s+)?(?P<name>[\w.]+)",
+        ],
+        Language::Yaml => &[r"^(?P<indent>)(?P<name>[\w.-]+):"],
+        Language::PlainText | Language::Json | Language::Xml => &[],
+    }
+}
+
+static PATTERNS: LazyLock<Vec<Vec<Regex>>> = LazyLock::new(|| {
+    Language::ALL
+        .iter()
+        .map(|&language| {
+            sources(language)
+                .iter()
+                .filter_map(|source| Regex::new(source).ok())
+                .collect()
+        })
+        .collect()
+});
+
+pub fn symbols(text: &str, language: Language) -> Vec<Symbol> {
+    let Some(patterns) = Language::ALL
+        .iter()
+        .position(|&candidate| candidate == language)
+        .and_then(|index| PATTERNS.get(index))
+    else {
+        return Vec::new();
+    };
+    if patterns.is_empty() {
+        return Vec::new();
+    }
+    let mut symbols = Vec::new();
+    let mut indents: Vec<usize> = Vec::new();
+    for (line, contents) in text.lines().enumerate() {
+        let Some(captures) = patterns
+            .iter()
+            .find_map(|pattern| pattern.captures(contents))
+        else {
+            continue;
+        };
+        let Some(name) = captures.name("name") else {
+            continue;
+        };
+        let indent = captures.name("indent").map_or(0, |indent| indent.len());
+        while indents.last().is_some_and(|&last| last >= indent) {
+            indents.pop();
+        }
+        symbols.push(Symbol {
+            line,
+            depth: indents.len(),
+            kind: captures
+                .name("kind")
+                .map(|kind| kind.as_str().to_owned())
+                .unwrap_or_default(),
+            name: name.as_str().trim().to_owned(),
+        });
+        indents.push(indent);
+    }
+    symbols
+}
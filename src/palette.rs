@@ -0,0 +1,190 @@
+use iced::highlighter;
+use iced::widget::pane_grid::Axis;
+
+use crate::Message;
+use crate::appearance::Appearance;
+use crate::convert::Conversion;
+use crate::keymap::{self, Shortcut};
+use crate::language::Language;
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub name: String,
+    pub message: Message,
+}
+
+impl Entry {
+    pub fn new(name: impl Into<String>, message: Message) -> Self {
+        Self {
+            name: name.into(),
+            message,
+        }
+    }
+
+    pub fn shortcut(&self) -> Option<Shortcut> {
+        keymap::shortcut(&self.message)
+    }
+}
+
+pub fn entries() -> Vec<Entry> {
+    let mut entries = vec![
+        Entry::new("New file", Message::New),
+        Entry::new("New file from clipboard", Message::NewFromClipboard),
+        Entry::new("Open file", Message::Open),
+        Entry::new("Open recent file", Message::ToggleRecent),
+        Entry::new("Open folder", Message::OpenFolder),
+        Entry::new("Toggle file explorer", Message::ToggleExplorer),
+        Entry::new("Toggle outline", Message::ToggleOutline),
+        Entry::new("Toggle problems panel", Message::ToggleProblems),
+        Entry::new("Toggle terminal", Message::ToggleTerminal),
+        Entry::new("Announce cursor position", Message::AnnouncePosition),
+        Entry::new("Focus next field", Message::FocusNext),
+        Entry::new("Focus previous field", Message::FocusPrevious),
+        Entry::new("Run current file", Message::RunCurrentFile),
+        Entry::new("Save", Message::Save),
+        Entry::new("Save as", Message::SaveAs),
+        Entry::new("Close tab", Message::CloseActiveTab),
+        Entry::new("Next tab", Message::NextTab),
+        Entry::new("Previous tab", Message::PreviousTab),
+        Entry::new("Split right", Message::SplitPane(Axis::Vertical)),
+        Entry::new("Split down", Message::SplitPane(Axis::Horizontal)),
+        Entry::new("Close pane", Message::ClosePane),
+        Entry::new("Undo", Message::Undo),
+        Entry::new("Redo", Message::Redo),
+        Entry::new("Find", Message::ToggleFind),
+        Entry::new("Replace", Message::ToggleReplace),
+        Entry::new("Find next", Message::FindNext),
+        Entry::new("Find previous", Message::FindPrevious),
+        Entry::new("Replace all", Message::ReplaceAll),
+        Entry::new("Find in files", Message::ToggleGrep),
+        Entry::new("Go to line", Message::ToggleGoToLine),
+        Entry::new("Select next occurrence", Message::SelectNextOccurrence),
+        Entry::new("Jump to matching bracket", Message::JumpToBracket),
+        Entry::new("Toggle comment", Message::ToggleComment),
+        Entry::new("Move lines up", Message::MoveLinesUp),
+        Entry::new("Move lines down", Message::MoveLinesDown),
+        Entry::new("Duplicate lines", Message::DuplicateLines),
+        Entry::new("Join lines", Message::JoinLines),
+        Entry::new("Delete lines", Message::DeleteLines),
+        Entry::new("Toggle path display", Message::TogglePathDisplay),
+        Entry::new("Toggle typewriter mode", Message::ToggleTypewriter),
+        Entry::new("Toggle spell check", Message::ToggleSpellCheck),
+        Entry::new("Git: revert hunk", Message::RevertHunk),
+        Entry::new("Git: diff against HEAD", Message::DiffAgainstHead),
+        Entry::new("Compare with file", Message::CompareWithFile),
+        Entry::new("Compare with saved file", Message::CompareWithSaved),
+        Entry::new("Show unsaved changes", Message::ShowUnsavedChanges),
+        Entry::new("Show local history", Message::ShowLocalHistory),
+        Entry::new("Pick color", Message::PickColorAtCursor),
+        Entry::new("Copy file path", Message::CopyPath),
+        Entry::new("Encrypt buffer", Message::EncryptBuffer),
+        Entry::new("Open containing folder", Message::RevealInFolder),
+        Entry::new(
+            "Search for selection in files",
+            Message::SearchSelectionInFiles,
+        ),
+        Entry::new("Export as HTML", Message::ExportHtml),
+        Entry::new("Export as PDF", Message::ExportPdf),
+        Entry::new("Print", Message::Print),
+        Entry::new("Spelling: suggest corrections", Message::SuggestSpelling),
+        Entry::new("Choose font", Message::ChooseFont),
+        Entry::new("Zoom in", Message::ZoomIn),
+        Entry::new("Zoom out", Message::ZoomOut),
+        Entry::new("Reset zoom", Message::ResetZoom),
+        Entry::new("Toggle word wrap", Message::ToggleWrap),
+        Entry::new("Toggle minimap", Message::ToggleMinimap),
+        Entry::new("Toggle whitespace", Message::ToggleWhitespace),
+        Entry::new("Toggle current line highlight", Message::ToggleCurrentLine),
+        Entry::new("Toggle rulers", Message::ToggleRulers),
+        Entry::new("Toggle indent guides", Message::ToggleIndentGuides),
+        Entry::new("Macro: start/stop recording", Message::ToggleRecording),
+        Entry::new("Macro: play last", Message::PlayMacro),
+        Entry::new("Macro: play, repeat or save...", Message::ShowMacros),
+        Entry::new("Paste from history", Message::ToggleClipboardHistory),
+        Entry::new("New file from template", Message::NewFromTemplate),
+        Entry::new("New scratch buffer", Message::NewScratch),
+        Entry::new("Go to file", Message::ToggleFinder),
+        Entry::new("Open remote file (SFTP)", Message::OpenRemote),
+        Entry::new("Open URL", Message::OpenUrl),
+        Entry::new("Reload plugins", Message::ReloadPlugins),
+        Entry::new("Show notifications", Message::ToggleNotifications),
+        Entry::new("Document statistics", Message::ToggleStatistics),
+        Entry::new("Show TODOs", Message::ToggleTodos),
+        Entry::new("Toggle fold", Message::FoldAtCursor),
+        Entry::new("Fold all", Message::FoldAll),
+        Entry::new("Unfold all", Message::UnfoldAll),
+        Entry::new("Toggle bookmark", Message::ToggleBookmark),
+        Entry::new("Next bookmark", Message::NextBookmark),
+        Entry::new("Previous bookmark", Message::PreviousBookmark),
+        Entry::new("Show bookmarks", Message::ToggleBookmarkPanel),
+        Entry::new("Toggle read-only", Message::ToggleReadonly),
+        Entry::new("Toggle hex view", Message::ToggleHex),
+        Entry::new("Toggle table view", Message::ToggleTable),
+        Entry::new("Format document", Message::FormatDocument),
+        Entry::new("Format with external tool", Message::FormatExternally),
+        Entry::new("Minify", Message::MinifyDocument),
+        Entry::new("Validate document", Message::ValidateDocument),
+        Entry::new("Toggle follow mode", Message::ToggleFollow),
+        Entry::new("Toggle save transforms", Message::ToggleSaveTransforms),
+        Entry::new("Toggle markdown preview", Message::TogglePreview),
+        Entry::new("Repeat last command", Message::RepeatLast),
+    ];
+    entries.extend(Conversion::ALL.iter().map(|conversion| {
+        Entry::new(
+            format!("Transform: {conversion}"),
+            Message::Convert(*conversion),
+        )
+    }));
+    entries.extend(Appearance::BUILT_IN.iter().map(|appearance| {
+        Entry::new(
+            format!("UI theme: {appearance}"),
+            Message::AppearanceSelected(appearance.clone()),
+        )
+    }));
+    entries.extend(highlighter::Theme::ALL.iter().map(|theme| {
+        Entry::new(
+            format!("Syntax theme: {theme}"),
+            Message::ThemeSelected(*theme),
+        )
+    }));
+    entries.extend(Language::ALL.iter().map(|language| {
+        Entry::new(
+            format!("Language: {language}"),
+            Message::LanguageSelected(*language),
+        )
+    }));
+    entries
+}
+
+pub fn matching(filter: &str, extra: Vec<Entry>) -> Vec<Entry> {
+    let mut scored: Vec<_> = entries()
+        .into_iter()
+        .chain(extra)
+        .filter_map(|entry| Some((score(&entry.name, filter)?, entry)))
+        .collect();
+    scored.sort_by_key(|(score, _)| std::cmp::Reverse(*score));
+    scored.into_iter().map(|(_, entry)| entry).collect()
+}
+
+pub fn score(name: &str, filter: &str) -> Option<i32> {
+    let name: Vec<char> = name.chars().flat_map(char::to_lowercase).collect();
+    let mut score = 0;
+    let mut position = 0;
+    let mut previous = None;
+    for wanted in filter.chars().flat_map(char::to_lowercase) {
+        if wanted.is_whitespace() {
+            continue;
+        }
+        let found = position + name[position..].iter().position(|&c| c == wanted)?;
+        if previous.is_some_and(|previous| previous + 1 == found) {
+            score += 5;
+        }
+        if found == 0 || !name[found - 1].is_alphanumeric() {
+            score += 10;
+        }
+        score -= (found - position) as i32;
+        previous = Some(found);
+        position = found + 1;
+    }
+    Some(score)
+}
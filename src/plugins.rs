@@ -0,0 +1,196 @@
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use rhai::{AST, Dynamic, Engine, Scope};
+
+use crate::Message;
+use crate::palette::Entry;
+
+const MAX_OPERATIONS: u64 = 10_000_000;
+
+#[derive(Debug, Clone, Default)]
+pub struct Context {
+    pub text: String,
+    pub selection: Option<String>,
+    pub cursor: (usize, usize),
+    pub new_text: Option<String>,
+    pub replacement: Option<String>,
+    pub moved: Option<(usize, usize)>,
+}
+
+struct Command {
+    name: String,
+    script: usize,
+    function: String,
+}
+
+pub struct Plugins {
+    engine: Engine,
+    scripts: Vec<AST>,
+    commands: Vec<Command>,
+    context: Arc<Mutex<Context>>,
+    registered: Arc<Mutex<Vec<(String, String)>>>,
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+impl Default for Plugins {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Plugins {
+    pub fn new() -> Self {
+        let context = Arc::new(Mutex::new(Context::default()));
+        let registered = Arc::new(Mutex::new(Vec::new()));
+        let mut engine = Engine::new();
+        engine.set_max_operations(MAX_OPERATIONS);
+        {
+            let registered = registered.clone();
+            engine.register_fn("register_command", move |name: &str, function: &str| {
+                lock(&registered).push((name.to_owned(), function.to_owned()));
+            });
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("text", move || {
+                let context = lock(&context);
+                context
+                    .new_text
+                    .clone()
+                    .unwrap_or_else(|| context.text.clone())
+            });
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("set_text", move |text: &str| {
+                lock(&context).new_text = Some(text.to_owned());
+            });
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("selection", move || {
+                lock(&context).selection.clone().unwrap_or_default()
+            });
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("replace_selection", move |text: &str| {
+                lock(&context).replacement = Some(text.to_owned());
+            });
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("cursor_line", move || lock(&context).cursor.0 as i64 + 1);
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("cursor_column", move || lock(&context).cursor.1 as i64 + 1);
+        }
+        {
+            let context = context.clone();
+            engine.register_fn("set_cursor", move |line: i64, column: i64| {
+                lock(&context).moved = Some((line.max(1) as usize - 1, column.max(1) as usize - 1));
+            });
+        }
+        Self {
+            engine,
+            scripts: Vec::new(),
+            commands: Vec::new(),
+            context,
+            registered,
+        }
+    }
+
+    pub fn load(&mut self, sources: Vec<(PathBuf, String)>) -> Vec<String> {
+        self.scripts.clear();
+        self.commands.clear();
+        let mut errors = Vec::new();
+        for (path, source) in sources {
+            let ast = match self.engine.compile(&source) {
+                Ok(ast) => ast,
+                Err(error) => {
+                    errors.push(format!("{}: {error}", path.display()));
+                    continue;
+                }
+            };
+            lock(&self.registered).clear();
+            if let Err(error) = self.engine.run_ast_with_scope(&mut Scope::new(), &ast) {
+                errors.push(format!("{}: {error}", path.display()));
+                continue;
+            }
+            let script = self.scripts.len();
+            self.scripts.push(ast);
+            self.commands
+                .extend(
+                    lock(&self.registered)
+                        .drain(..)
+                        .map(|(name, function)| Command {
+                            name,
+                            script,
+                            function,
+                        }),
+                );
+        }
+        errors
+    }
+
+    pub fn entries(&self) -> Vec<Entry> {
+        self.commands
+            .iter()
+            .enumerate()
+            .map(|(index, command)| {
+                Entry::new(
+                    format!("Plugin: {}", command.name),
+                    Message::RunPlugin(index),
+                )
+            })
+            .collect()
+    }
+
+    pub fn run(&self, index: usize, context: Context) -> Result<Context, String> {
+        let command = self
+            .commands
+            .get(index)
+            .ok_or_else(|| String::from("plugin command is no longer loaded"))?;
+        *lock(&self.context) = context;
+        self.engine
+            .call_fn::<Dynamic>(
+                &mut Scope::new(),
+                &self.scripts[command.script],
+                &command.function,
+                (),
+            )
+            .map_err(|error| format!("{}: {error}", command.name))?;
+        Ok(std::mem::take(&mut *lock(&self.context)))
+    }
+}
+
+fn plugins_dir() -> Option<PathBuf> {
+    crate::config::config_file().map(|config| config.with_file_name("plugins"))
+}
+
+pub async fn read() -> Vec<(PathBuf, String)> {
+    let Some(dir) = plugins_dir() else {
+        return Vec::new();
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+    let mut sources = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path
+            .extension()
+            .is_some_and(|extension| extension == "rhai")
+            && let Ok(source) = tokio::fs::read_to_string(&path).await
+        {
+            sources.push((path, source));
+        }
+    }
+    sources.sort_by(|(a, _), (b, _)| a.cmp(b));
+    sources
+}
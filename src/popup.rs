@@ -0,0 +1,248 @@
+use iced::advanced::layout::{self, Layout};
+use iced::advanced::renderer;
+use iced::advanced::widget::{self, Tree, Widget};
+use iced::advanced::{Clipboard, Shell, overlay};
+use iced::{Element, Event, Length, Point, Rectangle, Renderer, Size, Theme, Vector, event, mouse};
+
+pub struct Popup<'a, Message> {
+    base: Element<'a, Message>,
+    popup: Option<Element<'a, Message>>,
+    offset: Vector,
+    on_right_click: Option<Box<dyn Fn(Point) -> Message + 'a>>,
+}
+
+impl<'a, Message> Popup<'a, Message> {
+    pub fn new(
+        base: impl Into<Element<'a, Message>>,
+        popup: Option<Element<'a, Message>>,
+        offset: Vector,
+    ) -> Self {
+        Self {
+            base: base.into(),
+            popup,
+            offset,
+            on_right_click: None,
+        }
+    }
+
+    pub fn on_right_click(mut self, on_right_click: impl Fn(Point) -> Message + 'a) -> Self {
+        self.on_right_click = Some(Box::new(on_right_click));
+        self
+    }
+}
+
+impl<'a, Message> Widget<Message, Renderer> for Popup<'a, Message> {
+    fn width(&self) -> Length {
+        self.base.as_widget().width()
+    }
+
+    fn height(&self) -> Length {
+        self.base.as_widget().height()
+    }
+
+    fn children(&self) -> Vec<Tree> {
+        std::iter::once(&self.base)
+            .chain(&self.popup)
+            .map(Tree::new)
+            .collect()
+    }
+
+    fn diff(&self, tree: &mut Tree) {
+        let children: Vec<_> = std::iter::once(&self.base).chain(&self.popup).collect();
+        tree.diff_children(&children);
+    }
+
+    fn layout(
+        &self,
+        tree: &mut Tree,
+        renderer: &Renderer,
+        limits: &layout::Limits,
+    ) -> layout::Node {
+        self.base
+            .as_widget()
+            .layout(&mut tree.children[0], renderer, limits)
+    }
+
+    fn draw(
+        &self,
+        tree: &Tree,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+    ) {
+        self.base.as_widget().draw(
+            &tree.children[0],
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            viewport,
+        );
+    }
+
+    fn operate(
+        &self,
+        tree: &mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+        operation: &mut dyn widget::Operation<Message>,
+    ) {
+        self.base
+            .as_widget()
+            .operate(&mut tree.children[0], layout, renderer, operation);
+    }
+
+    fn on_event(
+        &mut self,
+        tree: &mut Tree,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+        viewport: &Rectangle,
+    ) -> event::Status {
+        if let (
+            Some(on_right_click),
+            Event::Mouse(mouse::Event::ButtonPressed(mouse::Button::Right)),
+        ) = (&self.on_right_click, &event)
+            && let Some(point) = cursor.position_in(layout.bounds())
+        {
+            shell.publish(on_right_click(point));
+            return event::Status::Captured;
+        }
+        self.base.as_widget_mut().on_event(
+            &mut tree.children[0],
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            viewport,
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        tree: &Tree,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.base.as_widget().mouse_interaction(
+            &tree.children[0],
+            layout,
+            cursor,
+            viewport,
+            renderer,
+        )
+    }
+
+    fn overlay<'b>(
+        &'b mut self,
+        tree: &'b mut Tree,
+        layout: Layout<'_>,
+        renderer: &Renderer,
+    ) -> Option<overlay::Element<'b, Message, Renderer>> {
+        let (base, rest) = tree.children.split_first_mut()?;
+        match (self.popup.as_mut(), rest.first_mut()) {
+            (Some(popup), Some(tree)) => Some(overlay::Element::new(
+                layout.position() + self.offset,
+                Box::new(Overlay {
+                    content: popup,
+                    tree,
+                }),
+            )),
+            _ => self.base.as_widget_mut().overlay(base, layout, renderer),
+        }
+    }
+}
+
+impl<'a, Message: 'a> From<Popup<'a, Message>> for Element<'a, Message> {
+    fn from(popup: Popup<'a, Message>) -> Self {
+        Element::new(popup)
+    }
+}
+
+struct Overlay<'a, 'b, Message> {
+    content: &'b mut Element<'a, Message>,
+    tree: &'b mut Tree,
+}
+
+impl<'a, 'b, Message> overlay::Overlay<Message, Renderer> for Overlay<'a, 'b, Message> {
+    fn layout(&mut self, renderer: &Renderer, bounds: Size, position: Point) -> layout::Node {
+        let limits = layout::Limits::new(Size::ZERO, bounds);
+        let mut node = self
+            .content
+            .as_widget()
+            .layout(self.tree, renderer, &limits);
+        let size = node.size();
+        let x = position.x.min(bounds.width - size.width).max(0.0);
+        let y = if position.y + size.height > bounds.height {
+            (position.y - size.height - crate::LINE_HEIGHT).max(0.0)
+        } else {
+            position.y
+        };
+        node.move_to(Point::new(x, y));
+        node
+    }
+
+    fn draw(
+        &self,
+        renderer: &mut Renderer,
+        theme: &Theme,
+        style: &renderer::Style,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+    ) {
+        self.content.as_widget().draw(
+            self.tree,
+            renderer,
+            theme,
+            style,
+            layout,
+            cursor,
+            &layout.bounds(),
+        );
+    }
+
+    fn on_event(
+        &mut self,
+        event: Event,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        renderer: &Renderer,
+        clipboard: &mut dyn Clipboard,
+        shell: &mut Shell<'_, Message>,
+    ) -> event::Status {
+        self.content.as_widget_mut().on_event(
+            self.tree,
+            event,
+            layout,
+            cursor,
+            renderer,
+            clipboard,
+            shell,
+            &layout.bounds(),
+        )
+    }
+
+    fn mouse_interaction(
+        &self,
+        layout: Layout<'_>,
+        cursor: mouse::Cursor,
+        viewport: &Rectangle,
+        renderer: &Renderer,
+    ) -> mouse::Interaction {
+        self.content
+            .as_widget()
+            .mouse_interaction(self.tree, layout, cursor, viewport, renderer)
+    }
+}
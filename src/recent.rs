@@ -0,0 +1,70 @@
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+pub const LIMIT: usize = 10;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Recent {
+    pub files: Vec<PathBuf>,
+}
+
+impl Recent {
+    pub fn push(&mut self, path: &Path) {
+        self.files.retain(|file| file != path);
+        self.files.insert(0, path.to_path_buf());
+        self.files.truncate(LIMIT);
+    }
+
+    pub fn matching(&self, filter: &str) -> Vec<RecentFile> {
+        let filter = filter.to_lowercase();
+        self.files
+            .iter()
+            .filter(|file| file.to_string_lossy().to_lowercase().contains(&filter))
+            .cloned()
+            .map(RecentFile)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecentFile(pub PathBuf);
+
+impl fmt::Display for RecentFile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.0.display())
+    }
+}
+
+fn recent_file() -> Option<PathBuf> {
+    crate::config::config_file().map(|config| config.with_file_name("recent.toml"))
+}
+
+pub async fn load() -> Recent {
+    let Some(path) = recent_file() else {
+        return Recent::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(recent: Recent) -> Result<(), Error> {
+    let path = recent_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents =
+        toml::to_string(&recent).map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
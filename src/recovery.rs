@@ -0,0 +1,84 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Recovered {
+    pub path: Option<PathBuf>,
+    pub text: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct Entry {
+    pub file: PathBuf,
+    pub recovered: Recovered,
+}
+
+fn recovery_dir() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("recovery"))
+}
+
+fn session_prefix() -> String {
+    format!("{}-", std::process::id())
+}
+
+fn io_error(error: std::io::Error) -> Error {
+    Error::IOFailed(error.kind())
+}
+
+async fn files(own_session: bool) -> Vec<PathBuf> {
+    let Some(dir) = recovery_dir() else {
+        return Vec::new();
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return Vec::new();
+    };
+    let prefix = session_prefix();
+    let mut files = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name();
+        if name.to_string_lossy().starts_with(&prefix) == own_session {
+            files.push(entry.path());
+        }
+    }
+    files
+}
+
+pub async fn write(buffers: Vec<(usize, Recovered)>) -> Result<(), Error> {
+    let dir = recovery_dir().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    tokio::fs::create_dir_all(&dir).await.map_err(io_error)?;
+    clear().await?;
+    for (id, recovered) in buffers {
+        let contents = toml::to_string(&recovered)
+            .map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+        let file = dir.join(format!("{}{id}.toml", session_prefix()));
+        tokio::fs::write(file, contents).await.map_err(io_error)?;
+    }
+    Ok(())
+}
+
+pub async fn clear() -> Result<(), Error> {
+    remove(files(true).await).await
+}
+
+pub async fn remove(files: Vec<PathBuf>) -> Result<(), Error> {
+    for file in files {
+        tokio::fs::remove_file(file).await.map_err(io_error)?;
+    }
+    Ok(())
+}
+
+pub async fn find() -> Vec<Entry> {
+    let mut found = Vec::new();
+    for file in files(false).await {
+        let Ok(contents) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+        if let Ok(recovered) = toml::from_str(&contents) {
+            found.push(Entry { file, recovered });
+        }
+    }
+    found
+}
@@ -0,0 +1,233 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use russh::client;
+use russh_keys::key::PublicKey;
+use russh_sftp::client::SftpSession;
+use russh_sftp::client::fs::Metadata;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+pub const SCHEME: &str = "sftp://";
+const DEFAULT_PORT: u16 = 22;
+const KEY_FILES: &[&str] = &["id_ed25519", "id_ecdsa", "id_rsa"];
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Location {
+    pub user: String,
+    pub host: String,
+    pub port: u16,
+    pub path: String,
+}
+
+impl Location {
+    pub fn parse(url: &str) -> Option<Self> {
+        let (authority, path) = url.strip_prefix(SCHEME)?.split_once('/')?;
+        let (user, host) = authority.rsplit_once('@')?;
+        let (host, port) = match host.rsplit_once(':') {
+            Some((host, port)) => (host, port.parse().ok()?),
+            None => (host, DEFAULT_PORT),
+        };
+        if user.is_empty() || host.is_empty() || path.is_empty() {
+            return None;
+        }
+        Some(Self {
+            user: user.to_owned(),
+            host: host.to_owned(),
+            port,
+            path: format!("/{path}"),
+        })
+    }
+
+    pub fn file_name(&self) -> &str {
+        self.path.rsplit('/').next().unwrap_or(&self.path)
+    }
+
+    pub fn server(&self) -> String {
+        match self.port {
+            DEFAULT_PORT => format!("{}@{}", self.user, self.host),
+            port => format!("{}@{}:{port}", self.user, self.host),
+        }
+    }
+}
+
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}{}{}", self.server(), self.path)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Failure {
+    Denied,
+    Failed(String),
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Denied => f.write_str("authentication failed"),
+            Failure::Failed(error) => f.write_str(error),
+        }
+    }
+}
+
+fn failed(error: impl fmt::Display) -> Failure {
+    Failure::Failed(error.to_string())
+}
+
+#[derive(Debug, Default)]
+pub struct Credentials {
+    passwords: HashMap<String, String>,
+}
+
+impl Credentials {
+    pub fn get(&self, location: &Location) -> Option<String> {
+        self.passwords.get(&location.server()).cloned()
+    }
+
+    pub fn insert(&mut self, location: &Location, password: String) {
+        self.passwords.insert(location.server(), password);
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Prompt {
+    pub url: String,
+    pub password: String,
+    pub needs_password: bool,
+    pub is_connecting: bool,
+    pub error: Option<String>,
+}
+
+struct Client {
+    host: String,
+    port: u16,
+}
+
+#[async_trait]
+impl client::Handler for Client {
+    type Error = russh::Error;
+
+    async fn check_server_key(&mut self, key: &PublicKey) -> Result<bool, Self::Error> {
+        Ok(russh_keys::check_known_hosts(&self.host, self.port, key).unwrap_or(false))
+    }
+}
+
+async fn connect(
+    location: &Location,
+    password: Option<String>,
+) -> Result<(client::Handle<Client>, SftpSession), Failure> {
+    let handler = Client {
+        host: location.host.clone(),
+        port: location.port,
+    };
+    let config = Arc::new(client::Config::default());
+    let mut session = client::connect(config, (location.host.as_str(), location.port), handler)
+        .await
+        .map_err(|error| match error {
+            russh::Error::UnknownKey => failed(format!("{} is not in known_hosts", location.host)),
+            error => failed(error),
+        })?;
+    let authenticated = match password {
+        Some(password) => session
+            .authenticate_password(&location.user, password)
+            .await
+            .map_err(failed)?,
+        None => {
+            let mut authenticated = false;
+            for key in keys() {
+                if session
+                    .authenticate_publickey(&location.user, Arc::new(key))
+                    .await
+                    .map_err(failed)?
+                {
+                    authenticated = true;
+                    break;
+                }
+            }
+            authenticated
+        }
+    };
+    if !authenticated {
+        return Err(Failure::Denied);
+    }
+    let channel = session.channel_open_session().await.map_err(failed)?;
+    channel
+        .request_subsystem(true, "sftp")
+        .await
+        .map_err(failed)?;
+    let sftp = SftpSession::new(channel.into_stream())
+        .await
+        .map_err(failed)?;
+    Ok((session, sftp))
+}
+
+fn keys() -> Vec<russh_keys::key::KeyPair> {
+    let Some(dir) = dirs::home_dir().map(|home| home.join(".ssh")) else {
+        return Vec::new();
+    };
+    KEY_FILES
+        .iter()
+        .filter_map(|name| russh_keys::load_secret_key(dir.join(name), None).ok())
+        .collect()
+}
+
+pub async fn download(location: Location, password: Option<String>) -> Result<Vec<u8>, Failure> {
+    let (_session, sftp) = connect(&location, password).await?;
+    let mut file = sftp.open(&location.path).await.map_err(failed)?;
+    let mut bytes = Vec::new();
+    file.read_to_end(&mut bytes).await.map_err(failed)?;
+    Ok(bytes)
+}
+
+pub async fn upload(
+    location: Location,
+    password: Option<String>,
+    bytes: Vec<u8>,
+) -> Result<(), Failure> {
+    let (_session, sftp) = connect(&location, password).await?;
+    let (dir, name) = location
+        .path
+        .rsplit_once('/')
+        .unwrap_or(("", &location.path));
+    let temporary = format!("{dir}/.{name}.{}.tmp", std::process::id());
+    let written = async {
+        let mut file = sftp.create(&temporary).await.map_err(failed)?;
+        file.write_all(&bytes).await.map_err(failed)?;
+        file.shutdown().await.map_err(failed)
+    }
+    .await;
+    if let Err(error) = written {
+        let _ = sftp.remove_file(&temporary).await;
+        return Err(error);
+    }
+    if let Some(permissions) = sftp
+        .metadata(&location.path)
+        .await
+        .ok()
+        .and_then(|metadata| metadata.permissions)
+    {
+        let mut metadata = Metadata::default();
+        metadata.permissions = Some(permissions & 0o7777);
+        let _ = sftp.set_metadata(&temporary, metadata).await;
+    }
+    if sftp.rename(&temporary, &location.path).await.is_ok() {
+        return Ok(());
+    }
+    // Servers speaking plain SFTP v3 refuse to rename over an existing file,
+    // so the original is moved aside and only removed once the new one is in.
+    let previous = format!("{dir}/.{name}.{}.old", std::process::id());
+    if let Err(error) = sftp.rename(&location.path, &previous).await {
+        let _ = sftp.remove_file(&temporary).await;
+        return Err(failed(error));
+    }
+    if let Err(error) = sftp.rename(&temporary, &location.path).await {
+        let _ = sftp.rename(&previous, &location.path).await;
+        let _ = sftp.remove_file(&temporary).await;
+        return Err(failed(error));
+    }
+    let _ = sftp.remove_file(&previous).await;
+    Ok(())
+}
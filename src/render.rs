@@ -0,0 +1,148 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use iced::futures::channel::oneshot;
+
+use crate::buffer::Buffer;
+use crate::git::{self, Marker};
+use crate::language::Language;
+use crate::{brackets, fold};
+
+pub const DEBOUNCE: Duration = Duration::from_millis(75);
+pub const SYNC_LIMIT: usize = 64 * 1024;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Key {
+    pub revision: u64,
+    pub language: Language,
+    pub head: Option<Arc<String>>,
+}
+
+impl Key {
+    pub fn of(buffer: &Buffer) -> Self {
+        Self {
+            revision: buffer.revision,
+            language: buffer.language,
+            head: buffer.git.as_ref().and_then(|info| info.head.clone()),
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub key: Key,
+    pub folds: BTreeMap<usize, usize>,
+    pub brackets: Vec<(usize, char)>,
+    pub changes: Vec<(usize, Marker)>,
+    pub longest: usize,
+}
+
+impl Layout {
+    pub fn new(key: Key, text: &str, git: Option<&git::Info>) -> Self {
+        Self {
+            folds: fold::regions(text, key.language),
+            brackets: brackets::scan(text),
+            changes: git.map(|info| info.markers(text)).unwrap_or_default(),
+            longest: text
+                .split('\n')
+                .map(|line| line.chars().count())
+                .max()
+                .unwrap_or_default(),
+            key,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Job {
+    key: Key,
+    cancel: Arc<AtomicBool>,
+}
+
+#[derive(Debug, Default)]
+pub struct Cache {
+    layouts: HashMap<usize, Layout>,
+    jobs: HashMap<usize, Job>,
+    seen: HashMap<usize, Key>,
+}
+
+impl Cache {
+    pub fn get(&self, buffer: usize) -> Option<&Layout> {
+        self.layouts.get(&buffer)
+    }
+
+    pub fn is_stale(&self, buffer: usize, key: &Key) -> bool {
+        self.layouts
+            .get(&buffer)
+            .is_none_or(|layout| layout.key != *key)
+            && !self.jobs.get(&buffer).is_some_and(|job| job.key == *key)
+    }
+
+    pub fn is_settled(&mut self, buffer: usize, key: &Key) -> bool {
+        self.seen.insert(buffer, key.clone()).as_ref() == Some(key)
+    }
+
+    pub fn insert(&mut self, buffer: usize, layout: Layout) {
+        if let Some(job) = self.jobs.remove(&buffer) {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+        self.layouts.insert(buffer, layout);
+    }
+
+    pub fn start(&mut self, buffer: usize, key: Key) -> Arc<AtomicBool> {
+        let cancel = Arc::new(AtomicBool::new(false));
+        let job = Job {
+            key,
+            cancel: cancel.clone(),
+        };
+        if let Some(superseded) = self.jobs.insert(buffer, job) {
+            superseded.cancel.store(true, Ordering::Relaxed);
+        }
+        cancel
+    }
+
+    pub fn finish(&mut self, buffer: usize, layout: Layout) {
+        if self
+            .jobs
+            .get(&buffer)
+            .is_some_and(|job| job.key == layout.key)
+        {
+            self.jobs.remove(&buffer);
+            self.layouts.insert(buffer, layout);
+        }
+    }
+
+    pub fn wipe(&mut self, buffer: usize) {
+        if let Some(layout) = self.layouts.get_mut(&buffer) {
+            layout.brackets.fill((0, '\0'));
+        }
+        self.remove(buffer);
+    }
+
+    pub fn remove(&mut self, buffer: usize) {
+        if let Some(job) = self.jobs.remove(&buffer) {
+            job.cancel.store(true, Ordering::Relaxed);
+        }
+        self.layouts.remove(&buffer);
+        self.seen.remove(&buffer);
+    }
+}
+
+pub async fn run<T: Send + 'static>(
+    cancel: Arc<AtomicBool>,
+    job: impl FnOnce() -> T + Send + 'static,
+) -> Option<T> {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        if cancel.load(Ordering::Relaxed) {
+            return;
+        }
+        let result = job();
+        if !cancel.load(Ordering::Relaxed) {
+            let _ = sender.send(result);
+        }
+    });
+    receiver.await.ok()
+}
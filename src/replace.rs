@@ -0,0 +1,130 @@
+use std::path::PathBuf;
+
+use regex::{NoExpand, Regex};
+
+const MAX_LINE_CHARS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Hunk {
+    pub line: usize,
+    pub before: String,
+    pub after: String,
+    pub selected: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct FilePreview {
+    pub path: PathBuf,
+    pub hunks: Vec<Hunk>,
+}
+
+impl FilePreview {
+    pub fn selected(&self) -> usize {
+        self.hunks.iter().filter(|hunk| hunk.selected).count()
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub files: usize,
+    pub replacements: usize,
+    pub skipped: Vec<PathBuf>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Replacement {
+    pub pattern: Regex,
+    pub text: String,
+    pub expand: bool,
+}
+
+impl Replacement {
+    fn apply(&self, line: &str) -> String {
+        if self.expand {
+            self.pattern
+                .replace_all(line, self.text.as_str())
+                .into_owned()
+        } else {
+            self.pattern
+                .replace_all(line, NoExpand(&self.text))
+                .into_owned()
+        }
+    }
+}
+
+pub fn shorten(line: &str) -> String {
+    line.trim().chars().take(MAX_LINE_CHARS).collect()
+}
+
+pub async fn preview(paths: Vec<PathBuf>, replacement: Replacement) -> Vec<FilePreview> {
+    let mut previews = Vec::new();
+    for path in paths {
+        let Ok(text) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+        let hunks: Vec<Hunk> = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| replacement.pattern.is_match(line))
+            .map(|(index, line)| Hunk {
+                line: index + 1,
+                before: line.to_owned(),
+                after: replacement.apply(line),
+                selected: true,
+            })
+            .filter(|hunk| hunk.before != hunk.after)
+            .collect();
+        if !hunks.is_empty() {
+            previews.push(FilePreview { path, hunks });
+        }
+    }
+    previews
+}
+
+pub async fn apply(
+    previews: Vec<FilePreview>,
+    replacement: Replacement,
+) -> Result<Summary, String> {
+    let mut summary = Summary::default();
+    let mut staged = Vec::new();
+    for preview in previews
+        .into_iter()
+        .filter(|preview| preview.selected() > 0)
+    {
+        let text = tokio::fs::read_to_string(&preview.path)
+            .await
+            .map_err(|error| format!("{}: {error}", preview.path.display()))?;
+        let mut hunks = preview.hunks.iter().filter(|hunk| hunk.selected).peekable();
+        let mut replaced = String::with_capacity(text.len());
+        let mut count = 0;
+        let mut is_stale = false;
+        for (index, line) in text.split_inclusive('\n').enumerate() {
+            let Some(hunk) = hunks.next_if(|hunk| hunk.line == index + 1) else {
+                replaced.push_str(line);
+                continue;
+            };
+            let body = line.trim_end_matches('\n');
+            let body = body.strip_suffix('\r').unwrap_or(body);
+            if body != hunk.before {
+                is_stale = true;
+                break;
+            }
+            replaced.push_str(&replacement.apply(body));
+            replaced.push_str(&line[body.len()..]);
+            count += 1;
+        }
+        if is_stale || hunks.next().is_some() {
+            summary.skipped.push(preview.path);
+            continue;
+        }
+        summary.files += 1;
+        summary.replacements += count;
+        staged.push((preview.path, replaced));
+    }
+    for (path, text) in staged {
+        crate::write_atomically(&path, text.as_bytes(), true)
+            .await
+            .map_err(|error| format!("{}: {error}", path.display()))?;
+    }
+    Ok(summary)
+}
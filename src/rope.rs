@@ -0,0 +1,230 @@
+use std::ops::Range;
+
+use iced::widget::text_editor;
+use ropey::{Rope, RopeBuilder};
+
+use crate::encoding::{self, TextEncoding};
+use crate::line_ending::LineEnding;
+
+const MAX_CHANGES: usize = 64;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub revision: u64,
+    pub bytes: Range<usize>,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Pending {
+    first: usize,
+    last: usize,
+    line_count: usize,
+}
+
+#[derive(Debug, Default)]
+pub struct Snapshot {
+    revision: Option<u64>,
+    rope: Rope,
+    changes: Vec<Change>,
+}
+
+impl Snapshot {
+    pub fn sync(&mut self, content: &text_editor::Content, revision: u64) {
+        if self.revision == Some(revision) && self.rope.len_lines() == content.line_count() {
+            return;
+        }
+        let mut builder = RopeBuilder::new();
+        for (index, line) in content.lines().enumerate() {
+            if index > 0 {
+                builder.append("\n");
+            }
+            builder.append(&line);
+        }
+        self.rope = builder.finish();
+        self.revision = Some(revision);
+        self.changes.clear();
+    }
+
+    pub fn rope(&self) -> &Rope {
+        &self.rope
+    }
+
+    pub fn revision(&self) -> Option<u64> {
+        self.revision
+    }
+
+    pub fn changes_since(&self, revision: u64) -> Option<&[Change]> {
+        if self.revision == Some(revision) {
+            return Some(&[]);
+        }
+        let first = self
+            .changes
+            .iter()
+            .position(|change| change.revision == revision + 1)?;
+        let changes = &self.changes[first..];
+        let is_contiguous = changes
+            .iter()
+            .zip(revision + 1..)
+            .all(|(change, expected)| change.revision == expected);
+        (is_contiguous && changes.last().map(|change| change.revision) == self.revision)
+            .then_some(changes)
+    }
+
+    pub fn wipe(&mut self, content: &text_editor::Content) {
+        self.revision = None;
+        self.sync(content, 0);
+        self.changes.clear();
+    }
+
+    pub fn begin(&self, content: &text_editor::Content, revision: u64) -> Option<Pending> {
+        if self.revision != Some(revision) {
+            return None;
+        }
+        let (line, _) = content.cursor_position();
+        let spanned = content
+            .selection()
+            .map_or(0, |selection| selection.matches('\n').count());
+        let line_count = content.line_count();
+        Some(Pending {
+            first: line.saturating_sub(spanned + 1),
+            last: (line + spanned + 1).min(line_count - 1),
+            line_count,
+        })
+    }
+
+    pub fn finish(&mut self, content: &text_editor::Content, pending: Pending, revision: u64) {
+        let line_count = content.line_count();
+        let last = (pending.last + line_count).checked_sub(pending.line_count);
+        let Some(last) = last.filter(|last| *last >= pending.first) else {
+            self.revision = None;
+            return;
+        };
+        if self.rope.len_lines() != pending.line_count {
+            self.revision = None;
+            return;
+        }
+        let start = self.rope.line_to_byte(pending.first);
+        let end = if pending.last + 1 < self.rope.len_lines() {
+            self.rope.line_to_byte(pending.last + 1)
+        } else {
+            self.rope.len_bytes()
+        };
+        let mut replacement = String::new();
+        for (index, line) in content
+            .lines()
+            .skip(pending.first)
+            .take(last + 1 - pending.first)
+            .enumerate()
+        {
+            if index > 0 {
+                replacement.push('\n');
+            }
+            replacement.push_str(&line);
+        }
+        if last + 1 < line_count {
+            replacement.push('\n');
+        }
+        let chars = self.rope.byte_to_char(start)..self.rope.byte_to_char(end);
+        self.rope.remove(chars.clone());
+        self.rope.insert(chars.start, &replacement);
+        if self.changes.len() == MAX_CHANGES {
+            self.changes.remove(0);
+        }
+        self.changes.push(Change {
+            revision,
+            bytes: start..end,
+            len: replacement.len(),
+        });
+        self.revision = Some(revision);
+    }
+}
+
+pub fn offset(rope: &Rope, line: usize, column: usize) -> usize {
+    if line >= rope.len_lines() {
+        return rope.len_bytes();
+    }
+    rope.char_to_byte((rope.line_to_char(line) + column).min(rope.len_chars()))
+}
+
+pub fn position(rope: &Rope, offset: usize) -> (usize, usize) {
+    let char = rope.byte_to_char(offset.min(rope.len_bytes()));
+    let line = rope.char_to_line(char);
+    (line, char - rope.line_to_char(line))
+}
+
+pub fn encode(
+    rope: &Rope,
+    line_ending: LineEnding,
+    encoding: TextEncoding,
+    bom: bool,
+) -> (Vec<u8>, bool) {
+    encoding::encode_chunks(
+        rope.chunks().map(|chunk| line_ending.apply(chunk)),
+        encoding,
+        bom,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::widget::text_editor::{Action, Content, Edit};
+    use ropey::Rope;
+
+    use super::{Change, Snapshot, encode, offset, position};
+    use crate::encoding::TextEncoding;
+    use crate::line_ending::LineEnding;
+
+    #[test]
+    fn applies_edits_incrementally() {
+        let mut content = Content::with("a\nb\nc");
+        let mut snapshot = Snapshot::default();
+        snapshot.sync(&content, 0);
+        let pending = snapshot.begin(&content, 0).unwrap();
+        content.edit(Action::Edit(Edit::Insert('x')));
+        snapshot.finish(&content, pending, 1);
+        assert_eq!(snapshot.rope().to_string(), "xa\nb\nc");
+        assert_eq!(snapshot.revision(), Some(1));
+        assert_eq!(
+            snapshot.changes_since(0),
+            Some(
+                &[Change {
+                    revision: 1,
+                    bytes: 0..4,
+                    len: 5,
+                }][..]
+            )
+        );
+        assert_eq!(snapshot.changes_since(1), Some(&[][..]));
+        assert_eq!(snapshot.changes_since(7), None);
+    }
+
+    #[test]
+    fn rebuilds_when_the_revision_is_unknown() {
+        let content = Content::with("one\ntwo");
+        let mut snapshot = Snapshot::default();
+        snapshot.sync(&content, 3);
+        assert!(snapshot.begin(&content, 2).is_none());
+        assert_eq!(snapshot.rope().to_string(), "one\ntwo");
+        assert_eq!(snapshot.changes_since(2), None);
+    }
+
+    #[test]
+    fn converts_between_offsets_and_positions() {
+        let rope = Rope::from_str("ab\ncé\nd");
+        assert_eq!(offset(&rope, 1, 1), 4);
+        assert_eq!(offset(&rope, 1, 2), 6);
+        assert_eq!(offset(&rope, 9, 0), rope.len_bytes());
+        assert_eq!(position(&rope, 6), (1, 2));
+        assert_eq!(position(&rope, 100), (2, 1));
+    }
+
+    #[test]
+    fn encodes_with_the_line_ending() {
+        let rope = Rope::from_str("a\nb");
+        assert_eq!(
+            encode(&rope, LineEnding::CrLf, TextEncoding::default(), false),
+            (b"a\r\nb".to_vec(), false)
+        );
+    }
+}
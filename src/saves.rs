@@ -0,0 +1,76 @@
+use std::collections::HashSet;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::Hasher;
+use std::path::Path;
+use std::time::SystemTime;
+
+use serde::{Deserialize, Deserializer, Serializer};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Stamp {
+    pub modified: Option<SystemTime>,
+    pub len: u64,
+    pub hash: u64,
+}
+
+impl Stamp {
+    pub fn new(bytes: &[u8], modified: Option<SystemTime>) -> Self {
+        let mut hasher = DefaultHasher::new();
+        hasher.write(bytes);
+        Self {
+            modified,
+            len: bytes.len() as u64,
+            hash: hasher.finish(),
+        }
+    }
+}
+
+pub fn serialize_hash<S: Serializer>(hash: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&format!("{hash:016x}"))
+}
+
+pub fn deserialize_hash<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let hash = String::deserialize(deserializer)?;
+    u64::from_str_radix(&hash, 16).map_err(serde::de::Error::custom)
+}
+
+pub async fn is_unchanged(path: &Path, expected: Stamp) -> bool {
+    let Ok(metadata) = tokio::fs::metadata(path).await else {
+        return true;
+    };
+    let modified = metadata.modified().ok();
+    if modified.is_some() && modified == expected.modified && metadata.len() == expected.len {
+        return true;
+    }
+    match tokio::fs::read(path).await {
+        Ok(bytes) => Stamp::new(&bytes, None).hash == expected.hash,
+        Err(_) => true,
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Queue {
+    in_flight: HashSet<usize>,
+    queued: HashSet<usize>,
+}
+
+impl Queue {
+    pub fn start(&mut self, buffer: usize) -> bool {
+        if self.in_flight.insert(buffer) {
+            true
+        } else {
+            self.queued.insert(buffer);
+            false
+        }
+    }
+
+    pub fn finish(&mut self, buffer: usize) -> bool {
+        self.in_flight.remove(&buffer);
+        self.queued.remove(&buffer)
+    }
+
+    pub fn cancel(&mut self, buffer: usize) {
+        self.in_flight.remove(&buffer);
+        self.queued.remove(&buffer);
+    }
+}
@@ -0,0 +1,40 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Scratch {
+    pub buffers: Vec<String>,
+}
+
+fn scratch_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("scratch.toml"))
+}
+
+pub async fn load() -> Scratch {
+    let Some(path) = scratch_file() else {
+        return Scratch::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(scratch: Scratch) -> Result<(), Error> {
+    let path = scratch_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents =
+        toml::to_string(&scratch).map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
@@ -0,0 +1,413 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use regex::{NoExpand, Regex, RegexBuilder};
+use regex_syntax::hir::{Class, Hir, HirKind, Literal};
+use ropey::Rope;
+
+use crate::rope::{Change, Snapshot};
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    pub case_sensitive: bool,
+    pub whole_word: bool,
+    pub regex: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Search {
+    pub query: String,
+    pub replacement: String,
+    pub options: Options,
+    pub is_replacing: bool,
+    pub current: Option<Range<usize>>,
+    pub error: Option<String>,
+    pattern: Option<Regex>,
+    spans_lines: bool,
+    cache: Option<Cache>,
+}
+
+#[derive(Debug)]
+struct Cache {
+    buffer: usize,
+    revision: u64,
+    matches: Vec<Range<usize>>,
+}
+
+pub struct Highlight {
+    pub row: usize,
+    pub columns: Range<usize>,
+    pub is_current: bool,
+}
+
+fn source(query: &str, options: Options) -> String {
+    let source = if options.regex {
+        query.to_owned()
+    } else {
+        regex::escape(query)
+    };
+    if options.whole_word {
+        format!(r"\b(?:{source})\b")
+    } else {
+        source
+    }
+}
+
+pub fn compile(query: &str, options: Options) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(&source(query, options))
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+}
+
+fn spans_lines(query: &str, options: Options) -> bool {
+    regex_syntax::ParserBuilder::new()
+        .case_insensitive(!options.case_sensitive)
+        .multi_line(true)
+        .build()
+        .parse(&source(query, options))
+        .map(|hir| matches_newline(&hir))
+        .unwrap_or(true)
+}
+
+fn matches_newline(hir: &Hir) -> bool {
+    match hir.kind() {
+        HirKind::Empty | HirKind::Look(_) => false,
+        HirKind::Literal(Literal(bytes)) => bytes.contains(&b'\n'),
+        HirKind::Class(Class::Unicode(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| (range.start()..=range.end()).contains(&'\n')),
+        HirKind::Class(Class::Bytes(class)) => class
+            .ranges()
+            .iter()
+            .any(|range| (range.start()..=range.end()).contains(&b'\n')),
+        HirKind::Repetition(repetition) => matches_newline(&repetition.sub),
+        HirKind::Capture(capture) => matches_newline(&capture.sub),
+        HirKind::Concat(hirs) | HirKind::Alternation(hirs) => hirs.iter().any(matches_newline),
+    }
+}
+
+fn find(pattern: &Regex, text: &str, offset: usize) -> impl Iterator<Item = Range<usize>> {
+    pattern
+        .find_iter(text)
+        .map(move |found| found.start() + offset..found.end() + offset)
+        .filter(|range| !range.is_empty())
+}
+
+impl Search {
+    pub fn new(query: String, is_replacing: bool) -> Self {
+        let mut search = Self {
+            is_replacing,
+            ..Self::default()
+        };
+        search.set_query(query);
+        search
+    }
+
+    pub fn set_query(&mut self, query: String) {
+        self.query = query;
+        self.compile();
+    }
+
+    pub fn set_options(&mut self, options: Options) {
+        self.options = options;
+        self.compile();
+    }
+
+    fn compile(&mut self) {
+        self.pattern = None;
+        self.cache = None;
+        self.error = None;
+        self.current = None;
+        if self.query.is_empty() {
+            return;
+        }
+        match compile(&self.query, self.options) {
+            Ok(pattern) => {
+                self.pattern = Some(pattern);
+                self.spans_lines = spans_lines(&self.query, self.options);
+            }
+            Err(error) => self.error = Some(error.to_string()),
+        }
+    }
+
+    pub fn matches(&self, rope: &Rope) -> Vec<Range<usize>> {
+        let Some(pattern) = &self.pattern else {
+            return Vec::new();
+        };
+        if self.spans_lines {
+            return find(pattern, &rope.to_string(), 0).collect();
+        }
+        self.matches_in(pattern, rope, 0..rope.len_lines())
+    }
+
+    fn matches_in(&self, pattern: &Regex, rope: &Rope, lines: Range<usize>) -> Vec<Range<usize>> {
+        let mut offset = rope.line_to_byte(lines.start);
+        let mut matches = Vec::new();
+        for line in rope.lines_at(lines.start).take(lines.len()) {
+            let line = Cow::from(line);
+            matches.extend(find(pattern, &line, offset));
+            offset += line.len();
+        }
+        matches
+    }
+
+    pub fn refresh(&mut self, buffer: usize, snapshot: &Snapshot) {
+        let Some(revision) = snapshot.revision() else {
+            return;
+        };
+        let cache = self.cache.take().filter(|cache| cache.buffer == buffer);
+        let matches = match cache {
+            Some(cache) if cache.revision == revision => cache.matches,
+            Some(cache) => match (&self.pattern, snapshot.changes_since(cache.revision)) {
+                (Some(pattern), Some(changes)) if !self.spans_lines => {
+                    self.update(pattern, cache.matches, snapshot.rope(), changes)
+                }
+                _ => self.matches(snapshot.rope()),
+            },
+            None => self.matches(snapshot.rope()),
+        };
+        self.cache = Some(Cache {
+            buffer,
+            revision,
+            matches,
+        });
+    }
+
+    fn update(
+        &self,
+        pattern: &Regex,
+        mut matches: Vec<Range<usize>>,
+        rope: &Rope,
+        changes: &[Change],
+    ) -> Vec<Range<usize>> {
+        let mut dirty: Option<Range<usize>> = None;
+        for change in changes {
+            let removed = &change.bytes;
+            let inserted = removed.start..removed.start + change.len;
+            let delta = change.len as isize - removed.len() as isize;
+            let shift = |offset: usize, inside: usize| {
+                if offset >= removed.end {
+                    offset.saturating_add_signed(delta)
+                } else if offset <= removed.start {
+                    offset
+                } else {
+                    inside
+                }
+            };
+            matches.retain(|range| range.end <= removed.start || range.start >= removed.end);
+            for range in &mut matches {
+                *range = shift(range.start, 0)..shift(range.end, 0);
+            }
+            dirty = Some(match dirty {
+                Some(dirty) => {
+                    shift(dirty.start, inserted.start).min(inserted.start)
+                        ..shift(dirty.end, inserted.end).max(inserted.end)
+                }
+                None => inserted,
+            });
+        }
+        let Some(dirty) = dirty else {
+            return matches;
+        };
+        let first = rope.byte_to_line(dirty.start.min(rope.len_bytes()));
+        let last = rope.byte_to_line(dirty.end.min(rope.len_bytes()));
+        let lines = first..(last + 1).min(rope.len_lines());
+        let start = rope.line_to_byte(lines.start);
+        let end = rope.line_to_byte(lines.end);
+        let index = matches.partition_point(|range| range.end <= start);
+        let after = matches.partition_point(|range| range.start < end);
+        matches.splice(index..after, self.matches_in(pattern, rope, lines));
+        matches
+    }
+
+    pub fn cached(&self, buffer: usize, revision: u64) -> Option<&[Range<usize>]> {
+        self.cache
+            .as_ref()
+            .filter(|cache| cache.buffer == buffer && cache.revision == revision)
+            .map(|cache| cache.matches.as_slice())
+    }
+
+    pub fn position(&self, matches: &[Range<usize>]) -> Option<usize> {
+        let current = self.current.as_ref()?;
+        matches
+            .binary_search_by_key(&current.start, |range| range.start)
+            .ok()
+            .map(|index| index + 1)
+    }
+
+    pub fn next_after(&self, offset: usize) -> Option<Range<usize>> {
+        let matches = self.cache.as_ref().map_or(&[][..], |cache| &cache.matches);
+        let index = matches.partition_point(|range| range.start < offset);
+        matches.get(index).or(matches.first()).cloned()
+    }
+
+    pub fn previous_before(&self, offset: usize) -> Option<Range<usize>> {
+        let matches = self.cache.as_ref().map_or(&[][..], |cache| &cache.matches);
+        let index = matches.partition_point(|range| range.start < offset);
+        index
+            .checked_sub(1)
+            .and_then(|index| matches.get(index))
+            .or(matches.last())
+            .cloned()
+    }
+
+    pub fn expand(&self, rope: &Rope, range: &Range<usize>) -> String {
+        if !self.options.regex {
+            return self.replacement.clone();
+        }
+        let (text, start) = if self.spans_lines {
+            (Cow::from(rope.to_string()), 0)
+        } else {
+            let line = rope.byte_to_line(range.start.min(rope.len_bytes()));
+            (Cow::from(rope.line(line)), rope.line_to_byte(line))
+        };
+        let Some(captures) = self
+            .pattern
+            .as_ref()
+            .and_then(|pattern| pattern.captures_at(&text, range.start - start))
+        else {
+            return self.replacement.clone();
+        };
+        let mut expanded = String::new();
+        captures.expand(&self.replacement, &mut expanded);
+        expanded
+    }
+
+    pub fn replace_all(&self, text: &str) -> Option<String> {
+        let pattern = self.pattern.as_ref()?;
+        let replaced = if self.options.regex {
+            pattern.replace_all(text, self.replacement.as_str())
+        } else {
+            pattern.replace_all(text, NoExpand(&self.replacement))
+        };
+        Some(replaced.into_owned())
+    }
+}
+
+pub fn visible(
+    matches: &[Range<usize>],
+    current: Option<&Range<usize>>,
+    text: &str,
+    top: usize,
+    rows: usize,
+) -> Vec<Highlight> {
+    let mut line_start: usize = text.split('\n').take(top).map(|line| line.len() + 1).sum();
+    let mut highlights = Vec::new();
+    for (row, line) in text.split('\n').skip(top).take(rows).enumerate() {
+        let line_end = line_start + line.len();
+        let first = matches.partition_point(|range| range.end <= line_start);
+        for range in matches[first..]
+            .iter()
+            .take_while(|range| range.start <= line_end)
+        {
+            let start = range.start.max(line_start) - line_start;
+            let end = range.end.min(line_end) - line_start;
+            let (Some(before), Some(inside)) = (line.get(..start), line.get(start..end)) else {
+                continue;
+            };
+            let column = before.chars().count();
+            highlights.push(Highlight {
+                row,
+                columns: column..column + inside.chars().count().max(1),
+                is_current: current == Some(range),
+            });
+        }
+        line_start = line_end + 1;
+    }
+    highlights
+}
+
+#[cfg(test)]
+mod tests {
+    use iced::widget::text_editor::{Action, Content, Edit, Motion};
+    use ropey::Rope;
+
+    use super::{Options, Search};
+    use crate::rope::Snapshot;
+
+    fn search(query: &str, options: Options) -> Search {
+        let mut search = Search::new(query.to_owned(), false);
+        search.set_options(options);
+        search
+    }
+
+    #[test]
+    fn finds_matches_on_every_line() {
+        let rope = Rope::from_str("Foo foo\nbar\nfood");
+        assert_eq!(
+            search("foo", Options::default()).matches(&rope),
+            [0..3, 4..7, 12..15]
+        );
+        let whole = Options {
+            case_sensitive: true,
+            whole_word: true,
+            ..Options::default()
+        };
+        assert_eq!(search("foo", whole).matches(&rope), [4..7]);
+    }
+
+    #[test]
+    fn searches_across_lines_only_when_needed() {
+        let regex = Options {
+            regex: true,
+            ..Options::default()
+        };
+        assert!(!search("a.b", regex).spans_lines);
+        assert!(search(r"a\nb", regex).spans_lines);
+        assert!(search(r"a\sb", regex).spans_lines);
+        let rope = Rope::from_str("a\nb");
+        assert_eq!(search(r"a\nb", regex).matches(&rope), [0..3]);
+    }
+
+    #[test]
+    fn refreshes_matches_incrementally() {
+        let mut content = Content::with("one x\ntwo\nx three");
+        let mut snapshot = Snapshot::default();
+        snapshot.sync(&content, 0);
+        let mut search = search("x", Options::default());
+        search.refresh(7, &snapshot);
+        assert_eq!(search.cached(7, 0), Some(&[4..5, 10..11][..]));
+
+        content.edit(Action::Move(Motion::Down));
+        let pending = snapshot.begin(&content, 0).unwrap();
+        content.edit(Action::Edit(Edit::Insert('x')));
+        snapshot.finish(&content, pending, 1);
+        search.refresh(7, &snapshot);
+        let expected = search.matches(snapshot.rope());
+        assert_eq!(expected, [4..5, 6..7, 11..12]);
+        assert_eq!(search.cached(7, 1), Some(&expected[..]));
+        assert_eq!(search.cached(7, 0), None);
+    }
+
+    #[test]
+    fn wraps_around_when_stepping_through_matches() {
+        let mut snapshot = Snapshot::default();
+        snapshot.sync(&Content::with("ab ab ab"), 0);
+        let mut search = search("ab", Options::default());
+        search.refresh(0, &snapshot);
+        assert_eq!(search.next_after(1), Some(3..5));
+        assert_eq!(search.next_after(7), Some(0..2));
+        assert_eq!(search.previous_before(3), Some(0..2));
+        assert_eq!(search.previous_before(0), Some(6..8));
+    }
+
+    #[test]
+    fn expands_captures_only_for_regex_searches() {
+        let rope = Rope::from_str("key = value");
+        let mut regex = search(
+            r"(\w+) = (\w+)",
+            Options {
+                regex: true,
+                ..Options::default()
+            },
+        );
+        regex.replacement = String::from("$2 = $1");
+        assert_eq!(regex.expand(&rope, &(0..11)), "value = key");
+        assert_eq!(regex.replace_all("a = b").as_deref(), Some("b = a"));
+        let mut literal = search("$1", Options::default());
+        literal.replacement = String::from("$0");
+        assert_eq!(literal.replace_all("cost $1").as_deref(), Some("cost $0"));
+    }
+}
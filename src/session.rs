@@ -0,0 +1,48 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Session {
+    pub files: Vec<SessionFile>,
+    pub active: Option<PathBuf>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionFile {
+    pub path: PathBuf,
+    pub line: usize,
+    pub column: usize,
+}
+
+fn session_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("session.toml"))
+}
+
+pub async fn load() -> Session {
+    let Some(path) = session_file() else {
+        return Session::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(session: Session) -> Result<(), Error> {
+    let path = session_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents =
+        toml::to_string(&session).map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
@@ -0,0 +1,222 @@
+use std::collections::BTreeMap;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::Deserialize;
+
+use crate::Error;
+use crate::complete::is_word;
+use crate::language::Language;
+
+const GLOBAL: &str = "global";
+
+#[derive(Debug, Clone, Default, PartialEq, Deserialize)]
+#[serde(transparent)]
+pub struct Snippets(BTreeMap<String, BTreeMap<String, String>>);
+
+impl Snippets {
+    pub fn find(&self, language: Language, before: &str) -> Option<(&str, &str)> {
+        [language.token(), GLOBAL]
+            .into_iter()
+            .filter_map(|table| self.0.get(table))
+            .flatten()
+            .filter(|(prefix, _)| {
+                !prefix.is_empty()
+                    && before.strip_suffix(prefix.as_str()).is_some_and(|rest| {
+                        !rest.ends_with(is_word) || !prefix.starts_with(is_word)
+                    })
+            })
+            .max_by_key(|(prefix, _)| prefix.len())
+            .map(|(prefix, body)| (prefix.as_str(), body.as_str()))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Expansion {
+    pub text: String,
+    pub stops: Vec<Range<usize>>,
+}
+
+pub fn expand(body: &str, indent: &str) -> Expansion {
+    let mut text = String::new();
+    let mut numbered: Vec<(usize, Range<usize>)> = Vec::new();
+    let mut chars = body.chars().peekable();
+    let push = |text: &mut String, c: char| {
+        text.push(c);
+        if c == '\n' {
+            text.push_str(indent);
+        }
+    };
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if matches!(chars.peek(), Some('$' | '}' | '\\')) => {
+                push(&mut text, chars.next().unwrap_or(c));
+            }
+            '$' => {
+                let braced = chars.next_if_eq(&'{').is_some();
+                let mut digits = String::new();
+                while let Some(digit) = chars.next_if(char::is_ascii_digit) {
+                    digits.push(digit);
+                }
+                let Ok(number) = digits.parse() else {
+                    text.push('$');
+                    if braced {
+                        text.push('{');
+                    }
+                    text.push_str(&digits);
+                    continue;
+                };
+                let start = text.len();
+                if braced && chars.next_if_eq(&':').is_some() {
+                    while let Some(c) = chars.next_if(|c| *c != '}') {
+                        match c {
+                            '\\' => push(&mut text, chars.next().unwrap_or(c)),
+                            c => push(&mut text, c),
+                        }
+                    }
+                }
+                if braced {
+                    chars.next_if_eq(&'}');
+                }
+                numbered.push((number, start..text.len()));
+            }
+            c => push(&mut text, c),
+        }
+    }
+    numbered.sort_by_key(|(number, _)| (*number == 0, *number));
+    numbered.dedup_by_key(|(number, _)| *number);
+    let has_final = numbered.iter().any(|(number, _)| *number == 0);
+    let mut stops: Vec<_> = numbered.into_iter().map(|(_, range)| range).collect();
+    if !has_final {
+        stops.push(text.len()..text.len());
+    }
+    Expansion { text, stops }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Session {
+    pub stops: Vec<Range<usize>>,
+    pub current: usize,
+}
+
+impl Session {
+    pub fn new(start: usize, expansion: &Expansion) -> Self {
+        Self {
+            stops: expansion
+                .stops
+                .iter()
+                .map(|stop| start + stop.start..start + stop.end)
+                .collect(),
+            current: 0,
+        }
+    }
+
+    pub fn stop(&self) -> Option<Range<usize>> {
+        self.stops.get(self.current).cloned()
+    }
+
+    pub fn is_last(&self) -> bool {
+        self.current + 1 >= self.stops.len()
+    }
+
+    pub fn edited(&mut self, delta: isize, cursor: usize) -> bool {
+        let Some(current) = self.stop() else {
+            return false;
+        };
+        let Some(end) = current.end.checked_add_signed(delta) else {
+            return false;
+        };
+        if end < current.start || !(current.start..=end).contains(&cursor) {
+            return false;
+        }
+        for (index, stop) in self.stops.iter_mut().enumerate() {
+            if index == self.current {
+                stop.end = end;
+            } else if stop.start >= current.end {
+                stop.start = stop.start.saturating_add_signed(delta);
+                stop.end = stop.end.saturating_add_signed(delta);
+            }
+        }
+        true
+    }
+}
+
+pub fn snippets_file() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("iced_editor").join("snippets.toml"))
+}
+
+pub async fn load() -> Result<Snippets, Error> {
+    let Some(path) = snippets_file() else {
+        return Ok(Snippets::default());
+    };
+    match tokio::fs::read_to_string(path).await {
+        Ok(contents) => {
+            toml::from_str(&contents).map_err(|error| Error::InvalidSnippets(error.to_string()))
+        }
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Snippets::default()),
+        Err(error) => Err(Error::IOFailed(error.kind())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Expansion, Session, Snippets, expand};
+    use crate::language::Language;
+
+    #[test]
+    fn expands_placeholders_in_order_with_the_final_stop_last() {
+        let expansion = expand("fn ${1:name}($2) {\n\t$0\n}", "    ");
+        assert_eq!(expansion.text, "fn name() {\n    \t\n    }");
+        assert_eq!(expansion.stops, [3..7, 8..8, 17..17]);
+    }
+
+    #[test]
+    fn ends_at_the_text_without_a_final_stop() {
+        assert_eq!(expand("hello", "").stops, [5..5]);
+        let repeated = expand("$1 $1", "");
+        assert_eq!(repeated.text, " ");
+        assert_eq!(repeated.stops, [0..0, 1..1]);
+    }
+
+    #[test]
+    fn keeps_escapes_and_malformed_placeholders_as_text() {
+        let escaped = expand(r"\$1 \} ${2:a\}b}", "");
+        assert_eq!(escaped.text, "$1 } a}b");
+        assert_eq!(escaped.stops, [5..8, 8..8]);
+        assert_eq!(expand("$x ${y}", "").text, "$x ${y}");
+    }
+
+    #[test]
+    fn finds_the_longest_prefix_at_a_word_boundary() {
+        let snippets: Snippets = toml::from_str(
+            "[rs]\nfn = \"fn $0\"\n[global]\ntodo = \"// TODO: $0\"\n\"->\" = \"→\"\n",
+        )
+        .unwrap();
+        assert_eq!(
+            snippets.find(Language::Rust, "let fn"),
+            Some(("fn", "fn $0"))
+        );
+        assert_eq!(snippets.find(Language::Rust, "xfn"), None);
+        assert_eq!(snippets.find(Language::Rust, "a->"), Some(("->", "→")));
+        assert_eq!(snippets.find(Language::Python, "fn"), None);
+        assert_eq!(
+            snippets.find(Language::Python, "todo"),
+            Some(("todo", "// TODO: $0"))
+        );
+    }
+
+    #[test]
+    fn shifts_later_stops_while_editing_the_current_one() {
+        let expansion = Expansion {
+            text: String::from("name(, )"),
+            stops: vec![0..4, 6..6, 9..9],
+        };
+        let mut session = Session::new(10, &expansion);
+        assert_eq!(session.stop(), Some(10..14));
+        assert!(session.edited(2, 15));
+        assert_eq!(session.stops, [10..16, 18..18, 21..21]);
+        assert!(!session.edited(-10, 12));
+        assert!(!session.edited(1, 30));
+        assert!(!session.is_last());
+    }
+}
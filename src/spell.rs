@@ -0,0 +1,400 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::ops::Range;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use regex::Regex;
+
+use crate::Error;
+use crate::language::Language;
+
+const DICTIONARIES: &[&str] = &[
+    "/usr/share/hunspell/en_US.dic",
+    "/usr/share/myspell/en_US.dic",
+    "/usr/share/myspell/dicts/en_US.dic",
+    "/Library/Spelling/en_US.dic",
+];
+const MAX_SUGGESTIONS: usize = 6;
+
+#[derive(Debug, Default)]
+pub struct Dictionary {
+    words: HashSet<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct Spelling {
+    pub dictionary: Option<Arc<Dictionary>>,
+    pub personal: BTreeSet<String>,
+    pub prompt: Option<Prompt>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Prompt {
+    pub buffer: usize,
+    pub range: Range<usize>,
+    pub word: String,
+    pub suggestions: Vec<String>,
+}
+
+impl Spelling {
+    pub fn applies_to(language: Language) -> bool {
+        matches!(language, Language::PlainText | Language::Markdown)
+    }
+
+    pub fn is_known(&self, word: &str) -> bool {
+        let Some(dictionary) = &self.dictionary else {
+            return true;
+        };
+        let lower = word.to_lowercase();
+        let capitalized = capitalize(&lower);
+        dictionary.words.contains(word)
+            || self.personal.contains(word)
+            || self.personal.contains(&lower)
+            || ((word == capitalized || word.chars().all(char::is_uppercase))
+                && (dictionary.words.contains(&lower) || dictionary.words.contains(&capitalized)))
+    }
+
+    pub fn misspelled(
+        &self,
+        text: &str,
+        language: Language,
+        lines: Range<usize>,
+    ) -> Vec<(usize, Range<usize>)> {
+        if self.dictionary.is_none() {
+            return Vec::new();
+        }
+        let mut in_fence = false;
+        let mut marks = Vec::new();
+        for (index, line) in text.split('\n').enumerate().take(lines.end) {
+            if language == Language::Markdown && line.trim_start().starts_with("```") {
+                in_fence = !in_fence;
+                continue;
+            }
+            if index < lines.start || in_fence {
+                continue;
+            }
+            marks.extend(
+                words(line, language)
+                    .filter(|range| !self.is_known(&line[range.clone()]))
+                    .map(|range| (index, range)),
+            );
+        }
+        marks
+    }
+
+    pub fn suggestions(&self, word: &str) -> Vec<String> {
+        let lower = word.to_lowercase();
+        let first = edits(&lower);
+        let mut found: Vec<String> = first
+            .iter()
+            .filter(|candidate| self.is_known(candidate))
+            .cloned()
+            .collect();
+        if found.is_empty() {
+            found = first
+                .iter()
+                .flat_map(|candidate| edits(candidate))
+                .filter(|candidate| self.is_known(candidate))
+                .collect();
+        }
+        let mut unique: Vec<String> = Vec::new();
+        for candidate in found {
+            if !unique.contains(&candidate) {
+                unique.push(candidate);
+            }
+        }
+        unique.truncate(MAX_SUGGESTIONS);
+        let restore_case = |suggestion: String| {
+            if word.chars().count() > 1 && word.chars().all(char::is_uppercase) {
+                suggestion.to_uppercase()
+            } else if word.starts_with(char::is_uppercase) {
+                capitalize(&suggestion)
+            } else {
+                suggestion
+            }
+        };
+        unique.into_iter().map(restore_case).collect()
+    }
+}
+
+pub fn word_at(line: &str, language: Language, column: usize) -> Option<Range<usize>> {
+    words(line, language).find(|range| range.start <= column && column <= range.end)
+}
+
+fn words(line: &str, language: Language) -> impl Iterator<Item = Range<usize>> + '_ {
+    let mut in_code = false;
+    let mut start = None;
+    let mut ranges = Vec::new();
+    let mut skip_until = 0;
+    for (index, c) in line.char_indices().chain([(line.len(), ' ')]) {
+        if index < skip_until {
+            continue;
+        }
+        if language == Language::Markdown && c == '`' {
+            in_code = !in_code;
+        }
+        let is_letter = c.is_alphabetic()
+            || c == '\'' && start.is_some() && line[index + 1..].starts_with(char::is_alphabetic);
+        match (is_letter && !in_code, start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                start = None;
+                let chunk_end = line[index..]
+                    .find(char::is_whitespace)
+                    .map_or(line.len(), |end| index + end);
+                let chunk_start = line[..from]
+                    .rfind(char::is_whitespace)
+                    .map_or(0, |start| start + 1);
+                let chunk = &line[chunk_start..chunk_end];
+                if chunk.contains("://")
+                    || chunk.contains(['@', '_'])
+                    || chunk.contains(|c: char| c.is_ascii_digit())
+                {
+                    skip_until = chunk_end;
+                } else if line[from..index].chars().count() > 1 {
+                    ranges.push(from..index);
+                }
+            }
+            _ => {}
+        }
+    }
+    ranges.into_iter()
+}
+
+fn edits(word: &str) -> Vec<String> {
+    let chars: Vec<char> = word.chars().collect();
+    let mut edits = Vec::new();
+    let splice = |at: usize, remove: usize, insert: Option<char>| {
+        chars[..at]
+            .iter()
+            .chain(insert.as_ref())
+            .chain(&chars[(at + remove).min(chars.len())..])
+            .collect::<String>()
+    };
+    for at in 0..chars.len() {
+        edits.push(splice(at, 1, None));
+        if at + 1 < chars.len() {
+            let mut swapped = chars.clone();
+            swapped.swap(at, at + 1);
+            edits.push(swapped.into_iter().collect());
+        }
+    }
+    for letter in 'a'..='z' {
+        for at in 0..chars.len() {
+            edits.push(splice(at, 1, Some(letter)));
+        }
+        for at in 0..=chars.len() {
+            edits.push(splice(at, 0, Some(letter)));
+        }
+    }
+    edits
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
+
+struct Affix {
+    is_prefix: bool,
+    cross: bool,
+    strip: String,
+    add: String,
+    condition: Option<Regex>,
+}
+
+impl Affix {
+    fn apply(&self, word: &str) -> Option<String> {
+        if self
+            .condition
+            .as_ref()
+            .is_some_and(|condition| !condition.is_match(word))
+        {
+            return None;
+        }
+        if self.is_prefix {
+            let rest = word.strip_prefix(self.strip.as_str())?;
+            Some(format!("{}{rest}", self.add))
+        } else {
+            let rest = word.strip_suffix(self.strip.as_str())?;
+            Some(format!("{rest}{}", self.add))
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FlagStyle {
+    Char,
+    Long,
+    Number,
+}
+
+impl FlagStyle {
+    fn split(self, flags: &str) -> Vec<String> {
+        match self {
+            FlagStyle::Char => flags.chars().map(String::from).collect(),
+            FlagStyle::Long => flags
+                .chars()
+                .collect::<Vec<_>>()
+                .chunks(2)
+                .map(|pair| pair.iter().collect())
+                .collect(),
+            FlagStyle::Number => flags.split(',').map(str::to_owned).collect(),
+        }
+    }
+}
+
+fn decode(bytes: &[u8], label: Option<&str>) -> String {
+    let encoding = label
+        .and_then(|label| encoding_rs::Encoding::for_label(label.as_bytes()))
+        .unwrap_or(encoding_rs::UTF_8);
+    encoding.decode(bytes).0.into_owned()
+}
+
+fn parse_affixes(aff: &str) -> (FlagStyle, HashMap<String, Vec<Affix>>) {
+    let mut style = FlagStyle::Char;
+    let mut affixes: HashMap<String, Vec<Affix>> = HashMap::new();
+    let mut cross: HashMap<String, bool> = HashMap::new();
+    let mut conditions: HashMap<(bool, String), Option<Regex>> = HashMap::new();
+    for line in aff.lines() {
+        let fields: Vec<&str> = line.split_whitespace().collect();
+        match fields.as_slice() {
+            ["FLAG", "long", ..] => style = FlagStyle::Long,
+            ["FLAG", "num", ..] => style = FlagStyle::Number,
+            [kind @ ("PFX" | "SFX"), flag, combines, count]
+                if count.parse::<usize>().is_ok() && combines.len() == 1 =>
+            {
+                cross.insert(format!("{kind}{flag}"), *combines == "Y");
+            }
+            [kind @ ("PFX" | "SFX"), flag, strip, add, condition, ..] => {
+                let is_prefix = *kind == "PFX";
+                let condition = conditions
+                    .entry((is_prefix, condition.to_string()))
+                    .or_insert_with(|| {
+                        if *condition == "." {
+                            return None;
+                        }
+                        let pattern = if is_prefix {
+                            format!("^{condition}")
+                        } else {
+                            format!("{condition}$")
+                        };
+                        Regex::new(&pattern).ok()
+                    })
+                    .clone();
+                let zero = |value: &str| {
+                    if value == "0" {
+                        String::new()
+                    } else {
+                        value.to_owned()
+                    }
+                };
+                let add = add.split('/').next().unwrap_or_default();
+                affixes.entry(flag.to_string()).or_default().push(Affix {
+                    is_prefix,
+                    cross: cross
+                        .get(&format!("{kind}{flag}"))
+                        .copied()
+                        .unwrap_or(false),
+                    strip: zero(*strip),
+                    add: zero(add),
+                    condition,
+                });
+            }
+            _ => {}
+        }
+    }
+    (style, affixes)
+}
+
+fn parse(dic: &str, aff: &str) -> Dictionary {
+    let (style, affixes) = parse_affixes(aff);
+    let mut words = HashSet::new();
+    for line in dic.lines().skip(1) {
+        let entry = line.split(['\t', ' ']).next().unwrap_or_default();
+        let (word, flags) = entry.split_once('/').unwrap_or((entry, ""));
+        if word.is_empty() {
+            continue;
+        }
+        let rules: Vec<&Affix> = style
+            .split(flags)
+            .iter()
+            .filter_map(|flag| affixes.get(flag))
+            .flatten()
+            .collect();
+        let mut suffixed = Vec::new();
+        for rule in rules.iter().filter(|rule| !rule.is_prefix) {
+            if let Some(form) = rule.apply(word) {
+                if rule.cross {
+                    suffixed.push(form.clone());
+                }
+                words.insert(form);
+            }
+        }
+        for rule in rules.iter().filter(|rule| rule.is_prefix) {
+            if let Some(form) = rule.apply(word) {
+                words.insert(form);
+            }
+            if rule.cross {
+                words.extend(suffixed.iter().filter_map(|form| rule.apply(form)));
+            }
+        }
+        words.insert(word.to_owned());
+    }
+    Dictionary { words }
+}
+
+fn personal_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("words.txt"))
+}
+
+pub async fn load(path: Option<PathBuf>) -> Result<(Arc<Dictionary>, Vec<String>), Error> {
+    let path = path
+        .or_else(|| {
+            DICTIONARIES
+                .iter()
+                .map(PathBuf::from)
+                .find(|path| path.exists())
+        })
+        .ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let dic = tokio::fs::read(&path)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    let aff = tokio::fs::read(path.with_extension("aff"))
+        .await
+        .unwrap_or_default();
+    let aff_text = String::from_utf8_lossy(&aff);
+    let label = aff_text
+        .lines()
+        .find_map(|line| line.strip_prefix("SET "))
+        .map(str::trim);
+    let dictionary = parse(&decode(&dic, label), &decode(&aff, label));
+    let personal = match personal_file() {
+        Some(file) => tokio::fs::read_to_string(file).await.unwrap_or_default(),
+        None => String::new(),
+    };
+    let personal = personal
+        .lines()
+        .map(str::trim)
+        .filter(|word| !word.is_empty())
+        .map(str::to_owned)
+        .collect();
+    Ok((Arc::new(dictionary), personal))
+}
+
+pub async fn save_personal(words: Vec<String>) -> Result<(), Error> {
+    let path = personal_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    let mut contents = words.join("\n");
+    contents.push('\n');
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
@@ -0,0 +1,84 @@
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::Error;
+use crate::appearance::Appearance;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct State {
+    pub path_display: PathDisplay,
+    pub typewriter: bool,
+    pub appearance: Appearance,
+    pub trusted_commands: Vec<String>,
+    pub spell_check: bool,
+    pub minimap: bool,
+    pub whitespace: bool,
+}
+
+impl State {
+    pub fn trust(&mut self, command: String) {
+        if !self.trusted_commands.contains(&command) {
+            self.trusted_commands.push(command);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathDisplay {
+    #[default]
+    Absolute,
+    Relative,
+    FileName,
+}
+
+impl PathDisplay {
+    pub fn next(self) -> Self {
+        match self {
+            PathDisplay::Absolute => PathDisplay::Relative,
+            PathDisplay::Relative => PathDisplay::FileName,
+            PathDisplay::FileName => PathDisplay::Absolute,
+        }
+    }
+
+    pub fn format(self, path: &Path, root: Option<&Path>) -> String {
+        let shown = match self {
+            PathDisplay::Absolute => path,
+            PathDisplay::Relative => root
+                .and_then(|root| path.strip_prefix(root).ok())
+                .unwrap_or(path),
+            PathDisplay::FileName => path.file_name().map(Path::new).unwrap_or(path),
+        };
+        shown.display().to_string()
+    }
+}
+
+fn state_file() -> Option<PathBuf> {
+    dirs::data_dir().map(|dir| dir.join("iced_editor").join("state.toml"))
+}
+
+pub async fn load() -> State {
+    let Some(path) = state_file() else {
+        return State::default();
+    };
+    tokio::fs::read_to_string(path)
+        .await
+        .ok()
+        .and_then(|contents| toml::from_str(&contents).ok())
+        .unwrap_or_default()
+}
+
+pub async fn save(state: State) -> Result<(), Error> {
+    let path = state_file().ok_or(Error::IOFailed(std::io::ErrorKind::NotFound))?;
+    let contents =
+        toml::to_string(&state).map_err(|_| Error::IOFailed(std::io::ErrorKind::InvalidData))?;
+    if let Some(dir) = path.parent() {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|error| Error::IOFailed(error.kind()))?;
+    }
+    tokio::fs::write(path, contents)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
@@ -0,0 +1,116 @@
+use std::borrow::Cow;
+use std::collections::HashSet;
+
+use iced::futures::channel::oneshot;
+use ropey::Rope;
+
+pub const SYNC_LIMIT: usize = 256 * 1024;
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Counts {
+    pub characters: usize,
+    pub words: usize,
+    pub lines: usize,
+    pub bytes: usize,
+    pub unique_words: usize,
+    pub longest_line: (usize, usize),
+}
+
+impl Counts {
+    pub fn new(text: &str) -> Self {
+        Self::count(text.split('\n'), text.chars().count(), text.len())
+    }
+
+    pub fn of_rope(rope: &Rope) -> Self {
+        let lines = rope.lines().map(Cow::from);
+        Self::count(lines, rope.len_chars(), rope.len_bytes())
+    }
+
+    fn count<S: AsRef<str>>(
+        lines: impl Iterator<Item = S>,
+        characters: usize,
+        bytes: usize,
+    ) -> Self {
+        let mut unique = HashSet::new();
+        let mut words = 0;
+        let mut count = 0;
+        let mut longest_line = (0, 0);
+        for (index, line) in lines.enumerate() {
+            let line = line.as_ref().trim_end_matches('\n');
+            count += 1;
+            let length = line.chars().count();
+            if length > longest_line.0 {
+                longest_line = (length, index + 1);
+            }
+            for word in line.split_whitespace() {
+                words += 1;
+                let word = word.trim_matches(|c: char| !c.is_alphanumeric());
+                if !word.is_empty() {
+                    unique.insert(word.to_lowercase());
+                }
+            }
+        }
+        Self {
+            characters,
+            words,
+            lines: count,
+            bytes,
+            unique_words: unique.len(),
+            longest_line,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Key {
+    pub buffer: usize,
+    pub revision: u64,
+    pub selection: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Report {
+    pub key: Key,
+    pub document: Counts,
+    pub selection: Option<Counts>,
+}
+
+impl Report {
+    pub fn new(key: Key, rope: &Rope) -> Self {
+        let selection = key.selection.as_deref().map(Counts::new);
+        Self {
+            document: Counts::of_rope(rope),
+            selection,
+            key,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub report: Option<Report>,
+    pub pending: Option<Key>,
+}
+
+impl Panel {
+    pub fn is_current(&self, key: &Key) -> bool {
+        self.pending.as_ref() == Some(key)
+            || self
+                .report
+                .as_ref()
+                .is_some_and(|report| report.key == *key)
+    }
+}
+
+pub async fn compute(key: Key, rope: Rope) -> Report {
+    let (sender, receiver) = oneshot::channel();
+    let fallback = key.clone();
+    std::thread::spawn(move || {
+        let _ = sender.send(Report::new(key, &rope));
+    });
+    receiver.await.unwrap_or_else(|_| Report {
+        key: fallback,
+        document: Counts::default(),
+        selection: None,
+    })
+}
@@ -0,0 +1,311 @@
+use std::cmp::Ordering;
+use std::path::Path;
+
+use iced::widget::{button, column, row, scrollable, text, text_input};
+use iced::{Element, Font, Length, theme};
+
+use crate::Message;
+use crate::compression::Codec;
+
+const MAX_ROWS: usize = 1000;
+const MAX_COLUMN_CHARS: usize = 40;
+const CHAR_WIDTH: f32 = 8.5;
+
+pub fn delimiter(path: &Path) -> Option<char> {
+    if Codec::from_path(path).is_some() {
+        return delimiter(&path.with_extension(""));
+    }
+    match path.extension()?.to_str()? {
+        "csv" => Some(','),
+        "tsv" | "tab" => Some('\t'),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, Default)]
+struct Cell {
+    text: String,
+    quoted: bool,
+}
+
+#[derive(Debug, Clone)]
+pub struct Table {
+    pub delimiter: char,
+    rows: Vec<Vec<Cell>>,
+    trailing_newline: bool,
+    has_header: bool,
+    pub sort: Option<(usize, bool)>,
+    pub is_modified: bool,
+}
+
+impl Table {
+    pub fn parse(source: &str, delimiter: char) -> Self {
+        let mut rows = Vec::new();
+        let mut cells = Vec::new();
+        let mut cell = Cell::default();
+        let mut in_quotes = false;
+        let mut chars = source.chars().peekable();
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                if c != '"' {
+                    cell.text.push(c);
+                } else if chars.next_if_eq(&'"').is_some() {
+                    cell.text.push('"');
+                } else {
+                    in_quotes = false;
+                }
+            } else if c == '"' && cell.text.is_empty() && !cell.quoted {
+                in_quotes = true;
+                cell.quoted = true;
+            } else if c == delimiter {
+                cells.push(std::mem::take(&mut cell));
+            } else if c == '\n' {
+                cells.push(std::mem::take(&mut cell));
+                rows.push(std::mem::take(&mut cells));
+            } else {
+                cell.text.push(c);
+            }
+        }
+        if !cells.is_empty() || !cell.text.is_empty() || cell.quoted || rows.is_empty() {
+            cells.push(cell);
+            rows.push(cells);
+        }
+        let has_header = rows.len() > 1
+            && rows[0].iter().all(|cell| {
+                !cell.text.trim().is_empty() && cell.text.trim().parse::<f64>().is_err()
+            });
+        Self {
+            delimiter,
+            rows,
+            trailing_newline: source.ends_with('\n'),
+            has_header,
+            sort: None,
+            is_modified: false,
+        }
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut output = String::new();
+        for (index, cells) in self.rows.iter().enumerate() {
+            if index > 0 {
+                output.push('\n');
+            }
+            for (column, cell) in cells.iter().enumerate() {
+                if column > 0 {
+                    output.push(self.delimiter);
+                }
+                let needs_quotes = cell.quoted
+                    || cell
+                        .text
+                        .contains(|c| c == self.delimiter || c == '"' || c == '\n' || c == '\r');
+                if needs_quotes {
+                    output.push('"');
+                    output.push_str(&cell.text.replace('"', "\"\""));
+                    output.push('"');
+                } else {
+                    output.push_str(&cell.text);
+                }
+            }
+        }
+        if self.trailing_newline {
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn columns(&self) -> usize {
+        self.rows.iter().map(Vec::len).max().unwrap_or_default()
+    }
+
+    fn text(&self, row: usize, column: usize) -> &str {
+        self.rows
+            .get(row)
+            .and_then(|cells| cells.get(column))
+            .map_or("", |cell| cell.text.as_str())
+    }
+
+    pub fn set(&mut self, row: usize, column: usize, value: String) {
+        let Some(cells) = self.rows.get_mut(row) else {
+            return;
+        };
+        if cells.len() <= column {
+            cells.resize_with(column + 1, Cell::default);
+        }
+        cells[column].text = value;
+        self.is_modified = true;
+    }
+
+    pub fn sort_by(&mut self, column: usize) {
+        let ascending = !matches!(self.sort, Some((sorted, true)) if sorted == column);
+        let start = usize::from(self.has_header).min(self.rows.len());
+        self.rows[start..].sort_by(|a, b| {
+            let a = a.get(column).map_or("", |cell| cell.text.as_str());
+            let b = b.get(column).map_or("", |cell| cell.text.as_str());
+            let ordering = compare(a, b);
+            if ascending {
+                ordering
+            } else {
+                ordering.reverse()
+            }
+        });
+        self.sort = Some((column, ascending));
+        self.is_modified = true;
+    }
+
+    fn widths(&self) -> Vec<f32> {
+        (0..self.columns())
+            .map(|column| {
+                let chars = self
+                    .rows
+                    .iter()
+                    .take(MAX_ROWS)
+                    .map(|cells| {
+                        cells
+                            .get(column)
+                            .map_or(0, |cell| cell.text.chars().count())
+                    })
+                    .max()
+                    .unwrap_or_default()
+                    .clamp(4, MAX_COLUMN_CHARS);
+                chars as f32 * CHAR_WIDTH + 12.0
+            })
+            .collect()
+    }
+}
+
+fn compare(a: &str, b: &str) -> Ordering {
+    match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+        (Ok(a), Ok(b)) => a.total_cmp(&b),
+        (Ok(_), Err(_)) => Ordering::Less,
+        (Err(_), Ok(_)) => Ordering::Greater,
+        (Err(_), Err(_)) => a.to_lowercase().cmp(&b.to_lowercase()),
+    }
+}
+
+fn column_name(mut column: usize) -> String {
+    let mut name = String::new();
+    loop {
+        name.insert(0, char::from(b'A' + (column % 26) as u8));
+        if column < 26 {
+            return name;
+        }
+        column = column / 26 - 1;
+    }
+}
+
+pub fn view(table: &Table, is_readonly: bool, font: Font) -> Element<'_, Message> {
+    let widths = table.widths();
+    let number_width = Length::Fixed(50.0);
+    let header = row(std::iter::once(text("").width(number_width).into())
+        .chain(widths.iter().enumerate().map(|(column, width)| {
+            let arrow = match table.sort {
+                Some((sorted, true)) if sorted == column => " ▲",
+                Some((sorted, false)) if sorted == column => " ▼",
+                _ => "",
+            };
+            button(text(format!("{}{arrow}", column_name(column))).size(14))
+                .width(Length::Fixed(*width))
+                .padding(4)
+                .style(theme::Button::Secondary)
+                .on_press(Message::SortTable(column))
+                .into()
+        }))
+        .collect())
+    .spacing(2);
+    let rows = (0..table.rows.len().min(MAX_ROWS)).map(|index| {
+        let cells = widths.iter().enumerate().map(|(column, width)| {
+            let input = text_input("", table.text(index, column))
+                .font(font)
+                .size(14)
+                .padding(4)
+                .width(Length::Fixed(*width));
+            if is_readonly {
+                input.into()
+            } else {
+                input
+                    .on_input(move |value| Message::TableCellChanged(index, column, value))
+                    .into()
+            }
+        });
+        row(std::iter::once(
+            text(format!("{}", index + 1))
+                .size(14)
+                .width(number_width)
+                .into(),
+        )
+        .chain(cells)
+        .collect())
+        .spacing(2)
+        .align_items(iced::Alignment::Center)
+        .into()
+    });
+    let mut body = column(std::iter::once(header.into()).chain(rows).collect()).spacing(2);
+    if table.rows.len() > MAX_ROWS {
+        body = body.push(
+            text(format!(
+                "Showing the first {MAX_ROWS} of {} rows",
+                table.rows.len()
+            ))
+            .size(14),
+        );
+    }
+    scrollable(body.padding(10))
+        .direction(scrollable::Direction::Both {
+            vertical: scrollable::Properties::default(),
+            horizontal: scrollable::Properties::default(),
+        })
+        .width(Length::Fill)
+        .height(Length::Fill)
+        .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{Table, column_name, delimiter};
+
+    #[test]
+    fn picks_the_delimiter_from_the_extension() {
+        assert_eq!(delimiter(Path::new("a.csv")), Some(','));
+        assert_eq!(delimiter(Path::new("a.tsv")), Some('\t'));
+        assert_eq!(delimiter(Path::new("a.csv.gz")), Some(','));
+        assert_eq!(delimiter(Path::new("a.txt")), None);
+    }
+
+    #[test]
+    fn round_trips_quoted_cells() {
+        let source = "name,note\nAl,\"a, b\"\nBo,\"say \"\"hi\"\"\"\n";
+        let table = Table::parse(source, ',');
+        assert!(table.has_header);
+        assert_eq!(table.columns(), 2);
+        assert_eq!(table.text(1, 1), "a, b");
+        assert_eq!(table.text(2, 1), "say \"hi\"");
+        assert_eq!(table.to_text(), source);
+        assert!(!Table::parse("1,2\n3,4", ',').has_header);
+    }
+
+    #[test]
+    fn sorts_numbers_before_text_below_the_header() {
+        let mut table = Table::parse("name,n\nb,10\na,9\nc,x", ',');
+        table.sort_by(1);
+        assert_eq!(table.to_text(), "name,n\na,9\nb,10\nc,x");
+        table.sort_by(1);
+        assert_eq!(table.sort, Some((1, false)));
+        assert_eq!(table.to_text(), "name,n\nc,x\nb,10\na,9");
+    }
+
+    #[test]
+    fn widens_rows_when_setting_a_new_column() {
+        let mut table = Table::parse("a,b", ',');
+        table.set(0, 3, String::from("z"));
+        assert!(table.is_modified);
+        assert_eq!(table.to_text(), "a,b,,z");
+    }
+
+    #[test]
+    fn names_columns_like_a_spreadsheet() {
+        let names: Vec<String> = [0, 25, 26, 27, 701, 702].map(column_name).into();
+        assert_eq!(names, ["A", "Z", "AA", "AB", "ZZ", "AAA"]);
+    }
+}
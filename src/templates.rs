@@ -0,0 +1,95 @@
+use std::path::PathBuf;
+use std::time::SystemTime;
+
+const BUILTIN: &[(&str, &str)] = &[
+    (
+        "module.rs",
+        "//! {{name}}, created {{date}}.\n\n#[cfg(test)]\nmod tests {\n    use super::*;\n}\n",
+    ),
+    (
+        "Cargo.toml",
+        "[package]\nname = \"\"\nversion = \"0.1.0\"\nedition = \"2024\"\nauthors = [\"{{author}}\"]\n\n[dependencies]\n",
+    ),
+    (
+        "README.md",
+        "# {{name}}\n\n_Created {{date}} by {{author}}._\n\n## Usage\n\n",
+    ),
+    (
+        "LICENSE",
+        "MIT License\n\nCopyright (c) {{year}} {{author}}\n\nPermission is hereby granted, free of charge, to any person obtaining a copy\nof this software and associated documentation files (the \"Software\"), to deal\nin the Software without restriction, including without limitation the rights\nto use, copy, modify, merge, publish, distribute, sublicense, and/or sell\ncopies of the Software, and to permit persons to whom the Software is\nfurnished to do so, subject to the following conditions:\n\nThe above copyright notice and this permission notice shall be included in all\ncopies or substantial portions of the Software.\n\nTHE SOFTWARE IS PROVIDED \"AS IS\", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR\nIMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,\nFITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE\nAUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER\nLIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,\nOUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE\nSOFTWARE.\n",
+    ),
+];
+
+#[derive(Debug, Clone)]
+pub struct Template {
+    pub file_name: String,
+    pub body: String,
+}
+
+impl Template {
+    pub fn expand(&self, author: Option<&str>) -> String {
+        let (year, month, day) = today();
+        let name = self.file_name.split('.').next().unwrap_or(&self.file_name);
+        self.body
+            .replace("{{date}}", &format!("{year:04}-{month:02}-{day:02}"))
+            .replace("{{year}}", &year.to_string())
+            .replace("{{filename}}", &self.file_name)
+            .replace("{{name}}", name)
+            .replace("{{author}}", author.unwrap_or_default())
+    }
+}
+
+fn templates_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("iced_editor").join("templates"))
+}
+
+pub async fn load() -> Vec<Template> {
+    let mut templates: Vec<Template> = BUILTIN
+        .iter()
+        .map(|(file_name, body)| Template {
+            file_name: file_name.to_string(),
+            body: body.to_string(),
+        })
+        .collect();
+    let Some(dir) = templates_dir() else {
+        return templates;
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(dir).await else {
+        return templates;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        let Some(file_name) = path.file_name().and_then(|name| name.to_str()) else {
+            continue;
+        };
+        let Ok(body) = tokio::fs::read_to_string(&path).await else {
+            continue;
+        };
+        let file_name = file_name.to_owned();
+        templates.retain(|template| template.file_name != file_name);
+        templates.push(Template { file_name, body });
+    }
+    templates.sort_by(|a, b| a.file_name.to_lowercase().cmp(&b.file_name.to_lowercase()));
+    templates
+}
+
+fn today() -> (i64, u32, u32) {
+    let days = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs() / 86_400) as i64;
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_index = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+    let month = if month_index < 10 {
+        month_index + 3
+    } else {
+        month_index - 9
+    } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
@@ -0,0 +1,343 @@
+use std::fmt;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+
+use iced::futures::{SinkExt, StreamExt, channel::mpsc};
+use iced::widget::{
+    button, column, container, horizontal_space, row, scrollable, text, text_input,
+};
+use iced::{Element, Font, Length, Subscription, subscription, theme};
+use portable_pty::{ChildKiller, CommandBuilder, MasterPty, PtySize, native_pty_system};
+
+use crate::Message;
+
+pub const OUTPUT: &str = "terminal-output";
+pub const INPUT: &str = "terminal-input";
+const MAX_LINES: usize = 2000;
+const HEIGHT: f32 = 220.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pending {
+    Shell,
+    Run(usize),
+}
+
+#[derive(Clone)]
+pub struct Handle {
+    writer: Arc<Mutex<Box<dyn Write + Send>>>,
+    killer: Arc<Mutex<Box<dyn ChildKiller + Send + Sync>>>,
+    _master: Arc<Mutex<Box<dyn MasterPty + Send>>>,
+}
+
+impl fmt::Debug for Handle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Handle")
+    }
+}
+
+impl Handle {
+    pub fn send(&self, input: &str) {
+        if let Ok(mut writer) = self.writer.lock() {
+            let _ = writer.write_all(input.as_bytes());
+            let _ = writer.flush();
+        }
+    }
+
+    pub fn kill(&self) {
+        if let Ok(mut killer) = self.killer.lock() {
+            let _ = killer.kill();
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Escape {
+    #[default]
+    None,
+    Start,
+    Charset,
+    Csi,
+    Osc,
+    OscEnd,
+}
+
+#[derive(Debug, Default)]
+pub struct Terminal {
+    pub session: usize,
+    pub cwd: PathBuf,
+    pub handle: Option<Handle>,
+    pub input: String,
+    pub queued: Option<String>,
+    pub status: Option<String>,
+    lines: Vec<String>,
+    current: Vec<char>,
+    column: usize,
+    escape: Escape,
+    parameters: String,
+}
+
+impl Terminal {
+    pub fn new(session: usize, cwd: PathBuf) -> Self {
+        Self {
+            session,
+            cwd,
+            ..Self::default()
+        }
+    }
+
+    pub fn send(&self, input: &str) {
+        if let Some(handle) = &self.handle {
+            handle.send(input);
+        }
+    }
+
+    pub fn close(&self) {
+        if let Some(handle) = &self.handle {
+            handle.kill();
+        }
+    }
+
+    pub fn feed(&mut self, output: &str) {
+        for c in output.chars() {
+            match self.escape {
+                Escape::None => self.print(c),
+                Escape::Start => {
+                    self.escape = match c {
+                        '[' => Escape::Csi,
+                        ']' => Escape::Osc,
+                        '(' | ')' => Escape::Charset,
+                        _ => Escape::None,
+                    };
+                    self.parameters.clear();
+                }
+                Escape::Charset => self.escape = Escape::None,
+                Escape::Csi if ('@'..='~').contains(&c) => {
+                    self.escape = Escape::None;
+                    self.control(c);
+                }
+                Escape::Csi => self.parameters.push(c),
+                Escape::Osc => match c {
+                    '\u{7}' => self.escape = Escape::None,
+                    '\u{1b}' => self.escape = Escape::OscEnd,
+                    _ => {}
+                },
+                Escape::OscEnd => {
+                    self.escape = if c == '\\' { Escape::None } else { Escape::Osc };
+                }
+            }
+        }
+    }
+
+    fn print(&mut self, c: char) {
+        match c {
+            '\u{1b}' => self.escape = Escape::Start,
+            '\n' => {
+                self.lines.push(self.current.drain(..).collect());
+                self.column = 0;
+                if self.lines.len() > MAX_LINES {
+                    self.lines.drain(..self.lines.len() - MAX_LINES);
+                }
+            }
+            '\r' => self.column = 0,
+            '\u{8}' => self.column = self.column.saturating_sub(1),
+            '\t' => {
+                let next = (self.column / 8 + 1) * 8;
+                while self.column < next {
+                    self.put(' ');
+                }
+            }
+            c if c.is_control() => {}
+            c => self.put(c),
+        }
+    }
+
+    fn put(&mut self, c: char) {
+        if self.column < self.current.len() {
+            self.current[self.column] = c;
+        } else {
+            self.current.push(c);
+        }
+        self.column += 1;
+    }
+
+    fn control(&mut self, command: char) {
+        match command {
+            'K' => {
+                let parameter = self.parameters.trim_start_matches('?');
+                match parameter {
+                    "" | "0" => self.current.truncate(self.column),
+                    "1" => self.current[..self.column.min(self.current.len())].fill(' '),
+                    _ => self.current.clear(),
+                }
+            }
+            'J' if matches!(self.parameters.as_str(), "2" | "3") => {
+                self.lines.clear();
+                self.current.clear();
+                self.column = 0;
+            }
+            'D' => {
+                let count = self.parameters.parse().unwrap_or(1);
+                self.column = self.column.saturating_sub(count);
+            }
+            'C' => {
+                let count = self.parameters.parse().unwrap_or(1);
+                self.column = (self.column + count).min(self.current.len());
+            }
+            'G' => {
+                let column: usize = self.parameters.parse().unwrap_or(1);
+                self.column = column.saturating_sub(1).min(self.current.len());
+            }
+            _ => {}
+        }
+    }
+
+    fn screen(&self) -> String {
+        let mut screen = self.lines.join("\n");
+        if !self.lines.is_empty() {
+            screen.push('\n');
+        }
+        screen.extend(&self.current);
+        screen
+    }
+}
+
+pub fn quote(path: &Path) -> String {
+    let path = path.display().to_string();
+    if cfg!(windows) {
+        format!("\"{path}\"")
+    } else {
+        format!("'{}'", path.replace('\'', "'\\''"))
+    }
+}
+
+pub fn run(session: usize, shell: Option<String>, cwd: PathBuf) -> Subscription<Message> {
+    subscription::channel(("terminal", session), 100, move |mut output| async move {
+        match spawn(shell.as_deref(), &cwd) {
+            Ok((handle, mut chunks)) => {
+                let _ = output.send(Message::TerminalStarted(session, handle)).await;
+                while let Some(chunk) = chunks.next().await {
+                    let _ = output.send(Message::TerminalOutput(session, chunk)).await;
+                }
+                let _ = output
+                    .send(Message::TerminalExited(
+                        session,
+                        String::from("process exited"),
+                    ))
+                    .await;
+            }
+            Err(error) => {
+                let _ = output.send(Message::TerminalExited(session, error)).await;
+            }
+        }
+        std::future::pending().await
+    })
+}
+
+fn spawn(
+    shell: Option<&str>,
+    cwd: &Path,
+) -> Result<(Handle, mpsc::UnboundedReceiver<String>), String> {
+    let pair = native_pty_system()
+        .openpty(PtySize {
+            rows: 24,
+            cols: 120,
+            pixel_width: 0,
+            pixel_height: 0,
+        })
+        .map_err(|error| error.to_string())?;
+    let mut command = match shell {
+        Some(shell) => CommandBuilder::new(shell),
+        None => CommandBuilder::new_default_prog(),
+    };
+    command.cwd(cwd);
+    command.env("TERM", "dumb");
+    let mut child = pair
+        .slave
+        .spawn_command(command)
+        .map_err(|error| error.to_string())?;
+    drop(pair.slave);
+    let mut reader = pair
+        .master
+        .try_clone_reader()
+        .map_err(|error| error.to_string())?;
+    let writer = pair
+        .master
+        .take_writer()
+        .map_err(|error| error.to_string())?;
+    let handle = Handle {
+        writer: Arc::new(Mutex::new(writer)),
+        killer: Arc::new(Mutex::new(child.clone_killer())),
+        _master: Arc::new(Mutex::new(pair.master)),
+    };
+    let (sender, chunks) = mpsc::unbounded();
+    std::thread::spawn(move || {
+        let mut buffer = [0; 4096];
+        let mut pending = Vec::new();
+        while let Ok(read) = reader.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            pending.extend_from_slice(&buffer[..read]);
+            let valid = match std::str::from_utf8(&pending) {
+                Ok(valid) => valid.len(),
+                Err(error) if error.error_len().is_none() => error.valid_up_to(),
+                Err(_) => pending.len(),
+            };
+            let chunk = String::from_utf8_lossy(&pending[..valid]).into_owned();
+            pending.drain(..valid);
+            if !chunk.is_empty() && sender.unbounded_send(chunk).is_err() {
+                break;
+            }
+        }
+        let _ = child.wait();
+    });
+    Ok((handle, chunks))
+}
+
+pub fn view(terminal: &Terminal, font: Font) -> Element<'_, Message> {
+    let header = row!(
+        text("Terminal").size(14),
+        text(terminal.status.clone().unwrap_or_default()).size(14),
+        horizontal_space(Length::Fill),
+        button(text("Ctrl+C").size(14))
+            .style(theme::Button::Secondary)
+            .on_press_maybe(
+                terminal
+                    .handle
+                    .is_some()
+                    .then_some(Message::TerminalInterrupt)
+            ),
+        button(text("Restart").size(14))
+            .style(theme::Button::Secondary)
+            .on_press(Message::RestartTerminal),
+        button(text("×"))
+            .style(theme::Button::Text)
+            .on_press(Message::ToggleTerminal),
+    )
+    .spacing(10)
+    .align_items(iced::Alignment::Center);
+    let screen = scrollable(
+        text(terminal.screen())
+            .font(font)
+            .size(14)
+            .width(Length::Fill),
+    )
+    .id(scrollable::Id::new(OUTPUT))
+    .height(HEIGHT);
+    let input = text_input("Type a command and press Enter", &terminal.input)
+        .id(text_input::Id::new(INPUT))
+        .font(font)
+        .size(14)
+        .padding(4);
+    let input = match terminal.handle {
+        Some(_) => input
+            .on_input(Message::TerminalInputChanged)
+            .on_submit(Message::TerminalSubmit),
+        None => input,
+    };
+    container(column!(header, screen, input).spacing(5))
+        .padding(10)
+        .style(theme::Container::Box)
+        .into()
+}
@@ -0,0 +1,81 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+pub const DURATION: Duration = Duration::from_secs(4);
+const SHOWN: usize = 3;
+const HISTORY: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Level {
+    Info,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Info => "info",
+            Level::Warning => "warning",
+            Level::Error => "error",
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Toast {
+    pub id: u64,
+    pub level: Level,
+    pub message: String,
+    pub at: Instant,
+    pub dismissed: bool,
+}
+
+impl Toast {
+    pub fn age(&self) -> String {
+        match self.at.elapsed().as_secs() {
+            0..=59 => String::from("just now"),
+            seconds @ 60..=3599 => format!("{}m ago", seconds / 60),
+            seconds => format!("{}h ago", seconds / 3600),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Toasts {
+    pub history: VecDeque<Toast>,
+    next_id: u64,
+}
+
+impl Toasts {
+    pub fn push(&mut self, level: Level, message: impl Into<String>) {
+        self.next_id += 1;
+        self.history.push_front(Toast {
+            id: self.next_id,
+            level,
+            message: message.into(),
+            at: Instant::now(),
+            dismissed: false,
+        });
+        self.history.truncate(HISTORY);
+    }
+
+    pub fn active(&self) -> impl Iterator<Item = &Toast> {
+        self.history
+            .iter()
+            .take_while(|toast| toast.at.elapsed() < DURATION)
+            .filter(|toast| !toast.dismissed)
+            .take(SHOWN)
+    }
+
+    pub fn is_showing(&self) -> bool {
+        self.active().next().is_some()
+    }
+
+    pub fn dismiss(&mut self, id: u64) {
+        if let Some(toast) = self.history.iter_mut().find(|toast| toast.id == id) {
+            toast.dismissed = true;
+        }
+    }
+}
@@ -0,0 +1,163 @@
+use std::path::{Path, PathBuf};
+
+use iced::futures::channel::oneshot;
+use iced::futures::{SinkExt, StreamExt, channel::mpsc};
+use iced::{Subscription, subscription};
+use ropey::Rope;
+
+use crate::grep::{self, Hit};
+use crate::{Message, large};
+
+pub const SYNC_LIMIT: usize = 256 * 1024;
+const TAGS: [&str; 3] = ["TODO", "FIXME", "HACK"];
+const COMMENT_MARKERS: [&str; 6] = ["//", "#", "/*", "--", ";", "*"];
+const MAX_TEXT_CHARS: usize = 200;
+
+#[derive(Debug, Clone)]
+pub struct Annotation {
+    pub line: usize,
+    pub tag: &'static str,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Key {
+    pub buffer: usize,
+    pub revision: u64,
+}
+
+#[derive(Debug, Default)]
+pub struct Panel {
+    pub key: Option<Key>,
+    pub pending: Option<Key>,
+    pub annotations: Vec<Annotation>,
+    pub root: PathBuf,
+    pub workspace: Option<Vec<Hit>>,
+    pub scanning: Option<usize>,
+    scans: usize,
+}
+
+impl Panel {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            ..Self::default()
+        }
+    }
+
+    pub fn is_current(&self, key: Key) -> bool {
+        self.pending == Some(key) || self.key == Some(key)
+    }
+
+    pub fn scan_workspace(&mut self) {
+        self.scans += 1;
+        self.scanning = Some(self.scans);
+        self.workspace = Some(Vec::new());
+    }
+
+    pub fn rescan(&mut self, path: &Path, text: &str) {
+        let Some(hits) = &mut self.workspace else {
+            return;
+        };
+        if !path.starts_with(&self.root) {
+            return;
+        }
+        hits.retain(|hit| hit.path != path);
+        hits.extend(scan(text.split('\n')).into_iter().map(|annotation| Hit {
+            path: path.to_path_buf(),
+            line: annotation.line,
+            text: annotation.text,
+        }));
+    }
+}
+
+pub fn scan<S: AsRef<str>>(lines: impl Iterator<Item = S>) -> Vec<Annotation> {
+    lines
+        .enumerate()
+        .filter_map(|(index, line)| {
+            let line = line.as_ref();
+            let (start, tag) = tag(line)?;
+            Some(Annotation {
+                line: index + 1,
+                tag,
+                text: line[start..]
+                    .trim_end()
+                    .trim_end_matches("*/")
+                    .trim_end_matches("-->")
+                    .trim_end()
+                    .chars()
+                    .take(MAX_TEXT_CHARS)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn tag(line: &str) -> Option<(usize, &'static str)> {
+    let comment = COMMENT_MARKERS
+        .iter()
+        .filter_map(|marker| line.find(marker))
+        .min()?;
+    TAGS.iter()
+        .filter_map(|tag| {
+            let start = comment + line[comment..].find(tag)?;
+            let before = line[..start].chars().next_back();
+            let after = line[start + tag.len()..].chars().next();
+            let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+            (!is_word(before) && !is_word(after)).then_some((start, *tag))
+        })
+        .min_by_key(|(start, _)| *start)
+}
+
+pub async fn compute(key: Key, rope: Rope) -> (Key, Vec<Annotation>) {
+    let (sender, receiver) = oneshot::channel();
+    std::thread::spawn(move || {
+        let _ = sender.send(scan(rope.lines().map(|line| line.to_string())));
+    });
+    (key, receiver.await.unwrap_or_default())
+}
+
+pub fn search(id: usize, root: PathBuf) -> Subscription<Message> {
+    subscription::channel(("todos", id), 100, move |mut output| async move {
+        let (sender, mut hits) = mpsc::unbounded();
+        std::thread::spawn(move || walk(root, sender));
+        while let Some(hits) = hits.next().await {
+            let _ = output.send(Message::TodosFound(id, hits)).await;
+        }
+        let _ = output.send(Message::TodosFinished(id)).await;
+        std::future::pending().await
+    })
+}
+
+fn walk(root: PathBuf, sender: mpsc::UnboundedSender<Vec<Hit>>) {
+    let mut found = 0;
+    for entry in ignore::WalkBuilder::new(root).build().flatten() {
+        if sender.is_closed() || found >= grep::MAX_HITS {
+            return;
+        }
+        let is_small_file = entry
+            .file_type()
+            .is_some_and(|file_type| file_type.is_file())
+            && entry
+                .metadata()
+                .is_ok_and(|metadata| metadata.len() <= large::STREAM_THRESHOLD);
+        if !is_small_file {
+            continue;
+        }
+        let Ok(text) = std::fs::read_to_string(entry.path()) else {
+            continue;
+        };
+        let hits: Vec<_> = scan(text.lines())
+            .into_iter()
+            .map(|annotation| Hit {
+                path: entry.path().to_path_buf(),
+                line: annotation.line,
+                text: annotation.text,
+            })
+            .collect();
+        found += hits.len();
+        if !hits.is_empty() && sender.unbounded_send(hits).is_err() {
+            return;
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use std::fmt;
+
+use crate::indent::Indent;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Options {
+    pub trim_trailing_whitespace: bool,
+    pub final_newline: bool,
+    pub normalize_indentation: bool,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Report {
+    pub trimmed: usize,
+    pub reindented: usize,
+    pub final_newline: bool,
+}
+
+impl Report {
+    pub fn is_empty(&self) -> bool {
+        *self == Report::default()
+    }
+}
+
+impl fmt::Display for Report {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut changes = Vec::new();
+        if self.trimmed > 0 {
+            changes.push(format!("trimmed {} lines", self.trimmed));
+        }
+        if self.reindented > 0 {
+            changes.push(format!("reindented {} lines", self.reindented));
+        }
+        if self.final_newline {
+            changes.push(String::from("fixed final newline"));
+        }
+        f.write_str(&changes.join(", "))
+    }
+}
+
+pub fn apply(text: &str, options: Options, indent: Indent, tab_width: usize) -> (String, Report) {
+    let mut report = Report::default();
+    let mut lines: Vec<String> = text
+        .split('\n')
+        .map(|line| {
+            let mut line = line.to_owned();
+            if options.normalize_indentation {
+                let reindented = reindent(&line, indent, tab_width);
+                if reindented != line {
+                    report.reindented += 1;
+                    line = reindented;
+                }
+            }
+            if options.trim_trailing_whitespace && line.ends_with(char::is_whitespace) {
+                report.trimmed += 1;
+                line.truncate(line.trim_end().len());
+            }
+            line
+        })
+        .collect();
+    if options.final_newline {
+        let mut trailing = lines
+            .iter()
+            .rev()
+            .take_while(|line| line.is_empty())
+            .count();
+        if trailing != 1 && lines.len() > trailing {
+            report.final_newline = true;
+            while trailing > 1 {
+                lines.pop();
+                trailing -= 1;
+            }
+            if trailing == 0 {
+                lines.push(String::new());
+            }
+        }
+    }
+    (lines.join("\n"), report)
+}
+
+fn reindent(line: &str, indent: Indent, tab_width: usize) -> String {
+    let tab_width = tab_width.max(1);
+    let content = line.trim_start_matches([' ', '\t']);
+    let leading = &line[..line.len() - content.len()];
+    let width = leading.chars().fold(0, |width, c| match c {
+        '\t' => width + tab_width - width % tab_width,
+        _ => width + 1,
+    });
+    let leading = match indent {
+        Indent::Tabs => "\t".repeat(width / tab_width) + &" ".repeat(width % tab_width),
+        Indent::Spaces(_) => " ".repeat(width),
+    };
+    leading + content
+}
@@ -0,0 +1,62 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::history::Snapshot;
+
+pub const MAX_BYTES: usize = 4 * 1024 * 1024;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Saved {
+    pub path: PathBuf,
+    #[serde(
+        serialize_with = "crate::saves::serialize_hash",
+        deserialize_with = "crate::saves::deserialize_hash"
+    )]
+    pub hash: u64,
+    pub undo: Vec<Snapshot>,
+    pub redo: Vec<Snapshot>,
+}
+
+fn history_file(path: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    dirs::data_dir().map(|dir| {
+        dir.join("iced_editor")
+            .join("undo")
+            .join(format!("{:016x}.toml", hasher.finish()))
+    })
+}
+
+pub async fn load(path: &Path, hash: u64) -> Option<Saved> {
+    let file = history_file(path)?;
+    let contents = tokio::fs::read_to_string(&file).await.ok()?;
+    let saved = toml::from_str::<Saved>(&contents)
+        .ok()
+        .filter(|saved| saved.path == path && saved.hash == hash);
+    if saved.is_none() {
+        let _ = tokio::fs::remove_file(&file).await;
+    }
+    saved
+}
+
+pub async fn save(histories: Vec<Saved>) {
+    for saved in histories {
+        let Some(file) = history_file(&saved.path) else {
+            continue;
+        };
+        if saved.undo.is_empty() && saved.redo.is_empty() {
+            let _ = tokio::fs::remove_file(&file).await;
+            continue;
+        }
+        let Ok(contents) = toml::to_string(&saved) else {
+            continue;
+        };
+        if let Some(dir) = file.parent() {
+            let _ = tokio::fs::create_dir_all(dir).await;
+        }
+        let _ = tokio::fs::write(file, contents).await;
+    }
+}
@@ -0,0 +1,69 @@
+use std::sync::Arc;
+
+use crate::diff::{self, Hunk};
+
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub buffer: usize,
+    saved: Arc<String>,
+    revision: Option<u64>,
+    changes: Vec<Change>,
+}
+
+#[derive(Debug, Clone)]
+pub struct Change {
+    pub hunk: Hunk,
+    pub removed: Vec<String>,
+    pub added: Vec<String>,
+}
+
+impl Panel {
+    pub fn new(buffer: usize, saved: Arc<String>) -> Self {
+        Self {
+            buffer,
+            saved,
+            revision: None,
+            changes: Vec::new(),
+        }
+    }
+
+    pub fn saved(&self) -> &str {
+        &self.saved
+    }
+
+    pub fn set_saved(&mut self, saved: Arc<String>) {
+        self.saved = saved;
+        self.revision = None;
+    }
+
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    pub fn refresh(&mut self, revision: u64, text: &str) -> &[Change] {
+        if self.revision != Some(revision) {
+            self.changes = self.diff(text);
+            self.revision = Some(revision);
+        }
+        &self.changes
+    }
+
+    fn diff(&self, text: &str) -> Vec<Change> {
+        let old: Vec<&str> = self.saved.split('\n').collect();
+        let new: Vec<&str> = text.split('\n').collect();
+        diff::diff(&old, &new)
+            .into_iter()
+            .map(|hunk| Change {
+                removed: old[hunk.old.clone()]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+                added: new[hunk.new.clone()]
+                    .iter()
+                    .map(|line| line.to_string())
+                    .collect(),
+                hunk,
+            })
+            .collect()
+    }
+}
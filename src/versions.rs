@@ -0,0 +1,134 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Limits {
+    pub max_age: Duration,
+    pub max_bytes: u64,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Version {
+    pub file: PathBuf,
+    pub saved_at: SystemTime,
+    pub len: u64,
+}
+
+impl Version {
+    pub fn age(&self) -> String {
+        let seconds = self.saved_at.elapsed().unwrap_or_default().as_secs();
+        match seconds {
+            0..60 => String::from("just now"),
+            60..3600 => format!("{}m ago", seconds / 60),
+            3600..86400 => format!("{}h ago", seconds / 3600),
+            _ => format!("{}d ago", seconds / 86400),
+        }
+    }
+
+    pub fn size(&self) -> String {
+        match self.len {
+            0..1024 => format!("{} B", self.len),
+            1024..1_048_576 => format!("{:.1} KB", self.len as f64 / 1024.0),
+            _ => format!("{:.1} MB", self.len as f64 / 1_048_576.0),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Action {
+    View,
+    Compare,
+    Restore,
+}
+
+#[derive(Debug, Clone)]
+pub struct Panel {
+    pub buffer: usize,
+    pub versions: Vec<Version>,
+}
+
+fn directory(path: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    dirs::data_dir().map(|dir| {
+        dir.join("iced_editor")
+            .join("versions")
+            .join(format!("{:016x}", hasher.finish()))
+    })
+}
+
+pub async fn list(path: PathBuf) -> Vec<Version> {
+    let Some(dir) = directory(&path) else {
+        return Vec::new();
+    };
+    let Ok(mut entries) = tokio::fs::read_dir(&dir).await else {
+        return Vec::new();
+    };
+    let mut versions = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let file = entry.path();
+        let Some(millis) = file
+            .file_stem()
+            .and_then(|stem| stem.to_str())
+            .and_then(|stem| stem.parse::<u64>().ok())
+        else {
+            continue;
+        };
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        versions.push(Version {
+            file,
+            saved_at: UNIX_EPOCH + Duration::from_millis(millis),
+            len: metadata.len(),
+        });
+    }
+    versions.sort_by(|a, b| b.saved_at.cmp(&a.saved_at));
+    versions
+}
+
+pub async fn read(version: Version) -> Result<String, Error> {
+    tokio::fs::read_to_string(&version.file)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))
+}
+
+pub async fn record(path: PathBuf, text: String, limits: Limits) -> Result<(), Error> {
+    let Some(dir) = directory(&path) else {
+        return Ok(());
+    };
+    let versions = list(path).await;
+    if let Some(newest) = versions.first()
+        && newest.len == text.len() as u64
+        && tokio::fs::read_to_string(&newest.file)
+            .await
+            .is_ok_and(|saved| saved == text)
+    {
+        return Ok(());
+    }
+    tokio::fs::create_dir_all(&dir)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    let millis = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+    let file = dir.join(format!("{millis}.txt"));
+    tokio::fs::write(&file, &text)
+        .await
+        .map_err(|error| Error::IOFailed(error.kind()))?;
+    let mut total = text.len() as u64;
+    for version in versions {
+        let is_expired = version.saved_at.elapsed().unwrap_or_default() > limits.max_age;
+        if is_expired || total + version.len > limits.max_bytes {
+            let _ = tokio::fs::remove_file(&version.file).await;
+        } else {
+            total += version.len;
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,53 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewState {
+    pub path: PathBuf,
+    #[serde(
+        serialize_with = "crate::saves::serialize_hash",
+        deserialize_with = "crate::saves::deserialize_hash"
+    )]
+    pub hash: u64,
+    pub line: usize,
+    pub column: usize,
+    pub top_line: usize,
+    pub folds: Vec<(usize, usize)>,
+    pub language: Option<String>,
+}
+
+fn state_file(path: &Path) -> Option<PathBuf> {
+    let mut hasher = DefaultHasher::new();
+    path.hash(&mut hasher);
+    dirs::data_dir().map(|dir| {
+        dir.join("iced_editor")
+            .join("views")
+            .join(format!("{:016x}.toml", hasher.finish()))
+    })
+}
+
+pub async fn load(path: &Path) -> Option<ViewState> {
+    let file = state_file(path)?;
+    let contents = tokio::fs::read_to_string(&file).await.ok()?;
+    toml::from_str::<ViewState>(&contents)
+        .ok()
+        .filter(|state| state.path == path)
+}
+
+pub async fn save(states: Vec<ViewState>) {
+    for state in states {
+        let Some(file) = state_file(&state.path) else {
+            continue;
+        };
+        let Ok(contents) = toml::to_string(&state) else {
+            continue;
+        };
+        if let Some(dir) = file.parent() {
+            let _ = tokio::fs::create_dir_all(dir).await;
+        }
+        let _ = tokio::fs::write(file, contents).await;
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt;
+
+use iced::widget::text_editor::{Action, Edit, Motion};
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Mode {
+    #[default]
+    Normal,
+    Insert,
+    Visual,
+    Command(String),
+}
+
+impl fmt::Display for Mode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Mode::Normal => f.write_str("-- NORMAL --"),
+            Mode::Insert => f.write_str("-- INSERT --"),
+            Mode::Visual => f.write_str("-- VISUAL --"),
+            Mode::Command(command) => write!(f, ":{command}"),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Delete,
+    Change,
+    Yank,
+}
+
+impl Operator {
+    fn of(c: char) -> Option<Self> {
+        match c {
+            'd' => Some(Operator::Delete),
+            'c' => Some(Operator::Change),
+            'y' => Some(Operator::Yank),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum Effect {
+    Perform(Action),
+    Selection(Operator),
+    Lines(Operator, usize),
+    Put { before: bool },
+    Undo,
+    Write,
+    Quit,
+    WriteQuit,
+    GoToLine(usize),
+}
+
+#[derive(Debug, Clone)]
+pub struct Register {
+    pub text: String,
+    pub linewise: bool,
+}
+
+#[derive(Debug, Default)]
+pub struct Vim {
+    pub mode: Mode,
+    pub register: Option<Register>,
+    count: Option<usize>,
+    operator: Option<Operator>,
+    is_g_pending: bool,
+}
+
+fn motion(c: char) -> Option<Motion> {
+    Some(match c {
+        'h' => Motion::Left,
+        'l' => Motion::Right,
+        'j' => Motion::Down,
+        'k' => Motion::Up,
+        'w' | 'e' => Motion::WordRight,
+        'b' => Motion::WordLeft,
+        '0' | '^' => Motion::Home,
+        '$' => Motion::End,
+        _ => return None,
+    })
+}
+
+impl Vim {
+    pub fn is_inserting(&self) -> bool {
+        self.mode == Mode::Insert
+    }
+
+    pub fn escape(&mut self) -> Vec<Effect> {
+        let mode = std::mem::take(&mut self.mode);
+        self.reset();
+        match mode {
+            Mode::Insert | Mode::Visual => vec![Effect::Perform(Action::Move(Motion::Left))],
+            Mode::Normal | Mode::Command(_) => Vec::new(),
+        }
+    }
+
+    fn reset(&mut self) {
+        self.count = None;
+        self.operator = None;
+        self.is_g_pending = false;
+    }
+
+    pub fn edit(&mut self, edit: &Edit) -> Vec<Effect> {
+        match (&mut self.mode, edit) {
+            (Mode::Command(command), Edit::Insert(c)) => {
+                command.push(*c);
+                Vec::new()
+            }
+            (Mode::Command(command), Edit::Backspace) => {
+                if command.pop().is_none() {
+                    self.mode = Mode::Normal;
+                }
+                Vec::new()
+            }
+            (Mode::Command(command), Edit::Enter) => {
+                let command = command.trim().to_owned();
+                self.mode = Mode::Normal;
+                run(&command).into_iter().collect()
+            }
+            (Mode::Command(_), _) => Vec::new(),
+            (_, Edit::Insert(c)) => self.key(*c),
+            (_, Edit::Backspace) => self.key('h'),
+            (_, Edit::Delete) => self.key('x'),
+            (_, Edit::Enter) => self.key('j'),
+            (_, Edit::Paste(_)) => Vec::new(),
+        }
+    }
+
+    fn key(&mut self, c: char) -> Vec<Effect> {
+        let visual = self.mode == Mode::Visual;
+        if let Some(digit) = c.to_digit(10)
+            && (digit != 0 || self.count.is_some())
+        {
+            self.count = Some(self.count.unwrap_or(0) * 10 + digit as usize);
+            return Vec::new();
+        }
+        let count = self.count.take().unwrap_or(1).max(1);
+        let explicit = count > 1;
+        if std::mem::take(&mut self.is_g_pending) {
+            self.operator = None;
+            return match c {
+                'g' => vec![Effect::Perform(self.motion_action(Motion::DocumentStart))],
+                _ => Vec::new(),
+            };
+        }
+        if let Some(motion) = motion(c) {
+            let operator = self.operator.take();
+            return match (operator, motion) {
+                (Some(operator), Motion::Down) => vec![Effect::Lines(operator, count + 1)],
+                (Some(operator), Motion::Up) => {
+                    let mut effects = repeat(Action::Move(Motion::Up), count);
+                    effects.push(Effect::Lines(operator, count + 1));
+                    effects
+                }
+                (Some(operator), motion) => {
+                    let mut effects = repeat(Action::Select(motion), count);
+                    effects.push(Effect::Selection(operator));
+                    self.after(operator);
+                    effects
+                }
+                (None, motion) => repeat(self.motion_action(motion), count),
+            };
+        }
+        if let Some(operator) = Operator::of(c) {
+            if visual {
+                self.mode = Mode::Normal;
+                self.after(operator);
+                return vec![Effect::Selection(operator)];
+            }
+            if self.operator.take() == Some(operator) {
+                self.after(operator);
+                return vec![Effect::Lines(operator, count)];
+            }
+            self.operator = Some(operator);
+            self.count = explicit.then_some(count);
+            return Vec::new();
+        }
+        self.operator = None;
+        match c {
+            'g' => {
+                self.is_g_pending = true;
+                self.count = explicit.then_some(count);
+                Vec::new()
+            }
+            'G' if explicit => vec![Effect::GoToLine(count)],
+            'G' => vec![Effect::Perform(self.motion_action(Motion::DocumentEnd))],
+            'x' if visual => {
+                self.mode = Mode::Normal;
+                vec![Effect::Selection(Operator::Delete)]
+            }
+            'x' => {
+                let mut effects = repeat(Action::Select(Motion::Right), count);
+                effects.push(Effect::Selection(Operator::Delete));
+                effects
+            }
+            'v' if visual => self.escape(),
+            'v' => {
+                self.mode = Mode::Visual;
+                Vec::new()
+            }
+            'i' => self.insert(Vec::new()),
+            'a' => self.insert(vec![Action::Move(Motion::Right)]),
+            'A' => self.insert(vec![Action::Move(Motion::End)]),
+            'I' => self.insert(vec![Action::Move(Motion::Home)]),
+            'o' => self.insert(vec![Action::Move(Motion::End), Action::Edit(Edit::Enter)]),
+            'O' => self.insert(vec![
+                Action::Move(Motion::Home),
+                Action::Edit(Edit::Enter),
+                Action::Move(Motion::Up),
+            ]),
+            'p' => vec![Effect::Put { before: false }; count],
+            'P' => vec![Effect::Put { before: true }; count],
+            'u' => vec![Effect::Undo; count],
+            ':' => {
+                self.mode = Mode::Command(String::new());
+                Vec::new()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    fn motion_action(&self, motion: Motion) -> Action {
+        if self.mode == Mode::Visual {
+            Action::Select(motion)
+        } else {
+            Action::Move(motion)
+        }
+    }
+
+    fn after(&mut self, operator: Operator) {
+        if operator == Operator::Change {
+            self.mode = Mode::Insert;
+        }
+    }
+
+    fn insert(&mut self, actions: Vec<Action>) -> Vec<Effect> {
+        self.mode = Mode::Insert;
+        actions.into_iter().map(Effect::Perform).collect()
+    }
+}
+
+fn repeat(action: Action, count: usize) -> Vec<Effect> {
+    vec![Effect::Perform(action); count]
+}
+
+fn run(command: &str) -> Option<Effect> {
+    match command {
+        "w" => Some(Effect::Write),
+        "q" | "q!" => Some(Effect::Quit),
+        "wq" | "x" => Some(Effect::WriteQuit),
+        line => line.parse().ok().map(Effect::GoToLine),
+    }
+}
@@ -0,0 +1,74 @@
+use std::path::PathBuf;
+
+use iced::futures::{SinkExt, StreamExt, channel::mpsc};
+use iced::{Subscription, subscription};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::Message;
+
+pub fn watch(mut paths: Vec<PathBuf>) -> Subscription<Message> {
+    paths.sort();
+    paths.dedup();
+    subscription::channel(paths.clone(), 100, move |mut output| async move {
+        let (sender, mut events) = mpsc::unbounded();
+        let watcher = notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+            let _ = sender.unbounded_send(event);
+        });
+        let Ok(mut watcher) = watcher else {
+            return std::future::pending().await;
+        };
+        let watched: Vec<_> = paths
+            .into_iter()
+            .filter_map(|path| Some((path.canonicalize().ok()?, path)))
+            .collect();
+        let mut directories: Vec<_> = watched
+            .iter()
+            .filter_map(|(absolute, _)| absolute.parent())
+            .collect();
+        directories.sort();
+        directories.dedup();
+        for directory in directories {
+            let _ = watcher.watch(directory, RecursiveMode::NonRecursive);
+        }
+        while let Some(event) = events.next().await {
+            let Ok(event) = event else {
+                continue;
+            };
+            let find = |changed: &PathBuf| {
+                watched
+                    .iter()
+                    .find(|(absolute, _)| absolute == changed)
+                    .map(|(_, path)| path.clone())
+            };
+            if let EventKind::Modify(ModifyKind::Name(RenameMode::Both)) = event.kind
+                && let [from, to] = event.paths.as_slice()
+            {
+                let target = find(to);
+                if let Some(path) = find(from) {
+                    let message = match target {
+                        Some(_) => Message::FileChanged(path),
+                        None => Message::FileRenamed(path, to.clone()),
+                    };
+                    let _ = output.send(message).await;
+                }
+                if let Some(path) = target {
+                    let _ = output.send(Message::FileChanged(path)).await;
+                }
+                continue;
+            }
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                continue;
+            }
+            for changed in event.paths {
+                if let Some((_, path)) = watched.iter().find(|(absolute, _)| *absolute == changed) {
+                    let _ = output.send(Message::FileChanged(path.clone())).await;
+                }
+            }
+        }
+        std::future::pending().await
+    })
+}
@@ -0,0 +1,42 @@
+const SCHEMES: &[&str] = &["https://", "http://"];
+const MAX_BYTES: usize = 64 * 1024 * 1024;
+
+pub fn is_url(text: &str) -> bool {
+    SCHEMES.iter().any(|scheme| text.starts_with(scheme))
+}
+
+pub fn file_name(url: &str) -> &str {
+    let rest = SCHEMES
+        .iter()
+        .find_map(|scheme| url.strip_prefix(scheme))
+        .unwrap_or(url);
+    let rest = rest.split(['?', '#']).next().unwrap_or(rest);
+    rest.rsplit('/')
+        .find(|segment| !segment.is_empty())
+        .unwrap_or(rest)
+}
+
+pub async fn fetch(url: String) -> Result<Vec<u8>, String> {
+    let mut response = reqwest::get(&url)
+        .await
+        .map_err(|error| error.to_string())?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("{url} returned {status}"));
+    }
+    let too_large = || format!("{url} is larger than {} MiB", MAX_BYTES >> 20);
+    if response
+        .content_length()
+        .is_some_and(|length| length as usize > MAX_BYTES)
+    {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(|error| error.to_string())? {
+        if bytes.len() + chunk.len() > MAX_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
+}
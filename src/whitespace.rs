@@ -0,0 +1,62 @@
+pub const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    Space,
+    Tab,
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Mark {
+    pub column: usize,
+    pub kind: Kind,
+    pub trailing: bool,
+    pub mixed: bool,
+}
+
+impl Mark {
+    pub fn columns(&self) -> usize {
+        match self.kind {
+            Kind::Space => 1,
+            Kind::Tab => TAB_WIDTH - self.column % TAB_WIDTH,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Line {
+    pub marks: Vec<Mark>,
+    pub width: usize,
+}
+
+impl Line {
+    pub fn new(text: &str) -> Self {
+        let indent = text.len() - text.trim_start_matches([' ', '\t']).len();
+        let is_mixed = text[..indent].contains(' ') && text[..indent].contains('\t');
+        let trailing = text.trim_end_matches([' ', '\t']).len();
+        let mut marks = Vec::new();
+        let mut column = 0;
+        for (offset, c) in text.char_indices() {
+            let kind = match c {
+                ' ' => Kind::Space,
+                '\t' => Kind::Tab,
+                _ => {
+                    column += 1;
+                    continue;
+                }
+            };
+            let mark = Mark {
+                column,
+                kind,
+                trailing: offset >= trailing,
+                mixed: is_mixed && offset < indent,
+            };
+            column += mark.columns();
+            marks.push(mark);
+        }
+        Self {
+            marks,
+            width: column,
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::{Path, PathBuf};
+
+use iced::futures::channel::oneshot;
+
+use crate::palette;
+
+pub const MAX_FILES: usize = 50_000;
+pub const SHOWN: usize = 30;
+
+#[derive(Debug, Default)]
+pub struct Workspace {
+    pub root: PathBuf,
+    pub files: Vec<PathBuf>,
+    pub is_indexing: bool,
+}
+
+impl Workspace {
+    pub fn new(root: PathBuf) -> Self {
+        Self {
+            root,
+            files: Vec::new(),
+            is_indexing: true,
+        }
+    }
+
+    pub fn matching(&self, query: &str) -> Vec<&Path> {
+        let mut scored: Vec<_> = self
+            .files
+            .iter()
+            .filter_map(|path| {
+                let shown = path.to_string_lossy();
+                let mut score = palette::score(&shown, query)?;
+                if let Some(name) = path.file_name()
+                    && palette::score(&name.to_string_lossy(), query).is_some()
+                {
+                    score += 20;
+                }
+                Some((score, path.as_path()))
+            })
+            .collect();
+        scored.sort_by(|(a, a_path), (b, b_path)| {
+            b.cmp(a)
+                .then_with(|| a_path.as_os_str().len().cmp(&b_path.as_os_str().len()))
+        });
+        scored
+            .into_iter()
+            .take(SHOWN)
+            .map(|(_, path)| path)
+            .collect()
+    }
+}
+
+pub async fn index(root: PathBuf) -> (PathBuf, Vec<PathBuf>) {
+    let (sender, receiver) = oneshot::channel();
+    let walked = root.clone();
+    std::thread::spawn(move || {
+        let files: Vec<PathBuf> = ignore::WalkBuilder::new(&walked)
+            .build()
+            .flatten()
+            .filter(|entry| {
+                entry
+                    .file_type()
+                    .is_some_and(|file_type| file_type.is_file())
+            })
+            .filter_map(|entry| {
+                entry
+                    .path()
+                    .strip_prefix(&walked)
+                    .ok()
+                    .map(Path::to_path_buf)
+            })
+            .take(MAX_FILES)
+            .collect();
+        let _ = sender.send(files);
+    });
+    let files = receiver.await.unwrap_or_default();
+    (root, files)
+}